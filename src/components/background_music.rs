@@ -11,7 +11,11 @@
 //! ├── tribulation_theme.ogg        # 渡劫
 //! ├── shop_theme.ogg               # 仙家坊市
 //! ├── rest_theme.ogg               # 休息场景
-//! └── victory_theme.ogg            # 胜利
+//! ├── victory_theme.ogg            # 胜利
+//! ├── spirit_realm_theme.ogg       # 灵界探索
+//! ├── spirit_battle_theme.ogg      # 灵界战斗
+//! ├── immortal_realm_theme.ogg     # 仙界探索
+//! └── immortal_battle_theme.ogg    # 仙界战斗
 //! ```
 //!
 //! # Suno Prompts
@@ -45,6 +49,14 @@ pub enum BgmType {
     Victory,
     /// 开场动画 - 「仙途开启」
     Opening,
+    /// 灵界探索 - 「灵山云渺」
+    SpiritRealm,
+    /// 灵界战斗 - 「万妖横行」
+    SpiritRealmBattle,
+    /// 仙界探索 - 「天阙遥望」
+    ImmortalRealm,
+    /// 仙界战斗 - 「仙魔同劫」
+    ImmortalRealmBattle,
 }
 
 impl BgmType {
@@ -60,6 +72,10 @@ impl BgmType {
             BgmType::Rest => "music/rest_theme.ogg",
             BgmType::Victory => "music/victory_theme.ogg",
             BgmType::Opening => "music/opening.mp3",
+            BgmType::SpiritRealm => "music/spirit_realm_theme.ogg",
+            BgmType::SpiritRealmBattle => "music/spirit_battle_theme.ogg",
+            BgmType::ImmortalRealm => "music/immortal_realm_theme.ogg",
+            BgmType::ImmortalRealmBattle => "music/immortal_battle_theme.ogg",
         }
     }
 
@@ -75,6 +91,10 @@ impl BgmType {
             BgmType::Rest => "rest_theme",
             BgmType::Victory => "victory_theme",
             BgmType::Opening => "opening",
+            BgmType::SpiritRealm => "spirit_realm_theme",
+            BgmType::SpiritRealmBattle => "spirit_battle_theme",
+            BgmType::ImmortalRealm => "immortal_realm_theme",
+            BgmType::ImmortalRealmBattle => "immortal_battle_theme",
         }
     }
 
@@ -90,6 +110,10 @@ impl BgmType {
             BgmType::Rest => "修炼打坐",
             BgmType::Victory => "众妖伏诛",
             BgmType::Opening => "仙途开启",
+            BgmType::SpiritRealm => "灵山云渺",
+            BgmType::SpiritRealmBattle => "万妖横行",
+            BgmType::ImmortalRealm => "天阙遥望",
+            BgmType::ImmortalRealmBattle => "仙魔同劫",
         }
     }
 
@@ -105,6 +129,10 @@ impl BgmType {
             BgmType::Rest => 0.5,
             BgmType::Victory => 0.75,
             BgmType::Opening => 1.0,
+            BgmType::SpiritRealm => 0.6,
+            BgmType::SpiritRealmBattle => 0.8,
+            BgmType::ImmortalRealm => 0.6,
+            BgmType::ImmortalRealmBattle => 0.85,
         }
     }
 }
@@ -1,9 +1,13 @@
 //! 卡牌组件和系统
 
 use bevy::prelude::*;
-use rand::prelude::SliceRandom;
 use serde::{Serialize, Deserialize};
 
+use crate::components::sect::Sect;
+use crate::components::status::StatusId;
+use crate::resources::card_data::CardLibrary;
+use crate::resources::status_data::StatusRegistry;
+
 // ============================================================================
 // 卡牌组件
 // ============================================================================
@@ -15,20 +19,41 @@ pub struct Card {
     pub id: u32,
     /// 卡牌名称
     pub name: String,
-    /// 卡牌描述
+    /// 卡牌描述（由效果列表生成）
     pub description: String,
     /// 卡牌类型
     pub card_type: CardType,
     /// 能量消耗
     pub cost: i32,
-    /// 卡牌效果
-    pub effect: CardEffect,
+    /// X 费：打出时耗尽全部灵力，效果按消耗量倍增
+    #[serde(default)]
+    pub x_cost: bool,
+    /// 卡牌效果（按顺序结算）
+    #[serde(alias = "effect", deserialize_with = "deserialize_effects")]
+    pub effects: Vec<CardEffect>,
     /// 稀有度
     pub rarity: CardRarity,
     /// 插画路径
     pub image_path: String,
     /// 是否已进阶
     pub upgraded: bool,
+    /// 进阶增量（缺省时沿用首个效果的默认增量）
+    #[serde(default)]
+    pub upgrade_delta: Option<CardUpgrade>,
+}
+
+/// 兼容旧存档：单个 `effect` 或 `effects` 列表均可读取
+fn deserialize_effects<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<CardEffect>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(CardEffect),
+        Many(Vec<CardEffect>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(effect) => vec![effect],
+        OneOrMany::Many(effects) => effects,
+    })
 }
 
 /// 卡牌类型
@@ -65,23 +90,202 @@ pub enum CardEffect {
     AttackAndDraw { damage: i32, cards: i32 },
     /// 多重攻击
     MultiAttack { damage: i32, times: i32 },
-    /// 施加状态
-    ApplyStatus { status: StatusType, count: i32 },
+    /// 对目标施加状态
+    ApplyStatus { status: StatusId, count: i32 },
+    /// 自身获得状态（如力量、荆棘）
+    GainStatus { status: StatusId, count: i32 },
     /// 改变环境
     ChangeEnvironment { name: String },
     /// 诅咒：虚弱（抽到时生效）
     CurseWeakness,
     /// 诅咒：扣血（抽到时生效）
     CurseDamage { amount: i32 },
+    /// 条件效果：满足条件时依次结算子效果
+    If { condition: EffectCondition, effects: Vec<CardEffect> },
+    /// 倍增效果：子效果的主数值乘以计数
+    Scaled { per: ScaleSource, effect: Box<CardEffect> },
+}
+
+/// 条件效果的判定条件
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EffectCondition {
+    /// 目标身负指定状态
+    TargetHas(StatusId),
 }
 
+/// 倍增效果的计数来源
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-pub enum StatusType {
-    Weakness,
-    Vulnerable,
-    Poison,
+pub enum ScaleSource {
+    /// 结算时的手牌数（不含本牌）
+    CardsInHand,
+    /// X 费功法消耗的灵力
+    EnergySpent,
+}
+
+impl CardEffect {
+    /// 可进阶的数值字段，如 `amount`、`damage`
+    pub fn numeric_fields(&self) -> Vec<(&'static str, i32)> {
+        match self {
+            CardEffect::DealDamage { amount }
+            | CardEffect::DealAoEDamage { amount }
+            | CardEffect::GainBlock { amount }
+            | CardEffect::Heal { amount }
+            | CardEffect::DrawCards { amount }
+            | CardEffect::GainEnergy { amount }
+            | CardEffect::CurseDamage { amount } => vec![("amount", *amount)],
+            CardEffect::AttackAndDraw { damage, cards } => vec![("damage", *damage), ("cards", *cards)],
+            CardEffect::MultiAttack { damage, times } => vec![("damage", *damage), ("times", *times)],
+            CardEffect::ApplyStatus { count, .. } | CardEffect::GainStatus { count, .. } => vec![("count", *count)],
+            CardEffect::If { effects, .. } => effects.iter().flat_map(|e| e.numeric_fields()).collect(),
+            CardEffect::Scaled { effect, .. } => effect.numeric_fields(),
+            CardEffect::ChangeEnvironment { .. } | CardEffect::CurseWeakness => Vec::new(),
+        }
+    }
+
+    /// 按字段名取可变数值（条件/倍增效果取首个含该字段的子效果）
+    fn numeric_field_mut(&mut self, field: &str) -> Option<&mut i32> {
+        match (self, field) {
+            (
+                CardEffect::DealDamage { amount }
+                | CardEffect::DealAoEDamage { amount }
+                | CardEffect::GainBlock { amount }
+                | CardEffect::Heal { amount }
+                | CardEffect::DrawCards { amount }
+                | CardEffect::GainEnergy { amount }
+                | CardEffect::CurseDamage { amount },
+                "amount",
+            ) => Some(amount),
+            (CardEffect::AttackAndDraw { damage, .. } | CardEffect::MultiAttack { damage, .. }, "damage") => Some(damage),
+            (CardEffect::AttackAndDraw { cards, .. }, "cards") => Some(cards),
+            (CardEffect::MultiAttack { times, .. }, "times") => Some(times),
+            (CardEffect::ApplyStatus { count, .. } | CardEffect::GainStatus { count, .. }, "count") => Some(count),
+            (CardEffect::If { effects, .. }, field) => effects.iter_mut().find_map(|e| e.numeric_field_mut(field)),
+            (CardEffect::Scaled { effect, .. }, field) => effect.numeric_field_mut(field),
+            _ => None,
+        }
+    }
+
+    /// 主数值乘以倍数后的效果（多段攻击倍增段数，攻击抽牌倍增伤害）
+    pub fn scaled(&self, factor: i32) -> CardEffect {
+        let mut effect = self.clone();
+        let field = match &effect {
+            CardEffect::MultiAttack { .. } => "times",
+            CardEffect::AttackAndDraw { .. } => "damage",
+            CardEffect::ApplyStatus { .. } | CardEffect::GainStatus { .. } => "count",
+            _ => "amount",
+        };
+        if let Some(value) = effect.numeric_field_mut(field) {
+            *value *= factor;
+        }
+        effect
+    }
+
+    /// 是否需要玩家指定单个敌人作为目标
+    pub fn requires_target(&self) -> bool {
+        match self {
+            CardEffect::DealDamage { .. }
+            | CardEffect::AttackAndDraw { .. }
+            | CardEffect::MultiAttack { .. }
+            | CardEffect::ApplyStatus { .. } => true,
+            // 条件判定的是目标身上的状态
+            CardEffect::If { condition: EffectCondition::TargetHas(_), .. } => true,
+            CardEffect::Scaled { effect, .. } => effect.requires_target(),
+            _ => false,
+        }
+    }
+
+    /// 生成效果的文字描述
+    pub fn describe(&self) -> String {
+        match self {
+            CardEffect::DealDamage { amount } => format!("造成{}点伤害", amount),
+            CardEffect::DealAoEDamage { amount } => format!("对所有妖兽造成{}点伤害", amount),
+            CardEffect::GainBlock { amount } => format!("获得{}点护盾", amount),
+            CardEffect::Heal { amount } => format!("恢复{}点道行", amount),
+            CardEffect::DrawCards { amount } => format!("抽{}张牌", amount),
+            CardEffect::GainEnergy { amount } => format!("获得{}点灵力", amount),
+            CardEffect::AttackAndDraw { damage, cards } => format!("造成{}点伤害，抽{}张牌", damage, cards),
+            CardEffect::MultiAttack { damage, times } => format!("造成{}点伤害{}次", damage, times),
+            CardEffect::ApplyStatus { status, count } => format!("施加{}层{}", count, StatusRegistry::builtin().name(status)),
+            CardEffect::GainStatus { status, count } => format!("获得{}层{}", count, StatusRegistry::builtin().name(status)),
+            CardEffect::ChangeEnvironment { name } if name == "雷暴" => {
+                format!("引动天雷对所有妖兽造成{}点伤害，天象变为【雷暴】：伤害提升 20%", HEAVENLY_STRIKE_DAMAGE)
+            }
+            CardEffect::ChangeEnvironment { name } if name == "浓雾" => {
+                "获得5点护盾，天象变为【浓雾】：防御提升 20%".to_string()
+            }
+            CardEffect::ChangeEnvironment { .. } => "获得5点护盾，天象复归平静".to_string(),
+            CardEffect::CurseWeakness => "留在手中时侵蚀心神，陷入1层虚弱".to_string(),
+            CardEffect::CurseDamage { amount } => format!("留在手中时反噬，受到{}点伤害", amount),
+            CardEffect::If { condition: EffectCondition::TargetHas(status), effects } => {
+                format!("若目标身负{}，则{}", StatusRegistry::builtin().name(status), describe_effects(effects))
+            }
+            CardEffect::Scaled { per, effect } => {
+                let per = match per {
+                    ScaleSource::CardsInHand => "每有1张手牌",
+                    ScaleSource::EnergySpent => "每消耗1点灵力",
+                };
+                format!("{}，{}", per, effect.describe())
+            }
+        }
+    }
 }
 
+/// 天象·雷暴引下的天雷伤害
+pub const HEAVENLY_STRIKE_DAMAGE: i32 = 20;
+
+/// 由效果列表生成卡牌描述
+pub fn describe_effects(effects: &[CardEffect]) -> String {
+    effects.iter().map(|e| e.describe()).collect::<Vec<_>>().join("，")
+}
+
+/// 进阶增量：按字段名叠加到效果数值上
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CardUpgrade {
+    pub amount: i32,
+    pub damage: i32,
+    pub cards: i32,
+    pub times: i32,
+    pub count: i32,
+    /// 能量消耗变化（通常为负）
+    pub cost: i32,
+}
+
+impl CardUpgrade {
+    /// 未配置增量时各效果的默认进阶幅度
+    pub fn default_for(effect: &CardEffect) -> Self {
+        match effect {
+            CardEffect::DealDamage { .. } | CardEffect::GainBlock { .. } => Self { amount: 3, ..default() },
+            CardEffect::DealAoEDamage { .. } | CardEffect::Heal { .. } => Self { amount: 2, ..default() },
+            CardEffect::DrawCards { .. } => Self { amount: 1, ..default() },
+            CardEffect::AttackAndDraw { .. } => Self { damage: 2, cards: 1, ..default() },
+            _ => Self::default(),
+        }
+    }
+
+    /// 以 (字段名, 增量) 形式列出非零增量，`cost` 除外
+    pub fn deltas(&self) -> Vec<(&'static str, i32)> {
+        [
+            ("amount", self.amount),
+            ("damage", self.damage),
+            ("cards", self.cards),
+            ("times", self.times),
+            ("count", self.count),
+        ]
+        .into_iter()
+        .filter(|(_, delta)| *delta != 0)
+        .collect()
+    }
+
+    /// 将增量叠加到效果列表上：每个字段作用于首个含该字段的效果
+    pub fn apply(&self, effects: &mut [CardEffect]) {
+        for (field, delta) in self.deltas() {
+            if let Some(value) = effects.iter_mut().find_map(|e| e.numeric_field_mut(field)) {
+                *value += delta;
+            }
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CardRarity {
@@ -107,28 +311,76 @@ impl CardRarity {
 }
 
 impl Card {
-    /// 创建一张新卡牌
+    /// 创建一张新卡牌（描述由效果列表生成）
     pub fn new(
         id: u32,
         name: impl Into<String>,
-        description: impl Into<String>,
         card_type: CardType,
         cost: i32,
-        effect: CardEffect,
+        effects: Vec<CardEffect>,
         rarity: CardRarity,
         image_path: impl Into<String>,
     ) -> Self {
-        Self {
+        let mut card = Self {
             id,
             name: name.into(),
-            description: description.into(),
+            description: String::new(),
             card_type,
             cost,
-            effect,
+            x_cost: false,
+            effects,
             rarity,
             image_path: image_path.into(),
             upgraded: false,
+            upgrade_delta: None,
+        };
+        card.refresh_description();
+        card
+    }
+
+    /// 设为 X 费功法
+    pub fn with_x_cost(mut self) -> Self {
+        self.x_cost = true;
+        self.cost = 0;
+        self.refresh_description();
+        self
+    }
+
+    /// 按效果列表重写描述
+    pub fn refresh_description(&mut self) {
+        let text = describe_effects(&self.effects);
+        self.description = if self.x_cost { format!("耗尽灵力。{}", text) } else { text };
+    }
+
+    /// 能量消耗的显示文字
+    pub fn cost_label(&self) -> String {
+        if self.x_cost { "X".to_string() } else { self.cost.to_string() }
+    }
+
+    /// 是否需要玩家指定单个敌人作为目标
+    pub fn requires_target(&self) -> bool {
+        self.effects.iter().any(|e| e.requires_target())
+    }
+
+    /// 首个伤害类效果（用于表现层区分攻击演出）
+    pub fn attack_effect(&self) -> Option<&CardEffect> {
+        fn find(effects: &[CardEffect]) -> Option<&CardEffect> {
+            effects.iter().find_map(|e| match e {
+                CardEffect::DealDamage { .. }
+                | CardEffect::DealAoEDamage { .. }
+                | CardEffect::AttackAndDraw { .. }
+                | CardEffect::MultiAttack { .. } => Some(e),
+                CardEffect::If { effects, .. } => find(effects),
+                CardEffect::Scaled { effect, .. } => find(std::slice::from_ref(effect.as_ref())),
+                _ => None,
+            })
         }
+        find(&self.effects)
+    }
+
+    /// 是否包含指定种类的效果（仅比较顶层效果的种类）
+    pub fn has_effect(&self, matches: impl Fn(&CardEffect) -> bool) -> bool {
+        self.effects.iter().any(matches)
     }
 
     /// 获取卡牌显示的颜色
@@ -149,46 +401,26 @@ impl Card {
         self.upgraded = true;
         self.name = format!("{}+", self.name);
 
-        match &mut self.effect {
-            CardEffect::DealDamage { amount } => {
-                *amount += 3;
-                self.description = format!("造成{}点伤害", *amount);
-            }
-            CardEffect::DealAoEDamage { amount } => {
-                *amount += 2;
-                self.description = format!("对所有妖兽造成{}点伤害", *amount);
-            }
-            CardEffect::GainBlock { amount } => {
-                *amount += 3;
-                self.description = format!("获得{}点护盾", *amount);
-            }
-            CardEffect::Heal { amount } => {
-                *amount += 2;
-                self.description = format!("恢复{}点道行", *amount);
-            }
-            CardEffect::DrawCards { amount } => {
-                *amount += 1;
-                self.description = format!("抽{}张牌", *amount);
-            }
-            CardEffect::AttackAndDraw { damage, cards } => {
-                *damage += 2;
-                *cards += 1;
-                self.description = format!("造成{}点伤害，抽{}张牌", *damage, *cards);
-            }
-            _ => {}
+        let delta = self.upgrade_delta.unwrap_or_else(|| {
+            self.effects.first().map(CardUpgrade::default_for).unwrap_or_default()
+        });
+        delta.apply(&mut self.effects);
+        if !self.x_cost {
+            self.cost = (self.cost + delta.cost).max(0);
         }
+        self.refresh_description();
     }
 
     /// 获取核心效果数值 (伤害/防御等)
     pub fn effect_amount(&self) -> i32 {
-        match self.effect {
-            CardEffect::DealDamage { amount } => amount,
-            CardEffect::DealAoEDamage { amount } => amount,
-            CardEffect::GainBlock { amount } => amount,
-            CardEffect::Heal { amount } => amount,
-            CardEffect::DrawCards { amount } => amount,
-            CardEffect::AttackAndDraw { damage, .. } => damage,
-            CardEffect::MultiAttack { damage, .. } => damage,
+        match self.effects.first() {
+            Some(CardEffect::DealDamage { amount }) => *amount,
+            Some(CardEffect::DealAoEDamage { amount }) => *amount,
+            Some(CardEffect::GainBlock { amount }) => *amount,
+            Some(CardEffect::Heal { amount }) => *amount,
+            Some(CardEffect::DrawCards { amount }) => *amount,
+            Some(CardEffect::AttackAndDraw { damage, .. }) => *damage,
+            Some(CardEffect::MultiAttack { damage, .. }) => *damage,
             _ => 0,
         }
     }
@@ -230,9 +462,9 @@ impl PlayerDeck {
         }
     }
 
-    /// 重置牌组
-    pub fn reset(&mut self) {
-        self.cards = create_starting_deck();
+    /// 按功法图谱重置为宗门的初始牌组
+    pub fn reset(&mut self, library: &CardLibrary, sect: Sect) {
+        self.cards = library.starting_deck(sect);
     }
 
     /// 添加卡牌到牌组
@@ -252,7 +484,7 @@ impl PlayerDeck {
 }
 
 /// 抽牌堆
-#[derive(Component, Debug, Clone)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct DrawPile {
     /// 卡牌列表
     pub cards: Vec<Card>,
@@ -278,16 +510,16 @@ impl DrawPile {
     }
 
     /// 洗牌（将弃牌堆的卡牌加入抽牌堆并打乱）
-    pub fn shuffle_from_discard(&mut self, mut discard_cards: Vec<Card>) {
+    pub fn shuffle_from_discard(&mut self, mut discard_cards: Vec<Card>, rng: &mut impl rand::Rng) {
         use rand::seq::SliceRandom;
-        discard_cards.shuffle(&mut rand::thread_rng());
+        discard_cards.shuffle(rng);
         self.cards.extend(discard_cards);
         self.count = self.cards.len();
     }
 }
 
 /// 弃牌堆
-#[derive(Component, Debug, Clone)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct DiscardPile {
     /// 卡牌列表
     pub cards: Vec<Card>,
@@ -320,7 +552,7 @@ impl DiscardPile {
 }
 
 /// 手牌
-#[derive(Component, Debug, Clone)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct Hand {
     /// 卡牌列表
     pub cards: Vec<Card>,
@@ -408,266 +640,17 @@ impl Default for DeckConfig {
     }
 }
 
-/// 初始牌组
+/// 默认宗门的初始牌组（取自内置功法图谱）
+///
+/// 运行时应优先使用 [`CardLibrary::starting_deck`]，以便读取热重载后的数据与所选宗门。
 pub fn create_starting_deck() -> Vec<Card> {
-    vec![
-        // 5张道法（攻击）卡 - 御剑术
-        Card::new(
-            0,
-            "御剑术",
-            "造成6点伤害",
-            CardType::Attack,
-            1,
-            CardEffect::DealDamage { amount: 6 },
-            CardRarity::Common,
-            "textures/cards/attack.png",
-        ),
-        Card::new(
-            1,
-            "御剑术",
-            "造成6点伤害",
-            CardType::Attack,
-            1,
-            CardEffect::DealDamage { amount: 6 },
-            CardRarity::Common,
-            "textures/cards/attack.png",
-        ),
-        Card::new(
-            2,
-            "御剑术",
-            "造成6点伤害",
-            CardType::Attack,
-            1,
-            CardEffect::DealDamage { amount: 6 },
-            CardRarity::Common,
-            "textures/cards/attack.png",
-        ),
-        Card::new(
-            3,
-            "御剑术",
-            "造成6点伤害",
-            CardType::Attack,
-            1,
-            CardEffect::DealDamage { amount: 6 },
-            CardRarity::Common,
-            "textures/cards/attack.png",
-        ),
-        Card::new(
-            4,
-            "御剑术",
-            "造成6点伤害",
-            CardType::Attack,
-            1,
-            CardEffect::DealDamage { amount: 6 },
-            CardRarity::Common,
-            "textures/cards/attack.png",
-        ),
-        // 4张护体（防御）卡 - 金光咒
-        Card::new(
-            5,
-            "金光咒",
-            "获得5点护盾",
-            CardType::Defense,
-            1,
-            CardEffect::GainBlock { amount: 5 },
-            CardRarity::Common,
-            "textures/cards/defense.png",
-        ),
-        Card::new(
-            6,
-            "金光咒",
-            "获得5点护盾",
-            CardType::Defense,
-            1,
-            CardEffect::GainBlock { amount: 5 },
-            CardRarity::Common,
-            "textures/cards/defense.png",
-        ),
-        Card::new(
-            7,
-            "金光咒",
-            "获得5点护盾",
-            CardType::Defense,
-            1,
-            CardEffect::GainBlock { amount: 5 },
-            CardRarity::Common,
-            "textures/cards/defense.png",
-        ),
-        Card::new(
-            8,
-            "金光咒",
-            "获得5点护盾",
-            CardType::Defense,
-            1,
-            CardEffect::GainBlock { amount: 5 },
-            CardRarity::Common,
-            "textures/cards/defense.png",
-        ),
-        // 1张剑气卡 - 剑气斩
-        Card::new(
-            9,
-            "剑气斩",
-            "造成3点伤害，抽1张牌",
-            CardType::Attack,
-            1,
-            CardEffect::AttackAndDraw {
-                damage: 3,
-                cards: 1,
-            },
-            CardRarity::Uncommon,
-            "textures/cards/attack.png",
-        ),
-        // 2张回复卡 - 回春术
-        Card::new(
-            10,
-            "回春术",
-            "恢复5点道行",
-            CardType::Skill,
-            1,
-            CardEffect::Heal { amount: 5 },
-            CardRarity::Uncommon,
-            "textures/cards/skill.png",
-        ),
-        Card::new(
-            11,
-            "回春术",
-            "恢复5点道行",
-            CardType::Skill,
-            1,
-            CardEffect::Heal { amount: 5 },
-            CardRarity::Uncommon,
-            "textures/cards/skill.png",
-        ),
-        // 1张状态卡 - 破绽百出
-        Card::new(
-            12,
-            "破绽百出",
-            "施加2层易伤",
-            CardType::Skill,
-            1,
-            CardEffect::ApplyStatus { status: StatusType::Vulnerable, count: 2 },
-            CardRarity::Common,
-            "textures/cards/skill.png",
-        ),
-        // [新增] 初始天象大招
-        Card::new(
-            500,
-            "天象·引雷术",
-            "造成20点全场伤害。将环境变为【雷暴】：伤害提升 20%",
-            CardType::Attack,
-            1,
-            CardEffect::ChangeEnvironment { name: "雷暴".to_string() },
-            CardRarity::Uncommon,
-            "textures/cards/attack.png",
-        ),
-        // [新增] 初始万剑归宗
-        Card::new(
-            151,
-            "万剑归宗",
-            "剑气纵横！对全场造成10点伤害",
-            CardType::Attack,
-            2,
-            CardEffect::DealAoEDamage { amount: 10 },
-            CardRarity::Rare,
-            "textures/cards/attack.png",
-        ),
-    ]
+    CardLibrary::builtin().starting_deck(Sect::default())
 }
 
 // ============================================================================
-// 卡牌池和奖励系统
+// 奖励系统
 // ============================================================================
 
-/// 卡牌池 - 包含所有可获得的卡牌
-pub struct CardPool;
-
-impl CardPool {
-    /// 获取所有可获得的卡牌
-    pub fn all_cards() -> Vec<Card> {
-        vec![
-            // === 普通功法 ===
-            Card::new(100, "雷法·掌心雷", "造成12点雷击伤害", CardType::Attack, 2, CardEffect::DealDamage { amount: 12 }, CardRarity::Common, "textures/cards/attack.png"),
-            Card::new(101, "不动明王", "获得8点护盾", CardType::Defense, 1, CardEffect::GainBlock { amount: 8 }, CardRarity::Common, "textures/cards/defense.png"),
-            Card::new(102, "疾风刺", "造成4点快速伤害", CardType::Attack, 0, CardEffect::DealDamage { amount: 4 }, CardRarity::Common, "textures/cards/attack.png"),
-            // === 群体功法 ===
-            Card::new(150, "横扫千军", "对所有妖兽造成6点伤害", CardType::Attack, 1, CardEffect::DealAoEDamage { amount: 6 }, CardRarity::Uncommon, "textures/cards/attack.png"),
-            Card::new(151, "万剑归宗", "剑气纵横！对全场造成10点伤害", CardType::Attack, 2, CardEffect::DealAoEDamage { amount: 10 }, CardRarity::Rare, "textures/cards/attack.png"),
-            // === 稀有功法 ===
-            Card::new(200, "御剑·流云", "造成8点伤害，抽2张牌", CardType::Attack, 2, CardEffect::AttackAndDraw { damage: 8, cards: 2 }, CardRarity::Uncommon, "textures/cards/attack.png"),
-            Card::new(201, "太极图", "获得12点护盾", CardType::Defense, 2, CardEffect::GainBlock { amount: 12 }, CardRarity::Uncommon, "textures/cards/defense.png"),
-            Card::new(202, "甘霖咒", "恢复10点道行", CardType::Skill, 2, CardEffect::Heal { amount: 10 }, CardRarity::Uncommon, "textures/cards/skill.png"),
-            Card::new(203, "破军剑", "造成6点伤害，抽1张牌", CardType::Attack, 1, CardEffect::AttackAndDraw { damage: 6, cards: 1 }, CardRarity::Uncommon, "textures/cards/attack.png"),
-            Card::new(204, "神识全开", "抽3张牌，获得2点灵力", CardType::Skill, 1, CardEffect::DrawCards { amount: 3 }, CardRarity::Uncommon, "textures/cards/skill.png"),
-            // === 传说功法 ===
-            Card::new(300, "九天神雷", "造成20点毁灭伤害", CardType::Attack, 3, CardEffect::DealDamage { amount: 20 }, CardRarity::Rare, "textures/cards/attack.png"),
-            Card::new(301, "金身法相", "获得15点护盾，恢复5点道行", CardType::Defense, 2, CardEffect::GainBlock { amount: 15 }, CardRarity::Rare, "textures/cards/defense.png"),
-            Card::new(302, "万物回春", "恢复15点道行", CardType::Skill, 2, CardEffect::Heal { amount: 15 }, CardRarity::Rare, "textures/cards/skill.png"),
-            // === 状态功法 ===
-            Card::new(400, "破绽百出", "施加2层易伤", CardType::Skill, 1, CardEffect::ApplyStatus { status: StatusType::Vulnerable, count: 2 }, CardRarity::Common, "textures/cards/skill.png"),
-            Card::new(401, "迷魂烟", "施加2层虚弱", CardType::Skill, 1, CardEffect::ApplyStatus { status: StatusType::Weakness, count: 2 }, CardRarity::Common, "textures/cards/skill.png"),
-            // === 天象功法 ===
-            Card::new(500, "天象·引雷术", "造成20点全场伤害。将环境变为【雷暴】：伤害提升 20%", CardType::Attack, 1, CardEffect::ChangeEnvironment { name: "雷暴".to_string() }, CardRarity::Uncommon, "textures/cards/attack.png"),
-            Card::new(501, "天象·迷踪阵", "获得5点护盾。将环境变为【浓雾】：防御提升 20%", CardType::Defense, 1, CardEffect::ChangeEnvironment { name: "浓雾".to_string() }, CardRarity::Uncommon, "textures/cards/defense.png"),
-        ]
-    }
-
-    /// 根据稀有度获取卡牌
-    pub fn get_by_rarity(rarity: CardRarity) -> Vec<Card> {
-        Self::all_cards().into_iter().filter(|c| c.rarity == rarity).collect()
-    }
-
-    /// 随机获取指定数量的卡牌（用于奖励）
-    pub fn random_cards(count: usize) -> Vec<Card> {
-        let all = Self::all_cards();
-        use rand::seq::SliceRandom;
-        let mut rng = rand::thread_rng();
-        all.choose_multiple(&mut rng, count).cloned().collect()
-    }
-
-    /// 随机获取卡牌（偏向稀有度）
-    pub fn random_rewards(count: usize) -> Vec<Card> {
-        let mut rewards = Vec::new();
-        use rand::Rng;
-
-        for i in 0..count {
-            let mut rng = rand::thread_rng();
-            // 奖励概率：50%普通，40%稀有，10%罕见
-            let rarity_roll = rng.gen::<f32>();
-            let rarity = if rarity_roll < 0.5 {
-                CardRarity::Common
-            } else if rarity_roll < 0.9 {
-                CardRarity::Uncommon
-            } else {
-                CardRarity::Rare
-            };
-
-            let cards = Self::get_by_rarity(rarity);
-            if let Some(card) = cards.choose(&mut rng) {
-                // 为每张卡创建唯一ID
-                let mut card = card.clone();
-                card.id = 1000 + i as u32;
-                rewards.push(card);
-            }
-        }
-
-        rewards
-    }
-
-    /// 获取筑基期本命功法
-    pub fn get_innate_spell() -> Card {
-        Card::new(
-            999,
-            "青莲剑歌",
-            "造成30点穿透伤害，恢复10点灵力",
-            CardType::Attack,
-            3,
-            CardEffect::MultiAttack { damage: 10, times: 3 },
-            CardRarity::Special,
-            "textures/cards/special.png",
-        )
-    }
-}
-
 /// 奖励选项组件
 #[derive(Component, Clone)]
 pub struct RewardCard {
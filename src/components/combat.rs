@@ -3,6 +3,12 @@
 use bevy::prelude::*;
 use serde::{Serialize, Deserialize};
 
+use crate::components::cultivation::Realm;
+use crate::components::sect::Sect;
+use crate::components::status::{settle_status_triggers, StatusEffects, StatusTrigger, BURN, POISON, WEAKNESS};
+use crate::resources::enemy_data::{EnemyDefinition, EnemyMove, EnemyPhase, EnemyRoster, MoveAction, MovePattern, PhaseVisuals};
+use crate::resources::status_data::StatusRegistry;
+
 // ============================================================================
 // 战斗状态
 // ============================================================================
@@ -37,14 +43,27 @@ pub struct Player {
     pub turn: u32,
     /// 剑意值 (0-5)
     pub sword_intent: i32,
-    /// 中毒层数 (每回合开始扣血)
-    pub poison: i32,
-    /// 灼烧层数 (每回合开始扣血，并随时间递减)
-    pub burn: i32,
-    /// 虚弱层数 (攻击力降低)
-    pub weakness: i32,
-    /// 易伤层数 (受创增加)
-    pub vulnerable: i32,
+    /// 身上的状态（中毒、虚弱、力量……）
+    #[serde(default)]
+    pub statuses: StatusEffects,
+    /// 本场战斗已打出的功法数（供"每第 N 张"类遗物计数）
+    #[serde(default)]
+    pub cards_played: i32,
+    /// 所属宗门
+    #[serde(default)]
+    pub sect: Sect,
+    /// 宗门心法层数（丹火、符印；剑宗用剑意）
+    #[serde(default)]
+    pub sect_charge: i32,
+    /// 所处境界（战斗开始时与修为同步，决定境界被动与神通）
+    #[serde(default)]
+    pub realm: Realm,
+    /// 本场战斗是否已施展神通
+    #[serde(default)]
+    pub divine_art_used: bool,
+    /// 本回合已打出的功法数（元婴分身只重演首张）
+    #[serde(default)]
+    pub turn_cards_played: i32,
 }
 
 impl Default for Player {
@@ -54,12 +73,43 @@ impl Default for Player {
             energy: 3, max_energy: 3,
             block: 0, gold: 100, turn: 1,
             sword_intent: 0,
-            poison: 0, burn: 0, weakness: 0, vulnerable: 0,
+            statuses: StatusEffects::default(),
+            cards_played: 0,
+            sect: Sect::default(),
+            sect_charge: 0,
+            realm: Realm::default(),
+            divine_art_used: false,
+            turn_cards_played: 0,
         }
     }
 }
 
 impl Player {
+    /// 指定宗门的初始修行者
+    pub fn for_sect(sect: Sect) -> Self {
+        Self {
+            hp: sect.base_max_hp(), max_hp: sect.base_max_hp(),
+            energy: sect.base_energy(), max_energy: sect.base_energy(),
+            sect,
+            ..Self::default()
+        }
+    }
+
+    /// 积累宗门心法（不超过宗门上限），返回当前层数
+    pub fn add_sect_charge(&mut self, amount: i32) -> i32 {
+        self.sect_charge = (self.sect_charge + amount).min(self.sect.charge_cap());
+        self.sect_charge
+    }
+
+    /// 宗门心法带来的额外伤害：剑宗看剑意，天符宗每层符印 +3
+    pub fn sect_damage_bonus(&self) -> i32 {
+        match self.sect {
+            Sect::Sword => self.get_intent_damage_bonus(),
+            Sect::Alchemy => 0,
+            Sect::Talisman => 3 * self.sect_charge,
+        }
+    }
+
     /// 积累剑意
     pub fn add_sword_intent(&mut self, amount: i32) {
         self.sword_intent = (self.sword_intent + amount).min(5);
@@ -80,39 +130,25 @@ impl Player {
         }
     }
 
-    /// 计算实际造成的伤害 (考虑虚弱和剑意)
+    /// 计算实际造成的伤害 (考虑状态修正和剑意)
     pub fn calculate_outgoing_damage(&self, base_amount: i32) -> i32 {
         self.calculate_outgoing_damage_with_env(base_amount, None)
     }
 
     pub fn calculate_outgoing_damage_with_env(&self, base_amount: i32, environment: Option<&Environment>) -> i32 {
-        // 先应用基础伤害 + 剑意加成
-        let total_base = base_amount + self.get_intent_damage_bonus();
-        
-        let damage = if self.weakness > 0 {
-            (total_base as f32 * 0.75) as i32
-        } else {
-            total_base
-        };
-
-        if let Some(env) = environment {
-            (damage as f32 * env.damage_modifier) as i32
-        } else {
-            damage
-        }
+        // 基础伤害 + 宗门心法加成，再叠加状态图谱中的全部修正
+        let total_base = base_amount + self.sect_damage_bonus();
+        let env_modifier = environment.map(|e| e.damage_modifier).unwrap_or(1.0);
+        self.statuses.modifiers(StatusRegistry::builtin(), |d| &d.outgoing_damage).apply(total_base, env_modifier)
     }
 
-    /// 计算实际受到的伤害 (考虑易伤)
+    /// 计算实际受到的伤害 (考虑状态修正)
     pub fn calculate_incoming_damage(&self, base_amount: i32) -> i32 {
         self.calculate_incoming_damage_with_env(base_amount, None)
     }
 
     pub fn calculate_incoming_damage_with_env(&self, base_amount: i32, _environment: Option<&Environment>) -> i32 {
-        if self.vulnerable > 0 {
-            (base_amount as f32 * 1.5) as i32
-        } else {
-            base_amount
-        }
+        self.statuses.modifiers(StatusRegistry::builtin(), |d| &d.incoming_damage).apply(base_amount, 1.0)
     }
 
     /// 受到伤害（护甲优先抵消）
@@ -150,8 +186,7 @@ impl Player {
 
     pub fn gain_block_with_env(&mut self, amount: i32, environment: Option<&Environment>) {
         let modifier = environment.map(|e| e.block_modifier).unwrap_or(1.0);
-        let final_amount = (amount as f32 * modifier) as i32;
-        self.block += final_amount;
+        self.block += self.statuses.modifiers(StatusRegistry::builtin(), |d| &d.block_gained).apply(amount, modifier);
     }
 
     /// 清空护甲（回合结束时）
@@ -178,6 +213,45 @@ impl Player {
     pub fn start_turn(&mut self) {
         self.energy = self.max_energy;
         self.turn += 1;
+        self.turn_cards_played = 0;
+    }
+
+    /// 施加状态（按图谱叠加规则）
+    pub fn apply_status(&mut self, id: &str, stacks: i32) {
+        self.statuses.apply(StatusRegistry::builtin(), id, stacks, None);
+    }
+
+    /// 回合开始时触发状态钩子（中毒、灼烧发作等）
+    pub fn tick_statuses(&mut self) -> Vec<StatusTrigger> {
+        let triggers = self.statuses.on_turn_start(StatusRegistry::builtin());
+        settle_status_triggers(&triggers, &mut self.hp, self.max_hp, &mut self.block);
+        triggers
+    }
+
+    /// 回合结束时触发状态钩子（虚弱、易伤消退等）
+    pub fn decay_statuses(&mut self) -> Vec<StatusTrigger> {
+        let triggers = self.statuses.on_turn_end(StatusRegistry::builtin());
+        settle_status_triggers(&triggers, &mut self.hp, self.max_hp, &mut self.block);
+        triggers
+    }
+
+    /// 打出功法后触发状态钩子
+    pub fn card_played_statuses(&mut self) -> Vec<StatusTrigger> {
+        let triggers = self.statuses.on_card_played(StatusRegistry::builtin());
+        settle_status_triggers(&triggers, &mut self.hp, self.max_hp, &mut self.block);
+        triggers
+    }
+
+    /// 被攻击命中后触发状态钩子（反伤由调用方结算）
+    pub fn attacked_statuses(&mut self) -> Vec<StatusTrigger> {
+        let triggers = self.statuses.on_attacked(StatusRegistry::builtin());
+        settle_status_triggers(&triggers, &mut self.hp, self.max_hp, &mut self.block);
+        triggers
+    }
+
+    /// 清除全部状态（战斗开始时）
+    pub fn clear_statuses(&mut self) {
+        self.statuses.clear();
     }
 }
 
@@ -206,6 +280,10 @@ pub struct StatusIndicator {
     pub owner: Entity,
 }
 
+/// 状态说明悬浮框
+#[derive(Component)]
+pub struct StatusTooltip;
+
 // --- UI 标记组件 ---
 #[derive(Component)]
 pub struct EnemyHpText {
@@ -227,6 +305,19 @@ pub struct EnemyStatusUi {
     pub owner: Entity,
 }
 
+/// 敌人的选靶点击区域（覆盖在敌人立绘上方的透明按钮）
+#[derive(Component)]
+pub struct EnemyTargetZone {
+    pub owner: Entity,
+}
+
+/// 选靶状态：单体功法打出前等待玩家指定敌人
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CardTargeting {
+    /// 等待选择目标的卡牌 id
+    pub card_id: Option<u32>,
+}
+
 #[derive(Component)]
 pub struct PlayerHpText;
 
@@ -254,6 +345,10 @@ pub struct EnergyOrb;
 #[derive(Component)]
 pub struct EndTurnButton;
 
+/// 境界神通按钮（本场施展后移除）
+#[derive(Component)]
+pub struct DivineArtButton;
+
 #[derive(Component)]
 pub struct HandArea;
 
@@ -306,7 +401,7 @@ pub struct DamageEffectEvent {
 }
 
 /// 敌人战斗属性
-#[derive(Component, Debug, Clone)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct Enemy {
     /// 敌人ID
     pub id: u32,
@@ -320,18 +415,14 @@ pub struct Enemy {
     pub max_hp: i32,
     /// 当前意图（下次行动）
     pub intent: EnemyIntent,
-    pub ai_pattern: AiPattern,
+    /// 招式与阶段进度
+    pub behavior: EnemyBehavior,
     /// 攻击力加成
     pub strength: i32,
     /// 当前护甲
     pub block: i32,
-    /// 行动轮次（用于 BOSS 固定招式循环）
-    pub turn_count: u32,
-    /// 虚弱层数
-    pub weakness: i32,
-    /// 易伤层数
-    pub vulnerable: i32,
-    pub poison: i32,
+    /// 身上的状态（中毒、虚弱、荆棘……）
+    pub statuses: StatusEffects,
     /// 敌人词缀
     pub affixes: Vec<EnemyAffix>,
     /// [新增] 是否处于“蓄势”状态（下一次攻击伤害翻倍）
@@ -425,7 +516,7 @@ impl Environment {
 }
 
 /// 敌人类型
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum EnemyType {
     /// 嗜血妖狼 - 激进攻击
     DemonicWolf,
@@ -437,138 +528,110 @@ pub enum EnemyType {
     GreatDemon,
 }
 
-/// AI模式配置 - 支持概率选择或固定序列
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AiPattern {
-    pub attack_chance: f32,
-    pub defend_chance: f32,
-    pub buff_chance: f32,
-    pub debuff_chance: f32,
-    pub curse_chance: f32,
-    pub seal_chance: f32,
-    pub damage_range: (i32, i32),
-    pub block_range: (i32, i32),
-    pub buff_range: (i32, i32),
-    /// [新增] 固定招式序列 (如果不为空，则优先按序列循环)
-    pub sequence: Vec<EnemyIntent>,
-    /// [新增] 当前招式进度
-    pub current_step: usize,
-}
-
-impl AiPattern {
-    pub fn new_random(
-        attack: f32, defend: f32, buff: f32, 
-        damage: (i32, i32), block: (i32, i32),
-    ) -> Self {
-        Self {
-            attack_chance: attack,
-            defend_chance: defend,
-            buff_chance: buff,
-            debuff_chance: 0.0,
-            curse_chance: 0.0,
-            seal_chance: 0.0,
-            damage_range: damage,
-            block_range: block,
-            buff_range: (1, 3),
-            sequence: Vec::new(),
-            current_step: 0,
-        }
-    }
-
-    pub fn demonic_wolf() -> Self {
-        Self {
-            attack_chance: 0.7, defend_chance: 0.1, buff_chance: 0.2, debuff_chance: 0.0,
-            curse_chance: 0.0, seal_chance: 0.0,
-            damage_range: (8, 12), block_range: (3, 5), buff_range: (1, 3),
-            sequence: Vec::new(),
-            current_step: 0,
+impl EnemyType {
+    /// 妖物种类名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            EnemyType::DemonicWolf => "嗜血妖狼",
+            EnemyType::PoisonSpider => "剧毒蛛",
+            EnemyType::CursedSpirit => "怨灵",
+            EnemyType::GreatDemon => "筑基大妖",
         }
     }
+}
 
-    pub fn poison_spider() -> Self {
+/// 妖物行为状态（招式、择招规则与阶段定义见 `assets/data/enemies.ron`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnemyBehavior {
+    /// 妖物定义 id
+    pub definition: String,
+    /// 当前阶段（只进不退）
+    pub phase: usize,
+    /// 固定序列的进度
+    pub step: usize,
+    /// 上一次施展的招式
+    pub last_move: Option<String>,
+    /// 上一招已连续施展的次数
+    pub repeats: u32,
+    /// 词缀对攻击招式权重的倍率
+    pub attack_bias: f32,
+    /// 词缀对防御招式权重的倍率
+    pub defend_bias: f32,
+}
+
+impl EnemyBehavior {
+    pub fn new(definition: &EnemyDefinition) -> Self {
         Self {
-            attack_chance: 0.3, defend_chance: 0.2, buff_chance: 0.0, debuff_chance: 0.3,
-            curse_chance: 0.0, seal_chance: 0.2, 
-            damage_range: (5, 8), block_range: (4, 6), buff_range: (0, 0),
-            sequence: Vec::new(),
-            current_step: 0,
+            definition: definition.id.clone(),
+            phase: 0,
+            step: 0,
+            last_move: None,
+            repeats: 0,
+            attack_bias: 1.0,
+            defend_bias: 1.0,
+        }
+    }
+
+    /// 按道行推进阶段，返回新进入的阶段
+    pub fn advance_phase(&mut self, definition: &EnemyDefinition, hp: i32, max_hp: i32) -> Option<usize> {
+        let reached = definition.phases.iter().rposition(|p| p.reached(hp, max_hp)).unwrap_or(0);
+        if reached > self.phase {
+            self.phase = reached;
+            self.step = 0;
+            Some(reached)
+        } else {
+            None
         }
     }
 
-    pub fn cursed_spirit() -> Self {
-        Self {
-            attack_chance: 0.2, defend_chance: 0.2, buff_chance: 0.0, debuff_chance: 0.2,
-            curse_chance: 0.4, seal_chance: 0.0,
-            damage_range: (10, 15), block_range: (5, 10), buff_range: (0, 0),
-            sequence: Vec::new(),
-            current_step: 0,
+    /// 按当前阶段的择招方式选出下一招
+    pub fn next_move<'a>(&mut self, definition: &'a EnemyDefinition, rng: &mut impl rand::Rng) -> &'a EnemyMove {
+        let phase = &definition.phases[self.phase.min(definition.phases.len() - 1)];
+        let chosen = match &phase.pattern {
+            MovePattern::Sequence(ids) => {
+                let id = &ids[self.step % ids.len()];
+                self.step = (self.step + 1) % ids.len();
+                definition.find_move(id)
+            }
+            MovePattern::Weighted(ids) => {
+                let candidates: Vec<&EnemyMove> = ids.iter().filter_map(|id| definition.find_move(id)).collect();
+                let allowed: Vec<&EnemyMove> = candidates.iter().copied().filter(|m| !self.exhausted(m)).collect();
+                let pool = if allowed.is_empty() { candidates } else { allowed };
+                let total: f32 = pool.iter().map(|m| self.weight_of(m)).sum();
+                if total <= 0.0 {
+                    pool.get(rng.gen_range(0..pool.len())).copied()
+                } else {
+                    let mut roll = rng.gen::<f32>() * total;
+                    pool.iter().copied().find(|m| {
+                        roll -= self.weight_of(m);
+                        roll < 0.0
+                    }).or(pool.last().copied())
+                }
+            }
         }
-    }
+        .expect("解析时已校验阶段引用的招式");
 
-    pub fn great_demon() -> Self {
-        // Boss 采用固定序列
-        Self {
-            attack_chance: 0.5, defend_chance: 0.2, buff_chance: 0.1, debuff_chance: 0.1,
-            curse_chance: 0.05, seal_chance: 0.05,
-            damage_range: (12, 18), block_range: (6, 10), buff_range: (3, 5),
-            sequence: vec![
-                EnemyIntent::Attack { damage: 15 },      // 1. 试探
-                EnemyIntent::Defend { block: 12 },       // 2. 蓄势 (获得护甲)
-                EnemyIntent::Attack { damage: 28 },      // 3. 破魔斩 (重击)
-                EnemyIntent::Wait,                       // 4. 喘息
-            ],
-            current_step: 0,
+        if self.last_move.as_deref() == Some(chosen.id.as_str()) {
+            self.repeats += 1;
+        } else {
+            self.last_move = Some(chosen.id.clone());
+            self.repeats = 1;
         }
+        chosen
     }
 
-    pub fn from_enemy_type(enemy_type: EnemyType) -> Self {
-        match enemy_type {
-            EnemyType::DemonicWolf => Self::demonic_wolf(),
-            EnemyType::PoisonSpider => Self::poison_spider(),
-            EnemyType::CursedSpirit => Self::cursed_spirit(),
-            EnemyType::GreatDemon => Self::great_demon(),
-        }
+    /// 该招式是否已达连续施展上限
+    fn exhausted(&self, candidate: &EnemyMove) -> bool {
+        candidate.max_repeats.is_some_and(|limit| {
+            self.last_move.as_deref() == Some(candidate.id.as_str()) && self.repeats >= limit
+        })
     }
 
-    /// 获取下一步意图
-    pub fn next_intent(&mut self, roll: f32, strength: i32) -> EnemyIntent {
-        if !self.sequence.is_empty() {
-            let mut intent = self.sequence[self.current_step].clone();
-            // 应用当前的攻击力加成
-            if let EnemyIntent::Attack { ref mut damage } = intent {
-                *damage += strength;
-            }
-            self.current_step = (self.current_step + 1) % self.sequence.len();
-            intent
-        } else {
-            // 原有的概率逻辑
-            if roll < self.attack_chance {
-                use rand::Rng;
-                let mut rng = rand::thread_rng();
-                let base_damage = rng.gen_range(self.damage_range.0..=self.damage_range.1);
-                EnemyIntent::Attack { damage: base_damage + strength }
-            } else if roll < self.attack_chance + self.defend_chance {
-                use rand::Rng;
-                let mut rng = rand::thread_rng();
-                let block = rng.gen_range(self.block_range.0..=self.block_range.1);
-                EnemyIntent::Defend { block }
-            } else if roll < self.attack_chance + self.defend_chance + self.buff_chance {
-                use rand::Rng;
-                let mut rng = rand::thread_rng();
-                let strength_gain = rng.gen_range(self.buff_range.0..=self.buff_range.1);
-                EnemyIntent::Buff { strength: strength_gain }
-            } else if roll < self.attack_chance + self.defend_chance + self.buff_chance + self.debuff_chance {
-                EnemyIntent::Debuff { poison: 2, weakness: 1 }
-            } else if roll < self.attack_chance + self.defend_chance + self.buff_chance + self.debuff_chance + self.curse_chance {
-                EnemyIntent::Curse { card_id: 500 }
-            } else if roll < self.attack_chance + self.defend_chance + self.buff_chance + self.debuff_chance + self.curse_chance + self.seal_chance {
-                use rand::Rng;
-                let mut rng = rand::thread_rng();
-                let slot = rng.gen_range(0..5);
-                EnemyIntent::Seal { slot_index: slot, duration: 2 }
-            } else {
-                EnemyIntent::Attack { damage: self.damage_range.0 + strength }
-            }
+    fn weight_of(&self, candidate: &EnemyMove) -> f32 {
+        match candidate.action {
+            MoveAction::Attack { .. } => candidate.weight * self.attack_bias,
+            MoveAction::Defend { .. } => candidate.weight * self.defend_bias,
+            _ => candidate.weight,
         }
     }
 }
@@ -576,84 +639,74 @@ impl AiPattern {
 impl Enemy {
     /// 创建新敌人（默认嗜血妖狼类型）
     pub fn new(id: u32, name: impl Into<String>, hp: i32) -> Self {
-        let enemy_type = EnemyType::DemonicWolf;
-        let ai_pattern = AiPattern::from_enemy_type(enemy_type);
-        Self {
-            id,
-            name: name.into(),
-            enemy_type,
-            hp,
-            max_hp: hp,
-            intent: EnemyIntent::Wait,
-            ai_pattern,
-            strength: 0,
-            block: 0,
-            turn_count: 0,
-            weakness: 0,
-            vulnerable: 0,
-            poison: 0,
-            affixes: Vec::new(),
-            is_charged: false,
-        }
+        Self::with_type(id, name, hp, EnemyType::DemonicWolf)
     }
 
-    /// 创建指定类型的敌人
+    /// 创建指定类型的敌人（采用图谱中该外形的首个妖物定义）
     pub fn with_type(id: u32, name: impl Into<String>, hp: i32, enemy_type: EnemyType) -> Self {
-        let ai_pattern = AiPattern::from_enemy_type(enemy_type);
+        Self::from_definition(id, name, hp, EnemyRoster::builtin().default_for(enemy_type))
+    }
+
+    /// 按妖物定义创建敌人
+    pub fn from_definition(id: u32, name: impl Into<String>, hp: i32, definition: &EnemyDefinition) -> Self {
         Self {
             id,
             name: name.into(),
-            enemy_type,
+            enemy_type: definition.enemy_type,
             hp,
             max_hp: hp,
             intent: EnemyIntent::Wait,
-            ai_pattern,
+            behavior: EnemyBehavior::new(definition),
             strength: 0,
             block: 0,
-            turn_count: 0,
-            weakness: 0,
-            vulnerable: 0,
-            poison: 0,
+            statuses: StatusEffects::default(),
             affixes: Vec::new(),
             is_charged: false,
         }
     }
 
+    /// 妖物定义
+    pub fn definition(&self) -> Option<&'static EnemyDefinition> {
+        EnemyRoster::builtin().get(&self.behavior.definition)
+    }
+
+    /// 当前阶段
+    pub fn phase(&self) -> Option<&'static EnemyPhase> {
+        self.definition().and_then(|d| d.phases.get(self.behavior.phase))
+    }
+
+    /// 当前阶段的表现钩子
+    pub fn phase_visuals(&self) -> PhaseVisuals {
+        self.phase().map(|p| p.visuals).unwrap_or_default()
+    }
+
+    /// 最近一次选定的招式
+    pub fn current_move(&self) -> Option<&'static EnemyMove> {
+        let id = self.behavior.last_move.as_deref()?;
+        self.definition()?.find_move(id)
+    }
+
     /// 计算实际造成的伤害 (考虑虚弱)
     pub fn calculate_outgoing_damage(&self, base_amount: i32) -> i32 {
         self.calculate_outgoing_damage_with_env(base_amount, None)
     }
 
     pub fn calculate_outgoing_damage_with_env(&self, base_amount: i32, environment: Option<&Environment>) -> i32 {
-        let mut damage = if self.weakness > 0 {
-            (base_amount as f32 * 0.75) as i32
-        } else {
-            base_amount
-        };
-
+        let mut env_modifier = environment.map(|e| e.damage_modifier).unwrap_or(1.0);
         // 应用蓄势加成 (翻倍)
         if self.is_charged {
-            damage *= 2;
-        }
-
-        if let Some(env) = environment {
-            (damage as f32 * env.damage_modifier) as i32
-        } else {
-            damage
+            env_modifier *= 2.0;
         }
+        self.statuses.modifiers(StatusRegistry::builtin(), |d| &d.outgoing_damage).apply(base_amount, env_modifier)
     }
 
-    /// 计算实际受到的伤害 (考虑易伤)
+    /// 计算实际受到的伤害 (考虑状态修正)
     pub fn calculate_incoming_damage(&self, base_amount: i32) -> i32 {
         self.calculate_incoming_damage_with_env(base_amount, None)
     }
 
     pub fn calculate_incoming_damage_with_env(&self, base_amount: i32, _environment: Option<&Environment>) -> i32 {
-        if self.vulnerable > 0 {
-            (base_amount as f32 * 1.5) as i32
-        } else {
-            base_amount
-        }
+        self.statuses.modifiers(StatusRegistry::builtin(), |d| &d.incoming_damage).apply(base_amount, 1.0)
     }
 
     pub fn take_damage(&mut self, amount: i32) {
@@ -693,27 +746,19 @@ impl Enemy {
         self.hp <= 0
     }
 
-    /// 使用AI选择新的意图
-    pub fn choose_new_intent(&mut self) {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-
-        // 如果是二阶段 Boss 且血量过低，切换至狂暴序列
-        if self.enemy_type == EnemyType::GreatDemon && self.hp < self.max_hp / 2 {
-            // 检查是否已经切换过序列 (通过序列第一个招式的伤害值来判断，或者检查长度)
-            let is_already_rage = self.ai_pattern.sequence.len() == 3;
-            if !is_already_rage {
-                 self.ai_pattern.sequence = vec![
-                    EnemyIntent::Attack { damage: 35 }, 
-                    EnemyIntent::Buff { strength: 8 },                 
-                    EnemyIntent::Attack { damage: 25 }, 
-                 ];
-                 self.ai_pattern.current_step = 0;
-                 info!("🔥 {} 进入了【狂暴二阶段】！", self.name);
-            }
+    /// 使用AI选择新的意图（道行跌破阈值时先进入下一阶段），返回新进入的阶段
+    pub fn choose_new_intent(&mut self, rng: &mut impl rand::Rng) -> Option<usize> {
+        let Some(definition) = self.definition() else {
+            self.intent = EnemyIntent::Wait;
+            return None;
+        };
+        let entered = self.behavior.advance_phase(definition, self.hp, self.max_hp);
+        if let Some(phase) = entered {
+            info!("🔥 {} 进入了【{}】！", self.name, definition.phases[phase].name);
         }
-
-        self.intent = self.ai_pattern.next_intent(rng.gen(), self.strength);
+        let chosen = self.behavior.next_move(definition, rng);
+        self.intent = chosen.action.roll(self.strength, rng);
+        entered
     }
 
     /// 执行意图（敌人回合行动）
@@ -726,8 +771,8 @@ impl Enemy {
             EnemyIntent::Defend { block } => {
                 // 获得护甲
                 self.block += block;
-                // 如果是大妖 (Boss)，防御即蓄势
-                if self.enemy_type == EnemyType::GreatDemon {
+                // 蓄势类招式：防御同时积蓄下一击
+                if self.current_move().is_some_and(|m| matches!(m.action, MoveAction::Defend { charge: true, .. })) {
                     self.is_charged = true;
                     info!("🛡️ {} 正在蓄势，其势待发！", self.name);
                 }
@@ -759,21 +804,47 @@ impl Enemy {
         }
     }
 
-    /// 回合开始时清理临时效果
-    pub fn start_turn(&mut self) {
+    /// 回合开始时清理临时效果，返回新进入的阶段
+    pub fn start_turn(&mut self, rng: &mut impl rand::Rng) -> Option<usize> {
         // 清空护甲
         self.block = 0;
         // 选择新的意图
-        self.choose_new_intent();
+        self.choose_new_intent(rng)
+    }
+
+    /// 施加状态（按图谱叠加规则）
+    pub fn apply_status(&mut self, id: &str, stacks: i32) {
+        self.statuses.apply(StatusRegistry::builtin(), id, stacks, None);
+    }
+
+    /// 行动前触发状态钩子（中毒、灼烧发作等）
+    pub fn tick_statuses(&mut self) -> Vec<StatusTrigger> {
+        let triggers = self.statuses.on_turn_start(StatusRegistry::builtin());
+        settle_status_triggers(&triggers, &mut self.hp, self.max_hp, &mut self.block);
+        triggers
+    }
+
+    /// 行动结束时触发状态钩子（虚弱、易伤消退等）
+    pub fn decay_statuses(&mut self) -> Vec<StatusTrigger> {
+        let triggers = self.statuses.on_turn_end(StatusRegistry::builtin());
+        settle_status_triggers(&triggers, &mut self.hp, self.max_hp, &mut self.block);
+        triggers
+    }
+
+    /// 被攻击命中后触发状态钩子（反伤由调用方结算）
+    pub fn attacked_statuses(&mut self) -> Vec<StatusTrigger> {
+        let triggers = self.statuses.on_attacked(StatusRegistry::builtin());
+        settle_status_triggers(&triggers, &mut self.hp, self.max_hp, &mut self.block);
+        triggers
     }
 
     /// 应用攻击附带的词缀效果
     pub fn apply_attack_affixes(&self, player: &mut Player) {
         for affix in &self.affixes {
             match affix {
-                EnemyAffix::Fire => player.burn += 3, 
-                EnemyAffix::Poison => player.poison += 2, 
-                EnemyAffix::Ice => player.weakness += 1, 
+                EnemyAffix::Fire => player.apply_status(BURN, 3),
+                EnemyAffix::Poison => player.apply_status(POISON, 2),
+                EnemyAffix::Ice => player.apply_status(WEAKNESS, 1),
                 _ => {} 
             }
         }
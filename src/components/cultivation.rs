@@ -3,8 +3,11 @@
 use bevy::prelude::*;
 use serde::{Serialize, Deserialize};
 
-/// 修仙境界枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+use crate::components::cards::{Card, CardEffect, CardRarity, CardType};
+use crate::components::status::{VULNERABLE, WEAKNESS};
+
+/// 修仙境界枚举（声明顺序即境界高低）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
 pub enum Realm {
     /// 炼气期 (Qi Refining)
     #[default]
@@ -15,6 +18,199 @@ pub enum Realm {
     GoldenCore,
     /// 元婴期 (Nascent Soul)
     NascentSoul,
+    /// 化神期 (Spirit Transformation)
+    SpiritTransformation,
+    /// 渡劫期 (Tribulation Transcendence)
+    TribulationTranscendence,
+}
+
+impl Realm {
+    /// 全部境界（由低到高）
+    pub const ALL: [Realm; 6] = [
+        Realm::QiRefining,
+        Realm::FoundationEstablishment,
+        Realm::GoldenCore,
+        Realm::NascentSoul,
+        Realm::SpiritTransformation,
+        Realm::TribulationTranscendence,
+    ];
+
+    /// 境界名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Realm::QiRefining => "炼气期",
+            Realm::FoundationEstablishment => "筑基期",
+            Realm::GoldenCore => "金丹期",
+            Realm::NascentSoul => "元婴期",
+            Realm::SpiritTransformation => "化神期",
+            Realm::TribulationTranscendence => "渡劫期",
+        }
+    }
+
+    /// 下一境界（渡劫期已是巅峰）
+    pub fn next(&self) -> Option<Realm> {
+        match self {
+            Realm::QiRefining => Some(Realm::FoundationEstablishment),
+            Realm::FoundationEstablishment => Some(Realm::GoldenCore),
+            Realm::GoldenCore => Some(Realm::NascentSoul),
+            Realm::NascentSoul => Some(Realm::SpiritTransformation),
+            Realm::SpiritTransformation => Some(Realm::TribulationTranscendence),
+            Realm::TribulationTranscendence => None,
+        }
+    }
+
+    /// 本境界领悟的被动（炼气期尚无）
+    pub fn passive(&self) -> Option<RealmPassive> {
+        match self {
+            Realm::QiRefining => None,
+            Realm::FoundationEstablishment => Some(RealmPassive::SolidFoundation),
+            Realm::GoldenCore => Some(RealmPassive::GoldenRetention),
+            Realm::NascentSoul => Some(RealmPassive::SpiritClone),
+            Realm::SpiritTransformation => Some(RealmPassive::DivineSense),
+            Realm::TribulationTranscendence => Some(RealmPassive::TemperedBody),
+        }
+    }
+
+    /// 被动是否已领悟（低境界的被动在突破后仍然保留）
+    pub fn has_passive(&self, passive: RealmPassive) -> bool {
+        Realm::ALL.iter().filter(|r| *r <= self).any(|r| r.passive() == Some(passive))
+    }
+
+    /// 已领悟的全部被动（由低到高）
+    pub fn passives(&self) -> impl Iterator<Item = RealmPassive> + '_ {
+        Realm::ALL.iter().filter(move |r| *r <= self).filter_map(|r| r.passive())
+    }
+
+    /// 本境界的神通（每场战斗限用一次，炼气期尚无）
+    pub fn divine_art(&self) -> Option<DivineArt> {
+        let art = match self {
+            Realm::QiRefining => return None,
+            Realm::FoundationEstablishment => DivineArt {
+                name: "聚气诀",
+                description: "回复 2 点灵力并抽 1 张牌",
+                effects: vec![CardEffect::GainEnergy { amount: 2 }, CardEffect::DrawCards { amount: 1 }],
+            },
+            Realm::GoldenCore => DivineArt {
+                name: "金丹护体",
+                description: "获得 20 点护盾",
+                effects: vec![CardEffect::GainBlock { amount: 20 }],
+            },
+            Realm::NascentSoul => DivineArt {
+                name: "元婴出窍",
+                description: "对所有敌人造成 18 点伤害",
+                effects: vec![CardEffect::DealAoEDamage { amount: 18 }],
+            },
+            Realm::SpiritTransformation => DivineArt {
+                name: "神念镇压",
+                description: "使所有敌人虚弱 2 层、易伤 2 层，并抽 2 张牌",
+                effects: vec![
+                    CardEffect::ApplyStatus { status: WEAKNESS.into(), count: 2 },
+                    CardEffect::ApplyStatus { status: VULNERABLE.into(), count: 2 },
+                    CardEffect::DrawCards { amount: 2 },
+                ],
+            },
+            Realm::TribulationTranscendence => DivineArt {
+                name: "引劫雷",
+                description: "对所有敌人造成 40 点伤害",
+                effects: vec![CardEffect::DealAoEDamage { amount: 40 }],
+            },
+        };
+        Some(art)
+    }
+
+    /// 冲击本境界时的雷劫规则
+    pub fn tribulation(&self) -> TribulationRules {
+        let (waves, cloud_hp, strike_bonus, insight_loss_percent, max_hp_loss) = match self {
+            Realm::QiRefining | Realm::FoundationEstablishment => (3, 60, 0, 50, 5),
+            Realm::GoldenCore => (4, 100, 2, 50, 8),
+            Realm::NascentSoul => (4, 150, 4, 50, 10),
+            // 化神雷劫更密，渡劫期九重天雷接连落下
+            Realm::SpiritTransformation => (5, 220, 6, 60, 15),
+            Realm::TribulationTranscendence => (6, 300, 8, 75, 20),
+        };
+        TribulationRules { waves, cloud_hp, strike_bonus, insight_loss_percent, max_hp_loss }
+    }
+}
+
+/// 境界被动
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RealmPassive {
+    /// 筑基·道基稳固：每场战斗开始时获得护盾
+    SolidFoundation,
+    /// 金丹·金丹留转：回合结束时保留 1 张手牌
+    GoldenRetention,
+    /// 元婴·元婴分身：每回合打出的第一张功法由分身再施展一次
+    SpiritClone,
+    /// 化神·神识通明：每回合多抽 1 张牌
+    DivineSense,
+    /// 渡劫·劫雷淬体：受到的攻击伤害降低
+    TemperedBody,
+}
+
+impl RealmPassive {
+    /// 道基稳固的开战护盾
+    pub const FOUNDATION_BLOCK: i32 = 6;
+    /// 劫雷淬体的减伤
+    pub const TEMPERED_REDUCTION: i32 = 3;
+
+    /// 被动名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            RealmPassive::SolidFoundation => "道基稳固",
+            RealmPassive::GoldenRetention => "金丹留转",
+            RealmPassive::SpiritClone => "元婴分身",
+            RealmPassive::DivineSense => "神识通明",
+            RealmPassive::TemperedBody => "劫雷淬体",
+        }
+    }
+
+    /// 被动说明
+    pub fn description(&self) -> String {
+        match self {
+            RealmPassive::SolidFoundation => format!("每场战斗开始时获得 {} 点护盾", Self::FOUNDATION_BLOCK),
+            RealmPassive::GoldenRetention => "回合结束时保留 1 张手牌".to_string(),
+            RealmPassive::SpiritClone => "每回合打出的第一张功法，元婴分身再施展一次".to_string(),
+            RealmPassive::DivineSense => "每回合多抽 1 张牌".to_string(),
+            RealmPassive::TemperedBody => format!("受到的攻击伤害 -{}", Self::TEMPERED_REDUCTION),
+        }
+    }
+}
+
+/// 境界神通：每场战斗可施展一次，不耗灵力
+#[derive(Debug, Clone, PartialEq)]
+pub struct DivineArt {
+    pub name: &'static str,
+    pub description: &'static str,
+    /// 按功法效果结算（单体效果取首个存活敌人）
+    pub effects: Vec<CardEffect>,
+}
+
+impl DivineArt {
+    /// 以功法形式呈现（供出牌演出复用），含攻伐效果者视作攻伐功法
+    pub fn as_card(&self) -> Card {
+        let mut card = Card::new(0, self.name, CardType::Skill, 0, self.effects.clone(), CardRarity::Special, "textures/cards/special.png");
+        if card.attack_effect().is_some() {
+            card.card_type = CardType::Attack;
+        }
+        card
+    }
+}
+
+/// 雷劫规则（冲击某一境界时劫云的强度与失败代价）
+///
+/// 天雷的招式序列见 `assets/data/enemies.ron` 中的劫云定义，此处只决定其强度。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TribulationRules {
+    /// 需撑过的劫波数（劫云行动的回合数）
+    pub waves: u32,
+    /// 劫云道行（击散即提前渡过）
+    pub cloud_hp: i32,
+    /// 每道天雷的额外威力（计作劫云攻击力）
+    pub strike_bonus: i32,
+    /// 渡劫失败时散去的感悟比例（百分比）
+    pub insight_loss_percent: u32,
+    /// 渡劫失败时折损的道行上限
+    pub max_hp_loss: i32,
 }
 
 /// 玩家修炼进度组件
@@ -47,12 +243,14 @@ impl Cultivation {
             Realm::FoundationEstablishment => 250,
             Realm::GoldenCore => 600,
             Realm::NascentSoul => 1500,
+            Realm::SpiritTransformation => 3000,
+            Realm::TribulationTranscendence => 6000,
         }
     }
 
-    /// 检查是否满足突破条件
+    /// 检查是否满足突破条件（巅峰境界无从突破）
     pub fn can_breakthrough(&self) -> bool {
-        self.insight >= self.get_threshold()
+        self.realm.next().is_some() && self.insight >= self.get_threshold()
     }
 
     /// 执行突破
@@ -63,14 +261,7 @@ impl Cultivation {
         }
 
         // 提升境界
-        let next_realm = match self.realm {
-            Realm::QiRefining => Some(Realm::FoundationEstablishment),
-            Realm::FoundationEstablishment => Some(Realm::GoldenCore),
-            Realm::GoldenCore => Some(Realm::NascentSoul),
-            Realm::NascentSoul => None, // 已达巅峰
-        };
-
-        if let Some(new_realm) = next_realm {
+        if let Some(new_realm) = self.realm.next() {
             // 扣除感悟值
             self.insight -= self.get_threshold();
             self.realm = new_realm;
@@ -87,6 +278,8 @@ impl Cultivation {
             Realm::FoundationEstablishment => 50,
             Realm::GoldenCore => 150,
             Realm::NascentSoul => 300,
+            Realm::SpiritTransformation => 450,
+            Realm::TribulationTranscendence => 600,
         }
     }
 
@@ -97,6 +290,8 @@ impl Cultivation {
             Realm::FoundationEstablishment => 1, // 筑基期 +1 能量
             Realm::GoldenCore => 2,
             Realm::NascentSoul => 3,
+            Realm::SpiritTransformation => 3,
+            Realm::TribulationTranscendence => 4,
         }
     }
 
@@ -107,6 +302,8 @@ impl Cultivation {
             Realm::FoundationEstablishment => 2, // 筑基期可携带 2 个法宝
             Realm::GoldenCore => 3,
             Realm::NascentSoul => 5,
+            Realm::SpiritTransformation => 6,
+            Realm::TribulationTranscendence => 8,
         }
     }
 }
@@ -1,9 +1,10 @@
 //! 对话系统组件
 
 use bevy::prelude::*;
+use serde::Deserialize;
 
 /// 单行对话数据
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct DialogueLine {
     /// 说话者名称
     pub speaker: String,
@@ -54,6 +55,11 @@ impl Dialogue {
         }
     }
 
+    /// 是否已停在最后一行
+    pub fn is_last_line(&self) -> bool {
+        self.index + 1 >= self.lines.len()
+    }
+
     /// 是否结束
     pub fn is_finished(&self) -> bool {
         self.finished
@@ -4,6 +4,7 @@ use bevy::prelude::*;
 use serde::{Serialize, Deserialize};
 use rand::Rng;
 use rand::prelude::SliceRandom;
+use std::collections::HashMap;
 
 // ============================================================================
 // 地图组件
@@ -47,6 +48,34 @@ pub enum NodeType {
     Unknown,
 }
 
+impl NodeType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Normal => "妖物",
+            Self::Elite => "精英",
+            Self::Boss => "首领",
+            Self::Rest => "洞府",
+            Self::Shop => "坊市",
+            Self::Event => "机缘",
+            Self::Treasure => "宝箱",
+            Self::Unknown => "未知",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Normal => "寻常妖物拦路，胜之可得功法",
+            Self::Elite => "强横妖物镇守，凶险而多宝",
+            Self::Boss => "本界镇守首领，伏诛方可踏入下一界",
+            Self::Rest => "闭关调息，或精进一部功法",
+            Self::Shop => "以灵石购置功法与法宝",
+            Self::Event => "福祸难料的奇遇",
+            Self::Treasure => "开启可得灵石与法宝",
+            Self::Unknown => "踏入方知：机缘、妖物、坊市或宝箱",
+        }
+    }
+}
+
 // ============================================================================
 // 地图进度系统
 // ============================================================================
@@ -62,34 +91,49 @@ pub struct MapProgress {
     pub current_layer: u32,
     /// 游戏是否完成
     pub game_completed: bool,
+    /// 所在大境（三界舆图中的序号）
+    pub act: u32,
+    /// 未知节点的揭示概率
+    pub unknown_odds: UnknownOdds,
 }
 
 impl MapProgress {
     /// 创建新地图进度
-    pub fn new(config: &MapConfig) -> Self {
-        let nodes = generate_map_nodes(config, 0);
+    pub fn new(config: &MapConfig, rng: &mut impl Rng) -> Self {
+        let nodes = generate_map_nodes(config, 0, rng);
         let mut progress = Self {
             nodes,
             current_node_id: None,
             current_layer: 0,
             game_completed: false,
+            act: 0,
+            unknown_odds: UnknownOdds::default(),
         };
         progress.refresh_unlocks();
         progress
     }
 
     /// 从存档恢复并刷新解锁
-    pub fn from_save(nodes: Vec<MapNode>, current_node_id: Option<u32>, current_layer: u32) -> Self {
+    pub fn from_save(nodes: Vec<MapNode>, current_node_id: Option<u32>, current_layer: u32, act: u32, unknown_odds: UnknownOdds) -> Self {
         let mut progress = Self {
             nodes,
             current_node_id,
             current_layer,
             game_completed: false,
+            act,
+            unknown_odds,
         };
         progress.refresh_unlocks();
         progress
     }
 
+    /// 踏入下一界：按新大境的地图参数重新生成地图（未知节点的概率随之复原）
+    pub fn advance_act(&mut self, config: &MapConfig, rng: &mut impl Rng) {
+        let act = self.act + 1;
+        *self = Self::new(config, rng);
+        self.act = act;
+    }
+
     /// 获取当前节点
     pub fn get_current_node(&self) -> Option<&MapNode> {
         self.current_node_id.and_then(|id| self.nodes.iter().find(|n| n.id == id))
@@ -163,6 +207,12 @@ impl MapProgress {
             .unwrap_or(false)
     }
 
+    pub fn is_at_elite(&self) -> bool {
+        self.get_current_node()
+            .map(|n| n.node_type == NodeType::Elite)
+            .unwrap_or(false)
+    }
+
     pub fn is_boss_defeated(&self) -> bool {
         self.nodes.iter().any(|n| n.node_type == NodeType::Boss && n.completed)
     }
@@ -175,7 +225,86 @@ impl MapProgress {
 impl Default for MapProgress {
     fn default() -> Self {
         let config = MapConfig::default();
-        Self::new(&config)
+        Self::new(&config, &mut rand::thread_rng())
+    }
+}
+
+/// 未知节点的揭示概率（百分比）
+///
+/// 每次揭示后，揭示出的结果（机缘除外）概率复原为基础值，其余结果的概率各自累加一次基础值
+/// （揭示为机缘时三者都累加）；剩余的概率都归于机缘。越久未遇到的结果越可能出现。
+/// 三者之和封顶于 100，超出部分依次从坊市、宝箱中扣除，妖物的概率不受影响。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnknownOdds {
+    pub combat: u32,
+    pub treasure: u32,
+    pub shop: u32,
+}
+
+impl UnknownOdds {
+    const BASE_COMBAT: u32 = 10;
+    const BASE_TREASURE: u32 = 2;
+    const BASE_SHOP: u32 = 3;
+    const MAX_TOTAL: u32 = 100;
+
+    /// 揭示一个未知节点，返回其实际类型（寻常遭遇 / 宝箱 / 坊市 / 机缘）并更新概率
+    pub fn roll(&mut self, rng: &mut impl Rng) -> NodeType {
+        let roll = rng.gen_range(0..100);
+        let outcome = if roll < self.combat {
+            NodeType::Normal
+        } else if roll < self.combat + self.treasure {
+            NodeType::Treasure
+        } else if roll < self.combat + self.treasure + self.shop {
+            NodeType::Shop
+        } else {
+            NodeType::Event
+        };
+
+        let shift = |chance: &mut u32, base: u32, rolled: bool| {
+            *chance = if rolled { base } else { *chance + base };
+        };
+        shift(&mut self.combat, Self::BASE_COMBAT, outcome == NodeType::Normal);
+        shift(&mut self.treasure, Self::BASE_TREASURE, outcome == NodeType::Treasure);
+        shift(&mut self.shop, Self::BASE_SHOP, outcome == NodeType::Shop);
+
+        let room = Self::MAX_TOTAL.saturating_sub(self.combat);
+        self.treasure = self.treasure.min(room);
+        self.shop = self.shop.min(room - self.treasure);
+        outcome
+    }
+}
+
+impl Default for UnknownOdds {
+    fn default() -> Self {
+        Self {
+            combat: Self::BASE_COMBAT,
+            treasure: Self::BASE_TREASURE,
+            shop: Self::BASE_SHOP,
+        }
+    }
+}
+
+/// 各类节点的出现权重（固定层之外的节点按此抽取）
+#[derive(Debug, Clone, Copy)]
+pub struct NodeWeights {
+    pub normal: f32,
+    pub event: f32,
+    pub shop: f32,
+    pub rest: f32,
+    pub elite: f32,
+    pub unknown: f32,
+}
+
+impl Default for NodeWeights {
+    fn default() -> Self {
+        Self {
+            normal: 0.43,
+            event: 0.14,
+            shop: 0.06,
+            rest: 0.12,
+            elite: 0.15,
+            unknown: 0.10,
+        }
     }
 }
 
@@ -184,48 +313,168 @@ pub struct MapConfig {
     pub layers: u32,
     pub nodes_per_layer: u32,
     pub node_spacing: f32,
+    /// 寻常遭遇化为精英的概率（百分比，由天劫重数决定）
+    pub elite_percent: u32,
+    /// 节点类型权重
+    pub weights: NodeWeights,
+    /// 精英最早出现的层（从 0 计：3 即第 4 层，精英只在前三层之后出现）
+    pub elite_min_layer: u32,
+    /// 整层皆为宝箱的层，None 表示不设
+    pub treasure_layer: Option<u32>,
+    /// 每条路线必经一座坊市的层区间（闭区间），None 表示不作保证
+    pub shop_window: Option<(u32, u32)>,
+    /// Boss 前一层是否整层设为洞府
+    pub rest_before_boss: bool,
 }
 
 impl Default for MapConfig {
     fn default() -> Self {
+        Self::for_layers(10)
+    }
+}
+
+impl MapConfig {
+    /// 指定层数的地图：宝箱层居中，坊市区间覆盖中段
+    pub fn for_layers(layers: u32) -> Self {
         Self {
-            layers: 10,
+            layers,
             nodes_per_layer: 4,
             node_spacing: 150.0,
+            elite_percent: 0,
+            weights: NodeWeights::default(),
+            elite_min_layer: 3,
+            treasure_layer: Some(layers / 2),
+            shop_window: Some((layers * 3 / 10, layers.saturating_sub(3))),
+            rest_before_boss: true,
         }
     }
+
+    fn boss_layer(&self) -> u32 {
+        self.layers - 1
+    }
+
+    /// 由层规则直接决定的节点类型
+    fn fixed_type(&self, layer: u32) -> Option<NodeType> {
+        if layer == self.boss_layer() {
+            Some(NodeType::Boss)
+        } else if self.rest_before_boss && layer + 1 == self.boss_layer() {
+            Some(NodeType::Rest)
+        } else if self.treasure_layer == Some(layer) {
+            Some(NodeType::Treasure)
+        } else if layer == 0 {
+            // 起点一律为寻常遭遇
+            Some(NodeType::Normal)
+        } else {
+            None
+        }
+    }
+
+    fn in_shop_window(&self, layer: u32) -> bool {
+        self.shop_window.is_some_and(|(from, to)| (from..=to).contains(&layer))
+    }
 }
 
-pub fn generate_map_nodes(config: &MapConfig, _current_layer: u32) -> Vec<MapNode> {
+/// 地图违反的节奏规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MapRuleViolation {
+    /// 一条连线两端是同一种特殊节点（含连续洞府）
+    RepeatedSpecial { from: u32, to: u32, node_type: NodeType },
+    /// 精英出现得过早
+    EarlyElite { node: u32 },
+    /// 宝箱层中混入了其它节点
+    MissingTreasure { node: u32 },
+    /// Boss 之前不是洞府
+    NoRestBeforeBoss { node: u32 },
+    /// 存在一条走到 Boss 都未经过坊市的路线
+    NoShopOnPath { boss: u32 },
+}
+
+impl std::fmt::Display for MapRuleViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::RepeatedSpecial { from, to, node_type } => {
+                write!(f, "节点 {} → {} 连续出现 {:?}", from, to, node_type)
+            }
+            Self::EarlyElite { node } => write!(f, "节点 {} 的精英出现得过早", node),
+            Self::MissingTreasure { node } => write!(f, "宝箱层的节点 {} 不是宝箱", node),
+            Self::NoRestBeforeBoss { node } => write!(f, "Boss 前的节点 {} 不是洞府", node),
+            Self::NoShopOnPath { boss } => write!(f, "存在通往 Boss {} 却不经坊市的路线", boss),
+        }
+    }
+}
+
+/// 同类相连会被视为重复的特殊节点
+fn is_special(node_type: NodeType) -> bool {
+    !matches!(node_type, NodeType::Normal | NodeType::Boss)
+}
+
+/// 检查地图的每一条起点到 Boss 的路线是否都合乎节奏规则
+pub fn validate_map(config: &MapConfig, nodes: &[MapNode]) -> Result<(), MapRuleViolation> {
+    let by_id = |id: u32| nodes.iter().find(|n| n.id == id);
+
+    for node in nodes {
+        let layer = node.layer();
+        if node.node_type == NodeType::Elite && layer < config.elite_min_layer {
+            return Err(MapRuleViolation::EarlyElite { node: node.id });
+        }
+        if config.treasure_layer == Some(layer) && node.node_type != NodeType::Treasure {
+            return Err(MapRuleViolation::MissingTreasure { node: node.id });
+        }
+        for next in node.next_nodes.iter().filter_map(|&id| by_id(id)) {
+            if is_special(node.node_type) && node.node_type == next.node_type {
+                return Err(MapRuleViolation::RepeatedSpecial {
+                    from: node.id,
+                    to: next.id,
+                    node_type: node.node_type,
+                });
+            }
+            if config.rest_before_boss
+                && next.node_type == NodeType::Boss
+                && node.node_type != NodeType::Rest
+            {
+                return Err(MapRuleViolation::NoRestBeforeBoss { node: node.id });
+            }
+        }
+    }
+
+    if config.shop_window.is_none() {
+        return Ok(());
+    }
+    let covered = shop_coverage(config, nodes);
+    match nodes.iter().find(|n| n.node_type == NodeType::Boss && !covered[&n.id]) {
+        Some(boss) => Err(MapRuleViolation::NoShopOnPath { boss: boss.id }),
+        None => Ok(()),
+    }
+}
+
+/// 逐层推进：某节点「所有来路都已经过坊市」当且仅当它本身是区间内的坊市，或其全部前驱皆如此
+fn shop_coverage(config: &MapConfig, nodes: &[MapNode]) -> HashMap<u32, bool> {
+    let mut covered = HashMap::new();
+    let mut ordered: Vec<&MapNode> = nodes.iter().collect();
+    ordered.sort_by_key(|n| n.position);
+    for node in ordered {
+        let own = node.node_type == NodeType::Shop && config.in_shop_window(node.layer());
+        let mut preds = nodes.iter().filter(|p| p.next_nodes.contains(&node.id)).peekable();
+        let inherited = preds.peek().is_some() && preds.all(|p| covered.get(&p.id).copied().unwrap_or(false));
+        covered.insert(node.id, own || inherited);
+    }
+    covered
+}
+
+/// 重抽节点类型的次数上限，超出后强行铺设坊市层
+const MAX_TYPE_ATTEMPTS: u32 = 32;
+
+pub fn generate_map_nodes(config: &MapConfig, _current_layer: u32, rng: &mut impl Rng) -> Vec<MapNode> {
     let mut nodes = Vec::new();
     let mut id = 0;
 
     for layer in 0..config.layers {
         for node_idx in 0..config.nodes_per_layer {
-            let unlocked = layer == 0;
-            use rand::Rng;
-            let mut rng = rand::thread_rng();
-            let rand_val = rng.gen_range(0..100);
-
-            let node_type = if layer == config.layers - 1 {
-                NodeType::Boss 
-            } else if layer == config.layers - 2 {
-                NodeType::Elite
-            } else if rand_val < 15 {
-                NodeType::Event 
-            } else if node_idx % 7 == 0 {
-                NodeType::Shop
-            } else if node_idx % 3 == 0 {
-                NodeType::Rest
-            } else {
-                NodeType::Normal
-            };
-
             nodes.push(MapNode {
                 id,
-                node_type,
+                node_type: NodeType::Normal,
                 position: (layer as i32, node_idx as i32),
-                unlocked,
+                unlocked: layer == 0,
                 completed: false,
                 next_nodes: Vec::new(),
             });
@@ -253,9 +502,7 @@ pub fn generate_map_nodes(config: &MapConfig, _current_layer: u32) -> Vec<MapNod
             }
             
             // 随机选择 1-2 个作为后继
-            use rand::seq::SliceRandom;
-            let mut rng = rand::thread_rng();
-            possible_next_indices.shuffle(&mut rng);
+            possible_next_indices.shuffle(rng);
             
             let count = if rng.gen_bool(0.8) { 1 } else { 2 }; // 80% 概率 1 条路，20% 分叉
             let selected_indices = &possible_next_indices[0..count.min(possible_next_indices.len())];
@@ -290,8 +537,7 @@ pub fn generate_map_nodes(config: &MapConfig, _current_layer: u32) -> Vec<MapNod
                     }
                 }
                 
-                let mut rng = rand::thread_rng();
-                if let Some(&prev_id) = prev_candidates.choose(&mut rng) {
+                if let Some(&prev_id) = prev_candidates.choose(rng) {
                     if let Some(prev_node) = nodes.iter_mut().find(|n| n.id == prev_id) {
                         prev_node.next_nodes.push(current_id);
                     }
@@ -300,9 +546,267 @@ pub fn generate_map_nodes(config: &MapConfig, _current_layer: u32) -> Vec<MapNod
         }
     }
 
+    // --- 步骤 3: 按节奏规则分配节点类型 ---
+    for _ in 0..MAX_TYPE_ATTEMPTS {
+        assign_node_types(config, &mut nodes, rng);
+        place_mid_shops(config, &mut nodes, rng);
+        if validate_map(config, &nodes).is_ok() {
+            return nodes;
+        }
+    }
+
+    force_shop_layer(config, &mut nodes);
+    if let Err(violation) = validate_map(config, &nodes) {
+        warn!("【地图生成】地图未能满足节奏规则: {}", violation);
+    }
     nodes
 }
 
+/// 该节点与已定类型的前驱、后继是否会构成重复的特殊节点（生成时节点 id 即下标）
+fn conflicts(nodes: &[MapNode], types: &[Option<NodeType>], index: usize, node_type: NodeType) -> bool {
+    if !is_special(node_type) {
+        return false;
+    }
+    let id = nodes[index].id;
+    let same = |i: usize| types[i] == Some(node_type);
+    nodes[index].next_nodes.iter().any(|&next| same(next as usize))
+        || nodes.iter().enumerate().any(|(i, n)| n.next_nodes.contains(&id) && same(i))
+}
+
+/// 按权重逐层抽取节点类型，固定层优先落定
+fn assign_node_types(config: &MapConfig, nodes: &mut [MapNode], rng: &mut impl Rng) {
+    let mut types: Vec<Option<NodeType>> = nodes.iter().map(|n| config.fixed_type(n.layer())).collect();
+
+    let w = &config.weights;
+    for index in 0..nodes.len() {
+        if types[index].is_some() {
+            continue;
+        }
+        let layer = nodes[index].layer();
+        let candidates = [
+            (NodeType::Event, w.event),
+            (NodeType::Shop, w.shop),
+            (NodeType::Rest, w.rest),
+            (NodeType::Unknown, w.unknown),
+            (NodeType::Elite, if layer >= config.elite_min_layer { w.elite } else { 0.0 }),
+        ];
+        let allowed: Vec<(NodeType, f32)> = std::iter::once((NodeType::Normal, w.normal))
+            .chain(candidates)
+            .filter(|&(t, weight)| weight > 0.0 && !conflicts(nodes, &types, index, t))
+            .collect();
+
+        let mut node_type = allowed
+            .choose_weighted(rng, |&(_, weight)| weight)
+            .map(|&(t, _)| t)
+            .unwrap_or(NodeType::Normal);
+
+        // 天劫修正：无修正时不额外消耗随机数，保证同一道种的地图不变
+        if node_type == NodeType::Normal
+            && layer >= config.elite_min_layer
+            && config.elite_percent > 0
+            && rng.gen_range(0..100) < config.elite_percent
+            && !conflicts(nodes, &types, index, NodeType::Elite)
+        {
+            node_type = NodeType::Elite;
+        }
+        types[index] = Some(node_type);
+    }
+
+    for (node, node_type) in nodes.iter_mut().zip(types) {
+        node.node_type = node_type.unwrap_or(NodeType::Normal);
+    }
+}
+
+/// 在坊市区间内随机挑层，把尚有「未经坊市」来路的节点改为坊市
+fn place_mid_shops(config: &MapConfig, nodes: &mut [MapNode], rng: &mut impl Rng) {
+    let Some((from, to)) = config.shop_window else { return };
+    let mut layers: Vec<u32> = (from..=to).filter(|&l| config.fixed_type(l).is_none()).collect();
+    layers.shuffle(rng);
+
+    for layer in layers {
+        let covered = shop_coverage(config, nodes);
+        let mut types: Vec<Option<NodeType>> = nodes.iter().map(|n| Some(n.node_type)).collect();
+        for index in 0..nodes.len() {
+            let node = &nodes[index];
+            if node.layer() != layer || covered[&node.id] || conflicts(nodes, &types, index, NodeType::Shop) {
+                continue;
+            }
+            types[index] = Some(NodeType::Shop);
+            nodes[index].node_type = NodeType::Shop;
+        }
+    }
+}
+
+/// 兜底：在坊市区间内挑一层整层设为坊市，相邻的坊市降为寻常遭遇
+fn force_shop_layer(config: &MapConfig, nodes: &mut [MapNode]) {
+    let Some((from, to)) = config.shop_window else { return };
+    let Some(layer) = (from..=to).rev().find(|&l| config.fixed_type(l).is_none()) else { return };
+
+    for node in nodes.iter_mut() {
+        let distance = node.layer().abs_diff(layer);
+        if distance == 0 {
+            node.node_type = NodeType::Shop;
+        } else if distance == 1 && node.node_type == NodeType::Shop {
+            node.node_type = NodeType::Normal;
+        }
+    }
+}
+
+// ============================================================================
+// 路线推演
+// ============================================================================
+
+/// 一段路线上的精英、洞府与坊市数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RouteSummary {
+    pub elites: u32,
+    pub rests: u32,
+    pub shops: u32,
+}
+
+impl RouteSummary {
+    fn of_type(node_type: NodeType) -> Self {
+        Self {
+            elites: (node_type == NodeType::Elite) as u32,
+            rests: (node_type == NodeType::Rest) as u32,
+            shops: (node_type == NodeType::Shop) as u32,
+        }
+    }
+
+    /// 统计一串节点（不存在的 id 忽略）
+    pub fn of(nodes: &[MapNode], route: &[u32]) -> Self {
+        route.iter()
+            .filter_map(|&id| nodes.iter().find(|n| n.id == id))
+            .fold(Self::default(), |acc, n| acc.plus(Self::of_type(n.node_type)))
+    }
+
+    fn plus(self, other: Self) -> Self {
+        Self {
+            elites: self.elites + other.elites,
+            rests: self.rests + other.rests,
+            shops: self.shops + other.shops,
+        }
+    }
+}
+
+/// 从某节点通往 Boss 的全部路线：路线条数与各类节点数的上下限（闭区间）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteRange {
+    pub routes: u64,
+    pub elites: (u32, u32),
+    pub rests: (u32, u32),
+    pub shops: (u32, u32),
+}
+
+impl RouteRange {
+    fn single(summary: RouteSummary) -> Self {
+        Self {
+            routes: 1,
+            elites: (summary.elites, summary.elites),
+            rests: (summary.rests, summary.rests),
+            shops: (summary.shops, summary.shops),
+        }
+    }
+
+    /// 每条路线都额外经过 `summary`
+    pub fn offset(self, summary: RouteSummary) -> Self {
+        let add = |(lo, hi): (u32, u32), n: u32| (lo + n, hi + n);
+        Self {
+            routes: self.routes,
+            elites: add(self.elites, summary.elites),
+            rests: add(self.rests, summary.rests),
+            shops: add(self.shops, summary.shops),
+        }
+    }
+
+    /// 两组路线合并（择其一而行）
+    fn merge(self, other: Self) -> Self {
+        let span = |a: (u32, u32), b: (u32, u32)| (a.0.min(b.0), a.1.max(b.1));
+        Self {
+            routes: self.routes + other.routes,
+            elites: span(self.elites, other.elites),
+            rests: span(self.rests, other.rests),
+            shops: span(self.shops, other.shops),
+        }
+    }
+}
+
+/// 沿 `next_nodes` 推演从 `from`（含）到 Boss 的全部路线；走不到 Boss 时为 None
+pub fn route_range(nodes: &[MapNode], from: u32) -> Option<RouteRange> {
+    fn visit(nodes: &[MapNode], id: u32, memo: &mut HashMap<u32, Option<RouteRange>>) -> Option<RouteRange> {
+        if let Some(&known) = memo.get(&id) {
+            return known;
+        }
+        let node = nodes.iter().find(|n| n.id == id)?;
+        let own = RouteSummary::of_type(node.node_type);
+        let range = if node.node_type == NodeType::Boss {
+            Some(RouteRange::single(own))
+        } else {
+            node.next_nodes.iter()
+                .filter_map(|&next| visit(nodes, next, memo))
+                .reduce(RouteRange::merge)
+                .map(|rest| rest.offset(own))
+        };
+        memo.insert(id, range);
+        range
+    }
+    visit(nodes, from, &mut HashMap::new())
+}
+
+/// 玩家规划的路线（地图上逐个标记的节点，首个为下一步可去的节点）
+#[derive(Resource, Debug, Clone, Default)]
+pub struct RoutePlan {
+    /// 是否处于规划模式（点击节点标记而非前往）
+    pub active: bool,
+    pub route: Vec<u32>,
+}
+
+impl RoutePlan {
+    /// 标记节点：已标记的节点连同其后的标记一并撤回；否则须与路线末端相连（首个须可前往）
+    pub fn mark(&mut self, nodes: &[MapNode], id: u32) -> bool {
+        if let Some(index) = self.route.iter().position(|&n| n == id) {
+            self.route.truncate(index);
+            return true;
+        }
+        let reachable = match self.route.last() {
+            Some(&last) => nodes.iter().any(|n| n.id == last && n.next_nodes.contains(&id)),
+            None => nodes.iter().any(|n| n.id == id && n.unlocked && !n.completed),
+        };
+        if reachable {
+            self.route.push(id);
+        }
+        reachable
+    }
+
+    /// 跟上地图进度：走过的节点出列，已偏离的路线作废
+    pub fn sync(&mut self, nodes: &[MapNode]) {
+        let completed = |id: u32| nodes.iter().any(|n| n.id == id && n.completed);
+        let leading = self.route.iter().take_while(|&&id| completed(id)).count();
+        self.route.drain(..leading);
+
+        let valid = self.route.first().is_none_or(|&first| nodes.iter().any(|n| n.id == first && n.unlocked && !n.completed))
+            && self.route.windows(2).all(|w| self.links(nodes, w[0], w[1]));
+        if !valid {
+            self.route.clear();
+        }
+    }
+
+    fn links(&self, nodes: &[MapNode], from: u32, to: u32) -> bool {
+        nodes.iter().any(|n| n.id == from && n.next_nodes.contains(&to))
+    }
+
+    /// 连线 `from → to` 是否在规划路线上
+    pub fn includes_edge(&self, from: u32, to: u32) -> bool {
+        self.route.windows(2).any(|w| w[0] == from && w[1] == to)
+    }
+
+    /// 规划路线（含其后通往 Boss 的各条余路）的统计；未规划时为 None
+    pub fn summary(&self, nodes: &[MapNode]) -> Option<RouteRange> {
+        let (&last, before) = self.route.split_last()?;
+        route_range(nodes, last).map(|rest| rest.offset(RouteSummary::of(nodes, before)))
+    }
+}
+
 // ============================================================================
 // UI 和 视觉组件
 // ============================================================================
@@ -321,6 +825,22 @@ pub struct MapNodeButton {
 #[derive(Component)]
 pub struct BreakthroughButtonMarker;
 
+/// 「规划路线」开关按钮
+#[derive(Component)]
+pub struct PlanRouteButton;
+
+/// 规划路线统计面板
+#[derive(Component)]
+pub struct RouteOverlayText;
+
+/// 节点悬停提示面板
+#[derive(Component)]
+pub struct MapNodeTooltip;
+
+/// 节点悬停提示文本
+#[derive(Component)]
+pub struct MapNodeTooltipText;
+
 #[derive(Component)]
 pub struct OriginalSize {
     pub width: Val,
@@ -433,3 +953,212 @@ impl Default for HoverEffect {
 
 #[derive(Component)]
 pub struct DimmedEffect;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn generate(config: &MapConfig, seed: u64) -> Vec<MapNode> {
+        generate_map_nodes(config, 0, &mut rand::rngs::StdRng::seed_from_u64(seed))
+    }
+
+    #[test]
+    fn test_every_path_obeys_pacing_rules() {
+        let harsh = MapConfig { elite_percent: 30, ..default() };
+        for seed in 0..200 {
+            for config in [MapConfig::default(), harsh.clone()] {
+                let nodes = generate(&config, seed);
+                assert_eq!(validate_map(&config, &nodes), Ok(()), "seed {}", seed);
+                assert!(nodes.iter().filter(|n| n.layer() < config.elite_min_layer).all(|n| n.node_type != NodeType::Elite));
+                assert!(nodes.iter().filter(|n| n.layer() == 5).all(|n| n.node_type == NodeType::Treasure));
+                assert!(nodes.iter().filter(|n| n.layer() == 8).all(|n| n.node_type == NodeType::Rest));
+            }
+        }
+    }
+
+    #[test]
+    fn test_elites_first_appear_after_layer_three() {
+        let lowest = (0..200)
+            .flat_map(|seed| generate(&MapConfig::default(), seed))
+            .filter(|n| n.node_type == NodeType::Elite)
+            .map(|n| n.layer())
+            .min();
+        assert_eq!(lowest, Some(3));
+    }
+
+    #[test]
+    fn test_shops_and_rests_are_not_fixed_columns() {
+        let shop_columns: std::collections::HashSet<i32> = (0..50)
+            .flat_map(|seed| generate(&MapConfig::default(), seed))
+            .filter(|n| n.node_type == NodeType::Shop)
+            .map(|n| n.position.1)
+            .collect();
+        assert_eq!(shop_columns.len(), 4);
+    }
+
+    #[test]
+    fn test_weights_are_tunable() {
+        let restful = MapConfig {
+            weights: NodeWeights { normal: 0.0, event: 0.0, shop: 0.0, rest: 1.0, elite: 0.0, unknown: 0.0 },
+            shop_window: None,
+            treasure_layer: None,
+            ..default()
+        };
+        for seed in 0..50 {
+            let nodes = generate(&restful, seed);
+            assert_eq!(validate_map(&restful, &nodes), Ok(()));
+            assert!(nodes.iter().any(|n| n.node_type == NodeType::Rest && n.layer() < 8));
+            assert!(nodes.iter().all(|n| n.node_type != NodeType::Shop));
+        }
+    }
+
+    #[test]
+    fn test_advance_act_starts_fresh_map() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let mut progress = MapProgress::new(&MapConfig::default(), &mut rng);
+        for node in progress.nodes.iter_mut().filter(|n| n.node_type == NodeType::Boss) {
+            node.completed = true;
+        }
+        assert!(progress.is_boss_defeated());
+
+        progress.advance_act(&MapConfig::for_layers(12), &mut rng);
+        assert_eq!(progress.act, 1);
+        assert_eq!(progress.nodes.len(), 48);
+        assert!(!progress.is_boss_defeated());
+        assert_eq!((progress.current_node_id, progress.current_layer), (None, 0));
+    }
+
+    #[test]
+    fn test_generator_places_unknown_nodes() {
+        let unknown = (0..20)
+            .flat_map(|seed| generate(&MapConfig::default(), seed))
+            .filter(|n| n.node_type == NodeType::Unknown)
+            .count();
+        assert!(unknown > 0);
+    }
+
+    #[test]
+    fn test_unknown_odds_shift_with_history() {
+        let mut odds = UnknownOdds::default();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut since_combat = 0;
+        for _ in 0..500 {
+            let before = odds;
+            let outcome = odds.roll(&mut rng);
+            assert!(matches!(outcome, NodeType::Normal | NodeType::Treasure | NodeType::Shop | NodeType::Event));
+            match outcome {
+                NodeType::Normal => {
+                    assert_eq!(odds.combat, 10);
+                    since_combat = 0;
+                }
+                _ => {
+                    assert_eq!(odds.combat, before.combat + 10);
+                    since_combat += 1;
+                }
+            }
+            // 连续十次未遇妖物后必然交战
+            assert!(since_combat < 10);
+            let treasure = if outcome == NodeType::Treasure { 2 } else { before.treasure + 2 };
+            assert_eq!(odds.treasure, treasure.min(100 - odds.combat));
+        }
+    }
+
+    #[test]
+    fn test_unknown_odds_never_exceed_hundred() {
+        let mut odds = UnknownOdds::default();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let mut events = 0;
+        for _ in 0..10_000 {
+            if odds.roll(&mut rng) == NodeType::Event {
+                events += 1;
+            }
+            assert!(odds.combat + odds.treasure + odds.shop <= 100, "{odds:?}");
+        }
+        // 机缘不会被挤占殆尽
+        assert!(events > 100, "10000 次揭示仅得 {events} 次机缘");
+    }
+
+    /// 0 → {1, 2}，1 → 3，2 → 3，3 为 Boss
+    fn diamond() -> Vec<MapNode> {
+        let node = |id: u32, layer: i32, node_type: NodeType, next_nodes: Vec<u32>| MapNode {
+            id,
+            node_type,
+            position: (layer, id as i32),
+            unlocked: layer == 0,
+            completed: false,
+            next_nodes,
+        };
+        vec![
+            node(0, 0, NodeType::Normal, vec![1, 2]),
+            node(1, 1, NodeType::Elite, vec![3]),
+            node(2, 1, NodeType::Shop, vec![3]),
+            node(3, 2, NodeType::Boss, vec![]),
+        ]
+    }
+
+    #[test]
+    fn test_route_range_counts_every_path() {
+        let nodes = diamond();
+        let range = route_range(&nodes, 0).unwrap();
+        assert_eq!(range.routes, 2);
+        assert_eq!((range.elites, range.shops, range.rests), ((0, 1), (0, 1), (0, 0)));
+
+        let generated = generate(&MapConfig::default(), 5);
+        for start in generated.iter().filter(|n| n.layer() == 0) {
+            let range = route_range(&generated, start.id).unwrap();
+            assert!(range.routes >= 1);
+            // 每条路线都经过 Boss 前的洞府与至少一座坊市
+            assert!(range.rests.0 >= 1 && range.shops.0 >= 1);
+        }
+    }
+
+    #[test]
+    fn test_route_plan_marks_connected_nodes() {
+        let mut nodes = diamond();
+        let mut plan = RoutePlan::default();
+        assert!(!plan.mark(&nodes, 1), "首个标记须是可前往的节点");
+        assert!(plan.mark(&nodes, 0) && plan.mark(&nodes, 1));
+        assert!(!plan.mark(&nodes, 2), "须与路线末端相连");
+        assert!(plan.mark(&nodes, 3));
+        assert!(plan.includes_edge(1, 3) && !plan.includes_edge(2, 3));
+        let summary = plan.summary(&nodes).unwrap();
+        assert_eq!((summary.routes, summary.elites, summary.shops), (1, (1, 1), (0, 0)));
+
+        // 撤回已标记的节点连同其后
+        assert!(plan.mark(&nodes, 1));
+        assert_eq!(plan.route, vec![0]);
+        assert_eq!(plan.summary(&nodes).unwrap().routes, 2);
+
+        // 走过的节点出列，偏离路线则作废
+        plan.mark(&nodes, 2);
+        nodes[0].completed = true;
+        nodes[2].unlocked = true;
+        plan.sync(&nodes);
+        assert_eq!(plan.route, vec![2]);
+        nodes[1].completed = true;
+        nodes[2].unlocked = false;
+        plan.sync(&nodes);
+        assert!(plan.route.is_empty());
+    }
+
+    #[test]
+    fn test_validator_reports_violations() {
+        let config = MapConfig::default();
+        let mut nodes = generate(&config, 1);
+        let from = nodes.iter().find(|n| n.layer() == 2).unwrap().clone();
+        for id in from.next_nodes.iter().chain([&from.id]) {
+            nodes[*id as usize].node_type = NodeType::Event;
+        }
+        assert!(matches!(
+            validate_map(&config, &nodes),
+            Err(MapRuleViolation::RepeatedSpecial { node_type: NodeType::Event, .. })
+        ));
+
+        let mut nodes = generate(&config, 1);
+        for node in nodes.iter_mut().filter(|n| n.node_type == NodeType::Shop) {
+            node.node_type = NodeType::Normal;
+        }
+        assert!(matches!(validate_map(&config, &nodes), Err(MapRuleViolation::NoShopOnPath { .. })));
+    }
+}
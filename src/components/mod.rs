@@ -13,8 +13,10 @@ pub mod map;
 pub mod particle;
 pub mod relic;
 pub mod screen_effect;
+pub mod sect;
 pub mod shop;
 pub mod sprite;
+pub mod status;
 
 // 批量重导出
 pub use animation::*;
@@ -28,5 +30,7 @@ pub use map::*;
 pub use particle::*;
 pub use relic::*;
 pub use screen_effect::*;
+pub use sect::*;
 pub use shop::*;
-pub use sprite::*;
\ No newline at end of file
+pub use sprite::*;
+pub use status::*;
\ No newline at end of file
@@ -1,9 +1,27 @@
 //! 遗物系统组件
 //!
-//! 遗物提供永久性被动效果，是杀戮尖塔风格游戏的核心元素
+//! 遗物提供永久性被动效果，是杀戮尖塔风格游戏的核心元素。
+//! 具体遗物定义在 `assets/data/relics/*.relics.ron` 中，由 [`RelicLibrary`](crate::resources::relic_data::RelicLibrary) 汇总。
+
+use std::fmt;
 
 use bevy::prelude::*;
-use serde::{Serialize, Deserialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::components::combat::{EnemyType, Player};
+use crate::components::cultivation::Realm;
+use crate::components::status::StatusId;
+
+/// 内置遗物 id
+pub const BURNING_BLOOD: &str = "burning_blood";
+pub const BAG_OF_PREPARATION: &str = "bag_of_preparation";
+pub const ANCHOR: &str = "anchor";
+pub const STRANGE_SPOON: &str = "strange_spoon";
+pub const ELIXIR_FURNACE: &str = "elixir_furnace";
+pub const CINNABAR_BRUSH: &str = "cinnabar_brush";
+
+/// 坊市折扣上限（百分比）
+pub const MAX_SHOP_DISCOUNT: i32 = 50;
 
 // ============================================================================
 // 遗物定义
@@ -22,16 +40,79 @@ pub struct Relic {
     pub rarity: RelicRarity,
     /// 遗物效果列表 (支持复合效果)
     pub effects: Vec<RelicEffect>,
+    /// 最低境界（未达到时不会出现在机缘与坊市中）
+    #[serde(default)]
+    pub min_realm: Option<Realm>,
+    /// 计数类效果的进度（跨战斗保留，随存档保存）
+    #[serde(default)]
+    pub counter: i32,
+    /// 藏经阁解锁所需道韵（填写后须先解锁才会出现在机缘与坊市中）
+    #[serde(default)]
+    pub unlock_cost: Option<u32>,
+    /// 藏经阁中换作开局法宝所需道韵（未填写则不可作开局法宝）
+    #[serde(default)]
+    pub starter_cost: Option<u32>,
+}
+
+/// 遗物标识（统一 snake_case，兼容旧存档中的 `"BurningBlood"` 与 `{"Custom": n}` 写法）
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(transparent)]
+pub struct RelicId(String);
+
+impl RelicId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// 旧版枚举名转为 snake_case（`BagOfPreparation` → `bag_of_preparation`）
+    fn from_legacy(name: &str) -> Self {
+        let mut id = String::new();
+        for (i, c) in name.chars().enumerate() {
+            if c.is_uppercase() {
+                if i > 0 {
+                    id.push('_');
+                }
+                id.extend(c.to_lowercase());
+            } else {
+                id.push(c);
+            }
+        }
+        Self(id)
+    }
 }
 
-/// 遗物ID (支持预定义与自定义扩展)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub enum RelicId {
-    BurningBlood,     // 飞剑符
-    BagOfPreparation, // 乾坤袋
-    Anchor,           // 定风珠
-    StrangeSpoon,     // 聚灵阵
-    Custom(u32),      // 自定义遗物 (用于扩展和测试)
+impl From<&str> for RelicId {
+    fn from(id: &str) -> Self {
+        Self::new(id)
+    }
+}
+
+impl fmt::Display for RelicId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for RelicId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Name(String),
+            Custom {
+                #[serde(rename = "Custom")]
+                custom: u32,
+            },
+        }
+        Ok(match Raw::deserialize(deserializer)? {
+            Raw::Name(name) => Self::from_legacy(&name),
+            Raw::Custom { custom } => Self(format!("custom_{}", custom)),
+        })
+    }
 }
 
 /// 遗物稀有度
@@ -41,15 +122,28 @@ pub enum RelicRarity {
     Uncommon,  // 罕见 - 中等效果
     Rare,      // 稀有 - 强力效果
     Special,   // 特殊 - 独特效果
+    Boss,      // 首领 - 仅在击败首领后出现
 }
 
 /// 遗物效果类型
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RelicEffect {
     /// 战斗开始时触发（造成伤害、获得护甲等）
-    OnCombatStart { damage: i32, block: i32, draw_cards: i32 },
+    OnCombatStart {
+        #[serde(default)]
+        damage: i32,
+        #[serde(default)]
+        block: i32,
+        #[serde(default)]
+        draw_cards: i32,
+    },
     /// 回合开始时触发（获得能量、抽牌等）
-    OnTurnStart { energy: i32, draw_cards: i32 },
+    OnTurnStart {
+        #[serde(default)]
+        energy: i32,
+        #[serde(default)]
+        draw_cards: i32,
+    },
     /// 回合结束时触发（保留手牌）
     OnTurnEnd { keep_cards: i32 },
     /// 抽牌时触发（额外抽牌）
@@ -60,6 +154,112 @@ pub enum RelicEffect {
     OnTakeDamage { reduction: i32 },
     /// 打出牌时触发（根据条件触发效果）
     OnCardPlayed { every_nth: i32, draw_cards: i32 },
+    /// 战斗开始时施加状态（`enemies` 为 true 时施加给全体敌人，否则施加给自身）
+    StatusAtCombatStart {
+        status: StatusId,
+        stacks: i32,
+        #[serde(default)]
+        enemies: bool,
+    },
+    /// 获得遗物时立即生效（灵石、道行上限）
+    OnPickup {
+        #[serde(default)]
+        gold: i32,
+        #[serde(default)]
+        max_hp: i32,
+    },
+    /// 战斗胜利时触发（灵石、回复道行）
+    OnVictory {
+        #[serde(default)]
+        gold: i32,
+        #[serde(default)]
+        heal: i32,
+    },
+    /// 坊市价格折扣（百分比，多件叠加，上限见 [`MAX_SHOP_DISCOUNT`]）
+    ShopDiscount { percent: i32 },
+    /// 条件满足时内层效果才生效
+    When { condition: RelicCondition, effect: Box<RelicEffect> },
+    /// 每累计 `every` 次事件结算一次内层效果，进度跨战斗保留
+    Counter { counts: CounterEvent, every: i32, effect: Box<RelicEffect> },
+}
+
+/// 条件效果的判定条件
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RelicCondition {
+    /// 对手为指定种类的妖物（攻击时看目标，其余时机看场上任一存活敌人）
+    EnemyType(EnemyType),
+    /// 道行低于上限的百分比
+    HpBelowPercent(i32),
+    /// 战斗的第一回合
+    FirstTurn,
+}
+
+/// 计数类遗物统计的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CounterEvent {
+    /// 打出任意功法
+    CardPlayed,
+    /// 打出攻伐功法
+    AttackPlayed,
+    /// 战斗开始
+    CombatStarted,
+    /// 玩家回合开始（不含首回合）
+    TurnStarted,
+    /// 战斗胜利
+    CombatWon,
+}
+
+impl RelicEffect {
+    /// 结算作用于修行者本身的收益（灵石、道行上限、回复），返回是否为此类效果
+    pub fn grant_to(&self, player: &mut Player) -> bool {
+        match self {
+            RelicEffect::OnPickup { gold, max_hp } => {
+                player.gold += gold;
+                player.max_hp += max_hp;
+                player.hp = (player.hp + max_hp).clamp(1, player.max_hp);
+                true
+            }
+            RelicEffect::OnVictory { gold, heal } => {
+                player.gold += gold;
+                player.heal(*heal);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Relic {
+    /// 推进计数类效果，计满时返回需要结算的内层效果并清零进度
+    pub fn advance_counter(&mut self, event: CounterEvent) -> Option<RelicEffect> {
+        for effect in &self.effects {
+            let RelicEffect::Counter { counts, every, effect } = effect else { continue; };
+            if *counts != event {
+                continue;
+            }
+            self.counter += 1;
+            if self.counter >= *every {
+                self.counter = 0;
+                return Some((**effect).clone());
+            }
+            return None;
+        }
+        None
+    }
+
+    /// 获得遗物时立即结算的收益
+    pub fn on_pickup(&self, player: &mut Player) {
+        for effect in &self.effects {
+            if matches!(effect, RelicEffect::OnPickup { .. }) {
+                effect.grant_to(player);
+            }
+        }
+    }
+
+    /// 当前境界下是否可获得
+    pub fn available_at(&self, realm: Realm) -> bool {
+        self.min_realm.map_or(true, |min| realm >= min)
+    }
 }
 
 /// 玩家遗物背包资源
@@ -99,13 +299,13 @@ impl RelicCollection {
     }
 
     /// 检查是否拥有某个遗物
-    pub fn has(&self, id: RelicId) -> bool {
-        self.relic.iter().any(|r| r.id == id)
+    pub fn has(&self, id: &str) -> bool {
+        self.relic.iter().any(|r| r.id.as_str() == id)
     }
 
     /// 获取拥有某个遗物的引用
-    pub fn get(&self, id: RelicId) -> Option<&Relic> {
-        self.relic.iter().find(|r| r.id == id)
+    pub fn get(&self, id: &str) -> Option<&Relic> {
+        self.relic.iter().find(|r| r.id.as_str() == id)
     }
 
     /// 遗物数量
@@ -117,77 +317,49 @@ impl RelicCollection {
     pub fn is_empty(&self) -> bool {
         self.relic.is_empty()
     }
-}
-
-// ============================================================================
-// 预定义遗物
-// ============================================================================
-
-impl Relic {
-    /// 创建燃烧之血遗物 -> 飞剑符
-    pub fn burning_blood() -> Self {
-        Self {
-            id: RelicId::BurningBlood,
-            name: "飞剑符".to_string(),
-            description: "每场战斗开始时，对所有敌人造成 3 点剑气伤害".to_string(),
-            rarity: RelicRarity::Common,
-            effects: vec![RelicEffect::OnCombatStart { damage: 3, block: 0, draw_cards: 0 }],
-        }
-    }
 
-    /// 创建准备背包遗物 -> 乾坤袋
-    pub fn bag_of_preparation() -> Self {
-        Self {
-            id: RelicId::BagOfPreparation,
-            name: "乾坤袋".to_string(),
-            description: "每场战斗开始时，从乾坤袋中额外获得 1 张随机功法".to_string(),
-            rarity: RelicRarity::Common,
-            effects: vec![RelicEffect::OnCombatStart { damage: 0, block: 0, draw_cards: 1 }],
-        }
+    /// 坊市折扣（百分比，已封顶）
+    pub fn shop_discount(&self) -> i32 {
+        let total: i32 = self
+            .relic
+            .iter()
+            .flat_map(|r| &r.effects)
+            .map(|e| match e {
+                RelicEffect::ShopDiscount { percent } => *percent,
+                _ => 0,
+            })
+            .sum();
+        total.clamp(0, MAX_SHOP_DISCOUNT)
     }
 
-    /// 创建锚遗物 -> 定风珠
-    pub fn anchor() -> Self {
-        Self {
-            id: RelicId::Anchor,
-            name: "定风珠".to_string(),
-            description: "每回合结束时，保留最多 3 张手牌到下回合".to_string(),
-            rarity: RelicRarity::Uncommon,
-            effects: vec![RelicEffect::OnTurnEnd { keep_cards: 3 }],
-        }
+    /// 折扣后的坊市价格
+    pub fn discounted_price(&self, price: i32) -> i32 {
+        price * (100 - self.shop_discount()) / 100
     }
 
-    /// 创建奇怪勺子遗物 -> 聚灵阵
-    pub fn strange_spoon() -> Self {
-        Self {
-            id: RelicId::StrangeSpoon,
-            name: "聚灵阵".to_string(),
-            description: "每打出第 3 张牌时，灵气涌动，抽 1 张牌".to_string(),
-            rarity: RelicRarity::Rare,
-            effects: vec![RelicEffect::OnCardPlayed { every_nth: 3, draw_cards: 1 }],
+    /// 战斗胜利：结算胜利类效果并推进胜场计数，返回生效的遗物效果（供日志与闪烁）
+    pub fn on_victory(&mut self, player: &mut Player) -> Vec<(RelicId, RelicEffect)> {
+        let mut fired = Vec::new();
+        for relic in &mut self.relic {
+            for effect in &relic.effects {
+                if matches!(effect, RelicEffect::OnVictory { .. }) && effect.grant_to(player) {
+                    fired.push((relic.id.clone(), effect.clone()));
+                }
+            }
+            if let Some(effect) = relic.advance_counter(CounterEvent::CombatWon) {
+                effect.grant_to(player);
+                fired.push((relic.id.clone(), effect));
+            }
         }
+        fired
     }
 
-    /// 随机生成一个遗物
-    pub fn random() -> Self {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-
-        match rng.gen_range(0..4) {
-            0 => Self::burning_blood(),
-            1 => Self::bag_of_preparation(),
-            2 => Self::anchor(),
-            _ => Self::strange_spoon(),
-        }
-    }
-
-    /// 根据稀有度生成遗物
-    pub fn by_rarity(rarity: RelicRarity) -> Vec<Self> {
-        match rarity {
-            RelicRarity::Common => vec![Self::burning_blood(), Self::bag_of_preparation()],
-            RelicRarity::Uncommon => vec![Self::anchor()],
-            RelicRarity::Rare => vec![Self::strange_spoon()],
-            RelicRarity::Special => vec![],
+    /// 同步战斗中推进的计数进度
+    pub fn sync_counters(&mut self, relics: &[Relic]) {
+        for relic in &mut self.relic {
+            if let Some(updated) = relics.iter().find(|r| r.id == relic.id) {
+                relic.counter = updated.counter;
+            }
         }
     }
 }
@@ -219,7 +391,16 @@ pub struct RelicUiMarker;
 
 /// 单个遗物显示项标记
 #[derive(Component)]
-pub struct RelicItemMarker;
+pub struct RelicItemMarker {
+    pub relic_id: RelicId,
+}
+
+/// 遗物触发时的闪烁（计时结束后恢复稀有度底色）
+#[derive(Component)]
+pub struct RelicFlash {
+    pub timer: Timer,
+    pub base_color: Color,
+}
 
 /// 遗物稀有度对应的颜色
 impl RelicRarity {
@@ -230,6 +411,7 @@ impl RelicRarity {
             RelicRarity::Uncommon => Color::srgb(0.3, 0.7, 0.3),    // 绿色
             RelicRarity::Rare => Color::srgb(0.7, 0.3, 0.9),        // 紫色
             RelicRarity::Special => Color::srgb(1.0, 0.8, 0.0),     // 金色
+            RelicRarity::Boss => Color::srgb(0.85, 0.25, 0.2),      // 赤色
         }
     }
 
@@ -240,6 +422,7 @@ impl RelicRarity {
             RelicRarity::Uncommon => Color::srgb(0.1, 0.3, 0.1),
             RelicRarity::Rare => Color::srgb(0.2, 0.1, 0.3),
             RelicRarity::Special => Color::srgb(0.3, 0.2, 0.0),
+            RelicRarity::Boss => Color::srgb(1.0, 0.95, 0.85),
         }
     }
 }
@@ -0,0 +1,113 @@
+//! 宗门（可选的修行者出身）
+//!
+//! 各宗门的初始牌组与专属功法见功法数据文件中的 `sect` 字段；
+//! 宗门心法（剑意、丹火、符印）由战斗规则引擎结算。
+
+use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::components::relic::{BURNING_BLOOD, CINNABAR_BRUSH, ELIXIR_FURNACE};
+
+/// 宗门
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Sect {
+    /// 青云剑宗：连出攻伐功法积累剑意
+    #[default]
+    Sword,
+    /// 丹鼎门：出牌积累丹火，回合结束时化作护盾
+    Alchemy,
+    /// 天符宗：非攻伐功法积累符印，下一张攻伐功法引爆
+    Talisman,
+}
+
+impl Sect {
+    /// 全部宗门（择宗界面顺序）
+    pub const ALL: [Sect; 3] = [Sect::Sword, Sect::Alchemy, Sect::Talisman];
+
+    /// 宗门名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            Sect::Sword => "青云剑宗",
+            Sect::Alchemy => "丹鼎门",
+            Sect::Talisman => "天符宗",
+        }
+    }
+
+    /// 宗门简介
+    pub fn description(&self) -> &'static str {
+        match self {
+            Sect::Sword => "以剑入道，攻伐凌厉。",
+            Sect::Alchemy => "炉火纯青，以丹养身，久战不衰。",
+            Sect::Talisman => "灵力充沛而体魄孱弱，以符蓄势，一击定乾坤。",
+        }
+    }
+
+    /// 宗门心法名称
+    pub fn mechanic_name(&self) -> &'static str {
+        match self {
+            Sect::Sword => "剑意",
+            Sect::Alchemy => "丹火",
+            Sect::Talisman => "符印",
+        }
+    }
+
+    /// 宗门心法说明
+    pub fn mechanic_description(&self) -> &'static str {
+        match self {
+            Sect::Sword => "连出攻伐功法积累剑意，三层起增伤，五层人剑合一；动用他法则溃散",
+            Sect::Alchemy => "每出一张功法积 1 层丹火（至多 5 层），回合结束时每层化作 2 点护盾，余火减半",
+            Sect::Talisman => "每出一张非攻伐功法积 1 层符印（至多 3 层），下一张攻伐功法每段伤害 +3 并耗尽符印",
+        }
+    }
+
+    /// 宗门心法层数上限
+    pub fn charge_cap(&self) -> i32 {
+        match self {
+            Sect::Sword | Sect::Alchemy => 5,
+            Sect::Talisman => 3,
+        }
+    }
+
+    /// 道行上限（不含境界加成）
+    pub fn base_max_hp(&self) -> i32 {
+        match self {
+            Sect::Sword => 80,
+            Sect::Alchemy => 90,
+            Sect::Talisman => 70,
+        }
+    }
+
+    /// 每回合灵力（不含境界加成）
+    pub fn base_energy(&self) -> i32 {
+        match self {
+            Sect::Sword | Sect::Alchemy => 3,
+            Sect::Talisman => 4,
+        }
+    }
+
+    /// 宗门本命法宝（开局赠送）
+    pub fn starting_relic(&self) -> &'static str {
+        match self {
+            Sect::Sword => BURNING_BLOOD,
+            Sect::Alchemy => ELIXIR_FURNACE,
+            Sect::Talisman => CINNABAR_BRUSH,
+        }
+    }
+
+    /// 随身法器模型（剑宗御剑，余宗不佩飞剑）
+    pub fn weapon_model(&self) -> Option<&'static str> {
+        match self {
+            Sect::Sword => Some("3d/player/sword_01.glb#Scene0"),
+            Sect::Alchemy | Sect::Talisman => None,
+        }
+    }
+
+    /// 宗门主色（择宗界面与修行者染色）
+    pub fn color(&self) -> Color {
+        match self {
+            Sect::Sword => Color::srgb(0.4, 1.0, 1.0),
+            Sect::Alchemy => Color::srgb(1.0, 0.55, 0.25),
+            Sect::Talisman => Color::srgb(1.0, 0.85, 0.3),
+        }
+    }
+}
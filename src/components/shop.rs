@@ -56,6 +56,7 @@ impl ShopItem {
                     crate::components::relic::RelicRarity::Uncommon => 75,
                     crate::components::relic::RelicRarity::Rare => 100,
                     crate::components::relic::RelicRarity::Special => 150,
+                    crate::components::relic::RelicRarity::Boss => 200,
                 }
             }
             ShopItem::Elixir { price, .. } => *price,
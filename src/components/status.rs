@@ -0,0 +1,317 @@
+//! 通用状态组件
+//!
+//! 战斗单位只记录"状态 id → 层数与持续回合"，叠加规则、钩子与数值修正由 [`StatusRegistry`] 中的数据定义解释。
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::resources::status_data::{StackingRule, StatusAction, StatusDefinition, StatusRegistry, ValueModifier};
+
+/// 内置状态 id
+pub const POISON: &str = "poison";
+pub const BURN: &str = "burn";
+pub const WEAKNESS: &str = "weakness";
+pub const VULNERABLE: &str = "vulnerable";
+
+/// 状态标识（统一小写，兼容旧存档中的 `"Weakness"` 等枚举写法）
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(transparent)]
+pub struct StatusId(String);
+
+impl StatusId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into().to_lowercase())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for StatusId {
+    fn from(id: &str) -> Self {
+        Self::new(id)
+    }
+}
+
+impl fmt::Display for StatusId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for StatusId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::new)
+    }
+}
+
+/// 单个状态的层数与持续回合
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusInstance {
+    pub stacks: i32,
+    /// 剩余回合（None 表示持续到层数耗尽）
+    pub duration: Option<u32>,
+}
+
+/// 钩子触发后需要由持有者结算的效果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggeredEffect {
+    LoseHp(i32),
+    Heal(i32),
+    GainBlock(i32),
+    /// 反伤攻击者（由战斗规则结算给攻击方）
+    DamageAttacker(i32),
+}
+
+/// 一次状态触发
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusTrigger {
+    pub status: StatusId,
+    pub effect: TriggeredEffect,
+}
+
+/// 汇总后的数值修正
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModifierTotal {
+    pub flat: i32,
+    pub factor: f32,
+    pub cap: Option<i32>,
+}
+
+impl Default for ModifierTotal {
+    fn default() -> Self {
+        Self { flat: 0, factor: 1.0, cap: None }
+    }
+}
+
+impl ModifierTotal {
+    /// 结算：加减 → 乘算（含外部系数，如天象）→ 上限，结果不为负
+    pub fn apply(&self, base: i32, extra_factor: f32) -> i32 {
+        let value = ((base + self.flat).max(0) as f32 * self.factor * extra_factor) as i32;
+        match self.cap {
+            Some(cap) => value.min(cap),
+            None => value,
+        }
+    }
+}
+
+/// 状态栏显示用的摘要
+#[derive(Debug, Clone)]
+pub struct StatusSummary {
+    pub icon: String,
+    pub name: String,
+    pub stacks: i32,
+    pub tooltip: String,
+    pub color: Color,
+    pub debuff: bool,
+}
+
+/// 战斗单位身上的全部状态
+#[derive(Component, Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatusEffects {
+    entries: BTreeMap<StatusId, StatusInstance>,
+}
+
+impl StatusEffects {
+    /// 当前层数（没有该状态时为 0）
+    pub fn stacks(&self, id: &str) -> i32 {
+        self.entries.get(&StatusId::new(id)).map(|s| s.stacks).unwrap_or(0)
+    }
+
+    pub fn has(&self, id: &str) -> bool {
+        self.stacks(id) > 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 施加状态，按图谱中的叠加规则合并；持续回合取较长者
+    pub fn apply(&mut self, registry: &StatusRegistry, id: &str, stacks: i32, duration: Option<u32>) {
+        if stacks <= 0 {
+            return;
+        }
+        let id = StatusId::new(id);
+        let rule = registry.get(&id).map(|d| d.stacking).unwrap_or_default();
+        match self.entries.get_mut(&id) {
+            Some(existing) => {
+                match rule {
+                    StackingRule::Stack => existing.stacks += stacks,
+                    StackingRule::Max => existing.stacks = existing.stacks.max(stacks),
+                    StackingRule::Unique => return,
+                }
+                existing.duration = match (existing.duration, duration) {
+                    (Some(a), Some(b)) => Some(a.max(b)),
+                    _ => None,
+                };
+            }
+            None => {
+                self.entries.insert(id, StatusInstance { stacks, duration });
+            }
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.entries.remove(&StatusId::new(id));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn set_stacks(&mut self, id: &StatusId, stacks: i32) {
+        if stacks <= 0 {
+            self.entries.remove(id);
+        } else if let Some(entry) = self.entries.get_mut(id) {
+            entry.stacks = stacks;
+        }
+    }
+
+    /// 持有者回合开始
+    pub fn on_turn_start(&mut self, registry: &StatusRegistry) -> Vec<StatusTrigger> {
+        self.fire(registry, |d| &d.on_turn_start)
+    }
+
+    /// 持有者回合结束：执行钩子后持续回合减一
+    pub fn on_turn_end(&mut self, registry: &StatusRegistry) -> Vec<StatusTrigger> {
+        let triggers = self.fire(registry, |d| &d.on_turn_end);
+        self.entries.retain(|_, entry| match entry.duration.as_mut() {
+            Some(turns) => {
+                *turns = turns.saturating_sub(1);
+                *turns > 0
+            }
+            None => true,
+        });
+        triggers
+    }
+
+    /// 持有者打出一张功法后
+    pub fn on_card_played(&mut self, registry: &StatusRegistry) -> Vec<StatusTrigger> {
+        self.fire(registry, |d| &d.on_card_played)
+    }
+
+    /// 持有者被攻击命中后
+    pub fn on_attacked(&mut self, registry: &StatusRegistry) -> Vec<StatusTrigger> {
+        self.fire(registry, |d| &d.on_attacked)
+    }
+
+    /// 按图谱顺序执行各状态的指定钩子
+    fn fire(&mut self, registry: &StatusRegistry, hook: impl Fn(&StatusDefinition) -> &Vec<StatusAction>) -> Vec<StatusTrigger> {
+        let mut triggers = Vec::new();
+        for definition in registry.definitions() {
+            for action in hook(definition) {
+                let Some(stacks) = self.entries.get(&definition.id).map(|s| s.stacks) else { break; };
+                let mut trigger = |effect| triggers.push(StatusTrigger { status: definition.id.clone(), effect });
+                match action {
+                    StatusAction::LoseHp(amount) => trigger(TriggeredEffect::LoseHp(amount.resolve(stacks))),
+                    StatusAction::Heal(amount) => trigger(TriggeredEffect::Heal(amount.resolve(stacks))),
+                    StatusAction::GainBlock(amount) => trigger(TriggeredEffect::GainBlock(amount.resolve(stacks))),
+                    StatusAction::DamageAttacker(amount) => trigger(TriggeredEffect::DamageAttacker(amount.resolve(stacks))),
+                    StatusAction::Decay(amount) => self.set_stacks(&definition.id, stacks - amount),
+                    StatusAction::Halve => self.set_stacks(&definition.id, stacks / 2),
+                    StatusAction::Remove => self.set_stacks(&definition.id, 0),
+                }
+            }
+        }
+        triggers
+    }
+
+    /// 汇总指定修正钩子（如造成伤害）的全部修正
+    pub fn modifiers(&self, registry: &StatusRegistry, hook: impl Fn(&StatusDefinition) -> &Vec<ValueModifier>) -> ModifierTotal {
+        let mut total = ModifierTotal::default();
+        for (id, entry) in &self.entries {
+            let Some(definition) = registry.get(id) else { continue; };
+            for modifier in hook(definition) {
+                match modifier {
+                    ValueModifier::PerStack(value) => total.flat += value * entry.stacks,
+                    ValueModifier::Multiply(factor) => total.factor *= factor,
+                    ValueModifier::Cap(cap) => total.cap = Some(total.cap.map_or(*cap, |c| c.min(*cap))),
+                }
+            }
+        }
+        total
+    }
+
+    /// 状态栏摘要（图谱顺序，未登记的状态不显示）
+    pub fn summaries(&self, registry: &StatusRegistry) -> Vec<StatusSummary> {
+        registry
+            .definitions()
+            .iter()
+            .filter_map(|d| {
+                let entry = self.entries.get(&d.id)?;
+                Some(StatusSummary {
+                    icon: d.icon.clone(),
+                    name: d.name.clone(),
+                    stacks: entry.stacks,
+                    tooltip: d.tooltip_for(entry.stacks),
+                    color: Color::srgb(d.color.0, d.color.1, d.color.2),
+                    debuff: d.debuff,
+                })
+            })
+            .collect()
+    }
+}
+
+/// 将触发效果结算到持有者的道行与护盾上（反伤由战斗规则结算给攻击方）
+pub fn settle_status_triggers(triggers: &[StatusTrigger], hp: &mut i32, max_hp: i32, block: &mut i32) {
+    for trigger in triggers {
+        match trigger.effect {
+            TriggeredEffect::LoseHp(amount) => *hp = (*hp - amount).max(0),
+            TriggeredEffect::Heal(amount) if *hp > 0 => *hp = (*hp + amount).min(max_hp),
+            TriggeredEffect::GainBlock(amount) => *block += amount,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stacking_rules_and_hooks() {
+        let registry = StatusRegistry::builtin();
+        let mut statuses = StatusEffects::default();
+        statuses.apply(registry, POISON, 3, None);
+        statuses.apply(registry, POISON, 2, None);
+        statuses.apply(registry, "intangible", 2, None);
+        statuses.apply(registry, "intangible", 1, None);
+        assert_eq!(statuses.stacks(POISON), 5);
+        assert_eq!(statuses.stacks("intangible"), 2);
+
+        let triggers = statuses.on_turn_start(registry);
+        assert_eq!(triggers, vec![StatusTrigger { status: StatusId::new(POISON), effect: TriggeredEffect::LoseHp(5) }]);
+        assert_eq!(statuses.stacks(POISON), 4);
+
+        // 带持续回合的临时力量在回合结束时消失
+        statuses.apply(registry, "strength", 2, Some(1));
+        assert_eq!(statuses.modifiers(registry, |d| &d.outgoing_damage).apply(6, 1.0), 8);
+        statuses.on_turn_end(registry);
+        assert!(!statuses.has("strength"));
+        assert_eq!(statuses.stacks("intangible"), 1);
+    }
+
+    #[test]
+    fn test_modifiers_sum_then_multiply_then_cap() {
+        let registry = StatusRegistry::builtin();
+        let mut statuses = StatusEffects::default();
+        statuses.apply(registry, "strength", 2, None);
+        statuses.apply(registry, WEAKNESS, 1, None);
+        // (6 + 2) × 0.75
+        assert_eq!(statuses.modifiers(registry, |d| &d.outgoing_damage).apply(6, 1.0), 6);
+
+        statuses.apply(registry, "intangible", 1, None);
+        statuses.apply(registry, VULNERABLE, 1, None);
+        assert_eq!(statuses.modifiers(registry, |d| &d.incoming_damage).apply(20, 1.0), 1);
+    }
+
+    #[test]
+    fn test_legacy_status_names_deserialize() {
+        let id: StatusId = serde_json::from_str("\"Weakness\"").unwrap();
+        assert_eq!(id.as_str(), WEAKNESS);
+    }
+}
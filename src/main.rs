@@ -51,7 +51,11 @@ fn main() {
                 }),
                 ..default()
             },
-        ).set(RenderPlugin {
+        ).set(AssetPlugin {
+            // 调试构建监听 assets 变更，功法数据等可热重载
+            watch_for_changes_override: Some(cfg!(debug_assertions)),
+            ..default()
+        }).set(RenderPlugin {
             render_creation: WgpuSettings {
                 // 优先高性能，但不再强制，确保兼容性
                 power_preference: PowerPreference::HighPerformance,
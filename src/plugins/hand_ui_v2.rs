@@ -158,7 +158,7 @@ pub fn update_hand_ui_v2(
 
                                     )).with_children(|card_ui| {
 
-                                        card_ui.spawn((Text::new(format!("{}", card.cost)), TextFont { font: chinese_font.clone(), font_size: 18.0, ..default() }, TextColor(Color::WHITE)));
+                                        card_ui.spawn((Text::new(card.cost_label()), TextFont { font: chinese_font.clone(), font_size: 18.0, ..default() }, TextColor(Color::WHITE)));
 
                                         card_ui.spawn((Text::new(card.name.clone()), TextFont { font: chinese_font.clone(), font_size: 14.0, ..default() }, TextColor(Color::WHITE), TextLayout::new_with_justify(JustifyText::Center)));
 
@@ -2,13 +2,13 @@
 pub mod hand_ui_v2;
 pub mod opening;
 use bevy::prelude::*;
-use crate::resources::{ArenaAssets, PlayerAssets};
+use crate::resources::{ArenaAssets, CardLibrary, PlayerAssets, RunSeed, RunStats};
 use crate::states::GameState;
 use crate::components::background_music::{BgmType, PlayBgmEvent, StopBgmEvent};
 
 use crate::components::{
     Player, Enemy, EnemyType, EnemyIntent, EnemyAffix, Card, CardType, CardEffect, CardRarity, Hand, DrawPile, DiscardPile,
-    CombatState, TurnPhase, NodeType, MapProgress, PlayerDeck, CardPool,
+    CombatState, TurnPhase, NodeType, MapProgress, PlayerDeck,
     MapUiRoot, MapNodeButton, RippleEffect, EntranceAnimation, HoverEffect, // 新增导入
     CharacterType, EnemyAttackEvent,
     SpriteMarker, ParticleMarker, EmitterMarker, EffectType, SpawnEffectEvent,
@@ -29,14 +29,27 @@ use crate::components::{
     EnemyActionQueue, RelicObtainedEvent, RelicTriggeredEvent, HeavenlyStrikeCinematic,
     ParticleEmitter, PlaySfxEvent, SfxType, CardHoverPanelMarker, RelicHoverPanelMarker, DialogueLine,
     EnvironmentPanel, EnvironmentText, DamageEffectEvent, BlockIconMarker, BlockText, StatusIndicator,
-    EnemyHpText, EnemyIntentText, EnemyStatusUi, PlayerHpText, PlayerEnergyText, PlayerBlockText,
-    SwordIntentText, 
-    TopBar, TopBarHpText, TopBarGoldText, EnergyOrb, EndTurnButton, HandArea, CombatUiRoot,
+    EnemyHpText, EnemyIntentText, EnemyStatusUi, EnemyTargetZone, CardTargeting, PlayerHpText, PlayerEnergyText, PlayerBlockText,
+    SwordIntentText, Sect,
+    TopBar, TopBarHpText, TopBarGoldText, EnergyOrb, EndTurnButton, DivineArtButton, HandArea, CombatUiRoot,
     StatusEffectEvent, Environment, CombatCamera,
 };
+use crate::components::status::{StatusId, TriggeredEffect, WEAKNESS};
+use crate::resources::status_data::StatusRegistry;
+use crate::resources::relic_data::RelicLibrary;
+use crate::resources::ascension::{Ascension, MAX_ASCENSION};
+use crate::resources::profile::{settle_run, MetaProfile, RunSettlement, RunVictory};
+use crate::resources::save::{ActiveSaveSlot, CombatSnapshot, GameStateSave, PendingCombatResume, PlayTime, PlayerRunState, SaveWriter, SlotSummary, SLOT_NAMES};
 use crate::components::sprite::{CharacterAssets, CharacterAnimationEvent, AnimationState, PlayerSpriteMarker, CharacterSprite};
 use crate::systems::sprite::{spawn_character_sprite};
-use crate::systems::enemy_gen::EnemyGenerator;
+use crate::systems::enemy_gen::{EnemyGenerator, GeneratedEnemy};
+use crate::systems::combat_engine::{CombatEngine, CombatEvent};
+use crate::systems::relic::send_relic_triggered_events;
+use crate::systems::tribulation::TribulationBattle;
+use crate::systems::event::EventEncounter;
+use crate::resources::event_data::EventHistory;
+use crate::resources::act_data::ActAtlas;
+use crate::components::map::RoutePlan;
 
 use crate::plugins::opening::FirstFrameResource;
 
@@ -46,11 +59,16 @@ pub struct CorePlugin;
 impl Plugin for CorePlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(opening::OpeningPlugin);
+        app.add_plugins(crate::systems::archive::ArchivePlugin);
+        app.add_plugins(crate::systems::TribulationPlugin);
         app.register_type::<GameState>();
         app.init_resource::<crate::resources::EnvironmentAssets>();
         app.init_resource::<ArenaAssets>();
         app.init_resource::<crate::resources::PlayerAssets>(); // [新增] 初始化玩家模块化资产
+        app.add_systems(Update, sync_player_assets.run_if(resource_changed::<Player>));
         app.init_resource::<CharacterAssets>(); // [核心修复] 注册角色资产资源
+        app.init_resource::<RunSeed>(); // 命途道种（开局时按玩家输入重置）
+        app.init_resource::<RunStats>(); // 本局战绩（开局时清零）
         app.add_systems(Startup, load_environment_assets);
         // 应用启动时设置相机与资产预热
         app.add_systems(Startup, (setup_camera, preload_assets, start_loading_first_frame));
@@ -96,9 +114,14 @@ fn check_wanjian_end(
 /// 此函数使用 world_mut().spawn() 而不是 commands.spawn()
 /// 在 Bevy 0.15 中，world_mut().spawn() 是立即生效的，而 commands.spawn() 是延迟的
 /// 这确保玩家实体在当前帧立即可用，避免重复创建
-pub fn init_player(mut commands: Commands, player_data: Option<Res<Player>>) {
+pub fn init_player(
+    mut commands: Commands,
+    player_data: Option<Res<Player>>,
+    cultivation_data: Option<Res<crate::components::Cultivation>>,
+) {
     // 关键修复：在闭包外部提取数据，避免生命周期冲突
     let initial_player = player_data.map(|p| p.clone());
+    let initial_cultivation = cultivation_data.map(|c| c.clone());
 
     // 使用 Deferred 视图访问 World 进行立即 spawn
     commands.queue(move |world: &mut World| {
@@ -111,12 +134,19 @@ pub fn init_player(mut commands: Commands, player_data: Option<Res<Player>>) {
             info!("【持久化】初始化修士: HP={}/{}, 灵石={}", player.hp, player.max_hp, player.gold);
             world.spawn((
                 player,
-                crate::components::Cultivation::new(),
+                initial_cultivation.unwrap_or_else(crate::components::Cultivation::new),
             ));
         }
     });
 }
 
+/// 修行者换了宗门（新开局或读档）时重载其法器模型
+fn sync_player_assets(player: Res<Player>, assets: Option<Res<PlayerAssets>>, asset_server: Res<AssetServer>, mut commands: Commands) {
+    if assets.is_some_and(|a| a.sect != player.sect) {
+        commands.insert_resource(PlayerAssets::load(&asset_server, player.sect));
+    }
+}
+
 /// 主菜单UI插件
 pub struct MenuPlugin;
 
@@ -141,6 +171,17 @@ impl Plugin for MenuPlugin {
         app.add_systems(OnExit(GameState::MainMenu), cleanup_main_menu);
         // 处理按钮点击
         app.add_systems(Update, handle_button_clicks.run_if(in_state(GameState::MainMenu)));
+        // 存档档位选择
+        app.add_systems(Update, handle_slot_picker_clicks.run_if(in_state(GameState::MainMenu)));
+        // 道种输入
+        app.init_resource::<SeedInput>();
+        app.add_systems(Update, handle_seed_input.run_if(in_state(GameState::MainMenu)));
+        // 天劫重数选择
+        app.add_systems(Update, handle_ascension_selector.run_if(in_state(GameState::MainMenu)));
+        // 开局择宗
+        app.add_systems(OnEnter(GameState::SectSelect), setup_sect_select);
+        app.add_systems(Update, handle_sect_select_clicks.run_if(in_state(GameState::SectSelect)));
+        app.add_systems(OnExit(GameState::SectSelect), cleanup_sect_select);
 
         // 在进入Map状态时设置地图UI - 已迁移至 MapPlugin
         // app.add_systems(OnEnter(GameState::Map)...);
@@ -161,6 +202,15 @@ impl Plugin for MenuPlugin {
         app.add_systems(OnEnter(GameState::Combat), reset_player_on_combat_start);
         // 在进入Combat状态时抽牌（必须在 setup_combat_ui 之后执行）
         app.add_systems(OnEnter(GameState::Combat), draw_cards_on_combat_start.after(setup_combat_ui));
+        // 读档续战：在重置之后写回修行者战况；每回合开始自动存档
+        app.init_resource::<CombatAutosave>();
+        app.init_resource::<SaveWriter>();
+        app.add_systems(OnExit(GameState::Combat), end_combat_autosave);
+        app.add_systems(OnEnter(GameState::Combat), (
+            reset_combat_autosave,
+            restore_combat_snapshot.after(init_player).after(reset_player_on_combat_start),
+        ));
+        app.add_systems(Update, autosave_combat.run_if(in_state(GameState::Combat)));
         // 在退出Combat状态时清理战斗UI
         app.add_systems(OnExit(GameState::Combat), cleanup_combat_ui);
         // 处理战斗界面按钮点击
@@ -170,10 +220,8 @@ impl Plugin for MenuPlugin {
             update_combat_ui,
             update_environment_ui, // 新增
         ).run_if(in_state(GameState::Combat)));
-        // 回合开始时抽牌
-        app.add_systems(Update, draw_cards_on_turn_start.run_if(in_state(GameState::Combat)));
-        // 处理手牌中的诅咒效果
-        app.add_systems(Update, handle_curse_effects.after(draw_cards_on_turn_start).run_if(in_state(GameState::Combat)));
+        // 处理手牌中的诅咒效果（回合开始抽牌由敌人队列结束时完成）
+        app.add_systems(Update, handle_curse_effects.after(process_enemy_turn_queue).run_if(in_state(GameState::Combat)));
         // 敌人队列处理系统
         app.add_systems(Update, process_enemy_turn_queue.run_if(in_state(GameState::Combat)));
         // 更新手牌UI
@@ -182,6 +230,13 @@ impl Plugin for MenuPlugin {
         app.add_systems(Update, handle_hand_card_hover.run_if(in_state(GameState::Combat)));
         // 处理出牌
         app.add_systems(Update, handle_card_play.run_if(in_state(GameState::Combat)));
+        // 单体功法选靶（取消与高亮）
+        app.init_resource::<CardTargeting>();
+        app.add_systems(Update, (
+            cancel_card_targeting.before(handle_card_play),
+            update_targeting_highlight.after(handle_card_play),
+            sync_divine_art_button.after(handle_card_play),
+        ).run_if(in_state(GameState::Combat)));
         // 检查战斗结束
         app.add_systems(Update, check_combat_end.run_if(in_state(GameState::Combat)));
         // 处理胜利延迟计时器
@@ -197,6 +252,7 @@ impl Plugin for MenuPlugin {
         app.add_systems(OnExit(GameState::Reward), cleanup_reward_ui);
         // 处理奖励界面点击
         app.add_systems(Update, handle_reward_clicks.run_if(in_state(GameState::Reward)));
+        app.add_systems(Update, handle_boss_relic_pick.run_if(in_state(GameState::Reward)));
         // 处理卡牌/遗物悬停显示详情
         app.add_systems(Update, handle_card_hover.run_if(in_state(GameState::Reward)));
         app.add_systems(Update, handle_relic_hover.run_if(in_state(GameState::Reward)));
@@ -206,7 +262,7 @@ impl Plugin for MenuPlugin {
         app.add_systems(Update, cleanup_hover_panels.run_if(in_state(GameState::Reward)));
 
         // 在进入GameOver状态时设置游戏结束UI
-        app.add_systems(OnEnter(GameState::GameOver), setup_game_over_ui);
+        app.add_systems(OnEnter(GameState::GameOver), setup_game_over_ui.after(settle_run));
         // 在退出GameOver状态时清理游戏结束UI
         app.add_systems(OnExit(GameState::GameOver), cleanup_game_over_ui);
         // 处理游戏结束界面按钮点击
@@ -217,17 +273,6 @@ impl Plugin for MenuPlugin {
     }
 }
 
-/// 渡劫计时器
-#[derive(Resource)]
-struct TribulationTimer {
-    /// 渡劫总时长
-    total_timer: Timer,
-    /// 天雷间隔
-    strike_timer: Timer,
-    /// 已降下天雷次数
-    strikes_count: u32,
-}
-
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
@@ -244,6 +289,10 @@ impl Plugin for GamePlugin {
             crate::systems::map::MapPlugin,
             crate::systems::background_music::BackgroundMusicPlugin, // 背景音乐插件
             crate::systems::audio::SfxPlugin, // 音效插件
+            crate::systems::card_data::CardDataPlugin, // 功法图谱（数据驱动）
+            crate::systems::relic_data::RelicDataPlugin, // 法宝图谱（数据驱动）
+            crate::resources::save::SavePlugin, // 存档档位与修行计时
+            crate::resources::profile::ProfilePlugin, // 修行档案与天劫重数
         ))
         .init_state::<GameState>()
         .init_resource::<Player>() // 初始化玩家全局资源
@@ -255,11 +304,6 @@ impl Plugin for GamePlugin {
         .init_resource::<HoveredCard>()
         .init_resource::<HoveredRelic>()
         .init_resource::<MousePosition>()
-        .insert_resource(TribulationTimer {
-            total_timer: Timer::from_seconds(10.0, TimerMode::Once),
-            strike_timer: Timer::from_seconds(1.5, TimerMode::Repeating),
-            strikes_count: 0,
-        })
         .add_event::<EnemyAttackEvent>()
         .add_event::<RelicObtainedEvent>()
         .add_event::<RelicTriggeredEvent>()
@@ -274,18 +318,15 @@ impl Plugin for GamePlugin {
         .add_systems(OnEnter(GameState::Reward), trigger_bgm_victory)
         .add_systems(OnEnter(GameState::Tribulation), trigger_bgm_tribulation)
         .add_systems(OnEnter(GameState::Event), trigger_bgm_map_exploration)
+        .add_systems(OnEnter(GameState::Treasure), trigger_bgm_map_exploration)
         .add_systems(OnEnter(GameState::GameOver), stop_bgm)
         // 其他系统
         .add_systems(OnEnter(GameState::Prologue), setup_prologue)
         .add_systems(Update, update_prologue.run_if(in_state(GameState::Prologue)))
         .add_systems(OnExit(GameState::Prologue), cleanup_prologue)
-        .add_systems(OnEnter(GameState::Tribulation), setup_tribulation)
         // Event系统已迁移至 EventPlugin
         .add_plugins(crate::systems::EventPlugin)
-        .add_systems(Update, (
-            update_tribulation.run_if(in_state(GameState::Tribulation)),
-        ))
-        .add_systems(OnExit(GameState::Tribulation), teardown_tribulation);
+        .add_plugins(crate::systems::TreasurePlugin);
     }
 }
 
@@ -299,26 +340,35 @@ fn trigger_bgm_main_menu(mut bgm_events: EventWriter<PlayBgmEvent>) {
     info!("【背景音乐】触发播放: {}", BgmType::MainMenu.chinese_name());
 }
 
-/// 背景音乐触发系统 - 地图探索
-fn trigger_bgm_map_exploration(mut bgm_events: EventWriter<PlayBgmEvent>) {
-    bgm_events.send(PlayBgmEvent::new(BgmType::MapExploration));
-    info!("【背景音乐】触发播放: {}", BgmType::MapExploration.chinese_name());
+/// 背景音乐触发系统 - 地图探索（按所在大境选曲）
+fn trigger_bgm_map_exploration(mut bgm_events: EventWriter<PlayBgmEvent>, map_progress: Option<Res<MapProgress>>) {
+    let bgm_type = ActAtlas::builtin().act(map_progress.map_or(0, |p| p.act)).music.map;
+    bgm_events.send(PlayBgmEvent::new(bgm_type));
+    info!("【背景音乐】触发播放: {}", bgm_type.chinese_name());
 }
 
-/// 背景音乐触发系统 - 战斗（根据敌人类型选择）
+/// 背景音乐触发系统 - 战斗（按所在大境与敌人类型选择）
 fn trigger_bgm_combat(
     mut bgm_events: EventWriter<PlayBgmEvent>,
     enemies: Query<&Enemy>,
+    tribulation: Option<Res<TribulationBattle>>,
+    map_progress: Option<Res<MapProgress>>,
 ) {
-    // 检查是否有Boss（GreatDemon类型或高血量敌人）
-    let has_boss = enemies.iter().any(|e| {
-        matches!(e.enemy_type, EnemyType::GreatDemon) || e.max_hp >= 100
-    });
+    // 渡劫之战延续雷劫曲
+    if tribulation.is_some() {
+        bgm_events.send(PlayBgmEvent::new(BgmType::Tribulation));
+        return;
+    }
+
+    // 检查是否有Boss（镇守节点或 GreatDemon 外形的妖物）
+    let has_boss = map_progress.as_ref().is_some_and(|p| p.is_at_boss())
+        || enemies.iter().any(|e| matches!(e.enemy_type, EnemyType::GreatDemon));
 
+    let music = ActAtlas::builtin().act(map_progress.map_or(0, |p| p.act)).music;
     let bgm_type = if has_boss {
-        BgmType::BossBattle
+        music.boss
     } else {
-        BgmType::NormalBattle
+        music.battle
     };
 
     bgm_events.send(PlayBgmEvent::new(bgm_type));
@@ -373,7 +423,7 @@ fn start_loading_first_frame(mut commands: Commands, asset_server: Res<AssetServ
 fn initial_state_redirection(
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    if crate::resources::save::GameStateSave::exists() {
+    if GameStateSave::exists() {
         info!("【启动重定向】检测到存档，直接进入主菜单");
         next_state.set(GameState::MainMenu);
     } else {
@@ -478,11 +528,7 @@ pub fn preload_assets(
     commands.insert_resource(arena_assets);
 
     // 5. 初始化玩家模块化资产
-    let player_assets = PlayerAssets {
-        body: asset_server.load("3d/player/warrior_main.glb#Scene0"),
-        weapon: asset_server.load("3d/player/sword_01.glb#Scene0"),
-    };
-    commands.insert_resource(player_assets);
+    commands.insert_resource(PlayerAssets::load(&asset_server, Sect::default()));
 
     info!("【发布准备】资产预热完成，系统进入巅峰性能模式");
 }
@@ -498,10 +544,22 @@ pub fn preload_assets(
 #[derive(Component)]
 pub struct MainMenuRoot;
 
-fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    seed_input: Res<SeedInput>,
+    profile: Res<MetaProfile>,
+    mut ascension: ResMut<Ascension>,
+) {
     let chinese_font: Handle<Font> = asset_server.load("fonts/Arial Unicode.ttf");
+    // 档案被重置或换机时，所选重数不超过已解锁的重数
+    if ascension.level > profile.max_ascension {
+        *ascension = Ascension::new(profile.max_ascension);
+    }
     let logo_handle: Handle<Image> = asset_server.load("textures/logo.png");
-    let has_save = crate::resources::save::GameStateSave::exists();
+    let slots = GameStateSave::list_slots();
+    let has_save = slots.iter().any(Option::is_some);
+    let slots_full = slots.iter().all(Option::is_some);
 
     commands
         .spawn((
@@ -567,9 +625,65 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                     Button,
                     StartGameButton,
                 )).with_children(|p| {
-                    let btn_text = if has_save { "重 塑 道 基" } else { "开 始 修 行" };
+                    // 档位已满时新开局会覆盖当前档位
+                    let btn_text = if slots_full { "重 塑 道 基" } else { "开 始 修 行" };
                     p.spawn((Text::new(btn_text), TextFont { font: chinese_font.clone(), font_size: 32.0, ..default() }, TextColor(Color::WHITE)));
                 });
+
+                // 藏经阁（跨局解锁）
+                btn_parent.spawn((
+                    Node {
+                        width: Val::Px(240.0), height: Val::Px(60.0),
+                        justify_content: JustifyContent::Center, align_items: AlignItems::Center,
+                        border: UiRect::all(Val::Px(2.0)), ..default()
+                    },
+                    BorderColor(Color::srgba(1.0, 0.85, 0.4, 0.5)),
+                    BackgroundColor(Color::srgba(0.3, 0.22, 0.1, 0.85)),
+                    Button,
+                    ArchiveButton,
+                )).with_children(|p| {
+                    p.spawn((Text::new("藏 经 阁"), TextFont { font: chinese_font.clone(), font_size: 32.0, ..default() }, TextColor(Color::WHITE)));
+                });
+
+                // 道种输入（新开局时生效，留空则随机）
+                btn_parent.spawn((
+                    Text::new(seed_input.label()),
+                    TextFont { font: chinese_font.clone(), font_size: 20.0, ..default() },
+                    TextColor(Color::srgba(1.0, 1.0, 1.0, 0.8)),
+                    SeedInputText,
+                ));
+
+                // 天劫重数（飞升过一次后解锁）
+                if profile.max_ascension > 0 {
+                    btn_parent.spawn(Node {
+                        column_gap: Val::Px(16.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    }).with_children(|row| {
+                        let spawn_step = |row: &mut ChildBuilder, label: &str, step: i32| {
+                            row.spawn((
+                                Node {
+                                    width: Val::Px(36.0), height: Val::Px(36.0),
+                                    justify_content: JustifyContent::Center, align_items: AlignItems::Center,
+                                    ..default()
+                                },
+                                BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.85)),
+                                Button,
+                                AscensionStepButton(step),
+                            )).with_children(|p| {
+                                p.spawn((Text::new(label), TextFont { font: chinese_font.clone(), font_size: 20.0, ..default() }, TextColor(Color::WHITE)));
+                            });
+                        };
+                        spawn_step(row, "◀", -1);
+                        row.spawn((
+                            Text::new(ascension_label(*ascension)),
+                            TextFont { font: chinese_font.clone(), font_size: 20.0, ..default() },
+                            TextColor(Color::srgb(1.0, 0.85, 0.4)),
+                            AscensionText,
+                        ));
+                        spawn_step(row, "▶", 1);
+                    });
+                }
             });
         });
 
@@ -613,6 +727,72 @@ struct ContinueGameButton;
 #[derive(Component)]
 struct QuitGameButton;
 
+/// 主菜单藏经阁按钮
+#[derive(Component)]
+struct ArchiveButton;
+
+/// 存档档位选择面板
+#[derive(Component)]
+struct SlotPickerRoot;
+
+/// 关闭档位选择面板
+#[derive(Component)]
+struct SlotPickerCloseButton;
+
+/// 档位操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SlotAction {
+    Load,
+    Duplicate,
+    Delete,
+}
+
+/// 档位操作按钮
+#[derive(Component)]
+struct SlotActionButton {
+    slot: usize,
+    action: SlotAction,
+}
+
+/// 主菜单道种文本标记
+#[derive(Component)]
+struct SeedInputText;
+
+/// 主菜单天劫重数文本标记
+#[derive(Component)]
+struct AscensionText;
+
+/// 天劫重数增减按钮
+#[derive(Component)]
+struct AscensionStepButton(i32);
+
+/// 天劫重数说明：第 n 重新增的修正（其下各重修正一并生效）
+fn ascension_label(ascension: Ascension) -> String {
+    match Ascension::description(ascension.level) {
+        Some(description) => format!("天劫第 {} 重：{}", ascension.level, description),
+        None => "天劫：凡尘（无修正）".to_string(),
+    }
+}
+
+/// 主菜单中玩家键入的道种
+#[derive(Resource, Default)]
+struct SeedInput {
+    text: String,
+}
+
+impl SeedInput {
+    /// 道种输入上限（36 进制 u64 最多 13 位，留余量给文字道种）
+    const MAX_LEN: usize = 16;
+
+    fn label(&self) -> String {
+        if self.text.is_empty() {
+            "道种：随机（键入以指定）".to_string()
+        } else {
+            format!("道种：{}", self.text)
+        }
+    }
+}
+
 // 地图组件已迁移至 src/components/map.rs
 // 视觉效果组件已迁移至 src/components/map.rs
 
@@ -620,6 +800,99 @@ struct QuitGameButton;
 // 按钮交互系统
 // ============================================================================
 
+/// 以给定道种与宗门开启新一局：重置随机流并按地图流推演新地图
+fn start_new_run(commands: &mut Commands, mut run_seed: RunSeed, sect: Sect) {
+    info!("【命途】本局道种: {}，宗门: {}", run_seed.code(), sect.name());
+    // 上一局（或刚读过的存档）的修行者、牌组与法宝不带入新局；开局按宗门与所选天劫重数修正，开局法宝取藏经阁中择用者
+    commands.queue(move |world: &mut World| {
+        let ascension = *world.resource::<Ascension>();
+        if ascension.level > 0 {
+            info!("【命途】天劫第 {} 重", ascension.level);
+        }
+        let stale: Vec<Entity> = world.query_filtered::<Entity, With<Player>>().iter(world).collect();
+        for entity in stale {
+            world.despawn(entity);
+        }
+        let mut player = Player::for_sect(sect);
+        ascension.apply_to_player(&mut player);
+
+        world.resource_mut::<MetaProfile>().total_runs += 1;
+        let starting = world.resource::<MetaProfile>().starting_relic(world.resource::<RelicLibrary>(), sect);
+        starting.on_pickup(&mut player);
+        let mut relics = RelicCollection::default();
+        relics.add_relic_forced(starting);
+        world.insert_resource(relics);
+        world.insert_resource(player);
+        world.insert_resource(crate::components::Cultivation::new());
+
+        let mut deck = world.resource::<CardLibrary>().starting_deck(sect);
+        let curses = ascension.starting_curses(deck.len() as u32);
+        deck.extend(curses);
+        world.insert_resource(PlayerDeck { cards: deck });
+
+        let first_act = ActAtlas::builtin().act(0);
+        world.insert_resource(MapProgress::new(&first_act.map_config(&ascension), &mut run_seed.map));
+        world.insert_resource(RoutePlan::default());
+        world.insert_resource(run_seed);
+        world.insert_resource(PlayTime::default());
+        world.insert_resource(EventHistory::default());
+        world.insert_resource(RunStats::default());
+    });
+}
+
+/// 主菜单道种输入：字母数字与中文直接键入，退格删除
+fn handle_seed_input(
+    mut keyboard_events: EventReader<bevy::input::keyboard::KeyboardInput>,
+    mut seed_input: ResMut<SeedInput>,
+    mut text_query: Query<&mut Text, With<SeedInputText>>,
+) {
+    use bevy::input::keyboard::Key;
+
+    let mut changed = false;
+    for event in keyboard_events.read() {
+        if !event.state.is_pressed() { continue; }
+        match &event.logical_key {
+            Key::Backspace => {
+                changed |= seed_input.text.pop().is_some();
+            }
+            Key::Character(chars) => {
+                for c in chars.chars().filter(|c| c.is_alphanumeric()) {
+                    if seed_input.text.chars().count() < SeedInput::MAX_LEN {
+                        seed_input.text.extend(c.to_uppercase());
+                        changed = true;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if changed {
+        for mut text in text_query.iter_mut() {
+            text.0 = seed_input.label();
+        }
+    }
+}
+
+/// 主菜单切换天劫重数（不超过档案中已解锁的重数）
+fn handle_ascension_selector(
+    button_query: Query<(&Interaction, &AscensionStepButton), Changed<Interaction>>,
+    mut text_query: Query<&mut Text, With<AscensionText>>,
+    mut ascension: ResMut<Ascension>,
+    profile: Res<MetaProfile>,
+    mut sfx_events: EventWriter<PlaySfxEvent>,
+) {
+    for (interaction, step) in button_query.iter() {
+        if !matches!(interaction, Interaction::Pressed) { continue; }
+        let level = (ascension.level as i32 + step.0).clamp(0, profile.max_ascension.min(MAX_ASCENSION) as i32);
+        *ascension = Ascension::new(level as u32);
+        sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
+        for mut text in text_query.iter_mut() {
+            text.0 = ascension_label(*ascension);
+        }
+    }
+}
+
 /// 处理主菜单按钮点击
 fn handle_button_clicks(
     mut commands: Commands,
@@ -628,51 +901,27 @@ fn handle_button_clicks(
         Query<&Interaction, (Changed<Interaction>, With<StartGameButton>)>,
         Query<&Interaction, (Changed<Interaction>, With<ContinueGameButton>)>,
         Query<&Interaction, (Changed<Interaction>, With<QuitGameButton>)>,
+        Query<&Interaction, (Changed<Interaction>, With<ArchiveButton>)>,
     )>,
     mut exit: EventWriter<AppExit>,
     mut sfx_events: EventWriter<PlaySfxEvent>,
+    asset_server: Res<AssetServer>,
+    picker_query: Query<Entity, With<SlotPickerRoot>>,
 ) {
-    // 1. 开始修行（重塑道基）
+    // 1. 开始修行（先择宗门）
     for interaction in button_queries.p0().iter() {
-        if matches!(interaction, Interaction::Pressed) {
+        if matches!(interaction, Interaction::Pressed) && picker_query.is_empty() {
             sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
-            info!("【主菜单】开始新修行，删除旧存档");
-            crate::resources::save::GameStateSave::delete_save();
-            next_state.set(GameState::Prologue);
+            next_state.set(GameState::SectSelect);
             return;
         }
     }
 
-    // 2. 继续修行（读档）
+    // 2. 继续修行（打开档位选择）
     for interaction in button_queries.p1().iter() {
-        if matches!(interaction, Interaction::Pressed) {
+        if matches!(interaction, Interaction::Pressed) && picker_query.is_empty() {
             sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
-            info!("【主菜单】继续修行，正在读取识海存档...");
-            
-            // [性能优化] 将同步 IO 移入队列，防止主线程卡顿
-            commands.queue(move |world: &mut World| {
-                match crate::resources::save::GameStateSave::load_from_disk() {
-                    Ok(save) => {
-                        world.insert_resource(save.player.clone());
-                        world.insert_resource(save.cultivation.clone());
-                        world.insert_resource(PlayerDeck { cards: save.deck.clone() });
-                        world.insert_resource(RelicCollection { relic: save.relics.clone() });
-                        world.insert_resource(MapProgress::from_save(
-                            save.map_nodes.clone(),
-                            save.current_map_node_id,
-                            save.current_map_layer,
-                        ));
-                        
-                        if let Some(mut next_state) = world.get_resource_mut::<NextState<GameState>>() {
-                            next_state.set(GameState::Map);
-                        }
-                        info!("【存档系统】读档成功，准备重返大地图");
-                    }
-                    Err(e) => {
-                        error!("【存档系统】读取识海失败: {}", e);
-                    }
-                }
-            });
+            spawn_slot_picker(&mut commands, asset_server.load("fonts/Arial Unicode.ttf"));
             return;
         }
     }
@@ -684,6 +933,353 @@ fn handle_button_clicks(
             exit.send(AppExit::Success);
         }
     }
+
+    // 4. 藏经阁
+    for interaction in button_queries.p3().iter() {
+        if matches!(interaction, Interaction::Pressed) && picker_query.is_empty() {
+            sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
+            next_state.set(GameState::Archive);
+            return;
+        }
+    }
+}
+
+/// 择宗界面根节点
+#[derive(Component)]
+struct SectSelectRoot;
+
+/// 择宗按钮（`None` 为返回主菜单）
+#[derive(Component)]
+struct SectSelectButton(Option<Sect>);
+
+/// 生成择宗界面：三宗的道行、灵力、心法与本命法宝
+fn setup_sect_select(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    profile: Res<MetaProfile>,
+    relics: Res<RelicLibrary>,
+) {
+    let font: Handle<Font> = asset_server.load("fonts/Arial Unicode.ttf");
+    let text_font = |size: f32| TextFont { font: font.clone(), font_size: size, ..default() };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(30.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.06, 0.06, 0.09)),
+            SectSelectRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new("择 宗 入 道"), text_font(52.0), TextColor(Color::srgb(1.0, 0.85, 0.4))));
+
+            parent.spawn(Node { column_gap: Val::Px(28.0), ..default() }).with_children(|row| {
+                for sect in Sect::ALL {
+                    let relic = profile.starting_relic(&relics, sect);
+                    row.spawn((
+                        Node {
+                            width: Val::Px(320.0),
+                            flex_direction: FlexDirection::Column,
+                            row_gap: Val::Px(12.0),
+                            padding: UiRect::all(Val::Px(18.0)),
+                            border: UiRect::all(Val::Px(2.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.12, 0.12, 0.16, 0.95)),
+                        BorderColor(sect.color()),
+                        Button,
+                        SectSelectButton(Some(sect)),
+                    )).with_children(|card| {
+                        card.spawn((Text::new(sect.name()), text_font(34.0), TextColor(sect.color())));
+                        card.spawn((Text::new(sect.description()), text_font(16.0), TextColor(Color::srgb(0.8, 0.8, 0.75))));
+                        card.spawn((
+                            Text::new(format!("道行 {} · 灵力 {}", sect.base_max_hp(), sect.base_energy())),
+                            text_font(18.0),
+                            TextColor(Color::WHITE),
+                        ));
+                        card.spawn((
+                            Text::new(format!("【{}】{}", sect.mechanic_name(), sect.mechanic_description())),
+                            text_font(16.0),
+                            TextColor(Color::srgb(0.6, 0.9, 0.7)),
+                        ));
+                        card.spawn((
+                            Text::new(format!("开局法宝：{}\n{}", relic.name, relic.description)),
+                            text_font(15.0),
+                            TextColor(Color::srgb(1.0, 0.85, 0.4)),
+                        ));
+                    });
+                }
+            });
+
+            parent.spawn((
+                Node {
+                    width: Val::Px(200.0), height: Val::Px(56.0),
+                    justify_content: JustifyContent::Center, align_items: AlignItems::Center,
+                    border: UiRect::all(Val::Px(2.0)), ..default()
+                },
+                BackgroundColor(Color::srgba(0.1, 0.1, 0.15, 0.9)),
+                BorderColor(Color::srgb(0.3, 0.3, 0.5)),
+                Button,
+                SectSelectButton(None),
+            )).with_children(|btn| {
+                btn.spawn((Text::new("返 回"), text_font(26.0), TextColor(Color::srgb(0.8, 0.8, 0.8))));
+            });
+        });
+}
+
+/// 择定宗门后开局（优先占用空档位，档位已满时覆盖当前档位）
+fn handle_sect_select_clicks(
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+    seed_input: Res<SeedInput>,
+    save_slot: Res<ActiveSaveSlot>,
+    button_query: Query<(&Interaction, &SectSelectButton), Changed<Interaction>>,
+    save_writer: Res<SaveWriter>,
+    mut sfx_events: EventWriter<PlaySfxEvent>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if !matches!(interaction, Interaction::Pressed) { continue; }
+        sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
+
+        let Some(sect) = button.0 else {
+            next_state.set(GameState::MainMenu);
+            return;
+        };
+        let slot = GameStateSave::first_empty_slot().unwrap_or(save_slot.0);
+        info!("【择宗】拜入{}，开始新修行，使用{}", sect.name(), SLOT_NAMES[slot]);
+        save_writer.delete(slot);
+        commands.insert_resource(ActiveSaveSlot(slot));
+        start_new_run(&mut commands, RunSeed::from_input(&seed_input.text), sect);
+        next_state.set(GameState::Prologue);
+        return;
+    }
+}
+
+fn cleanup_sect_select(mut commands: Commands, query: Query<Entity, With<SectSelectRoot>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// 档位预览文字
+fn slot_summary_label(summary: &SlotSummary) -> String {
+    let ascension = if summary.ascension > 0 { format!(" · 天劫第 {} 重", summary.ascension) } else { String::new() };
+    format!(
+        "{} · {}{} · 道行 {}/{} · 灵石 {} · {} · 累计第 {} 层 · 功法 {} 张\n修行 {} · 道种 {} · {}",
+        summary.sect.name(), summary.realm.name(), ascension, summary.hp, summary.max_hp, summary.gold,
+        ActAtlas::builtin().act(summary.act).name.replace(' ', ""), summary.layer + 1, summary.deck_size,
+        summary.play_time_label(), RunSeed::new(summary.seed).code(), summary.saved_at_label(),
+    )
+}
+
+/// 生成档位选择面板（随主菜单一同销毁）
+fn spawn_slot_picker(commands: &mut Commands, font: Handle<Font>) {
+    let slots = GameStateSave::list_slots();
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            // 遮住下方主菜单按钮
+            bevy::ui::FocusPolicy::Block,
+            ZIndex(10),
+            SlotPickerRoot,
+            MainMenuRoot,
+        ))
+        .with_children(|overlay| {
+            overlay.spawn((
+                Node {
+                    width: Val::Px(760.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(12.0),
+                    padding: UiRect::all(Val::Px(20.0)),
+                    border: UiRect::all(Val::Px(2.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.08, 0.08, 0.1, 0.95)),
+                BorderColor(Color::srgba(1.0, 0.85, 0.4, 0.5)),
+            )).with_children(|panel| {
+                panel.spawn((
+                    Text::new("识 海 档 位"),
+                    TextFont { font: font.clone(), font_size: 30.0, ..default() },
+                    TextColor(Color::srgb(1.0, 0.85, 0.4)),
+                ));
+
+                for (slot, summary) in slots.iter().enumerate() {
+                    panel.spawn((
+                        Node {
+                            width: Val::Percent(100.0),
+                            column_gap: Val::Px(12.0),
+                            align_items: AlignItems::Center,
+                            padding: UiRect::all(Val::Px(10.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.05)),
+                    )).with_children(|row| {
+                        row.spawn((
+                            Text::new(SLOT_NAMES[slot]),
+                            TextFont { font: font.clone(), font_size: 22.0, ..default() },
+                            TextColor(Color::srgb(1.0, 0.85, 0.4)),
+                            Node { width: Val::Px(90.0), ..default() },
+                        ));
+                        let (label, color) = match summary {
+                            Some(summary) => (slot_summary_label(summary), Color::WHITE),
+                            None => ("空档位".to_string(), Color::srgba(1.0, 1.0, 1.0, 0.4)),
+                        };
+                        row.spawn((
+                            Text::new(label),
+                            TextFont { font: font.clone(), font_size: 15.0, ..default() },
+                            TextColor(color),
+                            Node { flex_grow: 1.0, ..default() },
+                        ));
+                        if summary.is_some() {
+                            for (action, text, tint) in [
+                                (SlotAction::Load, "读取", Color::srgba(0.1, 0.3, 0.1, 0.9)),
+                                (SlotAction::Duplicate, "复刻", Color::srgba(0.15, 0.2, 0.35, 0.9)),
+                                (SlotAction::Delete, "删除", Color::srgba(0.4, 0.1, 0.1, 0.9)),
+                            ] {
+                                row.spawn((
+                                    Node {
+                                        width: Val::Px(64.0), height: Val::Px(36.0),
+                                        justify_content: JustifyContent::Center, align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    BackgroundColor(tint),
+                                    Button,
+                                    SlotActionButton { slot, action },
+                                )).with_children(|b| {
+                                    b.spawn((Text::new(text), TextFont { font: font.clone(), font_size: 18.0, ..default() }, TextColor(Color::WHITE)));
+                                });
+                            }
+                        }
+                    });
+                }
+
+                panel.spawn((
+                    Node {
+                        width: Val::Px(160.0), height: Val::Px(44.0),
+                        align_self: AlignSelf::Center,
+                        justify_content: JustifyContent::Center, align_items: AlignItems::Center,
+                        border: UiRect::all(Val::Px(1.0)),
+                        ..default()
+                    },
+                    BorderColor(Color::srgba(1.0, 1.0, 1.0, 0.3)),
+                    BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.85)),
+                    Button,
+                    SlotPickerCloseButton,
+                )).with_children(|b| {
+                    b.spawn((Text::new("返 回"), TextFont { font: font.clone(), font_size: 22.0, ..default() }, TextColor(Color::WHITE)));
+                });
+            });
+        });
+}
+
+/// 读取档位并重返大地图
+fn load_save_slot(commands: &mut Commands, slot: usize) {
+    info!("【主菜单】继续修行，正在读取{}...", SLOT_NAMES[slot]);
+    // [性能优化] 将同步 IO 移入队列，防止主线程卡顿
+    commands.queue(move |world: &mut World| {
+        match GameStateSave::load_slot(slot) {
+            Ok(save) => {
+                // 旧的修行者实体作废，由 init_player 按存档重建
+                let stale: Vec<Entity> = world.query_filtered::<Entity, With<Player>>().iter(world).collect();
+                for entity in stale {
+                    world.despawn(entity);
+                }
+                world.insert_resource(save.restore_player());
+                world.insert_resource(save.cultivation.clone());
+                world.insert_resource(PlayerDeck { cards: save.deck.clone() });
+                world.insert_resource(RelicCollection { relic: save.relics.clone() });
+                world.insert_resource(MapProgress::from_save(
+                    save.map_nodes.clone(),
+                    save.current_map_node_id,
+                    save.current_map_layer,
+                    save.act,
+                    save.unknown_odds,
+                ));
+                world.insert_resource(RoutePlan::default());
+                world.insert_resource(RunSeed::resume(save.seed, save.streams));
+                world.insert_resource(ActiveSaveSlot(slot));
+                world.insert_resource(PlayTime { seconds: save.play_time });
+                world.insert_resource(Ascension::new(save.ascension));
+                world.insert_resource(EventHistory { seen: save.seen_events.clone() });
+                world.insert_resource(save.run_stats);
+
+                // 战斗中途的存档直接回到战场
+                let target = if let Some(snapshot) = save.combat {
+                    world.insert_resource(PendingCombatResume(snapshot));
+                    info!("【存档系统】读档成功，重返战场");
+                    GameState::Combat
+                } else {
+                    info!("【存档系统】读档成功，准备重返大地图");
+                    GameState::Map
+                };
+                if let Some(mut next_state) = world.get_resource_mut::<NextState<GameState>>() {
+                    next_state.set(target);
+                }
+            }
+            Err(e) => {
+                error!("【存档系统】读取识海失败: {}", e);
+            }
+        }
+    });
+}
+
+/// 档位选择面板：读取、复刻、删除与关闭
+fn handle_slot_picker_clicks(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    action_query: Query<(&Interaction, &SlotActionButton), Changed<Interaction>>,
+    close_query: Query<&Interaction, (Changed<Interaction>, With<SlotPickerCloseButton>)>,
+    picker_query: Query<Entity, With<SlotPickerRoot>>,
+    mut sfx_events: EventWriter<PlaySfxEvent>,
+) {
+    let close_picker = |commands: &mut Commands| {
+        for entity in picker_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+    };
+
+    if close_query.iter().any(|i| matches!(i, Interaction::Pressed)) {
+        sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
+        close_picker(&mut commands);
+        return;
+    }
+
+    for (interaction, button) in action_query.iter() {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
+        match button.action {
+            SlotAction::Load => {
+                load_save_slot(&mut commands, button.slot);
+                return;
+            }
+            SlotAction::Duplicate => {
+                if let Err(e) = GameStateSave::duplicate_slot(button.slot) {
+                    warn!("【存档系统】复刻{}失败: {}", SLOT_NAMES[button.slot], e);
+                }
+            }
+            SlotAction::Delete => GameStateSave::delete_slot(button.slot),
+        }
+        // 档位变动后重建面板
+        close_picker(&mut commands);
+        spawn_slot_picker(&mut commands, asset_server.load("fonts/Arial Unicode.ttf"));
+        return;
+    }
 }
 
 /// 处理地图界面按钮点击
@@ -824,6 +1420,10 @@ struct RewardRelicButton {
     relic_id: RelicId,
 }
 
+/// 首领遗宝的候选区（择定后整体隐去）
+#[derive(Component)]
+struct BossRelicChoiceArea;
+
 #[derive(Component)]
 struct RestartButton;
 
@@ -858,6 +1458,7 @@ fn setup_combat_ui(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut graphs: ResMut<Assets<AnimationGraph>>,
+    (mut run_seed, resume, ascension, tribulation, card_library, encounter): (ResMut<RunSeed>, Option<Res<PendingCombatResume>>, Res<Ascension>, Option<Res<TribulationBattle>>, Res<CardLibrary>, Option<Res<EventEncounter>>),
 ) {
     let character_assets = if let Some(ca) = character_assets_opt { ca } else { error!("缺失 CharacterAssets"); return; };
     let player_deck = if let Some(pd) = player_deck_opt { pd } else { error!("缺失 PlayerDeck"); return; };
@@ -893,22 +1494,57 @@ fn setup_combat_ui(
     // --- 多敌人生成 (v0.2.0 生成式系统) ---
     if enemy_query.is_empty() {
         use rand::Rng;
-        let mut rng = rand::thread_rng();
+        let rng = &mut run_seed.combat;
         
         let current_layer = map_progress.current_layer;
+        let act = ActAtlas::builtin().act(map_progress.act);
+
+        let spawned: Vec<GeneratedEnemy> = if let Some(resume) = &resume {
+            // 读档续战：按存档重建仍存活的敌人
+            resume.0.enemies.iter()
+                .filter(|enemy| enemy.hp > 0)
+                .map(|enemy| EnemyGenerator::restore(enemy.clone(), is_boss_node))
+                .collect()
+        } else if let Some(battle) = &tribulation {
+            // 渡劫之战：劫云独自压阵
+            vec![battle.spawn_cloud()]
+        } else if let Some(encounter) = &encounter {
+            // 机缘引发的战斗：按事件点名的妖物生成
+            let roster = crate::resources::enemy_data::EnemyRoster::builtin();
+            encounter.enemies.iter()
+                .filter_map(|id| roster.get(id))
+                .enumerate()
+                .map(|(i, archetype)| {
+                    let mut generated = EnemyGenerator::generate_from(archetype, &act.scaling, current_layer, i as u32, rng);
+                    ascension.apply_to_enemy(&mut generated.enemy, false);
+                    generated
+                })
+                .collect()
+        } else if map_progress.is_at_elite() {
+            // 精英节点：按本界的精英阵容生成
+            let mut party = EnemyGenerator::generate_elite(act, current_layer, rng);
+            for generated in &mut party {
+                ascension.apply_to_enemy(&mut generated.enemy, false);
+            }
+            party
+        } else {
+            // 如果是 Boss 节点，固定生成 1 个 BOSS；否则随机生成 1~3 个小怪
+            let num_enemies = if is_boss_node { 1 } else { rng.gen_range(1..=3) };
+            (0..num_enemies).map(|i| {
+                // 使用 EnemyGenerator 生成敌人
+                let mut generated = if is_boss_node {
+                    EnemyGenerator::generate_boss(act, current_layer, i as u32, rng)
+                } else {
+                    EnemyGenerator::generate_enemy(act, current_layer, i as u32, rng)
+                };
+                ascension.apply_to_enemy(&mut generated.enemy, is_boss_node);
+                generated
+            }).collect()
+        };
+        let num_enemies = spawned.len();
 
-        // 如果是 Boss 节点，固定生成 1 个 BOSS；否则随机生成 1~3 个小怪
-        let num_enemies = if is_boss_node { 1 } else { rng.gen_range(1..=3) };
-
-        for i in 0..num_enemies {
-            let enemy_id = i as u32;
-            
-            // 使用 EnemyGenerator 生成敌人
-            let gen_enemy = if is_boss_node {
-                EnemyGenerator::generate_boss(current_layer, enemy_id)
-            } else {
-                EnemyGenerator::generate_enemy(current_layer, enemy_id)
-            };
+        for (i, gen_enemy) in spawned.into_iter().enumerate() {
+            let enemy_id = gen_enemy.enemy.id;
 
             // 提取关键信息用于后续渲染
             let e_type = gen_enemy.enemy.enemy_type;
@@ -967,6 +1603,24 @@ fn setup_combat_ui(
 
             let ui_left = 640.0 + x_world - 80.0;
             commands.entity(root_entity).with_children(|root| {
+                // 选靶区域：覆盖敌人立绘，选靶时高亮
+                root.spawn((
+                    Button,
+                    Node {
+                        position_type: PositionType::Absolute,
+                        left: Val::Px(ui_left),
+                        bottom: Val::Px(260.0),
+                        width: Val::Px(160.0),
+                        height: Val::Px(210.0),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::NONE),
+                    BorderColor(Color::NONE),
+                    EnemyTargetZone { owner: enemy_entity },
+                    ZIndex(140),
+                ));
+
                 root.spawn((
                     Node { position_type: PositionType::Absolute, left: Val::Px(ui_left), bottom: Val::Px(480.0), flex_direction: FlexDirection::Column, align_items: AlignItems::Center, ..default() },
                     EnemyStatusUi { owner: enemy_entity },
@@ -1038,6 +1692,7 @@ fn setup_combat_ui(
                         TextFont { font: chinese_font.clone(), font_size: 12.0, ..default() },
                         TextColor(Color::srgb(0.7, 0.4, 1.0)), // 紫色，醒目且符合状态色彩
                         StatusIndicator { owner: enemy_entity },
+                        Interaction::default(),
                     ));
 
                     // [大作级] 意图显示容器
@@ -1081,16 +1736,31 @@ fn setup_combat_ui(
         player_assets,
     );
 
-    commands.insert_resource(CombatState::default());
-    
-    // 准备全量牌组
-    let deck_cards = player_deck.cards.clone();
-    info!("【战斗】准备战斗牌组: {} 张", deck_cards.len());
-    
-    // 仅初始化空的堆栈，将抽牌权交给专门的 draw_cards 系统
-    commands.spawn(DrawPile::new(deck_cards));
-    commands.spawn(DiscardPile::new());
-    commands.spawn(Hand::new(10));
+    if let Some(resume) = &resume {
+        // 读档续战：牌堆与环境原样恢复，本回合已抽过牌
+        let snapshot = &resume.0;
+        info!("【战斗】续战：第 {} 回合，手牌 {} 张", snapshot.turn, snapshot.hand.cards.len());
+        commands.insert_resource(CombatState { cards_drawn_this_turn: true, ..default() });
+        commands.insert_resource(snapshot.environment.clone());
+        commands.spawn(snapshot.draw_pile.clone());
+        commands.spawn(snapshot.discard_pile.clone());
+        commands.spawn(snapshot.hand.clone());
+    } else {
+        commands.insert_resource(CombatState::default());
+
+        // 准备全量牌组
+        let mut deck_cards = player_deck.cards.clone();
+        // 渡劫功法只在本场加入剑冢
+        if tribulation.is_some() {
+            deck_cards.extend(card_library.tribulation_cards());
+        }
+        info!("【战斗】准备战斗牌组: {} 张", deck_cards.len());
+
+        // 仅初始化空的堆栈，将抽牌权交给专门的 draw_cards 系统
+        commands.spawn(DrawPile::new(deck_cards));
+        commands.spawn(DiscardPile::new());
+        commands.spawn(Hand::new(10));
+    }
     let player_data = player_query.get_single().ok();
     let player_entity = player_data.map(|(e, _, _)| e);
 
@@ -1114,7 +1784,8 @@ fn setup_combat_ui(
             BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.85)), TopBar,
         )).with_children(|bar| {
             if let Some((_, p, c)) = player_data {
-                let r_name = match c.realm { crate::components::cultivation::Realm::QiRefining => "炼气期", crate::components::cultivation::Realm::FoundationEstablishment => "筑基期", crate::components::cultivation::Realm::GoldenCore => "金丹期", _ => "修仙者" };
+                let passives = c.realm.passives().map(|p| p.name()).collect::<Vec<_>>().join(" · ");
+                let r_name = if passives.is_empty() { c.realm.name().to_string() } else { format!("{}（{}）", c.realm.name(), passives) };
                 bar.spawn((Text::new(format!("境界: {}", r_name)), TextFont { font: chinese_font.clone(), font_size: 20.0, ..default() }, TextColor(Color::srgb(0.4, 1.0, 0.4))));
                 bar.spawn((Text::new(format!("道行: {}/{}", p.hp, p.max_hp)), TextFont { font: chinese_font.clone(), font_size: 20.0, ..default() }, TextColor(Color::srgb(1.0, 0.4, 0.4)), TopBarHpText));
                 bar.spawn((Text::new(format!("灵石: {}", p.gold)), TextFont { font: chinese_font.clone(), font_size: 20.0, ..default() }, TextColor(Color::srgb(1.0, 0.8, 0.2)), TopBarGoldText));
@@ -1204,6 +1875,7 @@ fn setup_combat_ui(
                         TextFont { font: chinese_font.clone(), font_size: 14.0, ..default() },
                         TextColor(Color::srgb(0.7, 0.4, 1.0)),
                         StatusIndicator { owner: player_entity.unwrap_or(Entity::PLACEHOLDER) },
+                        Interaction::default(),
                     ));
 
                     status_row.spawn((
@@ -1227,6 +1899,13 @@ fn setup_combat_ui(
             Button, Node { position_type: PositionType::Absolute, right: Val::Px(100.0), bottom: Val::Px(140.0), width: Val::Px(160.0), height: Val::Px(50.0), justify_content: JustifyContent::Center, align_items: AlignItems::Center, border: UiRect::all(Val::Px(2.0)), ..default() },
             BackgroundColor(Color::srgb(0.2, 0.4, 0.2)), BorderColor(Color::BLACK), EndTurnButton
         )).with_children(|btn| { btn.spawn((Text::new("结束回合"), TextFont { font: chinese_font.clone(), font_size: 24.0, ..default() }, TextColor(Color::WHITE))); });
+        // 境界神通（每场一次）
+        if let Some(art) = player_data.and_then(|(_, _, c)| c.realm.divine_art()) {
+            root.spawn((
+                Button, Node { position_type: PositionType::Absolute, right: Val::Px(100.0), bottom: Val::Px(200.0), width: Val::Px(160.0), height: Val::Px(50.0), justify_content: JustifyContent::Center, align_items: AlignItems::Center, border: UiRect::all(Val::Px(2.0)), ..default() },
+                BackgroundColor(Color::srgb(0.45, 0.3, 0.05)), BorderColor(Color::srgb(1.0, 0.85, 0.4)), DivineArtButton,
+            )).with_children(|btn| { btn.spawn((Text::new(format!("神通·{}", art.name)), TextFont { font: chinese_font.clone(), font_size: 20.0, ..default() }, TextColor(Color::WHITE))); });
+        }
         root.spawn((
             Node { 
                 position_type: PositionType::Absolute, 
@@ -1403,8 +2082,8 @@ fn handle_curse_effects(
     if let Ok(hand) = hand_query.get_single() {
         if let Ok(mut player) = player_query.get_single_mut() {
             let env_ref = env.as_ref().map(|r| r.as_ref());
-            for card in &hand.cards {
-                match &card.effect {
+            for (card, effect) in hand.cards.iter().flat_map(|c| c.effects.iter().map(move |e| (c, e))) {
+                match effect {
                     CardEffect::CurseDamage { amount } => {
                         info!("【诅咒】{} 抽动，造成 {} 点伤害", card.name, amount);
                         player.take_damage_with_env(*amount, env_ref);
@@ -1413,7 +2092,7 @@ fn handle_curse_effects(
                     }
                     CardEffect::CurseWeakness => {
                         info!("【诅咒】{} 侵蚀，施加虚弱", card.name);
-                        player.weakness += 1;
+                        player.apply_status(WEAKNESS, 1);
                     }
                     _ => {}
                 }
@@ -1453,6 +2132,7 @@ pub fn cleanup_combat_ui(
     for entity in emitter_query.iter() { commands.entity(entity).despawn_recursive(); }
     for entity in piles_query.iter() { commands.entity(entity).despawn_recursive(); }
     for entity in light_query.iter() { commands.entity(entity).despawn_recursive(); }
+    commands.insert_resource(CardTargeting::default());
     
     info!("【战斗清理】已彻底销毁所有战斗相关实体（含光源）");
 }
@@ -1462,12 +2142,17 @@ fn handle_combat_button_clicks(
     mut commands: Commands,
     mut next_state: ResMut<NextState<GameState>>,
     mut combat_state: ResMut<CombatState>,
-    enemy_query: Query<(Entity, &Enemy)>,
-    mut player_query: Query<&mut Player>, // 新增
+    mut player_query: Query<(&mut Player, &crate::components::Cultivation)>,
+    mut enemy_query: Query<(Entity, &mut Enemy)>,
     mut queue: ResMut<EnemyActionQueue>,
     mut hand_query: Query<&mut Hand>,
+    mut draw_pile_query: Query<&mut DrawPile>,
     mut discard_pile_query: Query<&mut DiscardPile>,
     hand_area_query: Query<Entity, With<HandArea>>,
+    env: Option<Res<Environment>>,
+    mut run_seed: ResMut<RunSeed>,
+    mut relics: ResMut<RelicCollection>,
+    mut relic_events: EventWriter<RelicTriggeredEvent>,
     mut button_queries: ParamSet<(
         Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<EndTurnButton>)>,
         Query<(&Interaction, &mut BackgroundColor), (Changed<Interaction>, With<ReturnToMapButton>)>,
@@ -1477,24 +2162,30 @@ fn handle_combat_button_clicks(
         if matches!(interaction, Interaction::Pressed) {
             info!("【战斗】玩家结束回合，队列初始化开始行动");
 
-            // 0. 重置剑意
-            if let Ok(mut player) = player_query.get_single_mut() {
-                player.reset_sword_intent();
-            }
-
-            // 1. 立即清空手牌并进入弃牌堆
-            if let Ok(mut hand) = hand_query.get_single_mut() {
-                if let Ok(mut discard_pile) = discard_pile_query.get_single_mut() {
-                    while let Some(card) = hand.remove_card(0) {
-                        discard_pile.add_card(card);
-                    }
-                    info!("【战斗】手牌已清空至弃牌堆");
+            // 1. 规则层：剑意归零，手牌进入弃牌堆
+            let Some(mut engine) = snapshot_combat_engine(
+                &player_query, &enemy_query, &draw_pile_query, &discard_pile_query, &hand_query,
+                env.as_deref(), combat_state.phase, &run_seed, &relics,
+            ) else { continue; };
+            let combat_events = engine.end_turn();
+            write_back_combat_engine(
+                &engine, &mut player_query, &mut enemy_query, &mut draw_pile_query, &mut discard_pile_query, &mut hand_query, &mut run_seed, &mut relics,
+            );
+            send_relic_triggered_events(&combat_events, &mut relic_events);
+            for event in &combat_events {
+                match event {
+                    CombatEvent::SectChargeReleased { stacks } => info!("【丹火】{} 层丹火凝为护盾", stacks),
+                    CombatEvent::RealmPassiveTriggered { passive } => info!("【境界】{}：{}", passive.name(), passive.description()),
+                    _ => {}
                 }
             }
+            info!("【战斗】手牌已清空至弃牌堆（保留 {} 张）", engine.hand.cards.len());
 
-            // [关键修复] 强制销毁 UI，避免视觉残留或闪烁
-            if let Ok(hand_area) = hand_area_query.get_single() {
-                commands.entity(hand_area).despawn_descendants();
+            // [关键修复] 强制销毁 UI，避免视觉残留或闪烁（留牌时由手牌 UI 按新构成重建）
+            if engine.hand.is_empty() {
+                if let Ok(hand_area) = hand_area_query.get_single() {
+                    commands.entity(hand_area).despawn_descendants();
+                }
             }
             
             // 2. 搜集所有存活敌人进入行动队列
@@ -1511,7 +2202,7 @@ fn handle_combat_button_clicks(
             queue.timer = Timer::from_seconds(0.1, TimerMode::Once); // 立即开始第一个动作
             queue.processing = true;
 
-            combat_state.phase = TurnPhase::EnemyTurn;
+            combat_state.phase = engine.phase;
         }
     }
 
@@ -1525,16 +2216,13 @@ fn handle_combat_button_clicks(
 
 /// 核心系统：逐个处理敌人回合动作
 pub fn process_enemy_turn_queue(
-    commands: Commands,
     mut next_state: ResMut<NextState<GameState>>, 
-    meshes: ResMut<Assets<Mesh>>,
-    materials: ResMut<Assets<StandardMaterial>>,
-    asset_server: Res<AssetServer>,
     mut queue: ResMut<EnemyActionQueue>,
     combat_state_opt: Option<ResMut<CombatState>>,
     mut player_query: Query<(&mut Player, &crate::components::Cultivation)>,
-    mut enemy_query: Query<&mut Enemy>,
+    mut enemy_query: Query<(Entity, &mut Enemy)>,
     mut hand_query: Query<&mut Hand>,
+    mut draw_pile_query: Query<&mut DrawPile>,
     mut discard_pile_query: Query<&mut DiscardPile>,
     events: (
         EventWriter<CharacterAnimationEvent>,
@@ -1542,13 +2230,19 @@ pub fn process_enemy_turn_queue(
         EventWriter<ScreenEffectEvent>,
         EventWriter<EnemyAttackEvent>,
         EventWriter<PlaySfxEvent>,
+        EventWriter<StatusEffectEvent>,
+        EventWriter<RelicTriggeredEvent>,
     ),
     mut enemy_sprite_query: Query<(Entity, &crate::components::sprite::EnemySpriteMarker, &mut Transform), Without<crate::components::sprite::PlayerSpriteMarker>>,
     player_sprite_query: Query<&Transform, With<crate::components::sprite::PlayerSpriteMarker>>,
+    player_entity_query: Query<Entity, With<Player>>,
     time: Res<Time>,
     env: Option<Res<Environment>>,
+    (mut run_seed, mut relics, tribulation): (ResMut<RunSeed>, ResMut<RelicCollection>, Option<Res<TribulationBattle>>),
 ) {
-    let (mut anim_events, mut effect_events, mut screen_events, mut attack_events, mut sfx_events) = events;
+    let (mut anim_events, mut effect_events, mut screen_events, mut attack_events, mut sfx_events, mut status_events, mut relic_events) = events;
+    // 渡劫之战中道行耗尽不致身死，由渡劫插件结算失败代价
+    let mortal = tribulation.is_none();
     
     // 获取修行者坐标用于特效定位
     let player_pos = player_sprite_query.get_single().map(|t| t.translation).unwrap_or(Vec3::new(-4.0, 0.0, 0.0));
@@ -1561,145 +2255,184 @@ pub fn process_enemy_turn_queue(
     queue.timer.tick(time.delta());
 
     if queue.timer.finished() {
+        let Some(mut engine) = snapshot_combat_engine(
+            &player_query, &enemy_query, &draw_pile_query, &discard_pile_query, &hand_query,
+            env.as_deref(), combat_state.phase, &run_seed, &relics,
+        ) else { return; };
+
         if queue.current_index < queue.enemies.len() {
             let enemy_entity = queue.enemies[queue.current_index];
-            
-            if let Ok(mut enemy) = enemy_query.get_mut(enemy_entity) {
-                if enemy.hp <= 0 {
-                    queue.current_index += 1;
-                    queue.timer = Timer::from_seconds(0.1, TimerMode::Once);
-                    return;
-                }
+            let Ok((_, enemy_before)) = enemy_query.get(enemy_entity) else {
+                queue.current_index += 1;
+                return;
+            };
+            if enemy_before.hp <= 0 {
+                queue.current_index += 1;
+                queue.timer = Timer::from_seconds(0.1, TimerMode::Once);
+                return;
+            }
+            let enemy_id = enemy_before.id;
 
-                enemy.start_turn();
-                let intent = enemy.execute_intent();
-                let enemy_id = enemy.id;
-
-                // --- [增强] 视觉反馈分发 ---
-                for (render_entity, marker, mut transform) in enemy_sprite_query.iter_mut() {
-                    if marker.id == enemy_id {
-                        match intent {
-                            EnemyIntent::Attack { damage } => {
-                                // 基础位移修正：防止敌人直接重叠在玩家身上，由 PhysicalImpact 内部处理长跨度冲刺
-                                // transform.translation.x -= 6.0; // 移除这行硬编码，交给动画事件处理
-                                
-                                // 根据类型播放特效和动画
-                                let animation = match enemy.enemy_type {
-                                    EnemyType::DemonicWolf => {
-                                        // 如果狼血量低于 40%，触发大招动画 (SiriusFrenzy)
-                                        if enemy.hp < 15 { // 假设狼的基础 HP 较低，40% 约为 15
-                                            AnimationState::WolfHowl
-                                        } else {
-                                            AnimationState::WolfAttack
-                                        }
-                                    },
-                                    EnemyType::PoisonSpider => AnimationState::SpiderAttack,
-                                    EnemyType::CursedSpirit => AnimationState::SpiritAttack,
-                                    EnemyType::GreatDemon => AnimationState::DemonAttack,
-                                };
-
-                                if enemy.enemy_type == EnemyType::GreatDemon {
-                                    // 雷光锁定：落点在修行者身旁 (Y=0.0 为地面)
-                                    let strike_pos = Vec3::new(player_pos.x, 0.0, player_pos.z);
-                                    effect_events.send(SpawnEffectEvent::new(EffectType::Lightning, strike_pos).burst(35));
-                                    
-                                    // 额外增加冲击粒子
-                                    effect_events.send(SpawnEffectEvent::new(EffectType::SwordEnergy, strike_pos).burst(20));
+            // 规则层结算，随后写回 ECS
+            let combat_events = engine.resolve_enemy_action(enemy_id);
+            write_back_combat_engine(
+                &engine, &mut player_query, &mut enemy_query, &mut draw_pile_query, &mut discard_pile_query, &mut hand_query, &mut run_seed, &mut relics,
+            );
+            send_relic_triggered_events(&combat_events, &mut relic_events);
+            let Some(enemy) = engine.enemies.iter().find(|e| e.id == enemy_id) else { return; };
+
+            for event in &combat_events {
+                match event {
+                    CombatEvent::StatusTriggered { enemy_id: Some(_), status, effect, killed } => {
+                        send_status_trigger_popup(enemy_entity, status, *effect, &mut status_events);
+                        if matches!(effect, TriggeredEffect::LoseHp(_)) {
+                            let animation = if *killed { AnimationState::Death } else { AnimationState::Hit };
+                            for (render_entity, marker, _) in enemy_sprite_query.iter() {
+                                if marker.id == enemy_id {
+                                    anim_events.send(CharacterAnimationEvent { target: render_entity, animation });
+                                }
+                            }
+                        }
+                        if *killed {
+                            info!("【状态】{} 被{}夺去性命", enemy.name, StatusRegistry::builtin().name(status));
+                        }
+                    }
+                    // 修行者的受击状态（如荆棘）触发
+                    CombatEvent::StatusTriggered { enemy_id: None, status, effect, .. } => {
+                        if let Ok(player_entity) = player_entity_query.get_single() {
+                            send_status_trigger_popup(player_entity, status, *effect, &mut status_events);
+                        }
+                    }
+                    // 荆棘反伤攻击者
+                    CombatEvent::EnemyDamaged { killed, .. } => {
+                        let animation = if *killed { AnimationState::Death } else { AnimationState::Hit };
+                        for (render_entity, marker, _) in enemy_sprite_query.iter() {
+                            if marker.id == enemy_id {
+                                anim_events.send(CharacterAnimationEvent { target: render_entity, animation });
+                            }
+                        }
+                    }
+                    // 进入新阶段：按数据播放阶段表现
+                    CombatEvent::EnemyPhaseChanged { .. } => {
+                        let visuals = enemy.phase_visuals();
+                        if visuals.enter_shake > 0.0 {
+                            screen_events.send(ScreenEffectEvent::Shake { trauma: visuals.enter_shake, decay: 2.0 });
+                        }
+                        if let Some((r, g, b, a)) = visuals.enter_flash {
+                            screen_events.send(ScreenEffectEvent::Flash { color: Color::srgba(r, g, b, a), duration: 0.5 });
+                        }
+                        if visuals.enter_burst > 0 {
+                            effect_events.send(SpawnEffectEvent::new(EffectType::SwordEnergy, Vec3::new(3.0, 1.0, 0.0)).burst(visuals.enter_burst));
+                        }
+                    }
+                    CombatEvent::EnemyActed { intent, charged, .. } => {
+                        // --- [增强] 视觉反馈分发 ---
+                        let visuals = enemy.phase_visuals();
+                        for (render_entity, marker, mut transform) in enemy_sprite_query.iter_mut() {
+                            if marker.id != enemy_id { continue; }
+                            match intent {
+                                EnemyIntent::Attack { .. } => {
+                                    // 动画与特效由当前阶段的表现钩子决定，缺省按外形
+                                    let animation = visuals.attack_animation.unwrap_or(match enemy.enemy_type {
+                                        EnemyType::DemonicWolf => AnimationState::WolfAttack,
+                                        EnemyType::PoisonSpider => AnimationState::SpiderAttack,
+                                        EnemyType::CursedSpirit => AnimationState::SpiritAttack,
+                                        EnemyType::GreatDemon => AnimationState::DemonAttack,
+                                    });
 
+                                    if visuals.lightning {
+                                        // 雷光锁定：落点在修行者身旁 (Y=0.0 为地面)
+                                        let strike_pos = Vec3::new(player_pos.x, 0.0, player_pos.z);
+                                        effect_events.send(SpawnEffectEvent::new(EffectType::Lightning, strike_pos).burst(35));
+                                        
+                                        // 额外增加冲击粒子
+                                        effect_events.send(SpawnEffectEvent::new(EffectType::SwordEnergy, strike_pos).burst(20));
+                                    }
                                     // 如果是带蓄势的攻击，额外增加红光闪烁
-                                    if enemy.is_charged {
+                                    if *charged {
                                         screen_events.send(ScreenEffectEvent::Flash { color: Color::srgba(1.0, 0.0, 0.0, 0.6), duration: 0.4 });
                                         screen_events.send(ScreenEffectEvent::Shake { trauma: 0.8, decay: 4.0 });
                                     }
+                                    anim_events.send(CharacterAnimationEvent { target: render_entity, animation });
+                                },
+                                EnemyIntent::Defend { .. } => {
+                                    // 防御/蓄势时：身体后缩并发出光芒
+                                    transform.translation.x += 0.8;
+                                    if visuals.defend_aura > 0 {
+                                        // 蓄势光环
+                                        effect_events.send(SpawnEffectEvent::new(EffectType::AmbientSpirit, transform.translation).burst(visuals.defend_aura));
+                                        info!("🛡️ 视觉反馈：{} 正在凝聚煞气...", enemy.name);
+                                    }
+                                    anim_events.send(CharacterAnimationEvent { target: render_entity, animation: crate::components::sprite::AnimationState::DemonCast });
+                                },
+                                _ => {
+                                    anim_events.send(CharacterAnimationEvent { target: render_entity, animation: crate::components::sprite::AnimationState::DemonCast });
                                 }
-                                anim_events.send(CharacterAnimationEvent { target: render_entity, animation });
-                            },
-                            EnemyIntent::Defend { .. } => {
-                                // 防御/蓄势时：身体后缩并发出光芒
-                                transform.translation.x += 0.8;
-                                if enemy.enemy_type == EnemyType::GreatDemon {
-                                    // 蓄势光环
-                                    effect_events.send(SpawnEffectEvent::new(EffectType::AmbientSpirit, transform.translation).burst(50));
-                                    info!("🛡️ 视觉反馈：Boss 正在凝聚煞气...");
-                                }
-                                anim_events.send(CharacterAnimationEvent { target: render_entity, animation: crate::components::sprite::AnimationState::DemonCast });
-                            },
-                            _ => {
-                                anim_events.send(CharacterAnimationEvent { target: render_entity, animation: crate::components::sprite::AnimationState::DemonCast });
                             }
                         }
-                    }
-                }
 
-                // 检查二阶段转换瞬间的视觉触发
-                if enemy.enemy_type == EnemyType::GreatDemon && enemy.hp < enemy.max_hp / 2 {
-                    let is_already_rage = enemy.ai_pattern.sequence.len() == 3;
-                    if !is_already_rage {
-                        // 逻辑层切换已在 choose_new_intent 完成，这里只管视觉
-                        screen_events.send(ScreenEffectEvent::Shake { trauma: 1.0, decay: 2.0 });
-                        screen_events.send(ScreenEffectEvent::Flash { color: Color::srgba(0.5, 0.0, 0.0, 0.8), duration: 0.5 });
-                        effect_events.send(SpawnEffectEvent::new(EffectType::SwordEnergy, Vec3::new(3.0, 1.0, 0.0)).burst(100));
+                        if matches!(intent, EnemyIntent::Defend { .. }) {
+                            effect_events.send(SpawnEffectEvent::new(EffectType::Shield, Vec3::new(2.5, 0.5, 0.5)));
+                        }
+                    }
+                    CombatEvent::PlayerDamaged { damage, .. } => {
+                        attack_events.send(EnemyAttackEvent::new(*damage, false));
+                        sfx_events.send(PlaySfxEvent::new(SfxType::PlayerHit));
+                        screen_events.send(ScreenEffectEvent::Shake { trauma: 0.6, decay: 6.0 });
                     }
+                    CombatEvent::CurseAdded { .. } => info!("【战斗】敌人向你的归墟注入了心魔！"),
+                    CombatEvent::SlotSealed { .. } => info!("【战斗】你的气穴被封印了！"),
+                    CombatEvent::RealmPassiveTriggered { passive } => info!("【境界】{}卸去部分伤害", passive.name()),
+                    _ => {}
                 }
+            }
 
-                match intent {
-                    EnemyIntent::Attack { damage } => {
-                        let final_damage = enemy.calculate_outgoing_damage_with_env(damage, env.as_ref().map(|r| r.as_ref()));
-                        
-                        // [新增重构] 攻击后消耗蓄势状态
-                        enemy.consume_charge();
-
-                        if let Ok((mut player, _)) = player_query.get_single_mut() {
-                            player.take_damage_with_env(final_damage, env.as_ref().map(|r| r.as_ref()));
-                            
-                            // 应用攻击附带的词缀效果 (灼烧/中毒/虚弱)
-                            enemy.apply_attack_affixes(&mut player);
+            if combat_events.iter().any(|e| matches!(e, CombatEvent::Defeat)) {
+                if mortal { next_state.set(GameState::GameOver); }
+                queue.processing = false;
+                return;
+            }
+            // 毒火带走最后一名敌人：停止行动队列，交由战斗结束判定结算
+            if combat_events.iter().any(|e| matches!(e, CombatEvent::Victory)) {
+                queue.processing = false;
+                return;
+            }
 
-                            attack_events.send(EnemyAttackEvent::new(final_damage, false));
-                            sfx_events.send(PlaySfxEvent::new(SfxType::PlayerHit));
-                            screen_events.send(ScreenEffectEvent::Shake { trauma: 0.6, decay: 6.0 });
-                            if player.hp <= 0 {
-                                next_state.set(GameState::GameOver);
-                                queue.processing = false;
-                                return; 
-                            }
+            // 行动前即伏诛的敌人不再等待演出
+            let delay = if enemy.hp > 0 { 1.2 } else { 0.6 };
+            queue.current_index += 1;
+            queue.timer = Timer::from_seconds(delay, TimerMode::Once);
+        } else {
+            // 所有敌人行动完毕，开启玩家新回合（毒火发作、回复灵力、更新封印、抽牌）
+            queue.processing = false;
+            let combat_events = engine.start_player_turn();
+            write_back_combat_engine(
+                &engine, &mut player_query, &mut enemy_query, &mut draw_pile_query, &mut discard_pile_query, &mut hand_query, &mut run_seed, &mut relics,
+            );
+            send_relic_triggered_events(&combat_events, &mut relic_events);
+            for event in &combat_events {
+                match event {
+                    CombatEvent::StatusTriggered { status, effect, .. } => {
+                        if let Ok(player_entity) = player_entity_query.get_single() {
+                            send_status_trigger_popup(player_entity, status, *effect, &mut status_events);
                         }
-                    }
-                    EnemyIntent::Defend { .. } => {
-                        effect_events.send(SpawnEffectEvent::new(EffectType::Shield, Vec3::new(2.5, 0.5, 0.5)));
-                    }
-                    EnemyIntent::Curse { card_id } => {
-                        if let Ok(mut discard_pile) = discard_pile_query.get_single_mut() {
-                            // 创建诅咒卡并加入弃牌堆
-                            let curse_card = Card::new(
-                                card_id, "心魔干扰", "【诅咒】干扰心神，难以自拔。",
-                                CardType::Curse, 0, CardEffect::CurseDamage { amount: 2 },
-                                CardRarity::Special, "textures/cards/special.png"
-                            );
-                            discard_pile.add_card(curse_card);
-                            info!("【战斗】敌人向你的归墟注入了心魔！");
+                        if matches!(effect, TriggeredEffect::LoseHp(_)) {
+                            sfx_events.send(PlaySfxEvent::new(SfxType::PlayerHit));
+                            screen_events.send(ScreenEffectEvent::Shake { trauma: 0.3, decay: 6.0 });
                         }
                     }
-                    EnemyIntent::Seal { slot_index, duration } => {
-                        if let Ok(mut hand) = hand_query.get_single_mut() {
-                            hand.seal_slot(slot_index, duration);
-                            info!("【战斗】你的气穴被封印了！");
-                        }
+                    CombatEvent::CardsDrawn { count } => info!("回合开始：抽了 {} 张牌", count),
+                    CombatEvent::RealmPassiveTriggered { passive } => info!("【境界】{}：{}", passive.name(), passive.description()),
+                    CombatEvent::Defeat => {
+                        info!("【状态】毒火攻心，道行耗尽");
+                        if mortal { next_state.set(GameState::GameOver); }
+                        return;
                     }
                     _ => {}
                 }
             }
-            
-            queue.current_index += 1;
-            queue.timer = Timer::from_seconds(1.2, TimerMode::Once);
-        } else {
-            // (玩家回合开始代码省略)
-            queue.processing = false;
-            if let Ok((mut player, _)) = player_query.get_single_mut() {
-                player.start_turn();
-            }
-            combat_state.cards_drawn_this_turn = false;
-            combat_state.phase = TurnPhase::PlayerAction;
+            combat_state.cards_drawn_this_turn = true;
+            combat_state.phase = engine.phase;
         }
     }
 }
@@ -1753,13 +2486,18 @@ fn update_combat_ui(
         if let Ok(mut t) = text_queries.p3().get_single_mut() { t.0 = format!("道行: {}/{}", p.hp, p.max_hp); }
         if let Ok(mut t) = text_queries.p4().get_single_mut() { t.0 = format!("灵石: {}", p.gold); }
         if let Ok(mut t) = text_queries.p7().get_single_mut() { 
-            let tier = match p.sword_intent {
-                0..=2 => "平淡",
-                3..=4 => "锋芒",
-                5 => "合一",
-                _ => "未知"
+            t.0 = match p.sect {
+                Sect::Sword => {
+                    let tier = match p.sword_intent {
+                        0..=2 => "平淡",
+                        3..=4 => "锋芒",
+                        5 => "合一",
+                        _ => "未知"
+                    };
+                    format!("剑意: {} [{}]", p.sword_intent, tier)
+                }
+                _ => format!("{}: {}/{}", p.sect.mechanic_name(), p.sect_charge, p.sect.charge_cap()),
             };
-            t.0 = format!("剑意: {} [{}]", p.sword_intent, tier); 
         }
     }
 
@@ -1873,10 +2611,16 @@ fn update_combat_ui(
 fn reset_player_on_combat_start(mut player_query: Query<(&mut Player, &crate::components::Cultivation)>) {
     info!("reset_player_on_combat_start 被调用");
     if let Ok((mut player, cultivation)) = player_query.get_single_mut() {
-        player.max_energy = 3 + cultivation.get_energy_bonus();
+        player.max_energy = player.sect.base_energy() + cultivation.get_energy_bonus();
         player.energy = player.max_energy; // 重置能量
+        player.sect_charge = 0; // 丹火与符印不带入新战斗
+        player.realm = cultivation.realm; // 境界被动与神通随修为而定
+        player.divine_art_used = false;
+        player.turn_cards_played = 0;
         player.block = 0; // 清除护甲
         player.turn = 1; // 重置回合数
+        player.clear_statuses(); // 上一战残留的毒火与虚弱不带入新战斗
+        player.cards_played = 0;
         info!("战斗开始：重置玩家状态 - 能量: {}/{}, 护甲: {}, 回合: {}, 境界: {:?}",
               player.energy, player.max_energy, player.block, player.turn, cultivation.realm);
     } else {
@@ -1890,6 +2634,7 @@ fn draw_cards_on_combat_start(
     mut draw_pile_query: Query<&mut DrawPile>,
     mut hand_query: Query<&mut Hand>,
     combat_state_opt: Option<ResMut<CombatState>>, // 关键修复：改为 Option 防止系统参数未就绪闪退
+    mut run_seed: ResMut<RunSeed>,
 ) {
     let Some(mut combat_state) = combat_state_opt else { return; };
     if combat_state.cards_drawn_this_turn { return; }
@@ -1900,7 +2645,7 @@ fn draw_cards_on_combat_start(
             
             // 1. 全量洗牌
             use rand::seq::SliceRandom;
-            draw_pile.cards.shuffle(&mut rand::thread_rng());
+            draw_pile.cards.shuffle(&mut run_seed.combat);
             
             // 2. 抽取 5 张
             let to_draw = 5.min(draw_pile.cards.len());
@@ -1915,63 +2660,94 @@ fn draw_cards_on_combat_start(
     }
 }
 
-/// 回合开始时抽牌
-fn draw_cards_on_turn_start(
-    mut draw_pile_query: Query<&mut DrawPile>,
-    mut hand_query: Query<&mut Hand>,
-    mut discard_pile_query: Query<&mut DiscardPile>,
-    player_query: Query<&Player>,
-    combat_state_opt: Option<ResMut<CombatState>>, // 关键修复：改为 Option
+/// 读档续战：把修行者的回合、灵力、护甲与状态写回（须在重置之后）
+fn restore_combat_snapshot(
+    mut commands: Commands,
+    resume: Option<Res<PendingCombatResume>>,
+    mut player_query: Query<&mut Player>,
 ) {
-    let Some(mut combat_state) = combat_state_opt else { return; };
-    // 只在玩家回合且回合数大于1时抽牌（避免战斗开始时重复抽牌）
-    let player_turn = if let Ok(player) = player_query.get_single() {
-        player.turn
-    } else {
-        return;
-    };
-
-    if player_turn <= 1 {
-        return;
+    let Some(resume) = resume else { return; };
+    if let Ok(mut player) = player_query.get_single_mut() {
+        resume.0.restore_player(&mut player);
+        info!("【存档系统】续战：第 {} 回合，灵力 {}", player.turn, player.energy);
     }
+    commands.remove_resource::<PendingCombatResume>();
+}
 
-    // 检查是否已经在这个回合抽过牌
-    if combat_state.cards_drawn_this_turn {
-        return;
-    }
+/// 战斗存档进度：记录最近一次存档的回合
+#[derive(Resource, Default)]
+pub struct CombatAutosave {
+    pub saved_turn: Option<u32>,
+}
 
-    if let Ok(mut draw_pile) = draw_pile_query.get_single_mut() {
-        if let Ok(mut hand) = hand_query.get_single_mut() {
-            // 关键：更新封印状态（每回合减少持续时间）
-            hand.update_seals();
-            
-            let cards_to_draw = 5; // 每回合抽5张牌
-
-            // 如果抽牌堆为空，将弃牌堆洗入抽牌堆
-            if draw_pile.count == 0 {
-                if let Ok(mut discard_pile) = discard_pile_query.get_single_mut() {
-                    let cards = discard_pile.clear();
-                    if !cards.is_empty() {
-                        draw_pile.shuffle_from_discard(cards);
-                        info!("抽牌堆为空，将弃牌堆洗入抽牌堆，共 {} 张牌", draw_pile.count);
-                    }
-                }
-            }
+fn reset_combat_autosave(mut autosave: ResMut<CombatAutosave>) {
+    autosave.saved_turn = None;
+}
 
-            // 抽牌
-            let mut drawn = 0;
-            for _ in 0..cards_to_draw {
-                if let Some(card) = draw_pile.draw_card() {
-                    hand.add_card(card);
-                    drawn += 1;
-                }
-            }
-            if drawn > 0 {
-                info!("回合开始：抽了 {} 张牌", drawn);
-                combat_state.cards_drawn_this_turn = true;
-            }
-        }
+/// 战斗了结时作废尚未写入的战况存档，免得盖过其后的地图存档
+fn end_combat_autosave(save_writer: Res<SaveWriter>) {
+    save_writer.end_combat();
+}
+
+/// 每个玩家回合开始（抽牌完毕）时自动存档，中途退出可原地续战
+fn autosave_combat(
+    player_query: Query<(&Player, &crate::components::Cultivation)>,
+    enemy_query: Query<&Enemy>,
+    draw_pile_query: Query<&DrawPile>,
+    discard_pile_query: Query<&DiscardPile>,
+    hand_query: Query<&Hand>,
+    env: Option<Res<Environment>>,
+    combat_state: Option<Res<CombatState>>,
+    queue: Res<EnemyActionQueue>,
+    player_deck: Res<PlayerDeck>,
+    relic_collection: Res<RelicCollection>,
+    map_progress: Option<Res<MapProgress>>,
+    run_seed: Res<RunSeed>,
+    (save_slot, play_time, ascension, event_history): (Res<ActiveSaveSlot>, Res<PlayTime>, Res<Ascension>, Res<EventHistory>),
+    (run_stats, save_writer): (Res<RunStats>, Res<SaveWriter>),
+    mut autosave: ResMut<CombatAutosave>,
+    tribulation: Option<Res<TribulationBattle>>,
+) {
+    // 渡劫之战不留档：中途退出视作未曾引动雷劫
+    if tribulation.is_some() { return; }
+    let Some(combat_state) = combat_state else { return; };
+    if queue.processing || !combat_state.cards_drawn_this_turn || combat_state.phase == TurnPhase::EnemyTurn {
+        return;
+    }
+    let Ok((player, cultivation)) = player_query.get_single() else { return; };
+    if autosave.saved_turn == Some(player.turn) || player.hp <= 0 {
+        return;
     }
+    if !enemy_query.iter().any(|e| e.hp > 0) { return; }
+    let (Ok(draw_pile), Ok(discard_pile), Ok(hand)) =
+        (draw_pile_query.get_single(), discard_pile_query.get_single(), hand_query.get_single()) else { return; };
+    let Some(progress) = map_progress else { return; };
+
+    let mut enemies: Vec<Enemy> = enemy_query.iter().cloned().collect();
+    enemies.sort_by_key(|e| e.id);
+    let save = GameStateSave {
+        version: crate::resources::save::SAVE_VERSION,
+        player: PlayerRunState::capture(player, cultivation),
+        cultivation: cultivation.clone(),
+        deck: player_deck.cards.clone(),
+        relics: relic_collection.relic.clone(),
+        map_nodes: progress.nodes.clone(),
+        current_map_node_id: progress.current_node_id,
+        current_map_layer: progress.current_layer,
+        act: progress.act,
+        unknown_odds: progress.unknown_odds,
+        seed: run_seed.seed,
+        streams: run_seed.positions(),
+        play_time: play_time.seconds,
+        saved_at: crate::resources::save::unix_now(),
+        ascension: ascension.level,
+        combat: Some(CombatSnapshot::capture(player, enemies, draw_pile, discard_pile, hand, env.as_deref())),
+        seen_events: event_history.seen.clone(),
+        run_stats: *run_stats,
+    };
+    autosave.saved_turn = Some(player.turn);
+    save_writer.save_combat(save, save_slot.0);
+    info!("【存档系统】第 {} 回合战况已排队存档", player.turn);
 }
 
 fn handle_hand_card_hover(
@@ -2006,15 +2782,16 @@ fn handle_hand_card_hover(
 // ============================================================================
 
 /// 处理卡牌点击事件
+///
+/// 单体功法在场上有多个存活敌人时先进入选靶状态，由玩家点击（或拖拽至）敌人后结算。
 fn handle_card_play(
     mut commands: Commands,
-    asset_server: Res<AssetServer>,
     card_query: Query<(&Interaction, &HandCard), (Changed<Interaction>, With<HandCard>)>,
     mut player_query: Query<(&mut Player, &crate::components::Cultivation)>,
     mut hand_query: Query<&mut Hand>,
     mut draw_pile_query: Query<&mut DrawPile>,
     mut discard_pile_query: Query<&mut DiscardPile>,
-    mut enemy_query: Query<&mut Enemy>,
+    mut enemy_query: Query<(Entity, &mut Enemy)>,
     events: (
         EventWriter<SpawnEffectEvent>,
         EventWriter<ScreenEffectEvent>,
@@ -2022,177 +2799,376 @@ fn handle_card_play(
         EventWriter<CharacterAnimationEvent>,
         EventWriter<DamageEffectEvent>,
         EventWriter<StatusEffectEvent>,
+        EventWriter<RelicTriggeredEvent>,
     ),
     env: Option<Res<Environment>>,
     mut heavenly_cinematic: ResMut<HeavenlyStrikeCinematic>, 
     victory_delay: Res<VictoryDelay>, // 引入资源
+    combat_state: Option<Res<CombatState>>,
     player_assets_opt: Option<Res<PlayerAssets>>,
+    run: (ResMut<RunSeed>, ResMut<RelicCollection>),
     queries: (
         Query<(Entity, &mut CharacterSprite), With<PlayerSpriteMarker>>,
         Query<(Entity, &crate::components::sprite::EnemySpriteMarker, &Transform)>,
         Query<(Entity, &crate::components::sprite::EnemySpriteMarker, &crate::components::sprite::PhysicalImpact)>,
-        Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    ),
+    targeting: (
+        ResMut<CardTargeting>,
+        Query<(&Interaction, &EnemyTargetZone)>,
+        Res<ButtonInput<MouseButton>>,
+        Query<&Interaction, (Changed<Interaction>, With<DivineArtButton>)>,
     ),
 ) {
     // [安全门禁] 如果已经处于胜利结算阶段，禁止打牌
     if victory_delay.active { return; }
 
-    let (mut effect_events, mut screen_events, mut sfx_events, mut anim_events, mut damage_events, mut status_events) = events;
-    let (mut player_sprite_query, enemy_sprite_query, enemy_impact_query, camera_query) = queries;
-    for (interaction, hand_card) in card_query.iter() {
-        if matches!(interaction, Interaction::Pressed) {
-            // ... (能量检查省略)
-            let player_energy = if let Ok((p, _)) = player_query.get_single() {
-                p.energy
-            } else {
-                0
+    let (mut effect_events, mut screen_events, mut sfx_events, mut anim_events, mut damage_events, mut status_events, mut relic_events) = events;
+    let (mut run_seed, mut relics) = run;
+    let (mut player_sprite_query, enemy_sprite_query, enemy_impact_query) = queries;
+    let (mut targeting, zone_query, mouse, divine_art_query) = targeting;
+    let phase = combat_state.map(|s| s.phase).unwrap_or_default();
+
+    // 选靶中：点击敌人，或拖拽卡牌后在敌人上松开
+    let mut selection: Option<(u32, Option<u32>)> = None;
+    if let Some(pending) = targeting.card_id {
+        for (interaction, zone) in zone_query.iter() {
+            let picked = match interaction {
+                Interaction::Pressed => true,
+                Interaction::Hovered => mouse.just_released(MouseButton::Left),
+                Interaction::None => false,
             };
+            if !picked { continue; }
+            if let Ok((_, enemy)) = enemy_query.get(zone.owner) {
+                if enemy.hp > 0 {
+                    selection = Some((pending, Some(enemy.id)));
+                    break;
+                }
+            }
+        }
+    }
+
+    // 点击手牌：单体功法进入选靶，其余直接打出
+    if selection.is_none() {
+        for (interaction, hand_card) in card_query.iter() {
+            if !matches!(interaction, Interaction::Pressed) { continue; }
+
+            if targeting.card_id == Some(hand_card.card_id) {
+                targeting.card_id = None;
+                info!("【出牌】取消选靶");
+                return;
+            }
 
-            let card_opt = if let Ok(hand) = hand_query.get_single() {
-                let card_index = hand.cards.iter().position(|c| c.id == hand_card.card_id);
-                card_index.map(|i| hand.cards[i].clone())
+            let Some(card) = hand_query.iter()
+                .flat_map(|hand| hand.cards.iter())
+                .find(|c| c.id == hand_card.card_id)
+            else { continue; };
+
+            let alive_enemies = enemy_query.iter().filter(|(_, e)| e.hp > 0).count();
+            let energy = player_query.get_single().map(|(p, _)| p.energy).unwrap_or(0);
+            if card.requires_target()
+                && alive_enemies > 1
+                && energy >= card.cost
+                && phase != TurnPhase::EnemyTurn
+            {
+                targeting.card_id = Some(card.id);
+                info!("【出牌】{} 请选择目标", card.name);
+                return;
+            }
+
+            selection = Some((card.id, None));
+            break;
+        }
+    }
+
+    // 点击神通按钮：施展本境界神通（单体神通落在首个存活敌人）
+    let divine_art = selection.is_none() && divine_art_query.iter().any(|i| matches!(i, Interaction::Pressed));
+    if !divine_art && selection.is_none() { return; }
+    targeting.card_id = None;
+
+    let Some(mut engine) = snapshot_combat_engine(
+        &player_query, &enemy_query, &draw_pile_query, &discard_pile_query, &hand_query,
+        env.as_deref(), phase, &run_seed, &relics,
+    ) else { return; };
+
+    let (card, combat_events) = if let Some((card_id, target)) = selection {
+        let Some(card) = engine.hand.cards.iter().find(|c| c.id == card_id).cloned() else { return; };
+        match engine.play_card(card.id, target) {
+            Ok(events) => {
+                info!("打出卡牌: {} (消耗: {})", card.name, card.cost_label());
+                (card, events)
+            }
+            Err(msg) => {
+                info!("【出牌】{}", msg);
+                return;
+            }
+        }
+    } else {
+        let Some(art) = engine.player.realm.divine_art() else { return; };
+        match engine.use_divine_art(None) {
+            Ok(events) => (art.as_card(), events),
+            Err(msg) => {
+                info!("【神通】{}", msg);
+                return;
+            }
+        }
+    };
+
+    // 1. 触发玩家动画 (精准隔离：御剑冲刺，天象原地)
+    if let Ok((player_entity, mut sprite)) = player_sprite_query.get_single_mut() {
+        if card.card_type == CardType::Attack {
+            let anim = if card.name.contains("万剑归宗") {
+                // 万剑归宗：原地施法
+                effect_events.send(SpawnEffectEvent::new(EffectType::SwordEnergy, Vec3::new(-3.5, 1.0, 0.2)));
+                sprite.state = crate::components::sprite::AnimationState::ImperialSword;
+                crate::components::sprite::AnimationState::ImperialSword
+            } else if card.name.contains("御剑术") {
+                // 御剑术：大作级跑动
+                effect_events.send(SpawnEffectEvent::new(EffectType::SwordEnergy, Vec3::new(-3.5, 1.0, 0.2)));
+                sprite.state = crate::components::sprite::AnimationState::LinearRun;
+                crate::components::sprite::AnimationState::LinearRun
+            } else if card.name.contains("剑气斩") {
+                // 剑气斩：原地挥动
+                effect_events.send(SpawnEffectEvent::new(EffectType::Slash, Vec3::new(-3.5, 1.0, 0.2)));
+                sprite.state = crate::components::sprite::AnimationState::DemonAttack; // 复用 DemonAttack 作为前踢/挥动
+                crate::components::sprite::AnimationState::DemonAttack
+            } else if card.name.contains("天象") {
+                // 天象法术：原地施法
+                sprite.state = crate::components::sprite::AnimationState::HeavenCast;
+                crate::components::sprite::AnimationState::HeavenCast
             } else {
-                None
+                // 近战类执行冲刺
+                sprite.state = crate::components::sprite::AnimationState::Attack;
+                crate::components::sprite::AnimationState::Attack
             };
 
-            if let Some(card) = card_opt {
-                if player_energy >= card.cost {
-                    info!("打出卡牌: {} (消耗: {})", card.name, card.cost);
-                    
-                    // 1. 触发玩家动画 (精准隔离：御剑冲刺，天象原地)
-                    if let Ok((player_entity, mut sprite)) = player_sprite_query.get_single_mut() {
-                        if card.card_type == CardType::Attack {
-                            let anim = if card.name.contains("万剑归宗") {
-                                // 万剑归宗：原地施法
-                                effect_events.send(SpawnEffectEvent::new(EffectType::SwordEnergy, Vec3::new(-3.5, 1.0, 0.2)));
-                                sprite.state = crate::components::sprite::AnimationState::ImperialSword;
-                                crate::components::sprite::AnimationState::ImperialSword
-                            } else if card.name.contains("御剑术") {
-                                // 御剑术：大作级跑动
-                                effect_events.send(SpawnEffectEvent::new(EffectType::SwordEnergy, Vec3::new(-3.5, 1.0, 0.2)));
-                                sprite.state = crate::components::sprite::AnimationState::LinearRun;
-                                crate::components::sprite::AnimationState::LinearRun
-                            } else if card.name.contains("剑气斩") {
-                                // 剑气斩：原地挥动
-                                effect_events.send(SpawnEffectEvent::new(EffectType::Slash, Vec3::new(-3.5, 1.0, 0.2)));
-                                sprite.state = crate::components::sprite::AnimationState::DemonAttack; // 复用 DemonAttack 作为前踢/挥动
-                                crate::components::sprite::AnimationState::DemonAttack
-                            } else if card.name.contains("天象") {
-                                // 天象法术：原地施法
-                                sprite.state = crate::components::sprite::AnimationState::HeavenCast;
-                                crate::components::sprite::AnimationState::HeavenCast
-                            } else {
-                                // 近战类执行冲刺
-                                sprite.state = crate::components::sprite::AnimationState::Attack;
-                                crate::components::sprite::AnimationState::Attack
-                            };
-
-                            anim_events.send(CharacterAnimationEvent {
-                                target: player_entity,
-                                animation: anim,
-                            });
-                        } else if card.card_type == CardType::Defense {
-                            // 防御功法：彻底原地不动
-                            sprite.state = crate::components::sprite::AnimationState::Defense;
-                            anim_events.send(CharacterAnimationEvent {
-                                target: player_entity,
-                                animation: crate::components::sprite::AnimationState::Defense,
-                            });
-                        }
-                    }
+            anim_events.send(CharacterAnimationEvent {
+                target: player_entity,
+                animation: anim,
+            });
+        } else if card.card_type == CardType::Defense {
+            // 防御功法：彻底原地不动
+            sprite.state = crate::components::sprite::AnimationState::Defense;
+            anim_events.send(CharacterAnimationEvent {
+                target: player_entity,
+                animation: crate::components::sprite::AnimationState::Defense,
+            });
+        }
+    }
 
-                    sfx_events.send(PlaySfxEvent::new(SfxType::CardPlay));
-                    if let Ok((mut player, _)) = player_query.get_single_mut() {
-                        player.energy -= card.cost;
-                        
-                        // --- [新增] 剑意系统逻辑 ---
-                        if card.card_type == CardType::Attack {
-                            // 如果打出攻击卡时剑意已满 (5层)，触发“人剑合一”爆发
-                            if player.sword_intent >= 5 {
-                                info!("🔥【剑意】人剑合一！触发绝杀爆发！");
-                                
-                                // 1. 强力视觉反馈
-                                screen_events.send(ScreenEffectEvent::Flash { 
-                                    color: Color::srgba(1.0, 0.9, 0.5, 0.8), 
-                                    duration: 0.2 
-                                });
-                                screen_events.send(ScreenEffectEvent::Shake { 
-                                    trauma: 0.8, 
-                                    decay: 3.0 
-                                });
-                                
-                                // 2. 爆发粒子特效
-                                effect_events.send(SpawnEffectEvent::new(EffectType::SwordEnergy, Vec3::new(0.0, 1.0, 0.0)).burst(50));
-                                
-                                // 3. 额外音效
-                                sfx_events.send(PlaySfxEvent::new(SfxType::LightningStrike)); 
-
-                                // 4. 消耗所有剑意
-                                player.reset_sword_intent();
-                            } else {
-                                player.add_sword_intent(1);
-                                info!("【剑意】积累至 {} 层", player.sword_intent);
-                            }
-                        } else {
-                            player.reset_sword_intent();
-                            info!("【剑意】因动用非攻伐功法而溃散");
-                        }
-                    }
+    sfx_events.send(PlaySfxEvent::new(SfxType::CardPlay));
 
-                        // 获取玩家实体 ID
-                        let player_entity = player_sprite_query.get_single().map(|(e, _)| e).ok();
-
-                        apply_card_effect(
-                            &card,
-                            &mut commands,
-                            &asset_server,
-                            &mut player_query,
-                            &mut enemy_query,
-                            &mut draw_pile_query,
-                            &mut discard_pile_query,
-                            &mut hand_query,
-                            &mut effect_events,
-                            &mut screen_events,
-                            &mut anim_events,
-                            &mut damage_events,
-                            &mut status_events,
-                            &enemy_sprite_query,
-                            &enemy_impact_query,
-                            &camera_query,
-                            env.as_ref().map(|r| r.as_ref()),
-                            &mut heavenly_cinematic, // 传递演出资源
-                            &mut sfx_events,
-                            player_assets_opt.as_ref().map(|r| r.as_ref()),
-                            player_entity,
-                        );
-
-                    // 3. 移出手牌
-                    if let Ok(mut hand) = hand_query.get_single_mut() {
-                        if let Some(index) = hand.cards.iter().position(|c| c.id == card.id) {
-                            let played_card = hand.remove_card(index).unwrap();
-                            if let Ok(mut discard_pile) = discard_pile_query.get_single_mut() {
-                                discard_pile.add_card(played_card);
-                            }
-                        }
-                    }
-                } else {
-                    info!("能量不足！需要: {}, 当前: {}", card.cost, player_energy);
-                }
-            }
+    // 2. 规则结果写回 ECS
+    write_back_combat_engine(
+        &engine, &mut player_query, &mut enemy_query, &mut draw_pile_query, &mut discard_pile_query, &mut hand_query, &mut run_seed, &mut relics,
+    );
+    send_relic_triggered_events(&combat_events, &mut relic_events);
+
+    // 3. 领域事件映射为表现
+    let player_entity = player_sprite_query.get_single().map(|(e, _)| e).ok();
+    present_card_events(
+        &card,
+        &combat_events,
+        &mut commands,
+        &mut effect_events,
+        &mut screen_events,
+        &mut anim_events,
+        &mut damage_events,
+        &mut status_events,
+        &enemy_sprite_query,
+        &enemy_impact_query,
+        &mut heavenly_cinematic,
+        &mut sfx_events,
+        player_assets_opt.as_deref(),
+        player_entity,
+    );
+}
+
+/// 取消选靶：右键/Esc、离开出牌阶段或卡牌已不在手中
+fn cancel_card_targeting(
+    mut targeting: ResMut<CardTargeting>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    combat_state: Option<Res<CombatState>>,
+    hand_query: Query<&Hand>,
+) {
+    let Some(card_id) = targeting.card_id else { return; };
+
+    let cancelled = mouse.just_pressed(MouseButton::Right) || keyboard.just_pressed(KeyCode::Escape);
+    let out_of_phase = combat_state.map(|s| s.phase == TurnPhase::EnemyTurn).unwrap_or(true);
+    let card_gone = !hand_query.iter().any(|hand| hand.cards.iter().any(|c| c.id == card_id));
+
+    if cancelled || out_of_phase || card_gone {
+        targeting.card_id = None;
+        info!("【出牌】取消选靶");
+    }
+}
+
+/// 神通施展后（含读档续战）撤下神通按钮
+fn sync_divine_art_button(
+    mut commands: Commands,
+    player_query: Query<&Player, Changed<Player>>,
+    button_query: Query<Entity, With<DivineArtButton>>,
+) {
+    let Ok(player) = player_query.get_single() else { return; };
+    if !player.divine_art_used { return; }
+    for entity in button_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// 选靶高亮：存活敌人描金边，悬停者加亮；待出手牌描金边
+fn update_targeting_highlight(
+    targeting: Res<CardTargeting>,
+    enemy_query: Query<&Enemy>,
+    mut zone_query: Query<(&Interaction, &EnemyTargetZone, &mut Node, &mut BorderColor, &mut BackgroundColor)>,
+    mut card_query: Query<(&HandCard, &mut BorderColor), Without<EnemyTargetZone>>,
+) {
+    let gold = Color::srgb(1.0, 0.84, 0.2);
+
+    for (interaction, zone, mut node, mut border, mut bg) in zone_query.iter_mut() {
+        let alive = enemy_query.get(zone.owner).map(|e| e.hp > 0).unwrap_or(false);
+        // 陨落敌人的选靶区域不再拦截点击
+        let display = if alive { Display::Flex } else { Display::None };
+        if node.display != display {
+            node.display = display;
         }
+
+        let (new_border, new_bg) = match (targeting.card_id.is_some() && alive, interaction) {
+            (true, Interaction::Hovered | Interaction::Pressed) => (gold, Color::srgba(1.0, 0.84, 0.2, 0.18)),
+            (true, Interaction::None) => (gold.with_alpha(0.5), Color::srgba(1.0, 0.84, 0.2, 0.05)),
+            (false, _) => (Color::NONE, Color::NONE),
+        };
+        if border.0 != new_border { border.0 = new_border; }
+        if bg.0 != new_bg { bg.0 = new_bg; }
+    }
+
+    for (hand_card, mut border) in card_query.iter_mut() {
+        let new_border = if targeting.card_id == Some(hand_card.card_id) { gold } else { Color::BLACK };
+        if border.0 != new_border { border.0 = new_border; }
     }
 }
 
-/// 应用卡牌效果
-fn apply_card_effect(
-    card: &crate::components::cards::Card,
-    commands: &mut Commands,
-    asset_server: &Res<AssetServer>,
+/// 从 ECS 组件抓取战斗快照，交由规则引擎推演
+///
+/// 敌人按实体排序，与行动队列的从左到右顺序一致。
+pub fn snapshot_combat_engine(
+    player_query: &Query<(&mut Player, &crate::components::Cultivation)>,
+    enemy_query: &Query<(Entity, &mut Enemy)>,
+    draw_pile_query: &Query<&mut DrawPile>,
+    discard_pile_query: &Query<&mut DiscardPile>,
+    hand_query: &Query<&mut Hand>,
+    environment: Option<&Environment>,
+    phase: TurnPhase,
+    run_seed: &RunSeed,
+    relics: &RelicCollection,
+) -> Option<CombatEngine> {
+    let player = player_query.get_single().ok()?.0.clone();
+    let mut enemies: Vec<(Entity, Enemy)> = enemy_query.iter().map(|(entity, enemy)| (entity, enemy.clone())).collect();
+    enemies.sort_by_key(|(entity, _)| *entity);
+
+    Some(CombatEngine::from_parts(
+        player,
+        enemies.into_iter().map(|(_, enemy)| enemy).collect(),
+        draw_pile_query.get_single().ok()?.clone(),
+        discard_pile_query.get_single().ok()?.clone(),
+        hand_query.get_single().ok()?.clone(),
+        environment.cloned().unwrap_or_default(),
+        phase,
+        run_seed.combat.clone(),
+    ).with_relics(relics.relic.clone()))
+}
+
+/// 将引擎推演结果写回 ECS 组件
+pub fn write_back_combat_engine(
+    engine: &CombatEngine,
     player_query: &mut Query<(&mut Player, &crate::components::Cultivation)>,
-    enemy_query: &mut Query<&mut Enemy>,
+    enemy_query: &mut Query<(Entity, &mut Enemy)>,
     draw_pile_query: &mut Query<&mut DrawPile>,
     discard_pile_query: &mut Query<&mut DiscardPile>,
     hand_query: &mut Query<&mut Hand>,
+    run_seed: &mut RunSeed,
+    relics: &mut RelicCollection,
+) {
+    if let Ok((mut player, _)) = player_query.get_single_mut() {
+        *player = engine.player.clone();
+    }
+    run_seed.combat = engine.rng.clone();
+    relics.sync_counters(&engine.relics);
+    for (_, mut enemy) in enemy_query.iter_mut() {
+        if let Some(state) = engine.enemies.iter().find(|e| e.id == enemy.id) {
+            *enemy = state.clone();
+        }
+    }
+    if let Ok(mut draw_pile) = draw_pile_query.get_single_mut() {
+        *draw_pile = engine.draw_pile.clone();
+    }
+    if let Ok(mut discard_pile) = discard_pile_query.get_single_mut() {
+        *discard_pile = engine.discard_pile.clone();
+    }
+    if let Ok(mut hand) = hand_query.get_single_mut() {
+        *hand = engine.hand.clone();
+    }
+}
+
+/// 伤害飘字 (定位到敌人本体坐标)
+fn send_enemy_damage_number(
+    enemy_id: u32,
+    amount: i32,
+    enemy_impact_query: &Query<(Entity, &crate::components::sprite::EnemySpriteMarker, &crate::components::sprite::PhysicalImpact)>,
+    damage_events: &mut EventWriter<DamageEffectEvent>,
+) {
+    if let Some((_, _, impact)) = enemy_impact_query.iter().find(|(_, m, _)| m.id == enemy_id) {
+        let x_world = impact.home_position.x * 100.0;
+        let y_world = (impact.home_position.z - 0.1) * 100.0;
+        damage_events.send(DamageEffectEvent { position: Vec2::new(x_world, y_world), amount });
+    }
+}
+
+/// 状态钩子触发的飘字（名称与颜色取自状态图谱）
+fn send_status_trigger_popup(
+    target: Entity,
+    status: &StatusId,
+    effect: TriggeredEffect,
+    status_events: &mut EventWriter<StatusEffectEvent>,
+) {
+    let (name, color) = status_display(status);
+    let msg = match effect {
+        TriggeredEffect::LoseHp(amount) => format!("{} -{}", name, amount),
+        TriggeredEffect::Heal(amount) => format!("{} +{}", name, amount),
+        TriggeredEffect::GainBlock(amount) => format!("{} 护盾+{}", name, amount),
+        TriggeredEffect::DamageAttacker(amount) => format!("{} 反伤{}", name, amount),
+    };
+    status_events.send(StatusEffectEvent { target, msg, color });
+}
+
+/// 状态的显示名称与颜色
+fn status_display(status: &StatusId) -> (String, Color) {
+    match StatusRegistry::builtin().get(status) {
+        Some(def) => (def.name.clone(), Color::srgb(def.color.0, def.color.1, def.color.2)),
+        None => (status.to_string(), Color::WHITE),
+    }
+}
+
+/// 受击/死亡动画
+fn send_enemy_hit_animation(
+    enemy_id: u32,
+    killed: bool,
+    enemy_sprite_query: &Query<(Entity, &crate::components::sprite::EnemySpriteMarker, &Transform)>,
+    anim_events: &mut EventWriter<CharacterAnimationEvent>,
+) {
+    let animation = if killed { AnimationState::Death } else { AnimationState::Hit };
+    for (entity, marker, _) in enemy_sprite_query.iter() {
+        if marker.id == enemy_id {
+            anim_events.send(CharacterAnimationEvent { target: entity, animation });
+        }
+    }
+}
+
+/// 将规则引擎产出的出牌事件映射为动画、粒子与音效
+fn present_card_events(
+    card: &Card,
+    combat_events: &[CombatEvent],
+    commands: &mut Commands,
     effect_events: &mut EventWriter<SpawnEffectEvent>,
     screen_events: &mut EventWriter<ScreenEffectEvent>,
     anim_events: &mut EventWriter<CharacterAnimationEvent>,
@@ -2200,275 +3176,184 @@ fn apply_card_effect(
     status_events: &mut EventWriter<StatusEffectEvent>,
     enemy_sprite_query: &Query<(Entity, &crate::components::sprite::EnemySpriteMarker, &Transform)>,
     enemy_impact_query: &Query<(Entity, &crate::components::sprite::EnemySpriteMarker, &crate::components::sprite::PhysicalImpact)>,
-    camera_query: &Query<(&Camera, &GlobalTransform), With<Camera3d>>,
-    environment: Option<&Environment>,
     heavenly_cinematic: &mut HeavenlyStrikeCinematic,
     sfx_events: &mut EventWriter<PlaySfxEvent>,
     player_assets: Option<&PlayerAssets>,
     player_entity: Option<Entity>,
 ) {
-    let card_name = card.name.clone();
-    match &card.effect {
-        // ... (状态施加省略)
-        CardEffect::ApplyStatus { status, count } => {
-            if let Some(mut enemy) = enemy_query.iter_mut().find(|e| e.hp > 0) {
-                let target_id = enemy.id;
-                match status {
-                    crate::components::cards::StatusType::Weakness => {
-                        enemy.weakness += *count;
-                    }
-                    crate::components::cards::StatusType::Vulnerable => {
-                        enemy.vulnerable += *count;
-                    }
-                    crate::components::cards::StatusType::Poison => {
-                        enemy.poison += *count;
-                    }
+    let is_wanjian = card.name.contains("万剑归宗");
+    let mut aoe_damage = None;
+    let mut multi_hit: Option<(u32, i32, bool)> = None;
+
+    for event in combat_events {
+        match event {
+            CombatEvent::SwordIntentBurst => {
+                info!("🔥【剑意】人剑合一！触发绝杀爆发！");
+                screen_events.send(ScreenEffectEvent::Flash { color: Color::srgba(1.0, 0.9, 0.5, 0.8), duration: 0.2 });
+                screen_events.send(ScreenEffectEvent::Shake { trauma: 0.8, decay: 3.0 });
+                effect_events.send(SpawnEffectEvent::new(EffectType::SwordEnergy, Vec3::new(0.0, 1.0, 0.0)).burst(50));
+                sfx_events.send(PlaySfxEvent::new(SfxType::LightningStrike));
+            }
+            CombatEvent::SwordIntentGained { stacks } => info!("【剑意】积累至 {} 层", stacks),
+            CombatEvent::SwordIntentLost => info!("【剑意】因动用非攻伐功法而溃散"),
+            CombatEvent::SectChargeGained { stacks } => info!("【宗门心法】积累至 {} 层", stacks),
+            CombatEvent::RealmPassiveTriggered { passive } => {
+                info!("【境界】{}：分身再施一次 {}", passive.name(), card.name);
+                effect_events.send(SpawnEffectEvent::new(EffectType::AmbientSpirit, Vec3::new(-3.5, 1.0, 0.2)).burst(20));
+            }
+            CombatEvent::DivineArtCast { realm } => {
+                info!("✨【神通】{}神通施展：{}", realm.name(), card.name);
+                screen_events.send(ScreenEffectEvent::Flash { color: Color::srgba(1.0, 0.85, 0.4, 0.6), duration: 0.2 });
+                effect_events.send(SpawnEffectEvent::new(EffectType::Victory, Vec3::new(-3.5, 1.0, 0.2)).burst(40));
+            }
+            CombatEvent::SectChargeReleased { stacks } => {
+                info!("【宗门心法】{} 层尽数催发", stacks);
+                effect_events.send(SpawnEffectEvent::new(EffectType::SwordEnergy, Vec3::new(0.0, 1.0, 0.0)).burst(10 * *stacks as u32));
+            }
+            CombatEvent::EnemyDamaged { enemy_id, damage, killed } => match card.attack_effect() {
+                Some(CardEffect::DealAoEDamage { .. }) => {
+                    aoe_damage = Some(*damage);
+                    send_enemy_hit_animation(*enemy_id, *killed, enemy_sprite_query, anim_events);
                 }
-
-                for (entity, marker, _) in enemy_sprite_query.iter() {
-                    if marker.id == target_id {
-                        let (msg, color) = match status {
-                            crate::components::cards::StatusType::Weakness => ("虚弱！".to_string(), Color::srgb(0.7, 0.4, 1.0)),
-                            crate::components::cards::StatusType::Vulnerable => ("易伤！".to_string(), Color::srgb(1.0, 0.3, 0.3)),
-                            crate::components::cards::StatusType::Poison => ("中毒！".to_string(), Color::srgb(0.3, 0.8, 0.3)),
-                        };
-                        status_events.send(StatusEffectEvent { target: entity, msg, color });
-                    }
+                Some(CardEffect::MultiAttack { .. }) => {
+                    effect_events.send(SpawnEffectEvent::new(EffectType::Slash, Vec3::new(0.0, 0.0, 5.0)));
+                    let total = multi_hit.map(|(_, t, _)| t).unwrap_or(0) + damage;
+                    multi_hit = Some((*enemy_id, total, *killed));
                 }
-            }
-        }
-        CardEffect::DealDamage { amount } => {
-            if let Ok((player, _)) = player_query.get_single() {
-                let final_damage = player.calculate_outgoing_damage_with_env(*amount, environment);
-                
-                if let Some(mut enemy) = enemy_query.iter_mut().find(|e| e.hp > 0) {
-                    let target_id = enemy.id;
-                    enemy.take_damage_with_env(final_damage, environment);
-                    let is_dead = enemy.hp <= 0;
-                    
-                    // 播放受击音效
+                Some(CardEffect::AttackAndDraw { .. }) => {
+                    effect_events.send(SpawnEffectEvent::new(EffectType::Slash, Vec3::new(0.0, 0.0, 5.0)));
+                }
+                _ => {
                     sfx_events.send(PlaySfxEvent::new(SfxType::EnemyHit));
-                
-                    for (entity, marker, transform) in enemy_sprite_query.iter() {
-                        if marker.id == target_id {
+                    for (_, marker, transform) in enemy_sprite_query.iter() {
+                        if marker.id == *enemy_id {
                             effect_events.send(SpawnEffectEvent::new(EffectType::Fire, transform.translation));
-                            if let Some((_, _, impact)) = enemy_impact_query.iter().find(|(_, m, _)| m.id == target_id) {
-                                let x_world = impact.home_position.x * 100.0;
-                                let y_world = (impact.home_position.z - 0.1) * 100.0;
-                                damage_events.send(DamageEffectEvent { position: Vec2::new(x_world, y_world), amount: final_damage });
-                            }
-                            if is_dead {
-                                anim_events.send(CharacterAnimationEvent { target: entity, animation: crate::components::sprite::AnimationState::Death });
-                            } else {
-                                anim_events.send(CharacterAnimationEvent { target: entity, animation: crate::components::sprite::AnimationState::Hit });
-                            }
                         }
                     }
+                    send_enemy_damage_number(*enemy_id, *damage, enemy_impact_query, damage_events);
+                    send_enemy_hit_animation(*enemy_id, *killed, enemy_sprite_query, anim_events);
                     effect_events.send(SpawnEffectEvent::new(EffectType::Slash, Vec3::new(0.0, 0.0, 5.0)));
                     screen_events.send(ScreenEffectEvent::Shake { trauma: 0.5, decay: 8.0 });
                 }
-            }
-        }
-        CardEffect::DealAoEDamage { amount } => {
-            let mut hit_count = 0;
-            let final_damage = if let Ok((player, _)) = player_query.get_single() {
-                player.calculate_outgoing_damage_with_env(*amount, environment)
-            } else {
-                *amount
-            };
-
-            for mut enemy in enemy_query.iter_mut() {
-                if enemy.hp <= 0 { continue; }
-                enemy.take_damage_with_env(final_damage, environment);
-                let is_dead = enemy.hp <= 0;
-                hit_count += 1;
-
-                // 针对每个被击中的敌人，触发其渲染实体的动画
-                for (render_entity, marker, _) in enemy_sprite_query.iter() {
-                    if marker.id == enemy.id {
-                        if is_dead {
-                            anim_events.send(CharacterAnimationEvent { target: render_entity, animation: crate::components::sprite::AnimationState::Death });
-                        } else {
-                            anim_events.send(CharacterAnimationEvent { target: render_entity, animation: crate::components::sprite::AnimationState::Hit });
-                        }
+            },
+            CombatEvent::StatusApplied { enemy_id, status, .. } => {
+                let (name, color) = status_display(status);
+                for (entity, marker, _) in enemy_sprite_query.iter() {
+                    if marker.id == *enemy_id {
+                        status_events.send(StatusEffectEvent { target: entity, msg: format!("{}！", name), color });
                     }
                 }
             }
-            
-            if hit_count > 0 {
-                // 播放音效
-                if card_name.contains("万剑归宗") {
-                    sfx_events.send(PlaySfxEvent::new(SfxType::ThousandSwords));
-                } else {
-                    sfx_events.send(PlaySfxEvent::new(SfxType::EnemyHit));
+            CombatEvent::StatusGained { status, count } => {
+                let (name, color) = status_display(status);
+                info!("【卡牌】获得 {} 层{}", count, name);
+                if let Some(player_entity) = player_entity {
+                    status_events.send(StatusEffectEvent { target: player_entity, msg: format!("{}！", name), color });
                 }
-
-                // ... (后面是特效和飘字，保持不变，但删掉末尾统一发的 Hit 动画)
-                for (_, _marker, impact) in enemy_impact_query.iter() {
-                    let x_world = impact.home_position.x * 100.0;
-                    let y_world = (impact.home_position.z - 0.1) * 100.0;
-                    damage_events.send(DamageEffectEvent { position: Vec2::new(x_world, y_world), amount: final_damage });
-                }
-
-                if card_name.contains("万剑归宗") {
-                    // [关键修复] 触发防止位移的动画状态 (显隐已由状态同步接管)
-                    if let Some(target) = player_entity {
-                        anim_events.send(CharacterAnimationEvent {
-                            target,
-                            animation: crate::components::sprite::AnimationState::ImperialSword,
-                        });
-                    }
-
-                    screen_events.send(ScreenEffectEvent::Shake { trauma: 1.0, decay: 0.45 });
-                    let mut alive_enemies: Vec<(Entity, Vec2)> = Vec::new();
-                    for (entity, marker, impact) in enemy_impact_query.iter() {
-                        let world_pos_3d = impact.home_position;
-                        let x_world = world_pos_3d.x * 100.0;
-                        let y_world = (world_pos_3d.z - 0.1) * 100.0;
-                        alive_enemies.push((entity, Vec2::new(x_world, y_world)));
-                    }
-
-                    if !alive_enemies.is_empty() {
-                        let total_swords = 80;
-                        let swords_per_enemy = (total_swords / alive_enemies.len()) as u32;
-                        
-                        // 获取武器模型用于“飞剑夺路”
-                        let weapon_model = player_assets.map(|pa| pa.weapon.clone());
-
-                        for (idx, (entity, _)) in alive_enemies.iter().enumerate() {
-                            let mut event = SpawnEffectEvent::new(EffectType::WanJian, Vec3::new(-350.0, -80.0, 0.5))
-                                    .burst(swords_per_enemy)
-                                    .with_target(alive_enemies[idx].1)
-                                    .with_target_entity(*entity)
-                                    .with_target_group(alive_enemies.clone())
-                                    .with_target_index(idx);
-                            
-                            if let Some(ref model) = weapon_model {
-                                event = event.with_model(model.clone());
-                            }
-                            
-                            effect_events.send(event);
-                        }
-                    }
-                                } else {
-                                    screen_events.send(ScreenEffectEvent::Shake { trauma: 0.5, decay: 4.0 });
-                                }
-                            }
-                        }
-                
-        CardEffect::GainBlock { amount } => {
-            if let Ok((mut player, _)) = player_query.get_single_mut() {
-                player.gain_block_with_env(*amount, environment);
-                sfx_events.send(PlaySfxEvent::new(SfxType::ShieldUp));
-                info!("【卡牌】获得 {} 点护甲 (受环境修正)", amount);
             }
-        }
-        CardEffect::Heal { amount } => {
-            if let Ok((mut player, _)) = player_query.get_single_mut() {
-                player.heal(*amount);
-                sfx_events.send(PlaySfxEvent::new(SfxType::Heal));
-            }
-        }
-        CardEffect::ChangeEnvironment { name } => {
-            if card_name.contains("引雷术") {
-                info!("【卡牌】引动九天雷霆演出开始...");
-                // 仅启动演出，不立即扣血或切换环境，基础伤害提升至 20
-                heavenly_cinematic.start(20, name.clone());
-            } else {
-                info!("【卡牌】天象异变！环境变为: {}", name);
-                if name == "浓雾" {
-                    commands.insert_resource(Environment::thick_fog());
-                    screen_events.send(ScreenEffectEvent::Flash { color: Color::srgba(0.7, 0.7, 0.7, 0.4), duration: 0.5 });
-                } else {
-                    commands.insert_resource(Environment::default());
+            CombatEvent::StatusTriggered { enemy_id, status, effect, .. } => {
+                let target = match enemy_id {
+                    Some(id) => enemy_sprite_query.iter().find(|(_, m, _)| m.id == *id).map(|(e, _, _)| e),
+                    None => player_entity,
+                };
+                if let Some(target) = target {
+                    send_status_trigger_popup(target, status, *effect, status_events);
                 }
-                if let Ok((mut player, _)) = player_query.get_single_mut() {
-                    player.gain_block_with_env(5, environment);
+                if matches!(effect, TriggeredEffect::GainBlock(_)) {
+                    sfx_events.send(PlaySfxEvent::new(SfxType::ShieldUp));
                 }
             }
-        }
-
-        CardEffect::GainEnergy { amount } => {
-            if let Ok((mut player, _)) = player_query.get_single_mut() {
-                player.gain_energy(*amount);
+            CombatEvent::BlockGained { amount } => {
+                if card.has_effect(|e| matches!(e, CardEffect::GainBlock { .. })) {
+                    sfx_events.send(PlaySfxEvent::new(SfxType::ShieldUp));
+                }
+                info!("【卡牌】获得 {} 点护甲 (受环境修正)", amount);
+            }
+            CombatEvent::Healed { .. } => {
+                sfx_events.send(PlaySfxEvent::new(SfxType::Heal));
+            }
+            CombatEvent::EnergyGained { amount } => {
                 info!("【卡牌】获得 {} 点灵力", amount);
                 effect_events.send(SpawnEffectEvent::new(EffectType::AmbientSpirit, Vec3::new(-3.5, 0.0, 0.5)).burst(20));
             }
-        }
-        CardEffect::AttackAndDraw { damage, cards } => {
-            // 伤害部分
-            if let Ok((player, _)) = player_query.get_single() {
-                let final_damage = player.calculate_outgoing_damage_with_env(*damage, environment);
-                if let Some(mut enemy) = enemy_query.iter_mut().find(|e| e.hp > 0) {
-                    enemy.take_damage_with_env(final_damage, environment);
-                    effect_events.send(SpawnEffectEvent::new(EffectType::Slash, Vec3::new(0.0, 0.0, 5.0)));
-                }
+            CombatEvent::CardsDrawn { count } => {
+                if *count > 0 { info!("【卡牌】抽了 {} 张牌", count); }
             }
-            // 抽牌部分
-            let mut drawn = 0;
-            if let Ok(mut draw_pile) = draw_pile_query.get_single_mut() {
-                for _ in 0..*cards {
-                    if let Some(card) = draw_pile.draw_card() {
-                        if let Ok(mut hand) = hand_query.get_single_mut() {
-                            if hand.add_card(card) { drawn += 1; }
-                        }
-                    }
+            CombatEvent::EnvironmentChanged { environment } => {
+                info!("【卡牌】天象异变！环境变为: {}", environment.name);
+                if environment.name == "浓雾" {
+                    screen_events.send(ScreenEffectEvent::Flash { color: Color::srgba(0.7, 0.7, 0.7, 0.4), duration: 0.5 });
                 }
+                commands.insert_resource(environment.clone());
             }
-            if drawn > 0 { info!("【卡牌】造成伤害并抽了 {} 张牌", drawn); }
-        }
-        CardEffect::MultiAttack { damage, times } => {
-            if let Ok((player, _)) = player_query.get_single() {
-                let final_damage = player.calculate_outgoing_damage_with_env(*damage, environment);
-                if let Some(mut enemy) = enemy_query.iter_mut().find(|e| e.hp > 0) {
-                    let target_id = enemy.id;
-                    let mut total_damage = 0;
-                    
-                    for _ in 0..*times {
-                        enemy.take_damage_with_env(final_damage, environment);
-                        total_damage += final_damage;
-                        // 触发多次斩击特效
-                        effect_events.send(SpawnEffectEvent::new(EffectType::Slash, Vec3::new(0.0, 0.0, 5.0))); 
-                    }
-                    
-                    let is_dead = enemy.hp <= 0;
-                    info!("【卡牌】{} 次攻击，每次 {} 点伤害，共 {} 点，敌人剩余HP: {}", times, damage, total_damage, enemy.hp);
-
-                    // [关键修复] 补充动画和飘字反馈
-                    for (entity, marker, _) in enemy_sprite_query.iter() {
-                        if marker.id == target_id {
-                            // 飘字 (显示总伤害)
-                            if let Some((_, _, impact)) = enemy_impact_query.iter().find(|(_, m, _)| m.id == target_id) {
-                                let x_world = impact.home_position.x * 100.0;
-                                let y_world = (impact.home_position.z - 0.1) * 100.0;
-                                damage_events.send(DamageEffectEvent { position: Vec2::new(x_world, y_world), amount: total_damage });
-                            }
-                            
-                            // 死亡/受击动画
-                            if is_dead {
-                                anim_events.send(CharacterAnimationEvent { target: entity, animation: crate::components::sprite::AnimationState::Death });
-                            } else {
-                                anim_events.send(CharacterAnimationEvent { target: entity, animation: crate::components::sprite::AnimationState::Hit });
-                            }
-                        }
-                    }
-                }
+            CombatEvent::HeavenlyStrikeStarted { damage, environment_name } => {
+                info!("【卡牌】引动九天雷霆演出开始...");
+                heavenly_cinematic.start(*damage, environment_name.clone());
             }
+            _ => {}
         }
-        CardEffect::DrawCards { amount } => {
-            let mut drawn = 0;
-            if let Ok(mut draw_pile) = draw_pile_query.get_single_mut() {
-                for _ in 0..*amount {
-                    if let Some(card) = draw_pile.draw_card() {
-                        if let Ok(mut hand) = hand_query.get_single_mut() {
-                            if hand.add_card(card) { drawn += 1; }
-                        }
-                    } else { break; }
+    }
+
+    if let Some((enemy_id, total_damage, killed)) = multi_hit {
+        send_enemy_damage_number(enemy_id, total_damage, enemy_impact_query, damage_events);
+        send_enemy_hit_animation(enemy_id, killed, enemy_sprite_query, anim_events);
+    }
+
+    if let Some(final_damage) = aoe_damage {
+        // 播放音效
+        if is_wanjian {
+            sfx_events.send(PlaySfxEvent::new(SfxType::ThousandSwords));
+        } else {
+            sfx_events.send(PlaySfxEvent::new(SfxType::EnemyHit));
+        }
+
+        for (_, marker, _) in enemy_impact_query.iter() {
+            send_enemy_damage_number(marker.id, final_damage, enemy_impact_query, damage_events);
+        }
+
+        if is_wanjian {
+            // [关键修复] 触发防止位移的动画状态 (显隐已由状态同步接管)
+            if let Some(target) = player_entity {
+                anim_events.send(CharacterAnimationEvent {
+                    target,
+                    animation: crate::components::sprite::AnimationState::ImperialSword,
+                });
+            }
+
+            screen_events.send(ScreenEffectEvent::Shake { trauma: 1.0, decay: 0.45 });
+            let mut alive_enemies: Vec<(Entity, Vec2)> = Vec::new();
+            for (entity, _marker, impact) in enemy_impact_query.iter() {
+                let world_pos_3d = impact.home_position;
+                let x_world = world_pos_3d.x * 100.0;
+                let y_world = (world_pos_3d.z - 0.1) * 100.0;
+                alive_enemies.push((entity, Vec2::new(x_world, y_world)));
+            }
+
+            if !alive_enemies.is_empty() {
+                let total_swords = 80;
+                let swords_per_enemy = (total_swords / alive_enemies.len()) as u32;
+
+                // 获取武器模型用于“飞剑夺路”
+                let weapon_model = player_assets.and_then(|pa| pa.weapon.clone());
+
+                for (idx, (entity, _)) in alive_enemies.iter().enumerate() {
+                    let mut event = SpawnEffectEvent::new(EffectType::WanJian, Vec3::new(-350.0, -80.0, 0.5))
+                            .burst(swords_per_enemy)
+                            .with_target(alive_enemies[idx].1)
+                            .with_target_entity(*entity)
+                            .with_target_group(alive_enemies.clone())
+                            .with_target_index(idx);
+
+                    if let Some(ref model) = weapon_model {
+                        event = event.with_model(model.clone());
+                    }
+
+                    effect_events.send(event);
                 }
             }
-            if drawn > 0 { info!("【卡牌】抽了 {} 张牌", drawn); }
+        } else {
+            screen_events.send(ScreenEffectEvent::Shake { trauma: 0.5, decay: 4.0 });
         }
-        _ => {}
     }
 }
 
@@ -2487,8 +3372,13 @@ pub fn check_combat_end_wrapper(
     asset_server: Res<AssetServer>,
     commands: Commands,
     sfx_events: EventWriter<PlaySfxEvent>,
+    run_seed: ResMut<RunSeed>,
+    relics: ResMut<RelicCollection>,
+    relic_events: EventWriter<RelicTriggeredEvent>,
+    tribulation: Option<Res<TribulationBattle>>,
+    run: (Option<Res<MapProgress>>, ResMut<RunStats>),
 ) {
-    check_combat_end(state, player_query, enemy_query, next_state, victory_events, victory_delay, asset_server, commands, sfx_events);
+    check_combat_end(state, player_query, enemy_query, next_state, victory_events, victory_delay, asset_server, commands, sfx_events, run_seed, relics, relic_events, tribulation, run);
 }
 
 /// 检查战斗是否结束
@@ -2502,8 +3392,15 @@ fn check_combat_end(
     asset_server: Res<AssetServer>, // 新增参数
     mut commands: Commands, // 确保有 commands
     mut sfx_events: EventWriter<PlaySfxEvent>,
+    mut run_seed: ResMut<RunSeed>,
+    mut relics: ResMut<RelicCollection>,
+    mut relic_events: EventWriter<RelicTriggeredEvent>,
+    tribulation: Option<Res<TribulationBattle>>,
+    (map_progress, mut run_stats): (Option<Res<MapProgress>>, ResMut<RunStats>),
 ) {
     if **state != GameState::Combat { return; }
+    // 渡劫之战由渡劫插件判定成败
+    if tribulation.is_some() { return; }
 
     // 1. 检查玩家是否败北
     if let Ok((player, _)) = player_query.get_single() {
@@ -2522,20 +3419,31 @@ fn check_combat_end(
 
         info!("【战斗】众妖肃清，机缘显现！");
         let chinese_font = asset_server.load("fonts/Arial Unicode.ttf");
+
+        // 精英战所得更丰，并记入本局战绩
+        let elite = map_progress.is_some_and(|p| p.is_at_elite());
+        if elite {
+            run_stats.record_elite();
+            info!("【战斗】精英伏诛，本局已斩精英 {} 场", run_stats.elites_slain);
+        }
         
         // 1. 获得感悟
         if let Ok((mut player, mut cultivation)) = player_query.get_single_mut() {
-            let insight_gain = 50;
+            let insight_gain = if elite { 80 } else { 50 };
             cultivation.gain_insight(insight_gain);
             info!("【修仙】获得 {} 点感悟，当前: {}/{}", insight_gain, cultivation.insight, cultivation.get_threshold());
 
-            // 2. [新增] 战后搜刮：获得灵石掉落 (10-25 随机)
+            // 2. [新增] 战后搜刮：获得灵石掉落 (寻常 10-25，精英 25-40 随机)
             use rand::Rng;
-            let mut rng = rand::thread_rng();
-            let gold_drop = rng.gen_range(10..26);
+            let gold_drop = if elite { run_seed.rewards.gen_range(25..41) } else { run_seed.rewards.gen_range(10..26) };
             player.gold += gold_drop;
             sfx_events.send(PlaySfxEvent::new(SfxType::GoldGain));
             info!("【战斗】搜刮战场，获得 {} 块灵石！当前持有: {}", gold_drop, player.gold);
+
+            // 3. 胜利类法宝与胜场计数
+            for (relic_id, effect) in relics.on_victory(&mut player) {
+                relic_events.send(RelicTriggeredEvent { relic_id, effects: vec![effect] });
+            }
         }
 
         sfx_events.send(PlaySfxEvent::new(SfxType::Victory));
@@ -2669,22 +3577,51 @@ fn update_victory_delay(
 fn setup_reward_ui(
     mut commands: Commands, 
     asset_server: Res<AssetServer>, 
-    relic_collection: Res<RelicCollection>, 
+    mut relic_collection: ResMut<RelicCollection>, 
     mut reward_cards_resource: ResMut<CurrentRewardCards>, 
     mut reward_relic_resource: ResMut<CurrentRewardRelic>,
-    player_query: Query<&Player>,
+    mut player_query: Query<(&mut Player, &crate::components::Cultivation)>,
+    mut run_seed: ResMut<RunSeed>,
+    card_library: Res<CardLibrary>,
+    relic_library: Res<RelicLibrary>,
+    map_progress: Res<MapProgress>,
+    mut sfx_events: EventWriter<PlaySfxEvent>,
 ) {
     info!("【天道机缘】展现机缘界面");
 
-    let reward_cards = CardPool::random_rewards(3);
+    let sect = player_query.get_single().map(|(p, _)| p.sect).unwrap_or_default();
+    let reward_cards = card_library.random_rewards(3, sect, &mut run_seed.rewards);
     reward_cards_resource.cards = reward_cards.clone();
 
-    let relic_reward = generate_relic_reward(&relic_collection);
-    let show_relic = relic_reward.is_some();
-    reward_relic_resource.relic = relic_reward.clone();
+    // 精英战必得法宝：进入机缘界面时即收入囊中，不占择取功法的机会
+    let elite_relic = if map_progress.is_at_elite() {
+        player_query.get_single_mut().ok().and_then(|(mut player, cultivation)| {
+            let relic = generate_relic_reward(&relic_library, &relic_collection, cultivation.realm, &mut run_seed.rewards)?;
+            let obtained = relic_collection.add_relic(relic.clone(), cultivation);
+            if obtained {
+                relic.on_pickup(&mut player);
+                sfx_events.send(PlaySfxEvent::new(SfxType::RelicObtain));
+                info!("【天道机缘】精英战利品：获得法宝「{}」", relic.name);
+            }
+            Some((relic, obtained))
+        })
+    } else {
+        None
+    };
+
+    // 首领伏诛：三件首领法宝择一，法宝位已满时只可观之
+    let (boss_choices, boss_pickable) = match player_query.get_single() {
+        Ok((_, cultivation)) if map_progress.is_at_boss() => (
+            relic_library.roll_boss_choices(3, &relic_collection, cultivation.realm, &mut run_seed.rewards),
+            relic_collection.relic.len() < cultivation.get_relic_slots(),
+        ),
+        _ => (Vec::new(), false),
+    };
+    reward_relic_resource.relics = elite_relic.iter().map(|(relic, _)| relic.clone()).chain(boss_choices.iter().cloned()).collect();
+    reward_relic_resource.boss_pick_open = boss_pickable && !boss_choices.is_empty();
 
     let chinese_font: Handle<Font> = asset_server.load("fonts/Arial Unicode.ttf");
-    let player_gold = player_query.get_single().map(|p| p.gold).unwrap_or(0);
+    let player_gold = player_query.get_single().map(|(p, _)| p.gold).unwrap_or(0);
 
     commands
         .spawn((
@@ -2732,6 +3669,51 @@ fn setup_reward_ui(
                 }
             });
 
+            // 精英战利品区
+            if let Some((relic, obtained)) = elite_relic {
+                parent.spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(10.0),
+                    ..default()
+                }).with_children(|loot_area| {
+                    loot_area.spawn((
+                        Text::new(if obtained { "精英战利品（已收入囊中）" } else { "精英战利品（法宝位已满，与你无缘）" }),
+                        TextFont { font: chinese_font.clone(), font_size: 18.0, ..default() },
+                        TextColor(Color::srgb(1.0, 0.9, 0.6)),
+                    ));
+                    create_relic_reward_display(loot_area, relic, &asset_server);
+                });
+            }
+
+            // 首领遗宝区
+            if !boss_choices.is_empty() {
+                parent.spawn((
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        row_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                    BossRelicChoiceArea,
+                )).with_children(|boss_area| {
+                    boss_area.spawn((
+                        Text::new(if boss_pickable { "镇守遗宝（择一收入囊中）" } else { "镇守遗宝（法宝位已满，与你无缘）" }),
+                        TextFont { font: chinese_font.clone(), font_size: 18.0, ..default() },
+                        TextColor(Color::srgb(1.0, 0.9, 0.6)),
+                    ));
+                    boss_area.spawn(Node {
+                        flex_direction: FlexDirection::Row,
+                        column_gap: Val::Px(30.0),
+                        ..default()
+                    }).with_children(|row| {
+                        for relic in boss_choices {
+                            create_relic_reward_display(row, relic, &asset_server);
+                        }
+                    });
+                });
+            }
+
             // 底部操作区
             parent.spawn(Node {
                 flex_direction: FlexDirection::Column,
@@ -2809,7 +3791,7 @@ fn create_reward_card(parent: &mut ChildBuilder, card: &Card, _index: usize, ass
     )).with_children(|parent| {
         parent.spawn((Text::new(card.rarity.get_chinese_name()), TextFont { font: chinese_font.clone(), font_size: 16.0, ..default() }, TextColor(rarity_color)));
         parent.spawn((Text::new(card.name.clone()), TextFont { font: chinese_font.clone(), font_size: 24.0, ..default() }, TextColor(Color::WHITE)));
-        parent.spawn((Text::new(format!("灵力消耗: {}", card.cost)), TextFont { font: chinese_font.clone(), font_size: 16.0, ..default() }, TextColor(Color::srgb(0.4, 0.8, 1.0))));
+        parent.spawn((Text::new(format!("灵力消耗: {}", card.cost_label())), TextFont { font: chinese_font.clone(), font_size: 16.0, ..default() }, TextColor(Color::srgb(0.4, 0.8, 1.0))));
         
         // 卡牌插画 (Nano Banana 风格优化)
         parent.spawn((
@@ -2883,13 +3865,15 @@ fn handle_reward_clicks(
     mut map_progress: ResMut<MapProgress>,
     relics: Res<RelicCollection>, // 引入法宝资源
     player_query: Query<(&Player, &crate::components::Cultivation)>, // 引入玩家查询
+    card_library: Res<CardLibrary>,
 ) {
     for (interaction, reward_btn) in interactions.iter() {
         if matches!(interaction, Interaction::Pressed) {
             info!("选择了奖励卡牌 ID: {}", reward_btn.card_id);
 
             // 从卡牌池找到对应的卡牌
-            let all_cards = CardPool::all_cards();
+            let sect = player_query.get_single().map(|(p, _)| p.sect).unwrap_or_default();
+            let all_cards = card_library.all_cards(sect);
             if let Some(card) = all_cards.iter().find(|c| c.id == reward_btn.card_id) {
                 let card_name = card.name.clone();
                 // 添加到玩家牌组
@@ -2913,6 +3897,44 @@ fn handle_reward_clicks(
     }
 }
 
+/// 择取首领遗宝：收入囊中后隐去全部候选
+fn handle_boss_relic_pick(
+    interactions: Query<(&Interaction, &RewardRelicButton), Changed<Interaction>>,
+    mut reward_relic: ResMut<CurrentRewardRelic>,
+    mut relic_collection: ResMut<RelicCollection>,
+    mut player_query: Query<(&mut Player, &crate::components::Cultivation)>,
+    mut hovered_relic: ResMut<HoveredRelic>,
+    mut choice_areas: Query<&mut Node, With<BossRelicChoiceArea>>,
+    mut sfx_events: EventWriter<PlaySfxEvent>,
+) {
+    if !reward_relic.boss_pick_open {
+        return;
+    }
+    for (interaction, relic_button) in interactions.iter() {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        let Some(relic) = reward_relic.relics.iter()
+            .find(|r| r.id == relic_button.relic_id && r.rarity == crate::components::relic::RelicRarity::Boss)
+            .cloned() else { continue };
+        let Ok((mut player, cultivation)) = player_query.get_single_mut() else { return };
+        if !relic_collection.add_relic(relic.clone(), cultivation) {
+            continue;
+        }
+        relic.on_pickup(&mut player);
+        sfx_events.send(PlaySfxEvent::new(SfxType::RelicObtain));
+        info!("【天道机缘】镇守遗宝：获得法宝「{}」", relic.name);
+
+        // 悬停面板交由 cleanup_hover_panels 随之撤去
+        reward_relic.boss_pick_open = false;
+        hovered_relic.relic_id = None;
+        for mut area in choice_areas.iter_mut() {
+            area.display = Display::None;
+        }
+        return;
+    }
+}
+
 /// 处理牌组查看交互
 fn handle_deck_view_toggle(
     mut commands: Commands,
@@ -3054,8 +4076,13 @@ fn setup_game_over_ui(
     map_progress: Res<MapProgress>,
     player_query: Query<&Player>,
     cultivation_query: Query<&crate::components::Cultivation>,
+    run_seed: Res<RunSeed>,
+    victory: Option<Res<RunVictory>>,
+    ascension: Res<Ascension>,
+    settlement: Option<Res<RunSettlement>>,
+    run_stats: Res<RunStats>,
 ) {
-    info!("【UI】展现身死道消结算界面");
+    info!("【UI】展现结算界面（{}）", if victory.is_some() { "飞升" } else { "身死道消" });
 
     let player = player_query.get_single().cloned().unwrap_or_default();
     let cultivation = cultivation_query.get_single().cloned().unwrap_or_else(|_| crate::components::Cultivation::new());
@@ -3086,14 +4113,19 @@ fn setup_game_over_ui(
                     ..default()
                 },
             )).with_children(|title_box| {
+                let (title, title_color) = if victory.is_some() {
+                    ("飞 升 成 仙", Color::srgb(1.0, 0.85, 0.2)) // 纯正金
+                } else {
+                    ("身 死 道 消", Color::srgb(0.7, 0.1, 0.1)) // 惨淡的血红色
+                };
                 title_box.spawn((
-                    Text::new("身 死 道 消"),
+                    Text::new(title),
                     TextFont {
                         font: chinese_font.clone(),
                         font_size: 100.0,
                         ..default()
                     },
-                    TextColor(Color::srgb(0.7, 0.1, 0.1)), // 惨淡的血红色
+                    TextColor(title_color),
                     TextLayout::new_with_justify(JustifyText::Center),
                     EntranceAnimation::new(1.2), // 缓慢浮现
                 ));
@@ -3114,12 +4146,7 @@ fn setup_game_over_ui(
                 EntranceAnimation::new(1.8),
             )).with_children(|stats| {
                 // 1. 境界
-                let realm_name = match cultivation.realm {
-                    crate::components::cultivation::Realm::QiRefining => "炼气期",
-                    crate::components::cultivation::Realm::FoundationEstablishment => "筑基期",
-                    crate::components::cultivation::Realm::GoldenCore => "金丹期",
-                    crate::components::cultivation::Realm::NascentSoul => "元婴期",
-                };
+                let realm_name = cultivation.realm.name();
                 stats.spawn((
                     Text::new(format!("终焉境界：{}", realm_name)),
                     TextFont { font: chinese_font.clone(), font_size: 28.0, ..default() },
@@ -3128,7 +4155,7 @@ fn setup_game_over_ui(
 
                 // 2. 层数
                 stats.spawn((
-                    Text::new(format!("探索进度：第 {} 层", current_layer + 1)),
+                    Text::new(format!("探索进度：{}第 {} 层", ActAtlas::builtin().act(map_progress.act).name.replace(' ', ""), current_layer + 1)),
                     TextFont { font: chinese_font.clone(), font_size: 28.0, ..default() },
                     TextColor(Color::srgb(0.8, 0.8, 0.8)),
                 ));
@@ -3146,6 +4173,45 @@ fn setup_game_over_ui(
                     TextFont { font: chinese_font.clone(), font_size: 28.0, ..default() },
                     TextColor(Color::srgb(0.4, 0.8, 1.0)),
                 ));
+
+                // 5. 精英战绩
+                stats.spawn((
+                    Text::new(format!("斩杀精英：{} 场", run_stats.elites_slain)),
+                    TextFont { font: chinese_font.clone(), font_size: 28.0, ..default() },
+                    TextColor(Color::srgb(1.0, 0.9, 0.6)),
+                ));
+
+                // 6. 道种（可分享复现本局）
+                stats.spawn((
+                    Text::new(format!("命途道种：{}", run_seed.code())),
+                    TextFont { font: chinese_font.clone(), font_size: 28.0, ..default() },
+                    TextColor(Color::srgb(0.7, 0.6, 0.9)),
+                ));
+
+                // 7. 天劫重数与新解锁的重数
+                if ascension.level > 0 {
+                    stats.spawn((
+                        Text::new(format!("天劫重数：第 {} 重", ascension.level)),
+                        TextFont { font: chinese_font.clone(), font_size: 28.0, ..default() },
+                        TextColor(Color::srgb(0.9, 0.5, 0.3)),
+                    ));
+                }
+                if let Some(level) = victory.as_ref().and_then(|v| v.unlocked) {
+                    stats.spawn((
+                        Text::new(format!("已解锁天劫第 {} 重，可于主菜单择选", level)),
+                        TextFont { font: chinese_font.clone(), font_size: 28.0, ..default() },
+                        TextColor(Color::srgb(1.0, 0.85, 0.2)),
+                    ));
+                }
+
+                // 8. 本局所得道韵（藏经阁中解锁新内容）
+                if let Some(settlement) = settlement.as_ref() {
+                    stats.spawn((
+                        Text::new(format!("所得道韵：{}（可于藏经阁参悟秘传）", settlement.essence)),
+                        TextFont { font: chinese_font.clone(), font_size: 28.0, ..default() },
+                        TextColor(Color::srgb(0.6, 0.9, 0.7)),
+                    ));
+                }
             });
 
             // --- 交互按钮 ---
@@ -3226,42 +4292,31 @@ fn cleanup_game_over_ui(mut commands: Commands, query: Query<Entity, With<GameOv
     for entity in query.iter() {
         commands.entity(entity).despawn_recursive();
     }
+    commands.remove_resource::<RunVictory>();
+    commands.remove_resource::<RunSettlement>();
 }
 
 // ============================================================================
 // 遗物奖励辅助函数
 // ============================================================================
 
-/// 生成遗物奖励（基于当前拥有的遗物，避免重复）
-fn generate_relic_reward(relic_collection: &RelicCollection) -> Option<Relic> {
-    use rand::Rng;
-
-    // 获取所有未拥有的遗物
-    let all_relics = vec![
-        Relic::burning_blood(),
-        Relic::bag_of_preparation(),
-        Relic::anchor(),
-        Relic::strange_spoon(),
-    ];
-
-    let available_relics: Vec<_> = all_relics
-        .into_iter()
-        .filter(|r| !relic_collection.has(r.id))
-        .collect();
-
-    if available_relics.is_empty() {
+/// 生成遗物奖励（排除已拥有与境界不足的遗物）
+fn generate_relic_reward(
+    library: &RelicLibrary,
+    relic_collection: &RelicCollection,
+    realm: crate::components::Realm,
+    rng: &mut impl rand::Rng,
+) -> Option<Relic> {
+    // 概率：50% 常见, 35% 罕见, 15% 稀有
+    let relic = library.roll([0.5, 0.35, 0.15], relic_collection, realm, &[], rng);
+    if relic.is_none() {
         info!("没有可用的遗物奖励");
-        return None;
     }
-
-    // 随机选择一个可用遗物
-    let mut rng = rand::thread_rng();
-    let index = rng.gen_range(0..available_relics.len());
-    Some(available_relics[index].clone())
+    relic
 }
 
-/// 创建遗物奖励选项UI
-fn create_relic_reward_option(parent: &mut ChildBuilder, relic: Relic, asset_server: &AssetServer) {
+/// 创建遗物奖励展示UI（悬停可查看详情）
+fn create_relic_reward_display(parent: &mut ChildBuilder, relic: Relic, asset_server: &AssetServer) {
 
     let rarity_color = relic.rarity.color();
     let text_color = relic.rarity.text_color();
@@ -3281,7 +4336,7 @@ fn create_relic_reward_option(parent: &mut ChildBuilder, relic: Relic, asset_ser
             BackgroundColor(rarity_color),
             BorderRadius::all(Val::Px(8.0)),
             BorderColor(Color::srgb(0.3, 0.3, 0.3)),
-            RewardRelicButton { relic_id: relic.id },
+            RewardRelicButton { relic_id: relic.id.clone() },
         ))
         .with_children(|parent| {
             // 稀有度标签
@@ -3318,41 +4373,18 @@ fn create_relic_reward_option(parent: &mut ChildBuilder, relic: Relic, asset_ser
                 TextColor(text_color),
                 TextLayout::new_with_justify(JustifyText::Center),
             ));
-        })
-        .observe(move |_entity: Trigger<Pointer<Click>>, 
-                       mut relic_collection: ResMut<RelicCollection>, 
-                       mut next_state: ResMut<NextState<GameState>>, 
-                       mut map_progress: ResMut<MapProgress>,
-                       player_deck: Res<PlayerDeck>,
-                       player_query: Query<(&Player, &crate::components::Cultivation)>| {
-            info!("获得了法宝: {}", relic.name);
-            let cultivation = player_query.get_single().map(|(_, c)| c).cloned().unwrap_or_else(|_| crate::components::Cultivation::new());
-            let added = relic_collection.add_relic(relic.clone(), &cultivation);
-            if added {
-                info!("法宝已加入收藏");
-            }
-            
-            // 标记当前节点为完成
-            map_progress.complete_current_node();
-            
-            // --- [优化] 移除同步阻塞存档，防止 UI 销毁时的竞态卡死 ---
-            // 存档将由状态机在进入 Map 状态时自动处理
-
-            next_state.set(GameState::Map);
         });
 }
 
 /// 处理游戏结束界面按钮点击
 fn handle_game_over_clicks(
+    mut commands: Commands,
     mut next_state: ResMut<NextState<GameState>>,
-    mut player_query: Query<&mut Player>,
-    mut cultivation_query: Query<&mut crate::components::Cultivation>,
-    mut deck: ResMut<PlayerDeck>,
-    mut relics: ResMut<RelicCollection>,
-    mut map_progress: ResMut<MapProgress>,
     mut sfx_events: EventWriter<PlaySfxEvent>,
     restart_button_query: Query<&Interaction, (Changed<Interaction>, With<RestartButton>)>,
     menu_button_query: Query<&Interaction, (Changed<Interaction>, With<BackToMenuButton>)>,
+    (save_slot, save_writer): (Res<ActiveSaveSlot>, Res<SaveWriter>),
+    player: Res<Player>,
 ) {
     // 1. 重新开始 (重塑道基)
     for interaction in restart_button_query.iter() {
@@ -3360,19 +4392,10 @@ fn handle_game_over_clicks(
             sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
             info!("【游戏结束】点击重塑道基，因果轮转...");
             
-            crate::resources::save::GameStateSave::delete_save();
-
-            if let Ok(mut player) = player_query.get_single_mut() {
-                *player = Player::default();
-            }
-            if let Ok(mut cultivation) = cultivation_query.get_single_mut() {
-                *cultivation = crate::components::Cultivation::new();
-            }
+            save_writer.delete(save_slot.0);
 
-            deck.reset(); 
-            relics.relic.clear();
-            relics.add_relic_forced(crate::components::relic::Relic::burning_blood());
-            map_progress.reset();
+            // 重入轮回时沿用本局宗门
+            start_new_run(&mut commands, RunSeed::random(), player.sect);
 
             next_state.set(GameState::Prologue);
             return;
@@ -3493,278 +4516,6 @@ fn cleanup_prologue(
 }
 
 
-#[derive(Component)]
-pub struct TribulationUiMarker;
-
-fn setup_tribulation(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    mut timer: ResMut<TribulationTimer>,
-    mut screen_events: EventWriter<ScreenEffectEvent>,
-) {
-    info!("🌩️ 天地震动，雷劫将至！");
-    timer.total_timer.reset();
-    timer.strike_timer.reset();
-    timer.strikes_count = 0;
-
-    let chinese_font = asset_server.load("fonts/Arial Unicode.ttf");
-
-    // 创建渡劫背景（半透明深紫色遮罩，允许 3D 特效透出）
-    commands.spawn((
-        Node {
-            width: Val::Percent(100.0),
-            height: Val::Percent(100.0),
-            ..default()
-        },
-        BackgroundColor(Color::srgba(0.01, 0.0, 0.02, 0.65)),
-        ZIndex(-10), 
-        TribulationUiMarker,
-    ));
-
-    // 渡劫标题与倒计时容器
-    commands.spawn((
-        Node {
-            width: Val::Percent(100.0),
-            height: Val::Percent(100.0),
-            flex_direction: FlexDirection::Column,
-            justify_content: JustifyContent::Center,
-            align_items: AlignItems::Center,
-            position_type: PositionType::Absolute,
-            ..default()
-        },
-        ZIndex(50), // 文字悬浮
-        TribulationUiMarker,
-    )).with_children(|parent| {
-        parent.spawn((
-            Text::new("正在渡劫..."),
-            TextFont {
-                font: chinese_font.clone(),
-                font_size: 72.0,
-                ..default()
-            },
-            TextColor(Color::srgb(0.8, 0.8, 1.0)),
-        ));
-
-        parent.spawn((
-            Text::new("承受九天雷霆洗礼，存活即成大道"),
-            TextFont {
-                font: chinese_font.clone(),
-                font_size: 24.0,
-                ..default()
-            },
-            TextColor(Color::srgb(0.6, 0.6, 0.8)),
-        ));
-    });
-
-    // 初始屏幕闪光（紫色）
-    screen_events.send(ScreenEffectEvent::Flash { 
-        color: Color::srgba(0.5, 0.2, 0.8, 0.5), 
-        duration: 0.5 
-    });
-}
-
-fn update_tribulation(
-    time: Res<Time>,
-    mut timer: ResMut<TribulationTimer>,
-    mut player_query: Query<&mut Player>,
-    mut next_state: ResMut<NextState<GameState>>,
-    mut screen_events: EventWriter<ScreenEffectEvent>,
-    mut effect_events: EventWriter<SpawnEffectEvent>,
-    mut sfx_events: EventWriter<PlaySfxEvent>,
-) {
-    // 持续环境氛围演出：轻微震动与灵气扰动
-    screen_events.send(ScreenEffectEvent::Shake { trauma: 0.05, decay: 10.0 });
-    if time.elapsed_secs() as u32 % 2 == 0 {
-        effect_events.send(SpawnEffectEvent::new(EffectType::AmbientSpirit, Vec3::new(0.0, 0.0, 5.0)).burst(2));
-    }
-
-    // 推进总进度
-    timer.total_timer.tick(time.delta());
-    if timer.total_timer.finished() {
-        info!("🌩️ 雷云散去，渡劫成功！");
-        next_state.set(GameState::Map);
-        return;
-    }
-
-    // 推进天雷间隔
-    timer.strike_timer.tick(time.delta());
-    if timer.strike_timer.just_finished() {
-        timer.strikes_count += 1;
-        
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-        
-        if let Ok(mut player) = player_query.get_single_mut() {
-            // 天雷伤害
-            let damage = (player.max_hp as f32 * 0.12).max(10.0) as i32;
-            player.hp -= damage;
-            
-            info!("⚡ 第 {} 道天雷落下！造成 {} 点伤害，剩余道行: {}", timer.strikes_count, damage, player.hp);
-
-            // 播放天雷音效
-            sfx_events.send(PlaySfxEvent::new(SfxType::LightningStrike));
-
-            // 视觉特效：强力白光闪烁 + 剧烈震动
-            screen_events.send(ScreenEffectEvent::Flash { 
-                color: Color::srgba(1.0, 1.0, 1.0, 0.8), // 提高不透明度
-                duration: 0.15 
-            });
-            screen_events.send(ScreenEffectEvent::Shake { 
-                trauma: 25.0, // 显著增加震动强度
-                decay: 0.5 
-            });
-            
-            // [关键修复] 使用真实的 3D 世界坐标 (而非 2D 像素坐标)
-            // 确保雷霆劈在 3D 摄像机的视野范围内 (X: -10..10, Z: -5..5)
-            for _ in 0..3 {
-                let x = rng.gen_range(-12.0..12.0);
-                let z = rng.gen_range(-8.0..8.0);
-                // 3D 空间的落点 Y 轴应为 0 附近
-                effect_events.send(SpawnEffectEvent::new(EffectType::Lightning, Vec3::new(x, 0.0, z)));
-            }
-
-            // 增强瞬间白闪强度，覆盖 2D 背景，产生致盲感
-            screen_events.send(ScreenEffectEvent::Flash { 
-                color: Color::srgba(1.5, 1.5, 2.0, 0.95), 
-                duration: 0.12 
-            });
-
-            // 检查陨落
-            if player.hp <= 0 {
-                info!("💀 渡劫失败，身陨道消...");
-                next_state.set(GameState::GameOver);
-            }
-        }
-    }
-}
-
-pub fn teardown_tribulation_wrapper(
-    commands: Commands,
-    asset_server: Res<AssetServer>,
-    ui_query: Query<Entity, With<TribulationUiMarker>>,
-    player_query: Query<(&mut Player, &mut crate::components::Cultivation)>,
-    deck: ResMut<PlayerDeck>,
-    map_progress: ResMut<MapProgress>,
-    effect_events: EventWriter<SpawnEffectEvent>,
-    screen_events: EventWriter<ScreenEffectEvent>,
-    sfx_events: EventWriter<PlaySfxEvent>,
-) {
-    teardown_tribulation(commands, asset_server, ui_query, player_query, deck, map_progress, effect_events, screen_events, sfx_events);
-}
-
-fn teardown_tribulation(
-    mut commands: Commands,
-    asset_server: Res<AssetServer>,
-    ui_query: Query<Entity, With<TribulationUiMarker>>,
-    mut player_query: Query<(&mut Player, &mut crate::components::Cultivation)>,
-    mut deck: ResMut<PlayerDeck>,
-    mut map_progress: ResMut<MapProgress>,
-    mut effect_events: EventWriter<SpawnEffectEvent>,
-    mut screen_events: EventWriter<ScreenEffectEvent>, // 补全此参数
-    mut sfx_events: EventWriter<PlaySfxEvent>,
-) {
-    // 清理渡劫专用UI
-    for entity in ui_query.iter() {
-        commands.entity(entity).despawn_recursive();
-    }
-
-    if let Ok((mut player, mut cultivation)) = player_query.get_single_mut() {
-        info!("【渡劫清理】找到玩家实体: HP={}, 境界={:?}, 感悟={}", player.hp, cultivation.realm, cultivation.insight);
-        // 只有在没死的情况下才处理突破（避免 GameOver 状态逻辑冲突）
-        if player.hp > 0 {
-            if cultivation.breakthrough() {
-                info!("【渡劫清理】突破逻辑执行成功！新境界: {:?}", cultivation.realm);
-                // 1. 属性质变
-                let (hp_bonus, stone_bonus) = match cultivation.realm {
-                    crate::components::cultivation::Realm::FoundationEstablishment => (50, 100),
-                    crate::components::cultivation::Realm::GoldenCore => (100, 200),
-                    _ => (20, 50),
-                };
-                player.max_hp += hp_bonus;
-                player.hp = player.max_hp; // 状态全回满
-                player.gold += stone_bonus; // 天道赏赐灵石
-                
-                info!("✨【破境成功】成功晋升至 {:?}！道行大进，上限增加 {} 点，获灵石 {} 块", cultivation.realm, hp_bonus, stone_bonus);
-
-                // --- 2. 开启新征程：重新生成地图 ---
-                let map_config = crate::components::map::MapConfig::default();
-                map_progress.nodes = crate::components::map::generate_map_nodes(&map_config, 0);
-                map_progress.current_node_id = None;
-                map_progress.current_layer = 0;
-                map_progress.refresh_unlocks();
-                info!("🗺️【天道演化】新的地图已生成，开启下一境界的修行！");
-                
-                // 3. 功法质变：发放本命功法
-                if cultivation.realm == crate::components::cultivation::Realm::FoundationEstablishment {
-                    let innate_spell = crate::components::cards::CardPool::get_innate_spell();
-                    deck.add_card(innate_spell.clone());
-                    info!("📖【本命功法】获得筑基期本命功法：{}", innate_spell.name);
-                } else if cultivation.realm == crate::components::cultivation::Realm::GoldenCore {
-                    // 金丹期自动领悟万剑归宗
-                    let aoe_spell = Card::new(
-                        151, "万剑归宗", "金丹大能之怒！对全场造成10点伤害",
-                        CardType::Attack, 2, CardEffect::DealAoEDamage { amount: 10 },
-                        CardRarity::Rare,
-                        "textures/cards/attack.png"
-                    );
-                    deck.add_card(aoe_spell.clone());
-                    info!("📖【大能神通】晋升金丹，领悟群体攻伐：{}", aoe_spell.name);
-                }
-
-                // 3. 视听反馈
-                sfx_events.send(PlaySfxEvent::new(SfxType::BreakthroughSuccess));
-                effect_events.send(SpawnEffectEvent::new(EffectType::Victory, Vec3::new(0.0, 0.0, 999.0)).burst(100));
-
-                // --- 4. [新增] 境界突破视觉横幅 (大作级包装) ---
-                let banner_text = match cultivation.realm {
-                    crate::components::cultivation::Realm::FoundationEstablishment => "筑 基 大 成",
-                    crate::components::cultivation::Realm::GoldenCore => "金 丹 已 成",
-                    crate::components::cultivation::Realm::NascentSoul => "元 婴 出 世",
-                    _ => "破 境 成 功",
-                };
-
-                let chinese_font = asset_server.load("fonts/Arial Unicode.ttf");
-                commands.spawn((
-                    Node {
-                        position_type: PositionType::Absolute,
-                        width: Val::Percent(100.0),
-                        height: Val::Px(200.0),
-                        top: Val::Percent(35.0),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        ..default()
-                    },
-                    BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
-                    ZIndex(1000), 
-                    MapUiRoot, // 随地图 UI 一起清理
-                    EntranceAnimation::new(0.6),
-                )).with_children(|banner| {
-                    banner.spawn((
-                        Text::new(banner_text),
-                        TextFont {
-                            font: chinese_font,
-                            font_size: 120.0,
-                            ..default()
-                        },
-                        TextColor(Color::srgb(1.0, 0.9, 0.3)), // 华丽金
-                        EntranceAnimation::new(0.8),
-                    ));
-                });
-
-                // 瞬间白闪效果
-                screen_events.send(ScreenEffectEvent::Flash { 
-                    color: Color::srgba(1.5, 1.5, 2.0, 0.95), 
-                    duration: 0.5 
-                });
-                // 剧烈屏幕震动，体现破境时的力量爆发
-                screen_events.send(ScreenEffectEvent::Shake { 
-                    trauma: 1.0, 
-                    decay: 2.0 
-                });
-            }
-        }
-    }
-}
 
 /// 当前奖励的卡牌列表
 #[derive(Resource, Default)]
@@ -3772,10 +4523,12 @@ struct CurrentRewardCards {
     cards: Vec<Card>,
 }
 
-/// 当前奖励的遗物
+/// 当前奖励的遗物（精英战利品或首领遗宝候选）
 #[derive(Resource, Default)]
 struct CurrentRewardRelic {
-    relic: Option<Relic>,
+    relics: Vec<Relic>,
+    /// 首领遗宝尚待择取
+    boss_pick_open: bool,
 }
 
 /// 当前悬停的卡牌数据
@@ -3871,14 +4624,14 @@ fn handle_relic_hover(
         match interaction {
             Interaction::Hovered => {
                 // 防止重复重建
-                if hovered_relic.relic_id == Some(relic_button.relic_id) {
+                if hovered_relic.relic_id.as_ref() == Some(&relic_button.relic_id) {
                     continue;
                 }
 
                 info!("【悬停】遗物 ID: {:?}", relic_button.relic_id);
 
                 // 更新悬停状态
-                hovered_relic.relic_id = Some(relic_button.relic_id);
+                hovered_relic.relic_id = Some(relic_button.relic_id.clone());
 
                 // 清除旧面板
                 for panel in existing_panels.iter() {
@@ -3886,15 +4639,13 @@ fn handle_relic_hover(
                 }
 
                 // 从当前奖励遗物中获取数据
-                if let Some(relic) = &reward_relic.relic {
-                    if relic.id == relic_button.relic_id {
-                        spawn_relic_hover_panel(&mut commands, relic, &asset_server, &mouse_position);
-                    }
+                if let Some(relic) = reward_relic.relics.iter().find(|r| r.id == relic_button.relic_id) {
+                    spawn_relic_hover_panel(&mut commands, relic, &asset_server, &mouse_position);
                 }
             }
             Interaction::None => {
                 // 鼠标移开，直接清理面板
-                if hovered_relic.relic_id == Some(relic_button.relic_id) {
+                if hovered_relic.relic_id.as_ref() == Some(&relic_button.relic_id) {
                     info!("【悬停】鼠标从遗物 {:?} 移开，开始清理", relic_button.relic_id);
                     hovered_relic.relic_id = None;
 
@@ -4057,7 +4808,7 @@ fn spawn_card_hover_panel(commands: &mut Commands, card: &Card, asset_server: &A
 
             // 能量消耗
             parent.spawn((
-                Text::new(format!("能量: {}", card.cost)),
+                Text::new(format!("能量: {}", card.cost_label())),
                 TextFont {
                     font: asset_server.load("fonts/Arial Unicode.ttf"),
                     font_size: 16.0,
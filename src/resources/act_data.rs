@@ -0,0 +1,327 @@
+//! 三界舆图（数据驱动的大境定义）
+//!
+//! 大境定义在 `assets/data/acts.ron` 中，编译期内置并在首次使用时解析校验。
+//! 一局修行依次穿越各个大境，每界有自己的地图层数、妖物、镇守首领、精英阵容、强度曲线、氛围与曲目；
+//! 本界首领伏诛后带着牌组、法宝与修为踏入下一界，末界首领伏诛即飞升。
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::OnceLock;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::components::background_music::BgmType;
+use crate::components::map::MapConfig;
+use crate::resources::ascension::Ascension;
+use crate::resources::enemy_data::EnemyRoster;
+use crate::resources::EnvironmentConfig;
+
+/// 内置大境数据（路径相对 assets 目录）
+const BUILTIN_ACT_FILE: (&str, &str) = ("data/acts.ron", include_str!("../../assets/data/acts.ron"));
+
+/// 地图层数下限（需容纳首领、洞府、宝箱与坊市各层）
+const MIN_LAYERS: u32 = 6;
+
+/// 精英阵容人数上限（与寻常战斗同场妖物数一致）
+const MAX_ELITE_PARTY: usize = 3;
+
+// ============================================================================
+// 数据格式
+// ============================================================================
+
+/// 妖物强度曲线
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ScalingCurve {
+    /// 本界首层折算的深度
+    pub depth_offset: u32,
+    /// 妖物道行倍率
+    pub hp_multiplier: f32,
+    /// 妖物额外力量
+    pub strength_bonus: i32,
+}
+
+impl ScalingCurve {
+    /// 本界某层折算的深度
+    pub fn depth(&self, layer: u32) -> u32 {
+        self.depth_offset + layer
+    }
+
+    /// 按倍率缩放道行
+    pub fn scale_hp(&self, hp: i32) -> i32 {
+        ((hp as f32 * self.hp_multiplier) as i32).max(1)
+    }
+}
+
+/// 大境氛围
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ActEnvironment {
+    /// 雾色与远景底色
+    pub fog: (f32, f32, f32),
+    /// 环境光色
+    pub ambient: (f32, f32, f32),
+    pub ambient_brightness: f32,
+    pub wind: f32,
+}
+
+/// 大境曲目
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ActMusic {
+    pub map: BgmType,
+    pub battle: BgmType,
+    pub boss: BgmType,
+}
+
+/// 单个大境定义
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ActDefinition {
+    /// 大境 id（全舆图唯一）
+    pub id: String,
+    /// 显示名称
+    pub name: String,
+    /// 地图层数
+    pub layers: u32,
+    /// 出没的妖物 id
+    pub enemies: Vec<String>,
+    /// 镇守首领 id
+    pub bosses: Vec<String>,
+    /// 精英阵容（每个阵容为同场出战的妖物 id）
+    pub elites: Vec<Vec<String>>,
+    pub scaling: ScalingCurve,
+    pub environment: ActEnvironment,
+    pub music: ActMusic,
+}
+
+impl ActDefinition {
+    /// 本界在指定天劫重数下的地图参数
+    pub fn map_config(&self, ascension: &Ascension) -> MapConfig {
+        ascension.map_config(self.layers)
+    }
+
+    /// 本界氛围
+    pub fn environment_config(&self) -> EnvironmentConfig {
+        let (fr, fg, fb) = self.environment.fog;
+        let (ar, ag, ab) = self.environment.ambient;
+        EnvironmentConfig {
+            wind_strength: self.environment.wind,
+            fog_color: Color::srgb(fr, fg, fb),
+            ambient_color: Color::srgb(ar, ag, ab),
+            ambient_brightness: self.environment.ambient_brightness,
+        }
+    }
+
+    fn validate(&self, file: &str, roster: &EnemyRoster) -> Result<(), ActDataError> {
+        let err = |field: &str, message: &str| ActDataError::invalid(file, Some(&self.id), field, message);
+
+        if self.name.trim().is_empty() {
+            return Err(err("name", "名称不能为空"));
+        }
+        if self.layers < MIN_LAYERS {
+            return Err(err("layers", &format!("地图至少需要 {} 层", MIN_LAYERS)));
+        }
+        if self.enemies.is_empty() {
+            return Err(err("enemies", "至少需要一种妖物"));
+        }
+        if let Some(unknown) = self.enemies.iter().find(|id| roster.get(id).is_none()) {
+            return Err(err("enemies", &format!("妖物图谱中没有 `{}`", unknown)));
+        }
+        if self.bosses.is_empty() {
+            return Err(err("bosses", "至少需要一名镇守首领"));
+        }
+        for id in &self.bosses {
+            match roster.get(id) {
+                None => return Err(err("bosses", &format!("妖物图谱中没有 `{}`", id))),
+                Some(definition) if !definition.boss => {
+                    return Err(err("bosses", &format!("`{}` 不是镇守首领", id)));
+                }
+                Some(_) => {}
+            }
+        }
+        if self.elites.is_empty() {
+            return Err(err("elites", "至少需要一个精英阵容"));
+        }
+        for party in &self.elites {
+            if party.is_empty() || party.len() > MAX_ELITE_PARTY {
+                return Err(err("elites", &format!("精英阵容须有 1..={} 名妖物", MAX_ELITE_PARTY)));
+            }
+            let mut has_elite = false;
+            for id in party {
+                match roster.get(id) {
+                    None => return Err(err("elites", &format!("妖物图谱中没有 `{}`", id))),
+                    Some(definition) if definition.boss => {
+                        return Err(err("elites", &format!("`{}` 是镇守首领，不能列入精英阵容", id)));
+                    }
+                    Some(definition) => has_elite |= definition.elite,
+                }
+            }
+            if !has_elite {
+                return Err(err("elites", &format!("阵容 {:?} 中没有精英妖物", party)));
+            }
+        }
+        if self.scaling.hp_multiplier <= 0.0 {
+            return Err(err("hp_multiplier", "道行倍率须为正"));
+        }
+        let (fog, ambient) = (self.environment.fog, self.environment.ambient);
+        if [fog.0, fog.1, fog.2, ambient.0, ambient.1, ambient.2].iter().any(|c| !(0.0..=1.0).contains(c)) {
+            return Err(err("environment", "颜色分量须在 0..1 之间"));
+        }
+        if self.environment.ambient_brightness < 0.0 || self.environment.wind < 0.0 {
+            return Err(err("environment", "亮度与风力不能为负"));
+        }
+        Ok(())
+    }
+}
+
+/// 大境数据文件
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ActDataFile {
+    pub acts: Vec<ActDefinition>,
+}
+
+/// 大境数据错误（指明出错的文件、大境与字段）
+#[derive(Debug)]
+pub enum ActDataError {
+    Parse { file: String, message: String },
+    Invalid { file: String, act: Option<String>, field: String, message: String },
+}
+
+impl ActDataError {
+    fn invalid(file: &str, act: Option<&str>, field: &str, message: &str) -> Self {
+        Self::Invalid {
+            file: file.to_string(),
+            act: act.map(|s| s.to_string()),
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ActDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse { file, message } => write!(f, "{}: RON 解析失败：{}", file, message),
+            Self::Invalid { file, act: Some(id), field, message } => {
+                write!(f, "{}: 大境 `{}` 字段 `{}`：{}", file, id, field, message)
+            }
+            Self::Invalid { file, act: None, field, message } => {
+                write!(f, "{}: 字段 `{}`：{}", file, field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ActDataError {}
+
+// ============================================================================
+// 三界舆图
+// ============================================================================
+
+/// 三界舆图（按穿越顺序排列的大境）
+#[derive(Debug, Clone)]
+pub struct ActAtlas {
+    acts: Vec<ActDefinition>,
+}
+
+impl ActAtlas {
+    /// 编译期内置的三界舆图
+    pub fn builtin() -> &'static ActAtlas {
+        static BUILTIN: OnceLock<ActAtlas> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let (file, text) = BUILTIN_ACT_FILE;
+            Self::parse(file, text.as_bytes(), EnemyRoster::builtin())
+                .unwrap_or_else(|e| panic!("内置大境数据无效：{}", e))
+        })
+    }
+
+    /// 解析并校验大境数据（妖物与首领须在妖物图谱中）
+    pub fn parse(file: &str, bytes: &[u8], roster: &EnemyRoster) -> Result<Self, ActDataError> {
+        let data: ActDataFile = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_bytes(bytes)
+            .map_err(|e| ActDataError::Parse { file: file.to_string(), message: e.to_string() })?;
+
+        if data.acts.is_empty() {
+            return Err(ActDataError::invalid(file, None, "acts", "至少需要一个大境"));
+        }
+        let mut ids = HashSet::new();
+        for act in &data.acts {
+            if !ids.insert(act.id.clone()) {
+                return Err(ActDataError::invalid(file, Some(&act.id), "id", "大境 id 重复"));
+            }
+            act.validate(file, roster)?;
+        }
+        Ok(Self { acts: data.acts })
+    }
+
+    /// 第 `index` 个大境（越界时取末界，兼容删减大境后的旧存档）
+    pub fn act(&self, index: u32) -> &ActDefinition {
+        self.acts.get(index as usize).unwrap_or_else(|| self.acts.last().expect("解析时已校验至少有一个大境"))
+    }
+
+    /// 大境个数
+    pub fn count(&self) -> u32 {
+        self.acts.len() as u32
+    }
+
+    /// 是否为末界
+    pub fn is_final(&self, index: u32) -> bool {
+        index + 1 >= self.count()
+    }
+
+    /// 全部大境（穿越顺序）
+    pub fn acts(&self) -> &[ActDefinition] {
+        &self.acts
+    }
+
+    /// 一局中的累计层数：此前各界的层数之和加上本界所在层
+    pub fn total_layer(&self, act: u32, layer: u32) -> u32 {
+        self.acts.iter().take(act as usize).map(|a| a.layers).sum::<u32>() + layer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::map::{generate_map_nodes, validate_map};
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_builtin_acts_escalate() {
+        let atlas = ActAtlas::builtin();
+        assert_eq!(atlas.count(), 3);
+        assert!(atlas.acts().windows(2).all(|w| {
+            w[0].scaling.depth_offset < w[1].scaling.depth_offset && w[0].scaling.hp_multiplier < w[1].scaling.hp_multiplier
+        }));
+        assert!(!atlas.is_final(1) && atlas.is_final(2));
+        assert_eq!(atlas.act(99).id, "immortal");
+        // 仙界首层深于凡间末层
+        assert_eq!(atlas.total_layer(0, 9), 9);
+        assert_eq!(atlas.total_layer(2, 1), atlas.acts()[0].layers + atlas.acts()[1].layers + 1);
+
+        let ascension = Ascension::default();
+        for (index, act) in atlas.acts().iter().enumerate() {
+            let config = act.map_config(&ascension);
+            assert_eq!(config.layers, act.layers);
+            let nodes = generate_map_nodes(&config, 0, &mut rand::rngs::StdRng::seed_from_u64(index as u64));
+            assert_eq!(validate_map(&config, &nodes), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_invalid_act_names_field() {
+        let text = r#"(acts: [(id: "abyss", name: "深 渊", layers: 10, enemies: ["demonic_wolf"], bosses: ["demonic_wolf"],
+            elites: [["blood_wolf_king"]], scaling: (depth_offset: 0, hp_multiplier: 1.0, strength_bonus: 0),
+            environment: (fog: (0.1, 0.1, 0.1), ambient: (1.0, 1.0, 1.0), ambient_brightness: 100.0, wind: 1.0),
+            music: (map: MapExploration, battle: NormalBattle, boss: BossBattle))])"#;
+        let err = ActAtlas::parse("data/test.ron", text.as_bytes(), EnemyRoster::builtin()).unwrap_err().to_string();
+        assert!(err.contains("abyss") && err.contains("bosses"), "{}", err);
+
+        let text = text.replace(r#"bosses: ["demonic_wolf"]"#, r#"bosses: ["great_demon"]"#).replace("layers: 10", "layers: 3");
+        let err = ActAtlas::parse("data/test.ron", text.as_bytes(), EnemyRoster::builtin()).unwrap_err().to_string();
+        assert!(err.contains("layers"), "{}", err);
+
+        let text = text.replace("layers: 3", "layers: 10").replace(r#"[["blood_wolf_king"]]"#, r#"[["demonic_wolf"]]"#);
+        let err = ActAtlas::parse("data/test.ron", text.as_bytes(), EnemyRoster::builtin()).unwrap_err().to_string();
+        assert!(err.contains("elites") && err.contains("demonic_wolf"), "{}", err);
+    }
+}
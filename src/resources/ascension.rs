@@ -0,0 +1,146 @@
+//! 天劫重数（飞升后解锁的进阶难度）
+//!
+//! 每战胜一次镇守首领，便解锁下一重天劫（至多二十重）。重数逐级叠加：
+//! 第 n 重包含前 n - 1 重的全部修正。
+
+use bevy::prelude::*;
+use crate::components::{Card, CardEffect, CardRarity, CardType, Enemy, Player};
+use crate::components::map::MapConfig;
+
+/// 天劫重数上限
+pub const MAX_ASCENSION: u32 = 20;
+
+/// 各重天劫新增的修正（第 i 项为第 i + 1 重）
+pub const ASCENSION_LEVELS: [&str; MAX_ASCENSION as usize] = [
+    "精英妖物出没：一成寻常遭遇化为精英",
+    "妖气渐盛：妖物道行 +10%",
+    "囊中羞涩：开局灵石 -25",
+    "心魔暗生：初始牌组混入 1 张心魔",
+    "调息不畅：调息恢复降至 25%",
+    "镇守震怒：首领力量 +3",
+    "妖物凶戾：妖物力量 +1",
+    "根基受损：开局道行上限 -5",
+    "精英横行：两成寻常遭遇化为精英",
+    "妖气大盛：妖物道行 +20%",
+    "身无长物：开局灵石 -50",
+    "心魔缠身：初始牌组混入 2 张心魔",
+    "气血淤塞：调息恢复降至 20%",
+    "镇守狂怒：首领力量 +6",
+    "妖物暴虐：妖物力量 +2",
+    "道基动摇：开局道行上限 -10",
+    "群妖环伺：三成寻常遭遇化为精英",
+    "妖气滔天：妖物道行 +30%",
+    "镇守不灭：首领道行 +25%",
+    "天劫临身：首领开战即有 30 点护体",
+];
+
+/// 本局所选天劫重数（0 为凡尘，不加修正）
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Ascension {
+    pub level: u32,
+}
+
+impl Ascension {
+    pub fn new(level: u32) -> Self {
+        Self { level: level.min(MAX_ASCENSION) }
+    }
+
+    /// 第 level 重新增的修正说明
+    pub fn description(level: u32) -> Option<&'static str> {
+        level.checked_sub(1).and_then(|i| ASCENSION_LEVELS.get(i as usize).copied())
+    }
+
+    /// 已达到的重数门槛个数
+    fn tiers(&self, thresholds: &[u32]) -> u32 {
+        thresholds.iter().filter(|&&t| self.level >= t).count() as u32
+    }
+
+    /// 寻常遭遇化为精英的概率（百分比）
+    pub fn elite_percent(&self) -> u32 {
+        10 * self.tiers(&[1, 9, 17])
+    }
+
+    /// 本重天劫下指定层数的地图参数
+    pub fn map_config(&self, layers: u32) -> MapConfig {
+        MapConfig { elite_percent: self.elite_percent(), ..MapConfig::for_layers(layers) }
+    }
+
+    /// 调息恢复的道行比例（百分比）
+    pub fn rest_heal_percent(&self) -> u32 {
+        30 - 5 * self.tiers(&[5, 13])
+    }
+
+    /// 开局修行者：灵石与道行上限按重数削减
+    pub fn apply_to_player(&self, player: &mut Player) {
+        player.gold = (player.gold - 25 * self.tiers(&[3, 11]) as i32).max(0);
+        player.max_hp -= 5 * self.tiers(&[8, 16]) as i32;
+        player.hp = player.max_hp;
+    }
+
+    /// 混入初始牌组的心魔（实例 id 从 first_id 起编号）
+    pub fn starting_curses(&self, first_id: u32) -> Vec<Card> {
+        (0..self.tiers(&[4, 12]))
+            .map(|i| Card::new(
+                first_id + i, "心魔", CardType::Curse, 0,
+                vec![CardEffect::CurseWeakness],
+                CardRarity::Special, "textures/cards/special.png",
+            ))
+            .collect()
+    }
+
+    /// 强化刚生成的妖物
+    pub fn apply_to_enemy(&self, enemy: &mut Enemy, boss: bool) {
+        let mut hp_multiplier = 1.0 + 0.1 * self.tiers(&[2, 10, 18]) as f32;
+        enemy.strength += self.tiers(&[7, 15]) as i32;
+        if boss {
+            enemy.strength += 3 * self.tiers(&[6, 14]) as i32;
+            if self.level >= 19 {
+                hp_multiplier *= 1.25;
+            }
+            if self.level >= 20 {
+                enemy.block += 30;
+            }
+        }
+        enemy.max_hp = (enemy.max_hp as f32 * hp_multiplier) as i32;
+        enemy.hp = enemy.max_hp;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::EnemyType;
+
+    #[test]
+    fn test_modifiers_stack_with_level() {
+        let mortal = Ascension::default();
+        let mut player = Player::default();
+        mortal.apply_to_player(&mut player);
+        assert_eq!((player.gold, player.max_hp), (100, 80));
+        assert_eq!(mortal.rest_heal_percent(), 30);
+        assert!(mortal.starting_curses(0).is_empty());
+        assert_eq!(mortal.map_config(10).elite_percent, 0);
+
+        let peak = Ascension::new(99);
+        assert_eq!(peak.level, MAX_ASCENSION);
+        let mut player = Player::default();
+        peak.apply_to_player(&mut player);
+        assert_eq!((player.gold, player.hp, player.max_hp), (50, 70, 70));
+        assert_eq!(peak.rest_heal_percent(), 20);
+        assert_eq!(peak.starting_curses(10).iter().map(|c| c.id).collect::<Vec<_>>(), vec![10, 11]);
+        assert_eq!(peak.elite_percent(), 30);
+
+        let mut boss = Enemy::with_type(0, "大妖", 100, EnemyType::GreatDemon);
+        peak.apply_to_enemy(&mut boss, true);
+        assert_eq!((boss.max_hp, boss.hp), (162, 162));
+        assert_eq!(boss.strength, 2 + 6);
+        assert_eq!(boss.block, 30);
+    }
+
+    #[test]
+    fn test_every_level_is_described() {
+        assert_eq!(Ascension::description(0), None);
+        assert!((1..=MAX_ASCENSION).all(|level| Ascension::description(level).is_some()));
+        assert_eq!(Ascension::description(MAX_ASCENSION + 1), None);
+    }
+}
@@ -0,0 +1,559 @@
+//! 功法图谱（数据驱动的卡牌定义）
+//!
+//! 所有功法定义在 `assets/data/cards/*.cards.ron` 中，经资源加载器解析并校验后汇总为 [`CardLibrary`]。
+//! 同一批数据文件也在编译期内置一份，作为资源未就绪时（以及单元测试）的兜底图谱。
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::sync::OnceLock;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+
+use crate::components::cards::{Card, CardEffect, CardRarity, CardType, CardUpgrade, EffectCondition, ScaleSource};
+use crate::components::sect::Sect;
+use crate::components::status::StatusId;
+use crate::resources::status_data::StatusRegistry;
+
+/// 本命功法（筑基期发放）的定义 id
+pub const INNATE_SPELL_ID: u32 = 999;
+
+/// 渡劫之战额外加入剑冢的渡劫功法
+pub const TRIBULATION_CARD_IDS: [u32; 3] = [800, 801, 802];
+
+/// 单张功法允许的最大能量消耗
+pub const MAX_CARD_COST: i32 = 5;
+
+/// 编译期内置的功法数据（路径相对 assets 目录）
+const BUILTIN_CARD_FILES: &[(&str, &str)] = &[
+    ("data/cards/starter.cards.ron", include_str!("../../assets/data/cards/starter.cards.ron")),
+    ("data/cards/rewards.cards.ron", include_str!("../../assets/data/cards/rewards.cards.ron")),
+    ("data/cards/heavenly.cards.ron", include_str!("../../assets/data/cards/heavenly.cards.ron")),
+    ("data/cards/archive.cards.ron", include_str!("../../assets/data/cards/archive.cards.ron")),
+    ("data/cards/sects.cards.ron", include_str!("../../assets/data/cards/sects.cards.ron")),
+    ("data/cards/tribulation.cards.ron", include_str!("../../assets/data/cards/tribulation.cards.ron")),
+    ("data/cards/curses.cards.ron", include_str!("../../assets/data/cards/curses.cards.ron")),
+];
+
+// ============================================================================
+// 数据格式
+// ============================================================================
+
+/// 单个功法定义
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CardDefinition {
+    /// 定义 id（全图谱唯一）
+    pub id: u32,
+    /// 功法名称
+    pub name: String,
+    /// 功法类型
+    pub card_type: CardType,
+    /// 能量消耗（X 费功法填 0）
+    #[serde(default)]
+    pub cost: i32,
+    /// 是否为 X 费功法
+    #[serde(default)]
+    pub x_cost: bool,
+    /// 效果列表（按顺序结算，描述由此生成）
+    pub effects: Vec<CardEffect>,
+    /// 稀有度
+    pub rarity: CardRarity,
+    /// 进阶增量（缺省时沿用首个效果的默认增量）
+    #[serde(default)]
+    pub upgrade: Option<CardUpgrade>,
+    /// 插画路径
+    pub art: String,
+    /// 是否进入机缘/坊市奖励池
+    #[serde(default = "default_rewardable")]
+    pub rewardable: bool,
+    /// 藏经阁参悟所需道韵（填写后须先参悟才会进入奖励池）
+    #[serde(default)]
+    pub unlock_cost: Option<u32>,
+    /// 所属宗门（缺省为各宗通用；填写后只出现在该宗门的奖励池中）
+    #[serde(default)]
+    pub sect: Option<Sect>,
+}
+
+fn default_rewardable() -> bool {
+    true
+}
+
+/// 初始牌组条目
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StartingDeckEntry {
+    /// 引用的功法定义 id
+    pub card: u32,
+    /// 张数
+    pub copies: u32,
+    /// 所属宗门（缺省为青云剑宗）
+    #[serde(default)]
+    pub sect: Sect,
+}
+
+/// 一个功法数据文件
+#[derive(Asset, TypePath, Debug, Clone, Default, Deserialize)]
+pub struct CardDataFile {
+    #[serde(default)]
+    pub cards: Vec<CardDefinition>,
+    #[serde(default)]
+    pub starting_deck: Vec<StartingDeckEntry>,
+}
+
+/// 功法数据错误（均指明出错的文件与字段）
+#[derive(Debug)]
+pub enum CardDataError {
+    Io { file: String, source: std::io::Error },
+    Parse { file: String, message: String },
+    Invalid { file: String, card_id: Option<u32>, field: String, message: String },
+}
+
+impl CardDataError {
+    fn invalid(file: &str, card_id: Option<u32>, field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Invalid { file: file.to_string(), card_id, field: field.into(), message: message.into() }
+    }
+}
+
+impl fmt::Display for CardDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { file, source } => write!(f, "{}: 读取失败：{}", file, source),
+            Self::Parse { file, message } => write!(f, "{}: RON 解析失败：{}", file, message),
+            Self::Invalid { file, card_id: Some(id), field, message } => {
+                write!(f, "{}: 功法 #{} 字段 `{}`：{}", file, id, field, message)
+            }
+            Self::Invalid { file, card_id: None, field, message } => {
+                write!(f, "{}: 字段 `{}`：{}", file, field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CardDataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl CardDefinition {
+    /// 以指定实例 id 生成一张卡牌
+    pub fn instantiate(&self, id: u32) -> Card {
+        let mut card = Card::new(
+            id,
+            self.name.clone(),
+            self.card_type,
+            self.cost,
+            self.effects.clone(),
+            self.rarity,
+            self.art.clone(),
+        );
+        if self.x_cost {
+            card = card.with_x_cost();
+        }
+        card.upgrade_delta = self.upgrade;
+        card
+    }
+
+    /// 校验单个定义
+    fn validate(&self, file: &str) -> Result<(), CardDataError> {
+        let err = |field: &str, message: String| CardDataError::invalid(file, Some(self.id), field, message);
+
+        if self.name.trim().is_empty() {
+            return Err(err("name", "名称不能为空".to_string()));
+        }
+        if !(0..=MAX_CARD_COST).contains(&self.cost) {
+            return Err(err("cost", format!("能量消耗需在 0..={} 之间（当前 {}）", MAX_CARD_COST, self.cost)));
+        }
+        if self.x_cost && self.cost != 0 {
+            return Err(err("cost", "X 费功法的 cost 须为 0".to_string()));
+        }
+        if self.art.trim().is_empty() {
+            return Err(err("art", "插画路径不能为空".to_string()));
+        }
+        if self.effects.is_empty() {
+            return Err(err("effects", "至少需要一个效果".to_string()));
+        }
+        match self.unlock_cost {
+            Some(0) => return Err(err("unlock_cost", "参悟所需道韵须大于 0".to_string())),
+            Some(_) if !self.rewardable => return Err(err("unlock_cost", "不进入奖励池的功法无需参悟".to_string())),
+            _ => {}
+        }
+        for (index, effect) in self.effects.iter().enumerate() {
+            validate_effect(effect, self.x_cost).map_err(|message| err(&format!("effects[{}]", index), message))?;
+        }
+
+        if let Some(upgrade) = &self.upgrade {
+            let fields: Vec<&str> = self.effects.iter().flat_map(|e| e.numeric_fields()).map(|(name, _)| name).collect();
+            for (name, _) in upgrade.deltas() {
+                if !fields.contains(&name) {
+                    return Err(err(&format!("upgrade.{}", name), format!("效果中没有 `{}` 数值可进阶", name)));
+                }
+            }
+            if !self.x_cost && !(0..=MAX_CARD_COST).contains(&(self.cost + upgrade.cost)) {
+                return Err(err("upgrade.cost", "进阶后能量消耗越界".to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 校验单个效果（递归检查条件/倍增效果的子效果）
+fn validate_effect(effect: &CardEffect, x_cost: bool) -> Result<(), String> {
+    let known_status = |status: &StatusId| {
+        StatusRegistry::builtin().get(status).map(|_| ()).ok_or_else(|| format!("状态图谱中没有 `{}`", status))
+    };
+    match effect {
+        CardEffect::ApplyStatus { status, count } | CardEffect::GainStatus { status, count } => {
+            if *count <= 0 {
+                return Err("状态层数必须大于 0".to_string());
+            }
+            known_status(status)
+        }
+        CardEffect::If { condition: EffectCondition::TargetHas(status), effects } => {
+            known_status(status)?;
+            if effects.is_empty() {
+                return Err("条件效果至少需要一个子效果".to_string());
+            }
+            effects.iter().try_for_each(|e| validate_effect(e, x_cost))
+        }
+        CardEffect::Scaled { per, effect } => {
+            if *per == ScaleSource::EnergySpent && !x_cost {
+                return Err("仅 X 费功法可按消耗灵力倍增".to_string());
+            }
+            validate_effect(effect, x_cost)
+        }
+        _ => Ok(()),
+    }
+}
+
+impl CardDataFile {
+    /// 解析并校验一个数据文件
+    pub fn parse(file: &str, bytes: &[u8]) -> Result<Self, CardDataError> {
+        let data: Self = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_bytes(bytes)
+            .map_err(|e| CardDataError::Parse { file: file.to_string(), message: e.to_string() })?;
+        data.validate(file)?;
+        Ok(data)
+    }
+
+    fn validate(&self, file: &str) -> Result<(), CardDataError> {
+        let mut ids = HashSet::new();
+        for card in &self.cards {
+            if !ids.insert(card.id) {
+                return Err(CardDataError::invalid(file, Some(card.id), "id", "同一文件内 id 重复"));
+            }
+            card.validate(file)?;
+        }
+        for (index, entry) in self.starting_deck.iter().enumerate() {
+            if entry.copies == 0 {
+                return Err(CardDataError::invalid(file, None, format!("starting_deck[{}].copies", index), "张数必须大于 0"));
+            }
+        }
+        Ok(())
+    }
+}
+
+// ============================================================================
+// 资源加载器
+// ============================================================================
+
+/// `*.cards.ron` 资源加载器
+#[derive(Default)]
+pub struct CardDataLoader;
+
+impl AssetLoader for CardDataLoader {
+    type Asset = CardDataFile;
+    type Settings = ();
+    type Error = CardDataError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let file = load_context.path().display().to_string();
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|source| CardDataError::Io { file: file.clone(), source })?;
+        CardDataFile::parse(&file, &bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["cards.ron"]
+    }
+}
+
+// ============================================================================
+// 功法图谱
+// ============================================================================
+
+/// 功法图谱资源：汇总全部数据文件后的功法定义
+#[derive(Resource, Debug, Clone)]
+pub struct CardLibrary {
+    /// 按 id 排序的功法定义
+    definitions: Vec<CardDefinition>,
+    /// 初始牌组
+    starting_deck: Vec<StartingDeckEntry>,
+    /// 已在藏经阁参悟的功法 id（由修行档案同步）
+    unlocked: BTreeSet<u32>,
+}
+
+impl Default for CardLibrary {
+    fn default() -> Self {
+        Self::builtin().clone()
+    }
+}
+
+impl CardLibrary {
+    /// 编译期内置的功法图谱
+    pub fn builtin() -> &'static CardLibrary {
+        static BUILTIN: OnceLock<CardLibrary> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let files: Vec<(&str, CardDataFile)> = BUILTIN_CARD_FILES
+                .iter()
+                .map(|(path, text)| {
+                    let data = CardDataFile::parse(path, text.as_bytes()).unwrap_or_else(|e| panic!("内置功法数据无效：{}", e));
+                    (*path, data)
+                })
+                .collect();
+            Self::from_files(files.iter().map(|(path, data)| (*path, data)))
+                .unwrap_or_else(|e| panic!("内置功法数据无效：{}", e))
+        })
+    }
+
+    /// 由多个数据文件汇总图谱，校验跨文件的 id 冲突与初始牌组引用
+    pub fn from_files<'a>(files: impl IntoIterator<Item = (&'a str, &'a CardDataFile)>) -> Result<Self, CardDataError> {
+        let mut definitions: Vec<CardDefinition> = Vec::new();
+        let mut starting_deck = Vec::new();
+        let mut origins: Vec<(u32, &str)> = Vec::new();
+        let mut deck_refs: Vec<(&str, usize, &StartingDeckEntry)> = Vec::new();
+
+        for (file, data) in files {
+            for card in &data.cards {
+                if let Some((_, other)) = origins.iter().find(|(id, _)| *id == card.id) {
+                    return Err(CardDataError::invalid(file, Some(card.id), "id", format!("与 {} 中的功法重复", other)));
+                }
+                origins.push((card.id, file));
+                definitions.push(card.clone());
+            }
+            for (index, entry) in data.starting_deck.iter().enumerate() {
+                deck_refs.push((file, index, entry));
+                starting_deck.push(entry.clone());
+            }
+        }
+
+        for (file, index, entry) in deck_refs {
+            let field = format!("starting_deck[{}].card", index);
+            match definitions.iter().find(|d| d.id == entry.card) {
+                None => {
+                    return Err(CardDataError::invalid(file, None, field, format!("引用了不存在的功法 #{}", entry.card)));
+                }
+                Some(d) if d.unlock_cost.is_some() => {
+                    return Err(CardDataError::invalid(file, None, field, format!("初始牌组不能引用待参悟的功法 #{}", entry.card)));
+                }
+                Some(d) if d.sect.is_some_and(|sect| sect != entry.sect) => {
+                    return Err(CardDataError::invalid(file, None, field, format!("功法 #{} 不属于{}", entry.card, entry.sect.name())));
+                }
+                Some(_) => {}
+            }
+        }
+
+        definitions.sort_by_key(|d| d.id);
+        Ok(Self { definitions, starting_deck, unlocked: BTreeSet::new() })
+    }
+
+    /// 功法定义数量
+    pub fn len(&self) -> usize {
+        self.definitions.len()
+    }
+
+    /// 图谱是否为空
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty()
+    }
+
+    /// 按 id 查找功法定义
+    pub fn definition(&self, id: u32) -> Option<&CardDefinition> {
+        self.definitions.iter().find(|d| d.id == id)
+    }
+
+    /// 按名称查找功法定义（进阶后的 `+` 后缀忽略不计）
+    pub fn definition_by_name(&self, name: &str) -> Option<&CardDefinition> {
+        let name = name.trim_end_matches('+');
+        self.definitions.iter().find(|d| d.name == name)
+    }
+
+    /// 需在藏经阁参悟的功法
+    pub fn unlockable(&self) -> impl Iterator<Item = &CardDefinition> {
+        self.definitions.iter().filter(|d| d.unlock_cost.is_some())
+    }
+
+    /// 已参悟的功法 id
+    pub fn unlocked(&self) -> &BTreeSet<u32> {
+        &self.unlocked
+    }
+
+    /// 同步已参悟的功法（图谱重建后需重新同步）
+    pub fn set_unlocked(&mut self, unlocked: BTreeSet<u32>) {
+        self.unlocked = unlocked;
+    }
+
+    /// 按 id 生成一张卡牌（实例 id 与定义 id 相同）
+    pub fn card(&self, id: u32) -> Option<Card> {
+        self.definition(id).map(|d| d.instantiate(d.id))
+    }
+
+    /// 宗门的初始牌组（实例 id 从 0 依次编号）
+    pub fn starting_deck(&self, sect: Sect) -> Vec<Card> {
+        self.starting_deck
+            .iter()
+            .filter(|entry| entry.sect == sect)
+            .filter_map(|entry| self.definition(entry.card).map(|d| (d, entry.copies)))
+            .flat_map(|(d, copies)| std::iter::repeat(d).take(copies as usize))
+            .enumerate()
+            .map(|(i, d)| d.instantiate(i as u32))
+            .collect()
+    }
+
+    /// 宗门可获得的卡牌（奖励池：通用功法与本宗功法，待参悟的功法除外）
+    pub fn all_cards(&self, sect: Sect) -> Vec<Card> {
+        self.definitions
+            .iter()
+            .filter(|d| d.rewardable && d.sect.map_or(true, |s| s == sect))
+            .filter(|d| d.unlock_cost.map_or(true, |_| self.unlocked.contains(&d.id)))
+            .map(|d| d.instantiate(d.id))
+            .collect()
+    }
+
+    /// 根据稀有度获取卡牌
+    pub fn get_by_rarity(&self, rarity: CardRarity, sect: Sect) -> Vec<Card> {
+        self.all_cards(sect).into_iter().filter(|c| c.rarity == rarity).collect()
+    }
+
+    /// 随机获取指定数量的卡牌（用于奖励）
+    pub fn random_cards(&self, count: usize, sect: Sect, rng: &mut impl rand::Rng) -> Vec<Card> {
+        self.all_cards(sect).choose_multiple(rng, count).cloned().collect()
+    }
+
+    /// 随机获取卡牌（偏向稀有度）
+    pub fn random_rewards(&self, count: usize, sect: Sect, rng: &mut impl rand::Rng) -> Vec<Card> {
+        let mut rewards = Vec::new();
+
+        for i in 0..count {
+            // 奖励概率：50%普通，40%稀有，10%罕见
+            let rarity_roll = rng.gen::<f32>();
+            let rarity = if rarity_roll < 0.5 {
+                CardRarity::Common
+            } else if rarity_roll < 0.9 {
+                CardRarity::Uncommon
+            } else {
+                CardRarity::Rare
+            };
+
+            let cards = self.get_by_rarity(rarity, sect);
+            if let Some(card) = cards.choose(rng) {
+                // 为每张卡创建唯一ID
+                let mut card = card.clone();
+                card.id = 1000 + i as u32;
+                rewards.push(card);
+            }
+        }
+
+        rewards
+    }
+
+    /// 获取筑基期本命功法
+    pub fn innate_spell(&self) -> Option<Card> {
+        self.card(INNATE_SPELL_ID)
+    }
+
+    /// 渡劫功法（图谱中缺失者略过）
+    pub fn tribulation_cards(&self) -> Vec<Card> {
+        TRIBULATION_CARD_IDS.iter().filter_map(|&id| self.card(id)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "data/cards/test.cards.ron";
+
+    #[test]
+    fn test_builtin_library_matches_starting_deck() {
+        let library = CardLibrary::builtin();
+        let deck = library.starting_deck(Sect::Sword);
+
+        assert_eq!(deck.len(), 15);
+        assert_eq!(deck.iter().filter(|c| c.name == "御剑术").count(), 5);
+        assert_eq!(deck[0].description, "造成6点伤害");
+        assert!(library.innate_spell().is_some());
+        assert!(library.all_cards(Sect::Sword).iter().all(|c| c.id >= 100));
+    }
+
+    #[test]
+    fn test_sect_decks_and_reward_pools() {
+        let library = CardLibrary::builtin();
+        for sect in Sect::ALL {
+            assert_eq!(library.starting_deck(sect).len(), 15, "{}", sect.name());
+            let foreign = library.all_cards(sect).into_iter().find(|c| library.definition(c.id).is_some_and(|d| d.sect.is_some_and(|s| s != sect)));
+            assert!(foreign.is_none(), "{}的奖励池混入他宗功法 {:?}", sect.name(), foreign.map(|c| c.name));
+        }
+        let has = |sect, name: &str| library.all_cards(sect).iter().any(|c| c.name == name);
+        assert!(has(Sect::Sword, "万剑归宗") && !has(Sect::Talisman, "万剑归宗"));
+        assert!(has(Sect::Alchemy, "九转金丹") && has(Sect::Talisman, "雷法·掌心雷"));
+
+        let text = r#"(starting_deck: [(card: 7, copies: 1, sect: Alchemy)],
+            cards: [(id: 7, name: "符箭", card_type: Attack, cost: 1, effects: [DealDamage(amount: 5)],
+            rarity: Common, art: "a.png", rewardable: false, sect: Talisman)])"#;
+        let data = CardDataFile::parse(FILE, text.as_bytes()).unwrap();
+        let err = CardLibrary::from_files([(FILE, &data)]).unwrap_err().to_string();
+        assert!(err.contains("starting_deck[0].card") && err.contains("丹鼎门"), "{}", err);
+    }
+
+    #[test]
+    fn test_validation_names_file_and_field() {
+        let text = r#"(cards: [(id: 7, name: "歪招", card_type: Attack,
+            cost: 9, effects: [DealDamage(amount: 1)], rarity: Common, art: "a.png")])"#;
+        let err = CardDataFile::parse(FILE, text.as_bytes()).unwrap_err().to_string();
+        assert!(err.contains(FILE) && err.contains("#7") && err.contains("`cost`"), "{}", err);
+
+        let text = r#"(cards: [(id: 7, name: "歪招", card_type: Attack, cost: 1,
+            effects: [GainBlock(amount: 1), Scaled(per: EnergySpent, effect: DealDamage(amount: 1))],
+            rarity: Common, art: "a.png")])"#;
+        let err = CardDataFile::parse(FILE, text.as_bytes()).unwrap_err().to_string();
+        assert!(err.contains("`effects[1]`"), "{}", err);
+    }
+
+    #[test]
+    fn test_upgrade_applies_delta_and_regenerates_text() {
+        let text = r#"(cards: [(id: 7, name: "金身法相", card_type: Defense, cost: 2,
+            effects: [GainBlock(amount: 15), Heal(amount: 5)], rarity: Rare,
+            upgrade: (amount: 3, cost: -1), art: "a.png")])"#;
+        let data = CardDataFile::parse(FILE, text.as_bytes()).unwrap();
+        let library = CardLibrary::from_files([(FILE, &data)]).unwrap();
+
+        let mut card = library.card(7).unwrap();
+        assert_eq!(card.description, "获得15点护盾，恢复5点道行");
+        card.upgrade();
+        assert_eq!(card.description, "获得18点护盾，恢复5点道行");
+        assert_eq!(card.cost, 1);
+    }
+
+    #[test]
+    fn test_duplicate_ids_across_files_are_rejected() {
+        let (path, text) = BUILTIN_CARD_FILES[1];
+        let data = CardDataFile::parse(path, text.as_bytes()).unwrap();
+        let err = CardLibrary::from_files([("a.cards.ron", &data), ("b.cards.ron", &data)]).unwrap_err().to_string();
+        assert!(err.contains("b.cards.ron") && err.contains("a.cards.ron"), "{}", err);
+    }
+}
@@ -0,0 +1,439 @@
+//! 妖物图谱（数据驱动的敌人定义）
+//!
+//! 敌人定义在 `assets/data/enemies.ron` 中，编译期内置并在首次使用时解析校验。
+//! 每种妖物声明命名招式、择招规则与按道行阈值切换的阶段，阶段的表现钩子也一并写在数据里。
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::OnceLock;
+
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::components::combat::{EnemyIntent, EnemyType};
+use crate::components::sprite::AnimationState;
+
+/// 内置妖物数据（路径相对 assets 目录）
+const BUILTIN_ENEMY_FILE: (&str, &str) = ("data/enemies.ron", include_str!("../../assets/data/enemies.ron"));
+
+/// 诅咒招式注入的心魔卡牌 id
+pub const CURSE_CARD_ID: u32 = 500;
+/// 封印招式可选的气穴数
+const SEAL_SLOTS: usize = 5;
+
+// ============================================================================
+// 数据格式
+// ============================================================================
+
+/// 招式行动
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum MoveAction {
+    /// 攻击，伤害在范围内随机（再叠加自身攻击力）
+    Attack { damage: (i32, i32) },
+    /// 防御；`charge` 为真时同时蓄势（下一击伤害翻倍）
+    Defend {
+        block: (i32, i32),
+        #[serde(default)]
+        charge: bool,
+    },
+    /// 强化自身攻击力
+    Buff { strength: (i32, i32) },
+    /// 对修行者施加中毒与虚弱
+    Debuff { poison: i32, weakness: i32 },
+    /// 向修行者归墟注入心魔
+    Curse,
+    /// 随机封印一个气穴
+    Seal { duration: u32 },
+    /// 按兵不动
+    Wait,
+}
+
+impl MoveAction {
+    /// 掷出本次行动的具体意图
+    pub fn roll(&self, strength: i32, rng: &mut impl Rng) -> EnemyIntent {
+        match *self {
+            MoveAction::Attack { damage } => EnemyIntent::Attack { damage: rng.gen_range(damage.0..=damage.1) + strength },
+            MoveAction::Defend { block, .. } => EnemyIntent::Defend { block: rng.gen_range(block.0..=block.1) },
+            MoveAction::Buff { strength: gain } => EnemyIntent::Buff { strength: rng.gen_range(gain.0..=gain.1) },
+            MoveAction::Debuff { poison, weakness } => EnemyIntent::Debuff { poison, weakness },
+            MoveAction::Curse => EnemyIntent::Curse { card_id: CURSE_CARD_ID },
+            MoveAction::Seal { duration } => EnemyIntent::Seal { slot_index: rng.gen_range(0..SEAL_SLOTS), duration },
+            MoveAction::Wait => EnemyIntent::Wait,
+        }
+    }
+
+    fn ranges(&self) -> Vec<(&'static str, (i32, i32))> {
+        match *self {
+            MoveAction::Attack { damage } => vec![("damage", damage)],
+            MoveAction::Defend { block, .. } => vec![("block", block)],
+            MoveAction::Buff { strength } => vec![("strength", strength)],
+            MoveAction::Debuff { poison, weakness } => vec![("poison", (poison, poison)), ("weakness", (weakness, weakness))],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// 命名招式
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EnemyMove {
+    /// 招式 id（妖物内唯一）
+    pub id: String,
+    /// 显示名称
+    pub name: String,
+    pub action: MoveAction,
+    /// 按权重择招时的权重
+    #[serde(default = "default_move_weight")]
+    pub weight: f32,
+    /// 最多连续施展次数
+    #[serde(default)]
+    pub max_repeats: Option<u32>,
+}
+
+fn default_move_weight() -> f32 {
+    1.0
+}
+
+/// 阶段择招方式
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub enum MovePattern {
+    /// 按招式权重随机（遵守连续次数上限）
+    Weighted(Vec<String>),
+    /// 按顺序循环
+    Sequence(Vec<String>),
+}
+
+impl MovePattern {
+    pub fn moves(&self) -> &[String] {
+        match self {
+            MovePattern::Weighted(ids) | MovePattern::Sequence(ids) => ids,
+        }
+    }
+}
+
+/// 阶段表现钩子
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub struct PhaseVisuals {
+    /// 攻击动画（缺省按外形）
+    #[serde(default)]
+    pub attack_animation: Option<AnimationState>,
+    /// 攻击时在修行者身旁降下雷光
+    #[serde(default)]
+    pub lightning: bool,
+    /// 防御时凝聚的灵气粒子数
+    #[serde(default)]
+    pub defend_aura: u32,
+    /// 进入阶段时的屏幕闪光 (r, g, b, a)
+    #[serde(default)]
+    pub enter_flash: Option<(f32, f32, f32, f32)>,
+    /// 进入阶段时的震屏强度
+    #[serde(default)]
+    pub enter_shake: f32,
+    /// 进入阶段时迸发的剑气粒子数
+    #[serde(default)]
+    pub enter_burst: u32,
+}
+
+/// 战斗阶段
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EnemyPhase {
+    pub name: String,
+    /// 道行跌破最大值的该百分比时进入（起手阶段不填）
+    #[serde(default)]
+    pub below_hp_percent: Option<i32>,
+    pub pattern: MovePattern,
+    #[serde(default)]
+    pub visuals: PhaseVisuals,
+}
+
+impl EnemyPhase {
+    /// 当前道行是否已跌破本阶段阈值
+    pub fn reached(&self, hp: i32, max_hp: i32) -> bool {
+        match self.below_hp_percent {
+            Some(percent) => hp * 100 < max_hp * percent,
+            None => true,
+        }
+    }
+}
+
+/// 出场权重（深度达到 `from_depth` 后生效）
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct SpawnWeight {
+    pub from_depth: u32,
+    pub weight: f32,
+}
+
+/// 单个妖物定义
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EnemyDefinition {
+    /// 妖物 id（全图谱唯一）
+    pub id: String,
+    /// 显示名称
+    pub name: String,
+    pub enemy_type: EnemyType,
+    /// 基础道行范围
+    pub hp: (i32, i32),
+    /// 可作为镇守首领出场
+    #[serde(default)]
+    pub boss: bool,
+    /// 精英妖物（只随精英阵容出场，必带「精英」词缀）
+    #[serde(default)]
+    pub elite: bool,
+    #[serde(default)]
+    pub spawn: Vec<SpawnWeight>,
+    pub moves: Vec<EnemyMove>,
+    pub phases: Vec<EnemyPhase>,
+}
+
+impl EnemyDefinition {
+    /// 按 id 查找招式
+    pub fn find_move(&self, id: &str) -> Option<&EnemyMove> {
+        self.moves.iter().find(|m| m.id == id)
+    }
+
+    /// 指定深度下的出场权重
+    pub fn spawn_weight(&self, depth: u32) -> f32 {
+        self.spawn.iter().rev().find(|s| s.from_depth <= depth).map(|s| s.weight).unwrap_or(0.0)
+    }
+
+    fn validate(&self, file: &str) -> Result<(), EnemyDataError> {
+        let err = |field: &str, message: &str| EnemyDataError::invalid(file, Some(&self.id), field, message);
+
+        if self.name.trim().is_empty() {
+            return Err(err("name", "名称不能为空"));
+        }
+        if self.hp.0 <= 0 || self.hp.0 > self.hp.1 {
+            return Err(err("hp", "道行范围须为正且最小值不大于最大值"));
+        }
+        if self.elite && self.boss {
+            return Err(err("elite", "精英妖物不能同时为镇守首领"));
+        }
+        if self.spawn.iter().any(|s| s.weight < 0.0) {
+            return Err(err("spawn", "出场权重不能为负"));
+        }
+        if self.moves.is_empty() {
+            return Err(err("moves", "至少需要一个招式"));
+        }
+        let mut move_ids = HashSet::new();
+        for m in &self.moves {
+            if !move_ids.insert(m.id.as_str()) {
+                return Err(err("moves", &format!("招式 id `{}` 重复", m.id)));
+            }
+            if m.weight < 0.0 {
+                return Err(err("weight", &format!("招式 `{}` 权重不能为负", m.id)));
+            }
+            if m.max_repeats == Some(0) {
+                return Err(err("max_repeats", &format!("招式 `{}` 连续次数上限至少为 1", m.id)));
+            }
+            for (field, (low, high)) in m.action.ranges() {
+                if low < 0 || low > high {
+                    return Err(err(field, &format!("招式 `{}` 数值须非负且最小值不大于最大值", m.id)));
+                }
+            }
+        }
+
+        let Some(opening) = self.phases.first() else {
+            return Err(err("phases", "至少需要一个阶段"));
+        };
+        if opening.below_hp_percent.is_some() {
+            return Err(err("below_hp_percent", "起手阶段不能设置阈值"));
+        }
+        let mut previous = 100;
+        for (index, phase) in self.phases.iter().enumerate() {
+            if let Some(percent) = phase.below_hp_percent {
+                if percent <= 0 || percent >= previous {
+                    return Err(err("below_hp_percent", &format!("阶段 `{}` 阈值须在 1..{} 之间且逐段降低", phase.name, previous)));
+                }
+                previous = percent;
+            } else if index > 0 {
+                return Err(err("below_hp_percent", &format!("阶段 `{}` 缺少阈值", phase.name)));
+            }
+            if phase.pattern.moves().is_empty() {
+                return Err(err("pattern", &format!("阶段 `{}` 没有招式", phase.name)));
+            }
+            if let Some(unknown) = phase.pattern.moves().iter().find(|id| self.find_move(id).is_none()) {
+                return Err(err("pattern", &format!("阶段 `{}` 引用了不存在的招式 `{}`", phase.name, unknown)));
+            }
+            if let MovePattern::Weighted(ids) = &phase.pattern {
+                // 全部招式都有连续上限时可能无招可出
+                let unlimited = ids.iter().filter_map(|id| self.find_move(id)).any(|m| m.max_repeats.is_none());
+                if !unlimited && ids.len() < 2 {
+                    return Err(err("max_repeats", &format!("阶段 `{}` 唯一招式不能设置连续次数上限", phase.name)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 妖物数据文件
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EnemyDataFile {
+    pub enemies: Vec<EnemyDefinition>,
+}
+
+/// 妖物数据错误（指明出错的文件、妖物与字段）
+#[derive(Debug)]
+pub enum EnemyDataError {
+    Parse { file: String, message: String },
+    Invalid { file: String, enemy: Option<String>, field: String, message: String },
+}
+
+impl EnemyDataError {
+    fn invalid(file: &str, enemy: Option<&str>, field: &str, message: &str) -> Self {
+        Self::Invalid {
+            file: file.to_string(),
+            enemy: enemy.map(|s| s.to_string()),
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for EnemyDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse { file, message } => write!(f, "{}: RON 解析失败：{}", file, message),
+            Self::Invalid { file, enemy: Some(id), field, message } => {
+                write!(f, "{}: 妖物 `{}` 字段 `{}`：{}", file, id, field, message)
+            }
+            Self::Invalid { file, enemy: None, field, message } => {
+                write!(f, "{}: 字段 `{}`：{}", file, field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EnemyDataError {}
+
+// ============================================================================
+// 妖物图谱
+// ============================================================================
+
+/// 妖物图谱
+#[derive(Debug, Clone)]
+pub struct EnemyRoster {
+    definitions: Vec<EnemyDefinition>,
+}
+
+impl EnemyRoster {
+    /// 编译期内置的妖物图谱
+    pub fn builtin() -> &'static EnemyRoster {
+        static BUILTIN: OnceLock<EnemyRoster> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let (file, text) = BUILTIN_ENEMY_FILE;
+            Self::parse(file, text.as_bytes()).unwrap_or_else(|e| panic!("内置妖物数据无效：{}", e))
+        })
+    }
+
+    /// 解析并校验妖物数据
+    pub fn parse(file: &str, bytes: &[u8]) -> Result<Self, EnemyDataError> {
+        let data: EnemyDataFile = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_bytes(bytes)
+            .map_err(|e| EnemyDataError::Parse { file: file.to_string(), message: e.to_string() })?;
+
+        let mut ids = HashSet::new();
+        for definition in &data.enemies {
+            if !ids.insert(definition.id.clone()) {
+                return Err(EnemyDataError::invalid(file, Some(&definition.id), "id", "妖物 id 重复"));
+            }
+            definition.validate(file)?;
+        }
+        for enemy_type in [EnemyType::DemonicWolf, EnemyType::PoisonSpider, EnemyType::CursedSpirit, EnemyType::GreatDemon] {
+            if !data.enemies.iter().any(|d| d.enemy_type == enemy_type) {
+                return Err(EnemyDataError::invalid(file, None, "enemy_type", &format!("缺少外形为 {:?} 的妖物", enemy_type)));
+            }
+        }
+        if !data.enemies.iter().any(|d| d.boss) {
+            return Err(EnemyDataError::invalid(file, None, "boss", "至少需要一个镇守首领"));
+        }
+        Ok(Self { definitions: data.enemies })
+    }
+
+    /// 按 id 查找妖物定义
+    pub fn get(&self, id: &str) -> Option<&EnemyDefinition> {
+        self.definitions.iter().find(|d| d.id == id)
+    }
+
+    /// 全部妖物定义（数据文件顺序）
+    pub fn definitions(&self) -> &[EnemyDefinition] {
+        &self.definitions
+    }
+
+    /// 指定外形的首个妖物定义
+    pub fn default_for(&self, enemy_type: EnemyType) -> &EnemyDefinition {
+        self.definitions
+            .iter()
+            .find(|d| d.enemy_type == enemy_type)
+            .expect("解析时已校验每种外形都有定义")
+    }
+
+    /// 在妖物池中按深度的出场权重抽取一种妖物（池中皆不可出场时退回池中首个）
+    pub fn pick(&self, pool: &[String], depth: u32, rng: &mut impl Rng) -> &EnemyDefinition {
+        let candidates: Vec<&EnemyDefinition> = pool.iter().filter_map(|id| self.get(id)).collect();
+        Self::weighted(&candidates, |d| d.spawn_weight(depth), rng)
+            .or(candidates.first())
+            .copied()
+            .unwrap_or(&self.definitions[0])
+    }
+
+    /// 在首领池中抽取一名镇守首领（首领之间按该深度的出场权重，皆为 0 时等概率）
+    pub fn pick_boss(&self, pool: &[String], depth: u32, rng: &mut impl Rng) -> &EnemyDefinition {
+        let mut bosses: Vec<&EnemyDefinition> = pool.iter().filter_map(|id| self.get(id)).filter(|d| d.boss).collect();
+        if bosses.is_empty() {
+            bosses = self.definitions.iter().filter(|d| d.boss).collect();
+        }
+        Self::weighted(&bosses, |d| d.spawn_weight(depth), rng)
+            .copied()
+            .unwrap_or_else(|| bosses[rng.gen_range(0..bosses.len())])
+    }
+
+    fn weighted<'a, T>(items: &'a [T], weight: impl Fn(&T) -> f32, rng: &mut impl Rng) -> Option<&'a T> {
+        let total: f32 = items.iter().map(&weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+        let mut roll = rng.gen::<f32>() * total;
+        for item in items {
+            roll -= weight(item);
+            if roll < 0.0 {
+                return Some(item);
+            }
+        }
+        items.iter().rev().find(|item| weight(item) > 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_roster_declares_phases() {
+        let roster = EnemyRoster::builtin();
+        let demon = roster.default_for(EnemyType::GreatDemon);
+        assert!(demon.boss);
+        assert_eq!(demon.phases.len(), 2);
+        assert!(!demon.phases[1].reached(100, 200));
+        assert!(demon.phases[1].reached(99, 200));
+        assert_eq!(roster.default_for(EnemyType::DemonicWolf).spawn_weight(0), 0.6);
+        assert_eq!(roster.get("cursed_spirit").unwrap().spawn_weight(2), 0.0);
+
+        let king = roster.get("blood_wolf_king").unwrap();
+        assert!(king.elite && !king.boss);
+        assert_eq!(king.spawn_weight(20), 0.0);
+    }
+
+    #[test]
+    fn test_invalid_enemy_names_field() {
+        let text = r#"(enemies: [(id: "slime", name: "史莱姆", enemy_type: DemonicWolf, hp: (10, 12),
+            moves: [(id: "hit", name: "撞", action: Attack(damage: (3, 4)))],
+            phases: [(name: "起", pattern: Weighted(["hit"])), (name: "怒", below_hp_percent: 120, pattern: Weighted(["hit"]))])])"#;
+        let err = EnemyRoster::parse("data/test.ron", text.as_bytes()).unwrap_err().to_string();
+        assert!(err.contains("slime") && err.contains("below_hp_percent"), "{}", err);
+
+        let text = text.replace("120", "50").replace(r#"Weighted(["hit"])), (name"#, r#"Weighted(["kick"])), (name"#);
+        let err = EnemyRoster::parse("data/test.ron", text.as_bytes()).unwrap_err().to_string();
+        assert!(err.contains("pattern") && err.contains("kick"), "{}", err);
+    }
+}
@@ -0,0 +1,652 @@
+//! 机缘图谱（数据驱动的奇遇事件）
+//!
+//! 事件定义在 `assets/data/events/*.events.ron` 中，编译期内置并在首次使用时解析校验。
+//! 每个事件由若干页组成：页内台词交给 [`Dialogue`](crate::components::dialogue::Dialogue) 逐句播放，
+//! 选项可按灵石、道行、境界、法宝或功法设限，选中后依次结算，再翻页、转入战斗或就此离去。
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::OnceLock;
+
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::components::cards::{CardType, PlayerDeck};
+use crate::components::combat::Player;
+use crate::components::cultivation::{Cultivation, Realm};
+use crate::components::dialogue::DialogueLine;
+use crate::components::relic::RelicCollection;
+use crate::resources::card_data::CardLibrary;
+use crate::resources::enemy_data::EnemyRoster;
+use crate::resources::relic_data::RelicLibrary;
+
+/// 编译期内置的机缘数据（路径相对 assets 目录）
+const BUILTIN_EVENT_FILES: &[(&str, &str)] = &[
+    ("data/events/wilds.events.ron", include_str!("../../assets/data/events/wilds.events.ron")),
+    ("data/events/encounters.events.ron", include_str!("../../assets/data/events/encounters.events.ron")),
+    ("data/events/trials.events.ron", include_str!("../../assets/data/events/trials.events.ron")),
+];
+
+/// 随机法宝的稀有度权重（常见/罕见/稀有）
+const RANDOM_RELIC_WEIGHTS: [f32; 3] = [0.5, 0.35, 0.15];
+
+// ============================================================================
+// 数据格式
+// ============================================================================
+
+/// 选项门槛
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum EventRequirement {
+    /// 灵石不少于
+    Gold(i32),
+    /// 当前道行高于（付出道行的选项不至于陨落）
+    Hp(i32),
+    /// 境界不低于
+    Realm(Realm),
+    /// 持有指定法宝
+    Relic(String),
+    /// 牌组中有指定功法（进阶与否皆可）
+    Card(u32),
+    /// 牌组中有该类型的功法
+    CardType(CardType),
+}
+
+impl EventRequirement {
+    /// 修行者是否满足门槛
+    pub fn met(&self, view: &EventView) -> bool {
+        match self {
+            Self::Gold(gold) => view.player.gold >= *gold,
+            Self::Hp(hp) => view.player.hp > *hp,
+            Self::Realm(realm) => view.cultivation.realm >= *realm,
+            Self::Relic(id) => view.relics.has(id),
+            Self::Card(id) => view.cards.definition(*id).is_some_and(|definition| {
+                view.deck.cards.iter().any(|card| card.name.trim_end_matches('+') == definition.name)
+            }),
+            Self::CardType(card_type) => view.deck.cards.iter().any(|card| card.card_type == *card_type),
+        }
+    }
+
+    /// 选项上的门槛提示
+    pub fn describe(&self, cards: &CardLibrary, relics: &RelicLibrary) -> String {
+        match self {
+            Self::Gold(gold) => format!("需 {} 灵石", gold),
+            Self::Hp(hp) => format!("需道行高于 {}", hp),
+            Self::Realm(realm) => format!("需{}", realm.name()),
+            Self::Relic(id) => format!("需持有「{}」", relics.get(id).map(|r| r.name).unwrap_or_else(|| id.clone())),
+            Self::Card(id) => format!("需习得「{}」", cards.definition(*id).map(|d| d.name.as_str()).unwrap_or("?")),
+            Self::CardType(card_type) => format!("需有{}", card_type_name(*card_type)),
+        }
+    }
+}
+
+fn card_type_name(card_type: CardType) -> &'static str {
+    match card_type {
+        CardType::Attack => "攻伐功法",
+        CardType::Defense => "护体功法",
+        CardType::Skill => "秘术",
+        CardType::Power => "心法",
+        CardType::Curse => "心魔",
+    }
+}
+
+/// 选项结果（按书写顺序结算）
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum EventOutcome {
+    /// 灵石增减（至多扣到 0）
+    Gold(i32),
+    /// 道行增减（恢复不超过上限，损失至多降到 1）
+    Hp(i32),
+    /// 道行上限增减（增加时当前道行随之增加）
+    MaxHp(i32),
+    /// 获得指定功法
+    Card(u32),
+    /// 按奖励概率获得若干张随机功法
+    RandomCards(u32),
+    /// 混入一张心魔
+    Curse(u32),
+    /// 获得指定法宝（已持有或法宝位已满时落空）
+    Relic(String),
+    /// 按稀有度权重随机获得一件法宝
+    RandomRelic,
+    /// 获得感悟
+    Insight(u32),
+    /// 随机进阶若干张功法
+    Upgrade(u32),
+    /// 遗忘若干张功法（优先遗忘心魔）
+    Remove(u32),
+    /// 与指定妖物交战（离开机缘，胜后照常领取奖励）
+    Combat(Vec<String>),
+}
+
+impl EventOutcome {
+    /// 选项上的结果提示
+    pub fn describe(&self, cards: &CardLibrary, relics: &RelicLibrary) -> String {
+        match self {
+            Self::Gold(amount) => format!("灵石 {:+}", amount),
+            Self::Hp(amount) => format!("道行 {:+}", amount),
+            Self::MaxHp(amount) => format!("道行上限 {:+}", amount),
+            Self::Card(id) | Self::Curse(id) => {
+                format!("获得「{}」", cards.definition(*id).map(|d| d.name.as_str()).unwrap_or("?"))
+            }
+            Self::RandomCards(count) => format!("随机功法 ×{}", count),
+            Self::Relic(id) => format!("法宝「{}」", relics.get(id).map(|r| r.name).unwrap_or_else(|| id.clone())),
+            Self::RandomRelic => "随机法宝".to_string(),
+            Self::Insight(amount) => format!("感悟 +{}", amount),
+            Self::Upgrade(count) => format!("进阶 {} 张功法", count),
+            Self::Remove(count) => format!("遗忘 {} 张功法", count),
+            Self::Combat(enemies) => {
+                let roster = EnemyRoster::builtin();
+                let names: Vec<&str> = enemies.iter().map(|id| roster.get(id).map_or(id.as_str(), |d| d.name.as_str())).collect();
+                format!("与{}交战", names.join("、"))
+            }
+        }
+    }
+
+    /// 结算本结果，返回翻页后展示的一句说明（无事发生时为 None）
+    ///
+    /// 交战结果不在此结算，由机缘界面转入战斗。
+    pub fn apply(&self, target: &mut EventTarget, rng: &mut impl Rng) -> Option<String> {
+        let EventTarget { player, cultivation, deck, relics, cards, relic_library } = target;
+        match self {
+            Self::Gold(amount) => {
+                let before = player.gold;
+                player.gold = (player.gold + amount).max(0);
+                Some(format!("灵石 {:+}", player.gold - before))
+            }
+            Self::Hp(amount) => {
+                let before = player.hp;
+                player.hp = (player.hp + amount).clamp(1, player.max_hp);
+                Some(format!("道行 {:+}", player.hp - before))
+            }
+            Self::MaxHp(amount) => {
+                player.max_hp = (player.max_hp + amount).max(1);
+                player.hp = (player.hp + (*amount).max(0)).clamp(1, player.max_hp);
+                Some(format!("道行上限 {:+}", amount))
+            }
+            Self::Card(id) | Self::Curse(id) => {
+                let mut card = cards.card(*id)?;
+                card.id = 1000 + deck.cards.len() as u32;
+                let message = format!("获得「{}」", card.name);
+                deck.add_card(card);
+                Some(message)
+            }
+            Self::RandomCards(count) => {
+                let rolled = cards.random_rewards(*count as usize, player.sect, rng);
+                let names: Vec<String> = rolled.iter().map(|c| format!("「{}」", c.name)).collect();
+                for mut card in rolled {
+                    card.id = 1000 + deck.cards.len() as u32;
+                    deck.add_card(card);
+                }
+                (!names.is_empty()).then(|| format!("获得{}", names.join("")))
+            }
+            Self::Relic(id) => {
+                let relic = relic_library.get(id)?;
+                Self::grant_relic(relic, player, cultivation, relics)
+            }
+            Self::RandomRelic => {
+                let relic = relic_library.roll(RANDOM_RELIC_WEIGHTS, relics, cultivation.realm, &[], rng)?;
+                Self::grant_relic(relic, player, cultivation, relics)
+            }
+            Self::Insight(amount) => {
+                cultivation.gain_insight(*amount);
+                Some(format!("感悟 +{}", amount))
+            }
+            Self::Upgrade(count) => {
+                let mut upgradable: Vec<usize> = deck.cards.iter()
+                    .enumerate()
+                    .filter(|(_, c)| !c.upgraded && c.card_type != CardType::Curse)
+                    .map(|(i, _)| i)
+                    .collect();
+                upgradable.shuffle(rng);
+                let names: Vec<String> = upgradable.into_iter().take(*count as usize).map(|i| {
+                    deck.cards[i].upgrade();
+                    format!("「{}」", deck.cards[i].name)
+                }).collect();
+                (!names.is_empty()).then(|| format!("{}已进阶", names.join("")))
+            }
+            Self::Remove(count) => {
+                let mut names = Vec::new();
+                for _ in 0..*count {
+                    let curse = deck.cards.iter().position(|c| c.card_type == CardType::Curse);
+                    let index = match curse {
+                        Some(index) => index,
+                        None if !deck.cards.is_empty() => rng.gen_range(0..deck.cards.len()),
+                        None => break,
+                    };
+                    names.push(format!("「{}」", deck.cards.remove(index).name));
+                }
+                (!names.is_empty()).then(|| format!("遗忘了{}", names.join("")))
+            }
+            Self::Combat(_) => None,
+        }
+    }
+
+    fn grant_relic(relic: crate::components::relic::Relic, player: &mut Player, cultivation: &Cultivation, relics: &mut RelicCollection) -> Option<String> {
+        if relics.add_relic(relic.clone(), cultivation) {
+            relic.on_pickup(player);
+            Some(format!("获得法宝「{}」", relic.name))
+        } else {
+            Some(format!("「{}」与你无缘（法宝位已满或已持有）", relic.name))
+        }
+    }
+
+    fn validate(&self, cards: &CardLibrary, relics: &RelicLibrary) -> Result<(), String> {
+        let known_card = |id: &u32| cards.definition(*id).ok_or_else(|| format!("功法图谱中没有 id {}", id));
+        match self {
+            Self::Card(id) => known_card(id).map(|_| ()),
+            Self::Curse(id) => match known_card(id)?.card_type {
+                CardType::Curse => Ok(()),
+                _ => Err(format!("功法 {} 不是心魔", id)),
+            },
+            Self::Relic(id) => relics.get(id).map(|_| ()).ok_or_else(|| format!("法宝图谱中没有 `{}`", id)),
+            Self::RandomCards(0) | Self::Upgrade(0) | Self::Remove(0) => Err("数量须大于 0".to_string()),
+            Self::Combat(enemies) if enemies.is_empty() => Err("至少需要一只妖物".to_string()),
+            Self::Combat(enemies) => match enemies.iter().find(|id| EnemyRoster::builtin().get(id).is_none()) {
+                Some(unknown) => Err(format!("妖物图谱中没有 `{}`", unknown)),
+                None => Ok(()),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+/// 选项
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EventChoice {
+    pub label: String,
+    #[serde(default)]
+    pub requires: Vec<EventRequirement>,
+    #[serde(default)]
+    pub outcomes: Vec<EventOutcome>,
+    /// 结算后翻到的页（不填则离去）
+    #[serde(default)]
+    pub next: Option<String>,
+}
+
+impl EventChoice {
+    /// 修行者是否满足全部门槛
+    pub fn available(&self, view: &EventView) -> bool {
+        self.requires.iter().all(|r| r.met(view))
+    }
+
+    /// 选项引发的战斗
+    pub fn combat(&self) -> Option<&[String]> {
+        self.outcomes.iter().find_map(|o| match o {
+            EventOutcome::Combat(enemies) => Some(enemies.as_slice()),
+            _ => None,
+        })
+    }
+}
+
+/// 事件页
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EventPage {
+    /// 页 id（事件内唯一）
+    pub id: String,
+    /// 台词（按顺序播放）
+    pub lines: Vec<DialogueLine>,
+    pub choices: Vec<EventChoice>,
+}
+
+/// 出现权重（地图层数达到 `from_layer` 后生效）
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct LayerWeight {
+    pub from_layer: u32,
+    pub weight: f32,
+}
+
+/// 单个机缘定义
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct EventDefinition {
+    /// 事件 id（全图谱唯一）
+    pub id: String,
+    /// 标题
+    pub title: String,
+    pub weights: Vec<LayerWeight>,
+    /// 一局之内只会遇到一次
+    #[serde(default)]
+    pub once_per_run: bool,
+    /// 第一页为开场
+    pub pages: Vec<EventPage>,
+}
+
+impl EventDefinition {
+    /// 指定层数下的出现权重
+    pub fn weight(&self, layer: u32) -> f32 {
+        self.weights.iter().rev().find(|w| w.from_layer <= layer).map(|w| w.weight).unwrap_or(0.0)
+    }
+
+    /// 按 id 查找页
+    pub fn page(&self, id: &str) -> Option<&EventPage> {
+        self.pages.iter().find(|p| p.id == id)
+    }
+
+    /// 开场页
+    pub fn opening(&self) -> &EventPage {
+        &self.pages[0]
+    }
+
+    fn validate(&self, file: &str, cards: &CardLibrary, relics: &RelicLibrary) -> Result<(), EventDataError> {
+        let err = |field: &str, message: &str| EventDataError::invalid(file, Some(&self.id), field, message);
+
+        if self.title.trim().is_empty() {
+            return Err(err("title", "标题不能为空"));
+        }
+        if self.weights.is_empty() {
+            return Err(err("weights", "至少需要一条出现权重"));
+        }
+        if self.weights.iter().any(|w| w.weight < 0.0) {
+            return Err(err("weights", "出现权重不能为负"));
+        }
+        if self.pages.is_empty() {
+            return Err(err("pages", "至少需要一页"));
+        }
+        let mut page_ids = HashSet::new();
+        for page in &self.pages {
+            if !page_ids.insert(page.id.as_str()) {
+                return Err(err("pages", &format!("页 id `{}` 重复", page.id)));
+            }
+        }
+        for page in &self.pages {
+            let field = |name: &str| format!("pages[{}].{}", page.id, name);
+            if page.lines.is_empty() {
+                return Err(err(&field("lines"), "至少需要一句台词"));
+            }
+            if page.lines.iter().any(|l| l.text.trim().is_empty()) {
+                return Err(err(&field("lines"), "台词不能为空"));
+            }
+            // 门槛都不满足时须有退路
+            if !page.choices.iter().any(|c| c.requires.is_empty()) {
+                return Err(err(&field("choices"), "至少需要一个无门槛的选项"));
+            }
+            for (index, choice) in page.choices.iter().enumerate() {
+                let field = |name: &str| format!("pages[{}].choices[{}].{}", page.id, index, name);
+                if choice.label.trim().is_empty() {
+                    return Err(err(&field("label"), "选项文字不能为空"));
+                }
+                if let Some(next) = &choice.next {
+                    if self.page(next).is_none() {
+                        return Err(err(&field("next"), &format!("引用了不存在的页 `{}`", next)));
+                    }
+                    if choice.combat().is_some() {
+                        return Err(err(&field("next"), "交战的选项不能再翻页"));
+                    }
+                }
+                for (i, outcome) in choice.outcomes.iter().enumerate() {
+                    outcome.validate(cards, relics).map_err(|message| err(&field(&format!("outcomes[{}]", i)), &message))?;
+                }
+                for (i, requirement) in choice.requires.iter().enumerate() {
+                    let known = match requirement {
+                        EventRequirement::Relic(id) => relics.get(id).is_some(),
+                        EventRequirement::Card(id) => cards.definition(*id).is_some(),
+                        _ => true,
+                    };
+                    if !known {
+                        return Err(err(&field(&format!("requires[{}]", i)), "引用了图谱中不存在的法宝或功法"));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 机缘数据文件
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct EventDataFile {
+    pub events: Vec<EventDefinition>,
+}
+
+/// 机缘数据错误（指明出错的文件、事件与字段）
+#[derive(Debug)]
+pub enum EventDataError {
+    Parse { file: String, message: String },
+    Invalid { file: String, event: Option<String>, field: String, message: String },
+}
+
+impl EventDataError {
+    fn invalid(file: &str, event: Option<&str>, field: &str, message: &str) -> Self {
+        Self::Invalid {
+            file: file.to_string(),
+            event: event.map(|s| s.to_string()),
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for EventDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse { file, message } => write!(f, "{}: RON 解析失败：{}", file, message),
+            Self::Invalid { file, event: Some(id), field, message } => {
+                write!(f, "{}: 机缘 `{}` 字段 `{}`：{}", file, id, field, message)
+            }
+            Self::Invalid { file, event: None, field, message } => {
+                write!(f, "{}: 字段 `{}`：{}", file, field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EventDataError {}
+
+// ============================================================================
+// 结算上下文
+// ============================================================================
+
+/// 判定门槛时查看的修行者状态
+pub struct EventView<'a> {
+    pub player: &'a Player,
+    pub cultivation: &'a Cultivation,
+    pub deck: &'a PlayerDeck,
+    pub relics: &'a RelicCollection,
+    pub cards: &'a CardLibrary,
+}
+
+/// 结算结果时改动的修行者状态
+pub struct EventTarget<'a> {
+    pub player: &'a mut Player,
+    pub cultivation: &'a mut Cultivation,
+    pub deck: &'a mut PlayerDeck,
+    pub relics: &'a mut RelicCollection,
+    pub cards: &'a CardLibrary,
+    pub relic_library: &'a RelicLibrary,
+}
+
+impl EventTarget<'_> {
+    /// 借出只读视图（供结算途中判定门槛）
+    pub fn view(&self) -> EventView<'_> {
+        EventView { player: self.player, cultivation: self.cultivation, deck: self.deck, relics: self.relics, cards: self.cards }
+    }
+}
+
+/// 本局已遇到的机缘（一局一遇的事件不再出现，随存档保存）
+#[derive(Resource, Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventHistory {
+    pub seen: Vec<String>,
+}
+
+impl EventHistory {
+    pub fn record(&mut self, id: &str) {
+        if !self.has_seen(id) {
+            self.seen.push(id.to_string());
+        }
+    }
+
+    pub fn has_seen(&self, id: &str) -> bool {
+        self.seen.iter().any(|s| s == id)
+    }
+}
+
+// ============================================================================
+// 机缘图谱
+// ============================================================================
+
+/// 机缘图谱
+#[derive(Debug, Clone)]
+pub struct EventBook {
+    events: Vec<EventDefinition>,
+}
+
+impl EventBook {
+    /// 编译期内置的机缘图谱
+    pub fn builtin() -> &'static EventBook {
+        static BUILTIN: OnceLock<EventBook> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let files = BUILTIN_EVENT_FILES.iter().map(|(file, text)| (*file, text.as_bytes()));
+            Self::parse(files, CardLibrary::builtin(), RelicLibrary::builtin())
+                .unwrap_or_else(|e| panic!("内置机缘数据无效：{}", e))
+        })
+    }
+
+    /// 解析并校验机缘数据（所引用的功法、法宝与妖物须在图谱中）
+    pub fn parse<'a>(
+        files: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+        cards: &CardLibrary,
+        relics: &RelicLibrary,
+    ) -> Result<Self, EventDataError> {
+        let mut events: Vec<EventDefinition> = Vec::new();
+        for (file, bytes) in files {
+            let data: EventDataFile = ron::Options::default()
+                .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+                .from_bytes(bytes)
+                .map_err(|e| EventDataError::Parse { file: file.to_string(), message: e.to_string() })?;
+            for definition in data.events {
+                if events.iter().any(|e| e.id == definition.id) {
+                    return Err(EventDataError::invalid(file, Some(&definition.id), "id", "机缘 id 重复"));
+                }
+                definition.validate(file, cards, relics)?;
+                events.push(definition);
+            }
+        }
+        Ok(Self { events })
+    }
+
+    /// 按 id 查找机缘
+    pub fn get(&self, id: &str) -> Option<&EventDefinition> {
+        self.events.iter().find(|e| e.id == id)
+    }
+
+    /// 全部机缘（数据文件顺序）
+    pub fn events(&self) -> &[EventDefinition] {
+        &self.events
+    }
+
+    /// 按层数权重抽取一个机缘（已遇到的一局一遇事件除外）
+    pub fn pick(&self, layer: u32, history: &EventHistory, rng: &mut impl Rng) -> Option<&EventDefinition> {
+        let candidates: Vec<&EventDefinition> = self.events.iter()
+            .filter(|e| !(e.once_per_run && history.has_seen(&e.id)))
+            .filter(|e| e.weight(layer) > 0.0)
+            .collect();
+        candidates.choose_weighted(rng, |e| e.weight(layer)).ok().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    const FILE: &str = "data/events/test.events.ron";
+
+    fn parse(text: &str) -> Result<EventBook, EventDataError> {
+        EventBook::parse([(FILE, text.as_bytes())], CardLibrary::builtin(), RelicLibrary::builtin())
+    }
+
+    #[test]
+    fn test_builtin_book_has_enough_events() {
+        let book = EventBook::builtin();
+        assert!(book.events().len() >= 30, "仅有 {} 个机缘", book.events().len());
+        assert!(book.events().iter().any(|e| e.once_per_run));
+        assert!(book.events().iter().any(|e| e.pages.iter().flat_map(|p| &p.choices).any(|c| c.combat().is_some())));
+        // 每一层都有机缘可遇
+        for layer in 0..15 {
+            assert!(book.events().iter().any(|e| e.weight(layer) > 0.0), "第 {} 层无机缘", layer);
+        }
+    }
+
+    #[test]
+    fn test_validation_names_event_and_field() {
+        let text = r#"(events: [(id: "well", title: "古井", weights: [(from_layer: 0, weight: 1.0)],
+            pages: [(id: "start", lines: [(speaker: "旁白", text: "井水幽深。")],
+                choices: [(label: "探身", next: "bottom"), (label: "离去")])])])"#;
+        let err = parse(text).unwrap_err().to_string();
+        assert!(err.contains("well") && err.contains("choices[0].next") && err.contains("bottom"), "{}", err);
+
+        let text = text.replace(r#"next: "bottom""#, r#"outcomes: [Relic("no_such_relic")]"#);
+        let err = parse(&text).unwrap_err().to_string();
+        assert!(err.contains("outcomes[0]") && err.contains("no_such_relic"), "{}", err);
+
+        let text = text.replace(r#"Relic("no_such_relic")"#, r#"Curse(1)"#);
+        let err = parse(&text).unwrap_err().to_string();
+        assert!(err.contains("不是心魔"), "{}", err);
+
+        let text = r#"(events: [(id: "toll", title: "关卡", weights: [(from_layer: 0, weight: 1.0)],
+            pages: [(id: "start", lines: [(speaker: "守卫", text: "留下买路钱。")],
+                choices: [(label: "付钱", requires: [Gold(10)], outcomes: [Gold(-10)])])])])"#;
+        let err = parse(text).unwrap_err().to_string();
+        assert!(err.contains("无门槛"), "{}", err);
+    }
+
+    #[test]
+    fn test_requirements_and_outcomes() {
+        let cards = CardLibrary::builtin();
+        let relic_library = RelicLibrary::builtin();
+        let mut player = Player { gold: 40, hp: 20, max_hp: 50, ..Player::default() };
+        let mut cultivation = Cultivation::new();
+        let mut deck = PlayerDeck { cards: cards.starting_deck(player.sect) };
+        let mut relics = RelicCollection::default();
+        let deck_size = deck.len();
+
+        let view = EventView { player: &player, cultivation: &cultivation, deck: &deck, relics: &relics, cards };
+        assert!(!EventRequirement::Gold(50).met(&view));
+        assert!(EventRequirement::Hp(19).met(&view) && !EventRequirement::Hp(20).met(&view));
+        assert!(!EventRequirement::Realm(Realm::GoldenCore).met(&view));
+        assert!(EventRequirement::Card(1).met(&view));
+        assert!(!EventRequirement::CardType(CardType::Curse).met(&view));
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+        let mut target = EventTarget {
+            player: &mut player, cultivation: &mut cultivation, deck: &mut deck, relics: &mut relics, cards, relic_library,
+        };
+        EventOutcome::Gold(-100).apply(&mut target, &mut rng);
+        EventOutcome::Hp(-30).apply(&mut target, &mut rng);
+        EventOutcome::MaxHp(5).apply(&mut target, &mut rng);
+        EventOutcome::Curse(900).apply(&mut target, &mut rng);
+        assert!(EventRequirement::CardType(CardType::Curse).met(&target.view()));
+        EventOutcome::Remove(1).apply(&mut target, &mut rng);
+        EventOutcome::Relic("ginseng".to_string()).apply(&mut target, &mut rng);
+        EventOutcome::Insight(30).apply(&mut target, &mut rng);
+        EventOutcome::Upgrade(2).apply(&mut target, &mut rng);
+
+        assert_eq!(player.gold, 0);
+        // 扣血至多降到 1；上限 +5 与千年人参 +8 都计入
+        assert_eq!(player.max_hp, 63);
+        assert_eq!(player.hp, 14);
+        assert_eq!(deck.len(), deck_size);
+        assert!(deck.cards.iter().all(|c| c.card_type != CardType::Curse));
+        assert_eq!(deck.cards.iter().filter(|c| c.upgraded).count(), 2);
+        assert!(relics.has("ginseng"));
+        assert_eq!(cultivation.insight, 30);
+    }
+
+    #[test]
+    fn test_once_per_run_events_are_not_repeated() {
+        let text = r#"(events: [
+            (id: "once", title: "一", once_per_run: true, weights: [(from_layer: 0, weight: 1.0)],
+                pages: [(id: "start", lines: [(speaker: "旁白", text: "一。")], choices: [(label: "走")])]),
+            (id: "deep", title: "深", weights: [(from_layer: 5, weight: 1.0)],
+                pages: [(id: "start", lines: [(speaker: "旁白", text: "深。")], choices: [(label: "走")])]),
+        ])"#;
+        let book = parse(text).unwrap();
+        let mut history = EventHistory::default();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(1);
+        assert_eq!(book.pick(0, &history, &mut rng).unwrap().id, "once");
+        history.record("once");
+        assert!(book.pick(0, &history, &mut rng).is_none());
+        assert_eq!(book.pick(6, &history, &mut rng).unwrap().id, "deep");
+    }
+}
@@ -1,10 +1,25 @@
 //! 全局资源和状态管理
 
 pub mod save;
+pub mod run_seed;
+pub mod card_data;
+pub mod status_data;
+pub mod relic_data;
+pub mod enemy_data;
+pub mod event_data;
+pub mod act_data;
+pub mod ascension;
+pub mod profile;
+pub mod run_stats;
 
 
 
 use bevy::prelude::*;
+use crate::components::sect::Sect;
+
+pub use run_seed::RunSeed;
+pub use card_data::CardLibrary;
+pub use run_stats::RunStats;
 
 
 
@@ -18,6 +33,8 @@ pub struct EnvironmentConfig {
 
     pub fog_color: Color,
 
+    pub ambient_color: Color,
+
     pub ambient_brightness: f32,
 
 }
@@ -32,9 +49,11 @@ impl Default for EnvironmentConfig {
 
             wind_strength: 1.0,
 
-            fog_color: Color::srgba(0.01, 0.005, 0.02, 1.0),
+            fog_color: Color::srgb(0.1, 0.1, 0.15),
 
-            ambient_brightness: 0.1,
+            ambient_color: Color::srgb(0.85, 0.95, 1.0),
+
+            ambient_brightness: 150.0,
 
         }
 
@@ -87,9 +106,23 @@ pub struct ArenaAssets {
     pub sword_debris: Handle<Scene>,
 }
 
-/// [新增] 玩家模块化资产
+/// [新增] 玩家模块化资产（法器随宗门而定）
 #[derive(Resource, Default, Clone)]
 pub struct PlayerAssets {
     pub body: Handle<Scene>,
-    pub weapon: Handle<Scene>,
+    /// 随身法器（不佩飞剑的宗门为空）
+    pub weapon: Option<Handle<Scene>>,
+    /// 资产所属宗门
+    pub sect: Sect,
+}
+
+impl PlayerAssets {
+    /// 加载指定宗门的修行者模型
+    pub fn load(asset_server: &AssetServer, sect: Sect) -> Self {
+        Self {
+            body: asset_server.load("3d/player/warrior_main.glb#Scene0"),
+            weapon: sect.weapon_model().map(|path| asset_server.load(path)),
+            sect,
+        }
+    }
 }
@@ -0,0 +1,369 @@
+//! 修行档案（跨局保留，存于数据目录下的 profile.json）
+//!
+//! 与存档档位相互独立：道消身殒删除档位时档案不受影响。
+//! 每局结算所得的道韵可在藏经阁解锁秘传功法、法宝与开局法宝。
+
+use bevy::prelude::*;
+use serde::{Serialize, Deserialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
+use crate::components::{Cultivation, Enemy, EnemyType, Player, PlayerDeck, VictoryEvent};
+use crate::components::cultivation::Realm;
+use crate::components::map::MapProgress;
+use crate::components::relic::{Relic, RelicCollection};
+use crate::components::sect::Sect;
+use crate::resources::act_data::ActAtlas;
+use crate::resources::ascension::{Ascension, MAX_ASCENSION};
+use crate::resources::card_data::{CardDefinition, CardLibrary};
+use crate::resources::relic_data::RelicLibrary;
+use crate::resources::save::{data_dir, write_atomically};
+use crate::states::GameState;
+
+/// 修行档案
+#[derive(Resource, Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct MetaProfile {
+    /// 开局次数
+    #[serde(default)]
+    pub total_runs: u32,
+    /// 飞升（战胜镇守首领）次数
+    #[serde(default)]
+    pub wins: u32,
+    /// 已解锁的最高天劫重数（0 表示尚未解锁）
+    #[serde(default)]
+    pub max_ascension: u32,
+    /// 历局达到的最高境界
+    #[serde(default)]
+    pub highest_realm: Realm,
+    /// 各类妖物的斩杀数
+    #[serde(default)]
+    pub enemies_slain: BTreeMap<EnemyType, u32>,
+    /// 见识过的功法（定义 id）
+    #[serde(default)]
+    pub cards_discovered: BTreeSet<u32>,
+    /// 见识过的法宝
+    #[serde(default)]
+    pub relics_discovered: BTreeSet<String>,
+    /// 结余道韵（每局结算所得，于藏经阁消耗）
+    #[serde(default)]
+    pub essence: u32,
+    /// 已参悟的秘传功法
+    #[serde(default)]
+    pub unlocked_cards: BTreeSet<u32>,
+    /// 已解锁的法宝
+    #[serde(default)]
+    pub unlocked_relics: BTreeSet<String>,
+    /// 已换取的开局法宝
+    #[serde(default)]
+    pub unlocked_starters: BTreeSet<String>,
+    /// 择用的开局法宝（缺省为默认开局法宝）
+    #[serde(default)]
+    pub starting_relic: Option<String>,
+}
+
+/// 一局的结算依据
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunSummary {
+    /// 到达的累计层数（含此前各界）
+    pub layer: u32,
+    /// 终局境界
+    pub realm: Realm,
+    /// 是否飞升
+    pub victory: bool,
+    /// 天劫重数
+    pub ascension: u32,
+}
+
+impl RunSummary {
+    /// 本局所得道韵：层数与境界为本，飞升另加，每重天劫加成一成
+    pub fn essence(&self) -> u32 {
+        let base = 2 * self.layer + 15 * self.realm as u32 + if self.victory { 50 } else { 0 };
+        base + base * self.ascension / 10
+    }
+}
+
+impl MetaProfile {
+    /// 档案文件路径
+    pub fn path() -> PathBuf {
+        data_dir().join("profile.json")
+    }
+
+    /// 读取档案（不存在或损坏时从零开始）
+    pub fn load() -> Self {
+        let path = Self::path();
+        let Ok(data) = fs::read_to_string(&path) else { return Self::default(); };
+        serde_json::from_str(&data).unwrap_or_else(|e| {
+            warn!("【修行档案】{} 读取失败，从零开始: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("序列化失败: {}", e))
+            .and_then(|data| write_atomically(&Self::path(), &data));
+        if let Err(e) = result {
+            error!("【修行档案】保存失败: {}", e);
+        }
+    }
+
+    /// 记录一次飞升，返回新解锁的天劫重数
+    pub fn record_win(&mut self, ascension: Ascension) -> Option<u32> {
+        self.wins += 1;
+        if ascension.level >= self.max_ascension && ascension.level < MAX_ASCENSION {
+            self.max_ascension = ascension.level + 1;
+            return Some(self.max_ascension);
+        }
+        None
+    }
+
+    /// 结算一局，返回所得道韵
+    pub fn settle_run(&mut self, summary: &RunSummary) -> u32 {
+        let earned = summary.essence();
+        self.essence += earned;
+        self.reach_realm(summary.realm);
+        earned
+    }
+
+    /// 记录达到的境界
+    pub fn reach_realm(&mut self, realm: Realm) {
+        self.highest_realm = self.highest_realm.max(realm);
+    }
+
+    /// 记录斩杀一只妖物
+    pub fn record_kill(&mut self, enemy_type: EnemyType) {
+        *self.enemies_slain.entry(enemy_type).or_default() += 1;
+    }
+
+    /// 斩杀妖物总数
+    pub fn total_slain(&self) -> u32 {
+        self.enemies_slain.values().sum()
+    }
+
+    /// 花费道韵，不足时不扣除
+    fn spend(&mut self, cost: u32) -> bool {
+        if self.essence < cost {
+            return false;
+        }
+        self.essence -= cost;
+        true
+    }
+
+    /// 参悟秘传功法，返回是否成功
+    pub fn unlock_card(&mut self, card: &CardDefinition) -> bool {
+        let Some(cost) = card.unlock_cost else { return false; };
+        if self.unlocked_cards.contains(&card.id) || !self.spend(cost) {
+            return false;
+        }
+        self.unlocked_cards.insert(card.id)
+    }
+
+    /// 解锁法宝，返回是否成功
+    pub fn unlock_relic(&mut self, relic: &Relic) -> bool {
+        let Some(cost) = relic.unlock_cost else { return false; };
+        if self.unlocked_relics.contains(relic.id.as_str()) || !self.spend(cost) {
+            return false;
+        }
+        self.unlocked_relics.insert(relic.id.to_string())
+    }
+
+    /// 换取开局法宝，返回是否成功
+    pub fn unlock_starter(&mut self, relic: &Relic) -> bool {
+        let Some(cost) = relic.starter_cost else { return false; };
+        if self.unlocked_starters.contains(relic.id.as_str()) || !self.spend(cost) {
+            return false;
+        }
+        self.unlocked_starters.insert(relic.id.to_string())
+    }
+
+    /// 开局法宝是否可择用
+    pub fn starter_available(&self, id: &str) -> bool {
+        self.unlocked_starters.contains(id)
+    }
+
+    /// 本局的开局法宝：择用的法宝已换取且仍在图谱中时用之，否则用宗门本命法宝
+    pub fn starting_relic(&self, library: &RelicLibrary, sect: Sect) -> Relic {
+        self.starting_relic
+            .as_deref()
+            .filter(|id| self.starter_available(id))
+            .and_then(|id| library.get(id))
+            .unwrap_or_else(|| library.starting_relic(sect))
+    }
+}
+
+/// 本局结算所得的道韵（结算界面展示）
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct RunSettlement {
+    pub essence: u32,
+}
+
+/// 本局是否已飞升（结算界面据此区分飞升与陨落）
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct RunVictory {
+    /// 本次飞升新解锁的天劫重数
+    pub unlocked: Option<u32>,
+}
+
+/// 修行档案插件
+pub struct ProfilePlugin;
+
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(MetaProfile::load())
+            .init_resource::<Ascension>()
+            .add_systems(Update, (sync_unlocks, record_discoveries, record_kills, record_realm))
+            .add_systems(OnEnter(GameState::Map), save_profile)
+            .add_systems(OnEnter(GameState::GameOver), settle_run);
+    }
+}
+
+/// 把档案中的解锁同步进功法与法宝图谱（图谱热重载后同样会重新同步）
+fn sync_unlocks(profile: Res<MetaProfile>, mut cards: ResMut<CardLibrary>, mut relics: ResMut<RelicLibrary>) {
+    if cards.unlocked() != &profile.unlocked_cards {
+        cards.set_unlocked(profile.unlocked_cards.clone());
+    }
+    if relics.unlocked() != &profile.unlocked_relics {
+        relics.set_unlocked(profile.unlocked_relics.clone());
+    }
+}
+
+/// 牌组与法宝变动时记入见识
+fn record_discoveries(
+    deck: Res<PlayerDeck>,
+    relics: Res<RelicCollection>,
+    library: Res<CardLibrary>,
+    mut profile: ResMut<MetaProfile>,
+) {
+    if deck.is_changed() {
+        let new: Vec<u32> = deck.cards.iter()
+            .filter_map(|card| library.definition_by_name(&card.name))
+            .map(|d| d.id)
+            .filter(|id| !profile.cards_discovered.contains(id))
+            .collect();
+        if !new.is_empty() {
+            profile.cards_discovered.extend(new);
+        }
+    }
+    if relics.is_changed() {
+        let new: Vec<String> = relics.relic.iter()
+            .map(|r| r.id.to_string())
+            .filter(|id| !profile.relics_discovered.contains(id))
+            .collect();
+        if !new.is_empty() {
+            profile.relics_discovered.extend(new);
+        }
+    }
+}
+
+/// 战斗胜利时记下伏诛的妖物
+fn record_kills(mut victory_events: EventReader<VictoryEvent>, enemies: Query<&Enemy>, mut profile: ResMut<MetaProfile>) {
+    for _ in victory_events.read() {
+        for enemy in enemies.iter().filter(|e| e.hp <= 0) {
+            profile.record_kill(enemy.enemy_type);
+        }
+    }
+}
+
+/// 境界提升时记下最高境界
+fn record_realm(query: Query<&Cultivation, (With<Player>, Changed<Cultivation>)>, mut profile: ResMut<MetaProfile>) {
+    for cultivation in query.iter() {
+        if cultivation.realm > profile.highest_realm {
+            profile.reach_realm(cultivation.realm);
+        }
+    }
+}
+
+/// 进入地图时保存有变动的档案
+fn save_profile(profile: Res<MetaProfile>) {
+    if profile.is_changed() {
+        profile.save();
+    }
+}
+
+/// 身死或飞升时结算本局道韵
+pub fn settle_run(
+    mut commands: Commands,
+    mut profile: ResMut<MetaProfile>,
+    map_progress: Option<Res<MapProgress>>,
+    cultivation_query: Query<&Cultivation, With<Player>>,
+    victory: Option<Res<RunVictory>>,
+    ascension: Res<Ascension>,
+) {
+    let summary = RunSummary {
+        layer: map_progress.map_or(0, |p| ActAtlas::builtin().total_layer(p.act, p.current_layer)),
+        realm: cultivation_query.get_single().map_or(Realm::default(), |c| c.realm),
+        victory: victory.is_some(),
+        ascension: ascension.level,
+    };
+    let essence = profile.settle_run(&summary);
+    profile.save();
+    info!("【修行档案】本局得道韵 {}，结余 {}", essence, profile.essence);
+    commands.insert_resource(RunSettlement { essence });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::relic_data::STARTING_RELIC;
+
+    #[test]
+    fn test_wins_unlock_next_ascension_once() {
+        let mut profile = MetaProfile::default();
+        assert_eq!(profile.record_win(Ascension::new(0)), Some(1));
+        // 在已通过的较低重数再胜一次，不会重复解锁
+        assert_eq!(profile.record_win(Ascension::new(0)), None);
+        assert_eq!(profile.record_win(Ascension::new(1)), Some(2));
+        assert_eq!(profile.record_win(Ascension::new(MAX_ASCENSION)), None);
+        assert_eq!((profile.wins, profile.max_ascension), (4, 2));
+    }
+
+    #[test]
+    fn test_essence_unlocks_cards_relics_and_starters() {
+        let mut profile = MetaProfile::default();
+        let summary = RunSummary { layer: 10, realm: Realm::GoldenCore, victory: true, ascension: 5 };
+        assert_eq!(profile.settle_run(&summary), 150);
+        assert_eq!(profile.highest_realm, Realm::GoldenCore);
+
+        let cards = CardLibrary::builtin();
+        let card = cards.unlockable().next().unwrap();
+        assert!(cards.all_cards(Sect::Sword).iter().all(|c| c.id != card.id));
+        assert!(profile.unlock_card(card));
+        assert!(!profile.unlock_card(card), "已参悟的功法不再扣道韵");
+        let mut cards = cards.clone();
+        cards.set_unlocked(profile.unlocked_cards.clone());
+        assert!(cards.all_cards(Sect::Sword).iter().any(|c| c.id == card.id));
+
+        let relics = RelicLibrary::builtin();
+        let starter = relics.starter_choices().next().unwrap();
+        assert_eq!(profile.starting_relic(relics, Sect::Sword).id.as_str(), STARTING_RELIC);
+        assert_eq!(profile.starting_relic(relics, Sect::Talisman).id.as_str(), Sect::Talisman.starting_relic());
+        profile.starting_relic = Some(starter.id.to_string());
+        assert_eq!(profile.starting_relic(relics, Sect::Sword).id.as_str(), STARTING_RELIC, "未换取的开局法宝不可用");
+        let before = profile.essence;
+        assert!(profile.unlock_starter(starter));
+        assert_eq!(profile.essence, before - starter.starter_cost.unwrap());
+        assert_eq!(profile.starting_relic(relics, Sect::Alchemy).id, starter.id, "择用的开局法宝替代各宗本命法宝");
+
+        // 道韵不足时不扣除
+        let relic = relics.unlockable().max_by_key(|r| r.unlock_cost).unwrap();
+        profile.essence = relic.unlock_cost.unwrap() - 1;
+        assert!(!profile.unlock_relic(relic));
+        assert_eq!(profile.essence, relic.unlock_cost.unwrap() - 1);
+    }
+
+    #[test]
+    fn test_profile_json_round_trip_and_legacy_defaults() {
+        let mut profile = MetaProfile { total_runs: 3, essence: 42, ..Default::default() };
+        profile.record_kill(EnemyType::DemonicWolf);
+        profile.record_kill(EnemyType::DemonicWolf);
+        profile.record_kill(EnemyType::GreatDemon);
+        profile.relics_discovered.insert(STARTING_RELIC.to_string());
+        let json = serde_json::to_string(&profile).unwrap();
+        assert_eq!(serde_json::from_str::<MetaProfile>(&json).unwrap(), profile);
+        assert_eq!(profile.total_slain(), 3);
+
+        // 仅含天劫记录的旧档案照常读取
+        let legacy: MetaProfile = serde_json::from_str(r#"{"wins": 2, "max_ascension": 2}"#).unwrap();
+        assert_eq!((legacy.wins, legacy.essence, legacy.highest_realm), (2, 0, Realm::QiRefining));
+    }
+}
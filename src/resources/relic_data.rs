@@ -0,0 +1,484 @@
+//! 法宝图谱（数据驱动的遗物定义）
+//!
+//! 所有遗物定义在 `assets/data/relics/*.relics.ron` 中，经资源加载器解析并校验后汇总为 [`RelicLibrary`]。
+//! 与功法图谱相同，同一批数据文件也在编译期内置一份作为兜底。
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt;
+use std::sync::OnceLock;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
+
+use crate::components::cultivation::Realm;
+use crate::components::sect::Sect;
+use crate::components::relic::{
+    Relic, RelicCollection, RelicCondition, RelicEffect, RelicId, RelicRarity, BURNING_BLOOD, MAX_SHOP_DISCOUNT,
+};
+use crate::resources::status_data::StatusRegistry;
+
+/// 青云剑宗（默认宗门）开局赠送的遗物
+pub const STARTING_RELIC: &str = BURNING_BLOOD;
+
+/// 编译期内置的遗物数据（路径相对 assets 目录）
+const BUILTIN_RELIC_FILES: &[(&str, &str)] = &[
+    ("data/relics/common.relics.ron", include_str!("../../assets/data/relics/common.relics.ron")),
+    ("data/relics/uncommon.relics.ron", include_str!("../../assets/data/relics/uncommon.relics.ron")),
+    ("data/relics/rare.relics.ron", include_str!("../../assets/data/relics/rare.relics.ron")),
+    ("data/relics/boss.relics.ron", include_str!("../../assets/data/relics/boss.relics.ron")),
+    ("data/relics/archive.relics.ron", include_str!("../../assets/data/relics/archive.relics.ron")),
+    ("data/relics/sects.relics.ron", include_str!("../../assets/data/relics/sects.relics.ron")),
+];
+
+// ============================================================================
+// 数据格式
+// ============================================================================
+
+/// 一个遗物数据文件
+#[derive(Asset, TypePath, Debug, Clone, Default, Deserialize)]
+pub struct RelicDataFile {
+    #[serde(default)]
+    pub relics: Vec<Relic>,
+}
+
+/// 遗物数据错误（均指明出错的文件与字段）
+#[derive(Debug)]
+pub enum RelicDataError {
+    Io { file: String, source: std::io::Error },
+    Parse { file: String, message: String },
+    Invalid { file: String, relic: Option<String>, field: String, message: String },
+}
+
+impl RelicDataError {
+    fn invalid(file: &str, relic: Option<&RelicId>, field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Invalid {
+            file: file.to_string(),
+            relic: relic.map(|id| id.to_string()),
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for RelicDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { file, source } => write!(f, "{}: 读取失败：{}", file, source),
+            Self::Parse { file, message } => write!(f, "{}: RON 解析失败：{}", file, message),
+            Self::Invalid { file, relic: Some(id), field, message } => {
+                write!(f, "{}: 法宝 `{}` 字段 `{}`：{}", file, id, field, message)
+            }
+            Self::Invalid { file, relic: None, field, message } => {
+                write!(f, "{}: 字段 `{}`：{}", file, field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RelicDataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// 校验单个遗物
+fn validate_relic(relic: &Relic, file: &str) -> Result<(), RelicDataError> {
+    let err = |field: &str, message: String| RelicDataError::invalid(file, Some(&relic.id), field, message);
+
+    if relic.id.as_str().trim().is_empty() {
+        return Err(err("id", "id 不能为空".to_string()));
+    }
+    if relic.name.trim().is_empty() {
+        return Err(err("name", "名称不能为空".to_string()));
+    }
+    if relic.description.trim().is_empty() {
+        return Err(err("description", "描述不能为空".to_string()));
+    }
+    if relic.effects.is_empty() {
+        return Err(err("effects", "至少需要一个效果".to_string()));
+    }
+    if relic.counter != 0 {
+        return Err(err("counter", "计数进度由存档维护，数据文件中不应填写".to_string()));
+    }
+    if relic.unlock_cost == Some(0) {
+        return Err(err("unlock_cost", "解锁所需道韵须大于 0".to_string()));
+    }
+    if relic.starter_cost.is_some() && (relic.unlock_cost.is_some() || relic.rarity == RelicRarity::Boss) {
+        return Err(err("starter_cost", "开局法宝须无需解锁且非首领法宝".to_string()));
+    }
+    let counters = relic.effects.iter().filter(|e| matches!(e, RelicEffect::Counter { .. })).count();
+    if counters > 1 {
+        return Err(err("effects", "每件法宝至多一个计数效果".to_string()));
+    }
+    for (index, effect) in relic.effects.iter().enumerate() {
+        validate_effect(effect).map_err(|message| err(&format!("effects[{}]", index), message))?;
+    }
+    Ok(())
+}
+
+/// 校验单个效果（递归检查条件与计数效果的内层效果）
+fn validate_effect(effect: &RelicEffect) -> Result<(), String> {
+    let non_negative = |values: &[i32]| {
+        if values.iter().any(|v| *v < 0) {
+            Err("数值不能为负".to_string())
+        } else {
+            Ok(())
+        }
+    };
+    match effect {
+        RelicEffect::OnCombatStart { damage, block, draw_cards } => non_negative(&[*damage, *block, *draw_cards]),
+        RelicEffect::OnTurnStart { energy, draw_cards } => non_negative(&[*energy, *draw_cards]),
+        RelicEffect::OnTurnEnd { keep_cards } => non_negative(&[*keep_cards]),
+        RelicEffect::OnDraw { extra_cards } => non_negative(&[*extra_cards]),
+        RelicEffect::OnDealDamage { extra_damage } => non_negative(&[*extra_damage]),
+        RelicEffect::OnTakeDamage { reduction } => non_negative(&[*reduction]),
+        RelicEffect::OnCardPlayed { every_nth, draw_cards } => {
+            if *every_nth <= 0 {
+                return Err("every_nth 必须大于 0".to_string());
+            }
+            non_negative(&[*draw_cards])
+        }
+        RelicEffect::StatusAtCombatStart { status, stacks, .. } => {
+            if *stacks <= 0 {
+                return Err("状态层数必须大于 0".to_string());
+            }
+            StatusRegistry::builtin().get(status).map(|_| ()).ok_or_else(|| format!("状态图谱中没有 `{}`", status))
+        }
+        // 道行上限允许为负（以血换力的首领法宝）
+        RelicEffect::OnPickup { gold, .. } => non_negative(&[*gold]),
+        RelicEffect::OnVictory { gold, heal } => non_negative(&[*gold, *heal]),
+        RelicEffect::ShopDiscount { percent } => {
+            if !(1..=MAX_SHOP_DISCOUNT).contains(percent) {
+                return Err(format!("折扣需在 1..={} 之间（当前 {}）", MAX_SHOP_DISCOUNT, percent));
+            }
+            Ok(())
+        }
+        RelicEffect::When { condition, effect } => {
+            if let RelicCondition::HpBelowPercent(percent) = condition {
+                if !(1..=100).contains(percent) {
+                    return Err(format!("道行百分比需在 1..=100 之间（当前 {}）", percent));
+                }
+            }
+            match effect.as_ref() {
+                RelicEffect::OnPickup { .. } | RelicEffect::ShopDiscount { .. } | RelicEffect::Counter { .. } => {
+                    Err("条件效果只能包裹战斗内效果".to_string())
+                }
+                inner => validate_effect(inner),
+            }
+        }
+        RelicEffect::Counter { every, effect, .. } => {
+            if *every <= 0 {
+                return Err("every 必须大于 0".to_string());
+            }
+            match effect.as_ref() {
+                RelicEffect::OnCombatStart { .. } | RelicEffect::OnTurnStart { .. } | RelicEffect::OnVictory { .. } | RelicEffect::OnPickup { .. } => {
+                    validate_effect(effect)
+                }
+                _ => Err("计数效果只能结算 OnCombatStart / OnTurnStart / OnVictory / OnPickup".to_string()),
+            }
+        }
+    }
+}
+
+impl RelicDataFile {
+    /// 解析并校验一个数据文件
+    pub fn parse(file: &str, bytes: &[u8]) -> Result<Self, RelicDataError> {
+        let data: Self = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_bytes(bytes)
+            .map_err(|e| RelicDataError::Parse { file: file.to_string(), message: e.to_string() })?;
+        let mut ids = HashSet::new();
+        for relic in &data.relics {
+            if !ids.insert(relic.id.clone()) {
+                return Err(RelicDataError::invalid(file, Some(&relic.id), "id", "同一文件内 id 重复"));
+            }
+            validate_relic(relic, file)?;
+        }
+        Ok(data)
+    }
+}
+
+// ============================================================================
+// 资源加载器
+// ============================================================================
+
+/// `*.relics.ron` 资源加载器
+#[derive(Default)]
+pub struct RelicDataLoader;
+
+impl AssetLoader for RelicDataLoader {
+    type Asset = RelicDataFile;
+    type Settings = ();
+    type Error = RelicDataError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let file = load_context.path().display().to_string();
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|source| RelicDataError::Io { file: file.clone(), source })?;
+        RelicDataFile::parse(&file, &bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["relics.ron"]
+    }
+}
+
+// ============================================================================
+// 法宝图谱
+// ============================================================================
+
+/// 法宝图谱资源：汇总全部数据文件后的遗物定义
+#[derive(Resource, Debug, Clone)]
+pub struct RelicLibrary {
+    /// 按数据文件顺序保存的遗物
+    relics: Vec<Relic>,
+    /// 已在藏经阁解锁的法宝（由修行档案同步）
+    unlocked: BTreeSet<String>,
+}
+
+impl Default for RelicLibrary {
+    fn default() -> Self {
+        Self::builtin().clone()
+    }
+}
+
+impl RelicLibrary {
+    /// 编译期内置的法宝图谱
+    pub fn builtin() -> &'static RelicLibrary {
+        static BUILTIN: OnceLock<RelicLibrary> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let files: Vec<(&str, RelicDataFile)> = BUILTIN_RELIC_FILES
+                .iter()
+                .map(|(path, text)| {
+                    let data = RelicDataFile::parse(path, text.as_bytes()).unwrap_or_else(|e| panic!("内置法宝数据无效：{}", e));
+                    (*path, data)
+                })
+                .collect();
+            Self::from_files(files.iter().map(|(path, data)| (*path, data)))
+                .unwrap_or_else(|e| panic!("内置法宝数据无效：{}", e))
+        })
+    }
+
+    /// 由多个数据文件汇总图谱，校验跨文件的 id 冲突与开局法宝
+    pub fn from_files<'a>(files: impl IntoIterator<Item = (&'a str, &'a RelicDataFile)>) -> Result<Self, RelicDataError> {
+        let mut relics: Vec<Relic> = Vec::new();
+        let mut origins: Vec<(RelicId, &str)> = Vec::new();
+        let mut last_file = "";
+
+        for (file, data) in files {
+            last_file = file;
+            for relic in &data.relics {
+                if let Some((_, other)) = origins.iter().find(|(id, _)| *id == relic.id) {
+                    return Err(RelicDataError::invalid(file, Some(&relic.id), "id", format!("与 {} 中的法宝重复", other)));
+                }
+                origins.push((relic.id.clone(), file));
+                relics.push(relic.clone());
+            }
+        }
+
+        for sect in Sect::ALL {
+            match relics.iter().find(|r| r.id.as_str() == sect.starting_relic()) {
+                None => {
+                    let reason = format!("缺少{}的开局法宝 `{}`", sect.name(), sect.starting_relic());
+                    return Err(RelicDataError::invalid(last_file, None, "relics", reason));
+                }
+                Some(r) if r.unlock_cost.is_some() || r.starter_cost.is_some() => {
+                    return Err(RelicDataError::invalid(last_file, Some(&r.id), "unlock_cost", "宗门本命法宝无需解锁"));
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(Self { relics, unlocked: BTreeSet::new() })
+    }
+
+    /// 遗物数量
+    pub fn len(&self) -> usize {
+        self.relics.len()
+    }
+
+    /// 图谱是否为空
+    pub fn is_empty(&self) -> bool {
+        self.relics.is_empty()
+    }
+
+    /// 全部遗物（数据文件顺序）
+    pub fn all(&self) -> &[Relic] {
+        &self.relics
+    }
+
+    /// 按 id 生成一件遗物
+    pub fn get(&self, id: &str) -> Option<Relic> {
+        self.relics.iter().find(|r| r.id.as_str() == id).cloned()
+    }
+
+    /// 宗门开局赠送的本命法宝
+    pub fn starting_relic(&self, sect: Sect) -> Relic {
+        self.get(sect.starting_relic()).expect("图谱已校验宗门开局法宝")
+    }
+
+    /// 可在藏经阁换取、替代宗门本命法宝的开局法宝
+    pub fn starter_choices(&self) -> impl Iterator<Item = &Relic> {
+        self.relics.iter().filter(|r| r.starter_cost.is_some())
+    }
+
+    /// 需在藏经阁解锁的法宝
+    pub fn unlockable(&self) -> impl Iterator<Item = &Relic> {
+        self.relics.iter().filter(|r| r.unlock_cost.is_some())
+    }
+
+    /// 已解锁的法宝 id
+    pub fn unlocked(&self) -> &BTreeSet<String> {
+        &self.unlocked
+    }
+
+    /// 同步已解锁的法宝（图谱重建后需重新同步）
+    pub fn set_unlocked(&mut self, unlocked: BTreeSet<String>) {
+        self.unlocked = unlocked;
+    }
+
+    /// 可获得的遗物：已解锁、未拥有、未被排除且已达到境界要求
+    fn pool<'a>(&'a self, rarity: RelicRarity, owned: &'a RelicCollection, realm: Realm, exclude: &'a [RelicId]) -> impl Iterator<Item = &'a Relic> {
+        self.relics.iter().filter(move |r| {
+            r.rarity == rarity
+                && r.available_at(realm)
+                && r.unlock_cost.map_or(true, |_| self.unlocked.contains(r.id.as_str()))
+                && !owned.has(r.id.as_str())
+                && !exclude.contains(&r.id)
+        })
+    }
+
+    /// 按稀有度权重抽取一件遗物（常见/罕见/稀有），指定稀有度已无货时逐级降级
+    ///
+    /// `exclude` 为同一批次已经出现的遗物（如坊市的其它货架）。
+    pub fn roll(&self, weights: [f32; 3], owned: &RelicCollection, realm: Realm, exclude: &[RelicId], rng: &mut impl rand::Rng) -> Option<Relic> {
+        let roll = rng.gen::<f32>() * weights.iter().sum::<f32>();
+        let tiers = [RelicRarity::Common, RelicRarity::Uncommon, RelicRarity::Rare];
+        let mut tier = 0;
+        let mut acc = weights[0];
+        while tier < 2 && roll >= acc {
+            tier += 1;
+            acc += weights[tier];
+        }
+        (0..=tier).rev().find_map(|t| {
+            let candidates: Vec<&Relic> = self.pool(tiers[t], owned, realm, exclude).collect();
+            candidates.choose(rng).map(|r| (*r).clone())
+        })
+    }
+
+    /// 首领法宝（击败首领后的奖励）
+    pub fn roll_boss(&self, owned: &RelicCollection, realm: Realm, exclude: &[RelicId], rng: &mut impl rand::Rng) -> Option<Relic> {
+        let candidates: Vec<&Relic> = self.pool(RelicRarity::Boss, owned, realm, exclude).collect();
+        candidates.choose(rng).map(|r| (*r).clone())
+    }
+
+    /// 首领伏诛后供择一的首领法宝（互不重复，库存不足时少于 `count` 件）
+    pub fn roll_boss_choices(&self, count: usize, owned: &RelicCollection, realm: Realm, rng: &mut impl rand::Rng) -> Vec<Relic> {
+        let mut choices: Vec<Relic> = Vec::with_capacity(count);
+        while choices.len() < count {
+            let exclude: Vec<RelicId> = choices.iter().map(|r| r.id.clone()).collect();
+            let Some(relic) = self.roll_boss(owned, realm, &exclude, rng) else { break };
+            choices.push(relic);
+        }
+        choices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::relic::{CounterEvent, ANCHOR};
+    use rand::SeedableRng;
+
+    const FILE: &str = "data/relics/test.relics.ron";
+
+    #[test]
+    fn test_builtin_library_has_every_tier() {
+        let library = RelicLibrary::builtin();
+        assert!((40..=60).contains(&library.len()), "法宝数量 {}", library.len());
+        for rarity in [RelicRarity::Common, RelicRarity::Uncommon, RelicRarity::Rare, RelicRarity::Boss] {
+            assert!(library.all().iter().filter(|r| r.rarity == rarity).count() >= 4, "{:?} 法宝过少", rarity);
+        }
+        assert_eq!(library.starting_relic(Sect::Sword).id.as_str(), STARTING_RELIC);
+        for sect in Sect::ALL {
+            assert_eq!(library.starting_relic(sect).id.as_str(), sect.starting_relic());
+        }
+        assert!(library.get(ANCHOR).is_some());
+    }
+
+    #[test]
+    fn test_validation_names_file_relic_and_field() {
+        let text = r#"(relics: [(id: "cheap_charm", name: "贱卖符", description: "坊市打折", rarity: Common,
+            effects: [OnPickup(gold: 5), ShopDiscount(percent: 90)])])"#;
+        let err = RelicDataFile::parse(FILE, text.as_bytes()).unwrap_err().to_string();
+        assert!(err.contains(FILE) && err.contains("cheap_charm") && err.contains("`effects[1]`"), "{}", err);
+
+        let text = r#"(relics: [(id: "odd", name: "怪珠", description: "计数", rarity: Common,
+            effects: [Counter(counts: CardPlayed, every: 3, effect: OnDealDamage(extra_damage: 1))])])"#;
+        let err = RelicDataFile::parse(FILE, text.as_bytes()).unwrap_err().to_string();
+        assert!(err.contains("`effects[0]`"), "{}", err);
+    }
+
+    #[test]
+    fn test_realm_gating_and_owned_relics_are_excluded() {
+        let library = RelicLibrary::builtin();
+        let mut owned = RelicCollection::default();
+        owned.add_relic_forced(library.starting_relic(Sect::Sword));
+        let mut rng = rand::thread_rng();
+        for _ in 0..200 {
+            let relic = library.roll([0.5, 0.35, 0.15], &owned, Realm::QiRefining, &[], &mut rng).unwrap();
+            assert!(relic.min_realm.is_none() && relic.id.as_str() != STARTING_RELIC);
+            assert_ne!(relic.rarity, RelicRarity::Boss);
+        }
+        let boss = library.roll_boss(&owned, Realm::NascentSoul, &[], &mut rng).unwrap();
+        assert_eq!(boss.rarity, RelicRarity::Boss);
+    }
+
+    #[test]
+    fn test_boss_choices_are_distinct_unowned_boss_relics() {
+        let library = RelicLibrary::builtin();
+        let mut owned = RelicCollection::default();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+        let held = library.roll_boss(&owned, Realm::QiRefining, &[], &mut rng).unwrap();
+        owned.add_relic_forced(held.clone());
+
+        let choices = library.roll_boss_choices(3, &owned, Realm::QiRefining, &mut rng);
+        assert_eq!(choices.len(), 3);
+        assert!(choices.iter().all(|r| r.rarity == RelicRarity::Boss && r.id != held.id));
+        assert!(choices[0].id != choices[1].id && choices[1].id != choices[2].id && choices[0].id != choices[2].id);
+
+        // 库存将尽时只给剩下的
+        let total = library.all().iter().filter(|r| r.rarity == RelicRarity::Boss).count();
+        assert_eq!(library.roll_boss_choices(total + 5, &owned, Realm::QiRefining, &mut rng).len(), total - 1);
+    }
+
+    #[test]
+    fn test_counter_persists_and_victory_pays_out() {
+        let text = r#"(relics: [(id: "bounty", name: "悬赏令", description: "每胜三场得 30 灵石", rarity: Common,
+            effects: [OnVictory(heal: 2), Counter(counts: CombatWon, every: 3, effect: OnVictory(gold: 30))])])"#;
+        let data = RelicDataFile::parse(FILE, text.as_bytes()).unwrap();
+        let mut owned = RelicCollection::default();
+        owned.add_relic_forced(data.relics[0].clone());
+        let mut player = crate::components::combat::Player { gold: 0, hp: 50, ..Default::default() };
+
+        owned.on_victory(&mut player);
+        owned.on_victory(&mut player);
+        assert_eq!((player.gold, player.hp, owned.relic[0].counter), (0, 54, 2));
+        let fired = owned.on_victory(&mut player);
+        assert_eq!((player.gold, owned.relic[0].counter, fired.len()), (30, 0, 2));
+        assert_eq!(owned.relic[0].clone().advance_counter(CounterEvent::CardPlayed), None);
+    }
+}
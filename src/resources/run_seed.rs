@@ -0,0 +1,206 @@
+//! 命途道种（整局随机种子）
+//!
+//! 一局游戏的所有玩法随机性都源自同一个种子，并按用途拆分为互不干扰的随机流：
+//! 地图、战斗、奖励、坊市与机缘。输入相同道种即可复现相同的地图、遭遇与奖励。
+//! 存档记下每条流已消耗的位置，读档后从原处接续，与不曾中断的一局分毫不差。
+
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+
+/// 道种编码使用的字符集（36 进制，便于口头分享）
+const SEED_ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// 各随机流的派生盐值，保证流之间相互独立
+const MAP_SALT: u64 = 0x6D61_7000_0000_0001;
+const COMBAT_SALT: u64 = 0x636F_6D62_0000_0002;
+const REWARDS_SALT: u64 = 0x7265_7761_0000_0003;
+const SHOP_SALT: u64 = 0x7368_6F70_0000_0004;
+const EVENTS_SALT: u64 = 0x6576_6E74_0000_0005;
+
+/// 整局随机种子资源
+#[derive(Resource, Debug, Clone)]
+pub struct RunSeed {
+    /// 原始种子
+    pub seed: u64,
+    /// 地图生成
+    pub map: ChaCha12Rng,
+    /// 战斗（敌人编组、敌人意图、洗牌）
+    pub combat: ChaCha12Rng,
+    /// 战后奖励（功法、法宝、灵石）
+    pub rewards: ChaCha12Rng,
+    /// 坊市货架
+    pub shop: ChaCha12Rng,
+    /// 机缘事件与洞府
+    pub events: ChaCha12Rng,
+}
+
+/// 各随机流已消耗的位置（以 32 位字计）
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StreamPositions {
+    pub map: u64,
+    pub combat: u64,
+    pub rewards: u64,
+    pub shop: u64,
+    pub events: u64,
+}
+
+impl RunSeed {
+    /// 以指定种子开局
+    pub fn new(seed: u64) -> Self {
+        Self::resume(seed, StreamPositions::default())
+    }
+
+    /// 以指定种子开局，并把各随机流拨到存档时的位置
+    pub fn resume(seed: u64, positions: StreamPositions) -> Self {
+        let stream = |salt: u64, position: u64| {
+            let mut rng = ChaCha12Rng::seed_from_u64(seed ^ salt);
+            rng.set_word_pos(position as u128);
+            rng
+        };
+        Self {
+            seed,
+            map: stream(MAP_SALT, positions.map),
+            combat: stream(COMBAT_SALT, positions.combat),
+            rewards: stream(REWARDS_SALT, positions.rewards),
+            shop: stream(SHOP_SALT, positions.shop),
+            events: stream(EVENTS_SALT, positions.events),
+        }
+    }
+
+    /// 各随机流当前的位置（存档用）
+    pub fn positions(&self) -> StreamPositions {
+        StreamPositions {
+            map: self.map.get_word_pos() as u64,
+            combat: self.combat.get_word_pos() as u64,
+            rewards: self.rewards.get_word_pos() as u64,
+            shop: self.shop.get_word_pos() as u64,
+            events: self.events.get_word_pos() as u64,
+        }
+    }
+
+    /// 随机生成一个新种子
+    pub fn random() -> Self {
+        Self::new(rand::thread_rng().gen())
+    }
+
+    /// 由玩家输入的道种开局（空输入则随机）
+    pub fn from_input(input: &str) -> Self {
+        match Self::parse_code(input) {
+            Some(seed) => Self::new(seed),
+            None => Self::random(),
+        }
+    }
+
+    /// 道种的展示编码（36 进制大写）
+    pub fn code(&self) -> String {
+        Self::encode(self.seed)
+    }
+
+    /// 将种子编码为 36 进制字符串
+    pub fn encode(mut seed: u64) -> String {
+        if seed == 0 {
+            return "0".to_string();
+        }
+        let mut digits = Vec::new();
+        while seed > 0 {
+            digits.push(SEED_ALPHABET[(seed % 36) as usize]);
+            seed /= 36;
+        }
+        digits.reverse();
+        String::from_utf8(digits).expect("字符集均为 ASCII")
+    }
+
+    /// 解析玩家输入的道种
+    ///
+    /// 合法的 36 进制编码按原值解析；其余任意文字（如中文）按内容哈希为种子。
+    pub fn parse_code(input: &str) -> Option<u64> {
+        let trimmed = input.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        let mut value: u64 = 0;
+        let mut valid = true;
+        for c in trimmed.chars() {
+            let Some(digit) = c.to_digit(36) else { valid = false; break; };
+            match value.checked_mul(36).and_then(|v| v.checked_add(digit as u64)) {
+                Some(v) => value = v,
+                None => { valid = false; break; }
+            }
+        }
+        if valid {
+            return Some(value);
+        }
+
+        // FNV-1a：跨平台稳定，保证同一段文字得到同一道种
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for byte in trimmed.as_bytes() {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        Some(hash)
+    }
+}
+
+impl Default for RunSeed {
+    fn default() -> Self {
+        Self::random()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::map::{generate_map_nodes, MapConfig};
+
+    #[test]
+    fn test_code_round_trip() {
+        let seed = RunSeed::new(0xDEAD_BEEF_1234);
+        assert_eq!(RunSeed::parse_code(&seed.code()), Some(seed.seed));
+        assert_eq!(RunSeed::parse_code(&seed.code().to_lowercase()), Some(seed.seed));
+        assert_eq!(RunSeed::encode(0), "0");
+    }
+
+    #[test]
+    fn test_text_seed_is_stable() {
+        assert_eq!(RunSeed::parse_code("渡劫飞升"), RunSeed::parse_code("渡劫飞升"));
+        assert_ne!(RunSeed::parse_code("渡劫飞升"), RunSeed::parse_code("身死道消"));
+        assert_eq!(RunSeed::parse_code("   "), None);
+    }
+
+    #[test]
+    fn test_same_seed_reproduces_map() {
+        let config = MapConfig::default();
+        let mut a = RunSeed::new(42);
+        let mut b = RunSeed::new(42);
+        let nodes_a = generate_map_nodes(&config, 0, &mut a.map);
+        let nodes_b = generate_map_nodes(&config, 0, &mut b.map);
+
+        let layout = |nodes: &[crate::components::map::MapNode]| {
+            nodes.iter().map(|n| (n.node_type, n.next_nodes.clone())).collect::<Vec<_>>()
+        };
+        assert_eq!(layout(&nodes_a), layout(&nodes_b));
+    }
+
+    #[test]
+    fn test_streams_are_independent() {
+        let mut a = RunSeed::new(7);
+        let mut b = RunSeed::new(7);
+        // 在一条流上额外消耗随机数，不应影响其它流
+        for _ in 0..100 {
+            let _: u32 = a.shop.gen();
+        }
+        assert_eq!(a.rewards.gen::<u64>(), b.rewards.gen::<u64>());
+        assert_eq!(a.map.gen::<u64>(), b.map.gen::<u64>());
+    }
+
+    #[test]
+    fn test_streams_match_std_rng() {
+        // 随机流与 StdRng 同源，换用可定位的 ChaCha12 不改变既有道种的结果
+        let mut seed = RunSeed::new(42);
+        let mut std = rand::rngs::StdRng::seed_from_u64(42 ^ MAP_SALT);
+        assert_eq!(seed.map.gen::<u64>(), std.gen::<u64>());
+    }
+}
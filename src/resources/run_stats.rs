@@ -0,0 +1,20 @@
+//! 本局战绩
+//!
+//! 记录一局修行中的战果，随存档保存，开新局时清零，终局时在结算界面展示。
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// 本局战绩资源
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunStats {
+    /// 击败的精英阵容（按场计）
+    pub elites_slain: u32,
+}
+
+impl RunStats {
+    /// 记下一场精英战的胜利
+    pub fn record_elite(&mut self) {
+        self.elites_slain += 1;
+    }
+}
@@ -1,70 +1,997 @@
 use bevy::prelude::*;
+use directories::ProjectDirs;
 use serde::{Serialize, Deserialize};
+use serde_json::Value;
 use std::fs;
-use std::path::Path;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{SystemTime, UNIX_EPOCH};
 use crate::components::*;
-use crate::components::cards::Card;
+use crate::components::cards::{Card, DiscardPile, DrawPile, Hand};
 use crate::components::relic::Relic;
-use crate::components::map::MapNode;
-use crate::components::cultivation::Cultivation;
+use crate::components::map::{MapNode, UnknownOdds};
+use crate::components::cultivation::{Cultivation, Realm};
+use crate::states::GameState;
+use crate::components::status::{StatusEffects, BURN, POISON, VULNERABLE, WEAKNESS};
+use crate::resources::status_data::StatusRegistry;
+use crate::resources::run_stats::RunStats;
+use crate::resources::run_seed::StreamPositions;
+use crate::resources::act_data::ActAtlas;
+
+/// 当前存档格式版本（旧版存档读取时按迁移链逐级升级）
+pub const SAVE_VERSION: u32 = 11;
+/// 存档档位名称
+pub const SLOT_NAMES: [&str; 4] = ["天字档", "地字档", "玄字档", "黄字档"];
+/// 存档档位数
+pub const SLOT_COUNT: usize = SLOT_NAMES.len();
+/// 单档时代的存档文件名（位于存档目录与工作目录，读取后迁入空档位）
+const LEGACY_SAVE_FILE: &str = "savegame.json";
+/// 保留的滚动备份份数
+const BACKUP_COUNT: usize = 2;
+
+/// 存档迁移：第 i 项把版本 i 的存档升级为版本 i + 1
+/// 存档结构的每次改动（包括新增字段）都递增版本并补上一步迁移
+type Migration = fn(&mut Value) -> Result<(), String>;
+const MIGRATIONS: [Migration; SAVE_VERSION as usize] = [
+    migrate_v0_to_v1, migrate_v1_to_v2, migrate_v2_to_v3, migrate_v3_to_v4, migrate_v4_to_v5,
+    migrate_v5_to_v6, migrate_v6_to_v7, migrate_v7_to_v8, migrate_v8_to_v9, migrate_v9_to_v10,
+    migrate_v10_to_v11,
+];
+
+/// 当前使用的存档档位
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ActiveSaveSlot(pub usize);
+
+/// 本局累计修行时长
+#[derive(Resource, Debug, Clone, Copy, Default)]
+pub struct PlayTime {
+    pub seconds: f64,
+}
+
+/// 存档档位预览
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotSummary {
+    pub slot: usize,
+    pub sect: Sect,
+    pub realm: Realm,
+    pub hp: i32,
+    pub max_hp: i32,
+    pub gold: i32,
+    pub act: u32,
+    /// 累计层数（含此前各界，与结算道韵所用层数一致）
+    pub layer: u32,
+    pub ascension: u32,
+    pub deck_size: usize,
+    /// 修行时长（秒）
+    pub play_time: f64,
+    pub seed: u64,
+    /// 存档时刻（Unix 秒）
+    pub saved_at: u64,
+}
+
+impl SlotSummary {
+    /// 修行时长，如 "1时05分"
+    pub fn play_time_label(&self) -> String {
+        let minutes = (self.play_time / 60.0) as u64;
+        format!("{}时{:02}分", minutes / 60, minutes % 60)
+    }
+
+    /// 存档时刻（UTC），如 "2026-10-17 08:30"
+    pub fn saved_at_label(&self) -> String {
+        if self.saved_at == 0 {
+            return "时刻不明".to_string();
+        }
+        let days = (self.saved_at / 86_400) as i64;
+        let secs = self.saved_at % 86_400;
+        // 公历换算（Howard Hinnant 的 civil_from_days）
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = doy - (153 * mp + 2) / 5 + 1;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 };
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        format!("{}-{:02}-{:02} {:02}:{:02}", year, month, day, secs / 3600, secs % 3600 / 60)
+    }
+}
+
+/// 平台数据目录（取不到时退回工作目录）
+pub fn data_dir() -> PathBuf {
+    ProjectDirs::from("com", "PeterFei", "Jiujie")
+        .map(|dirs| dirs.data_dir().to_path_buf())
+        .unwrap_or_default()
+}
+
+/// 先写临时文件再替换，写到一半崩溃也不会留下残缺文件
+///
+/// 每次写入各用一个临时文件，同时写同一路径也不会互相覆盖临时文件。
+pub fn write_atomically(path: &Path, contents: &str) -> Result<(), String> {
+    static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(format!(".{}-{}.tmp", std::process::id(), TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)));
+    let temp_path = path.with_file_name(temp_name);
+    let mut file = fs::File::create(&temp_path).map_err(|e| format!("写入文件失败: {}", e))?;
+    file.write_all(contents.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("写入文件失败: {}", e))?;
+    drop(file);
+    fs::rename(&temp_path, path).map_err(|e| format!("替换文件失败: {}", e))
+}
+
+/// 档位写入任务
+enum SaveJob {
+    Write {
+        save: Box<GameStateSave>,
+        slot: usize,
+        path: PathBuf,
+        /// 战斗存档所属的战斗（战斗了结后尚未写入的即作废）
+        combat_epoch: Option<u64>,
+    },
+    Delete(usize),
+    Flush(mpsc::Sender<()>),
+}
+
+/// 存档写入队列
+///
+/// 档位的写入与删除都交给同一条后台线程，按提交顺序逐个执行：
+/// 备份轮转不会交错，先提交的战斗存档也不会盖过其后的地图存档。
+#[derive(Resource)]
+pub struct SaveWriter {
+    jobs: mpsc::Sender<SaveJob>,
+    combat_epoch: Arc<AtomicU64>,
+}
+
+impl Default for SaveWriter {
+    fn default() -> Self {
+        let (jobs, queue) = mpsc::channel::<SaveJob>();
+        let combat_epoch = Arc::new(AtomicU64::new(0));
+        let current_epoch = Arc::clone(&combat_epoch);
+        std::thread::Builder::new()
+            .name("save-writer".to_string())
+            .spawn(move || {
+                for job in queue {
+                    match job {
+                        SaveJob::Write { save, slot, path, combat_epoch } => {
+                            if combat_epoch.is_some_and(|epoch| epoch != current_epoch.load(Ordering::SeqCst)) {
+                                info!("【存档系统】战斗已了结，弃去未写入的战况存档");
+                                continue;
+                            }
+                            match save.save_to(&path) {
+                                Ok(()) => info!("【存档系统】修仙进度已保存至{}", SLOT_NAMES[slot]),
+                                Err(e) => error!("【存档系统】{}存档失败: {}", SLOT_NAMES[slot], e),
+                            }
+                        }
+                        SaveJob::Delete(slot) => GameStateSave::delete_slot(slot),
+                        SaveJob::Flush(done) => {
+                            let _ = done.send(());
+                        }
+                    }
+                }
+            })
+            .expect("无法启动存档线程");
+        Self { jobs, combat_epoch }
+    }
+}
+
+impl SaveWriter {
+    /// 排队写入档位
+    pub fn save(&self, save: GameStateSave, slot: usize) {
+        self.write(save, slot, GameStateSave::slot_path(slot), None);
+    }
+
+    /// 排队写入战斗中途存档（写入前战斗已了结则作废）
+    pub fn save_combat(&self, save: GameStateSave, slot: usize) {
+        let epoch = self.combat_epoch.load(Ordering::SeqCst);
+        self.write(save, slot, GameStateSave::slot_path(slot), Some(epoch));
+    }
+
+    /// 战斗了结：作废尚未写入的战斗存档
+    pub fn end_combat(&self) {
+        self.combat_epoch.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// 排队删除档位及其备份（排在此前的写入之后）
+    pub fn delete(&self, slot: usize) {
+        self.submit(SaveJob::Delete(slot));
+    }
+
+    /// 等待此前提交的任务全部完成
+    pub fn flush(&self) {
+        let (done, finished) = mpsc::channel();
+        self.submit(SaveJob::Flush(done));
+        let _ = finished.recv();
+    }
+
+    fn write(&self, save: GameStateSave, slot: usize, path: PathBuf, combat_epoch: Option<u64>) {
+        self.submit(SaveJob::Write { save: Box::new(save), slot, path, combat_epoch });
+    }
+
+    fn submit(&self, job: SaveJob) {
+        if self.jobs.send(job).is_err() {
+            error!("【存档系统】存档线程已退出，本次存档未能写入");
+        }
+    }
+}
+
+/// 当前 Unix 时间（秒）
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
 
 /// 完整的游戏存档数据
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GameStateSave {
-    pub player: Player,
+    /// 存档格式版本
+    pub version: u32,
+    /// 修行者的跨战斗状态（战斗内状态见 combat）
+    pub player: PlayerRunState,
     pub cultivation: Cultivation,
     pub deck: Vec<Card>,
     pub relics: Vec<Relic>,
     pub map_nodes: Vec<MapNode>,
     pub current_map_node_id: Option<u32>,
     pub current_map_layer: u32,
+    /// 所在大境（旧存档迁移为首界）
+    pub act: u32,
+    /// 未知节点的揭示概率（旧存档迁移为基础值）
+    pub unknown_odds: UnknownOdds,
+    /// 命途道种（旧存档缺失时为 0）
+    #[serde(default)]
+    pub seed: u64,
+    /// 各随机流已消耗的位置（读档后从原处接续）
+    pub streams: StreamPositions,
+    /// 累计修行时长（秒）
+    pub play_time: f64,
+    /// 存档时刻（Unix 秒）
+    pub saved_at: u64,
+    /// 天劫重数
+    pub ascension: u32,
+    /// 战斗中途存档（不在战斗中时为 None）
+    #[serde(default)]
+    pub combat: Option<CombatSnapshot>,
+    /// 本局已遇到的机缘 id
+    pub seen_events: Vec<String>,
+    /// 本局战绩（旧存档迁移为零）
+    pub run_stats: RunStats,
+}
+
+/// 修行者跨战斗保留的状态
+///
+/// 灵力、护甲、回合、剑意与状态只在战斗内有效，存于 [`CombatSnapshot`]；
+/// 道行上限只记不含境界加成的部分，读档时由 [`Cultivation::get_hp_bonus`] 补回。
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PlayerRunState {
+    pub hp: i32,
+    /// 道行上限（不含境界加成）
+    pub base_max_hp: i32,
+    pub gold: i32,
+    /// 所属宗门（旧存档迁移为青云剑宗）
+    pub sect: Sect,
+}
+
+impl PlayerRunState {
+    pub fn capture(player: &Player, cultivation: &Cultivation) -> Self {
+        Self {
+            hp: player.hp,
+            base_max_hp: player.max_hp - cultivation.get_hp_bonus(),
+            gold: player.gold,
+            sect: player.sect,
+        }
+    }
+
+    /// 重建战斗外的修行者（战斗字段取初始值）
+    pub fn to_player(&self, cultivation: &Cultivation) -> Player {
+        let max_hp = self.base_max_hp + cultivation.get_hp_bonus();
+        let energy = self.sect.base_energy() + cultivation.get_energy_bonus();
+        Player {
+            hp: self.hp.min(max_hp),
+            max_hp,
+            gold: self.gold,
+            max_energy: energy,
+            energy,
+            realm: cultivation.realm,
+            ..Player::for_sect(self.sect)
+        }
+    }
+}
+
+/// 战斗中途存档：读档时据此原样重建战场
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CombatSnapshot {
+    /// 参战敌人（道行、意图、状态与招式进度）
+    pub enemies: Vec<Enemy>,
+    pub draw_pile: DrawPile,
+    pub discard_pile: DiscardPile,
+    pub hand: Hand,
+    pub environment: Environment,
+    /// 回合数
+    pub turn: u32,
+    /// 剑意
+    pub sword_intent: i32,
+    /// 宗门心法层数（丹火、符印）
+    pub sect_charge: i32,
+    pub energy: i32,
+    pub block: i32,
+    /// 修行者身上的状态
+    pub statuses: StatusEffects,
+    /// 本场战斗已打出的功法数
+    pub cards_played: i32,
+    /// 本场战斗是否已施展神通
+    pub divine_art_used: bool,
+}
+
+impl CombatSnapshot {
+    /// 记录当前战场
+    pub fn capture(
+        player: &Player,
+        enemies: Vec<Enemy>,
+        draw_pile: &DrawPile,
+        discard_pile: &DiscardPile,
+        hand: &Hand,
+        environment: Option<&Environment>,
+    ) -> Self {
+        Self {
+            enemies,
+            draw_pile: draw_pile.clone(),
+            discard_pile: discard_pile.clone(),
+            hand: hand.clone(),
+            environment: environment.cloned().unwrap_or_default(),
+            turn: player.turn,
+            sword_intent: player.sword_intent,
+            sect_charge: player.sect_charge,
+            energy: player.energy,
+            block: player.block,
+            statuses: player.statuses.clone(),
+            cards_played: player.cards_played,
+            divine_art_used: player.divine_art_used,
+        }
+    }
+
+    /// 把修行者的战斗状态写回
+    pub fn restore_player(&self, player: &mut Player) {
+        player.turn = self.turn;
+        player.sword_intent = self.sword_intent;
+        player.sect_charge = self.sect_charge;
+        player.energy = self.energy;
+        player.block = self.block;
+        player.statuses = self.statuses.clone();
+        player.cards_played = self.cards_played;
+        player.divine_art_used = self.divine_art_used;
+    }
 }
 
+/// 读档后待重建的战斗（进入战斗时消费）
+#[derive(Resource, Debug, Clone)]
+pub struct PendingCombatResume(pub CombatSnapshot);
+
 impl GameStateSave {
-    /// 存档文件路径
-    pub fn get_save_path() -> String {
-        "savegame.json".to_string()
+    /// 读档后的修行者（战斗中途的存档另需 [`CombatSnapshot::restore_player`]）
+    pub fn restore_player(&self) -> Player {
+        self.player.to_player(&self.cultivation)
+    }
+
+    /// 存档目录（平台数据目录下的 saves）
+    pub fn save_dir() -> PathBuf {
+        data_dir().join("saves")
+    }
+
+    /// 档位存档文件路径
+    pub fn slot_path(slot: usize) -> PathBuf {
+        Self::save_dir().join(format!("slot{}.json", slot + 1))
+    }
+
+    /// 第 n 份滚动备份的路径（1 为最近一份）
+    fn backup_path(path: &Path, n: usize) -> PathBuf {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".bak{}", n));
+        path.with_file_name(name)
     }
 
-    /// 检查是否存在存档
+    /// 读档时依次尝试的文件：正式存档 → 滚动备份（由新到旧）
+    fn candidates(path: &Path) -> Vec<PathBuf> {
+        let mut paths = vec![path.to_path_buf()];
+        paths.extend((1..=BACKUP_COUNT).map(|n| Self::backup_path(path, n)));
+        paths
+    }
+
+    /// 检查是否存在任一档位存档
     pub fn exists() -> bool {
-        Path::new(&Self::get_save_path()).exists()
+        (0..SLOT_COUNT).any(Self::slot_exists) || Self::legacy_paths().iter().any(|p| p.exists())
     }
 
-    /// 执行存档
-    pub fn save_to_disk(&self) -> Result<(), String> {
-        let serialized = serde_json::to_string_pretty(self)
-            .map_err(|e| format!("序列化失败: {}", e))?;
-        fs::write(Self::get_save_path(), serialized)
-            .map_err(|e| format!("写入文件失败: {}", e))?;
-        info!("【存档系统】修仙进度已保存至磁盘");
-        Ok(())
+    /// 档位是否有存档（含备份）
+    pub fn slot_exists(slot: usize) -> bool {
+        Self::candidates(&Self::slot_path(slot)).iter().any(|p| p.exists())
     }
 
-    /// 执行读档
-    pub fn load_from_disk() -> Result<Self, String> {
-        let data = fs::read_to_string(Self::get_save_path())
+    /// 第一个空档位
+    pub fn first_empty_slot() -> Option<usize> {
+        (0..SLOT_COUNT).find(|slot| !Self::slot_exists(*slot))
+    }
+
+    /// 读取档位（正式存档损坏时回退到备份）
+    pub fn load_slot(slot: usize) -> Result<Self, String> {
+        let save = Self::load_with_fallback(&Self::slot_path(slot))?;
+        info!("【存档系统】已从{}恢复修仙进度", SLOT_NAMES[slot]);
+        Ok(save)
+    }
+
+    /// 删除档位及其备份（道消身殒或重开时调用）
+    pub fn delete_slot(slot: usize) {
+        if Self::remove_files(&Self::candidates(&Self::slot_path(slot))) {
+            info!("【存档系统】{}已清空", SLOT_NAMES[slot]);
+        }
+    }
+
+    /// 把档位复制到第一个空档位，返回新档位
+    pub fn duplicate_slot(slot: usize) -> Result<usize, String> {
+        let target = Self::first_empty_slot().ok_or("没有空余档位")?;
+        let mut save = Self::load_with_fallback(&Self::slot_path(slot))?;
+        save.saved_at = unix_now();
+        save.save_to(&Self::slot_path(target))?;
+        info!("【存档系统】{}已复刻至{}", SLOT_NAMES[slot], SLOT_NAMES[target]);
+        Ok(target)
+    }
+
+    /// 全部档位的预览（空档位或损坏时为 None）
+    pub fn list_slots() -> Vec<Option<SlotSummary>> {
+        Self::adopt_legacy_save();
+        (0..SLOT_COUNT)
+            .map(|slot| {
+                if !Self::slot_exists(slot) {
+                    return None;
+                }
+                Self::load_with_fallback(&Self::slot_path(slot)).ok().map(|save| save.summary(slot))
+            })
+            .collect()
+    }
+
+    /// 档位预览
+    pub fn summary(&self, slot: usize) -> SlotSummary {
+        let player = self.restore_player();
+        SlotSummary {
+            slot,
+            sect: player.sect,
+            realm: self.cultivation.realm,
+            hp: player.hp,
+            max_hp: player.max_hp,
+            gold: player.gold,
+            act: self.act,
+            layer: ActAtlas::builtin().total_layer(self.act, self.current_map_layer),
+            ascension: self.ascension,
+            deck_size: self.deck.len(),
+            play_time: self.play_time,
+            seed: self.seed,
+            saved_at: self.saved_at,
+        }
+    }
+
+    /// 单档时代的存档位置
+    fn legacy_paths() -> [PathBuf; 2] {
+        [Self::save_dir().join(LEGACY_SAVE_FILE), PathBuf::from(LEGACY_SAVE_FILE)]
+    }
+
+    /// 把单档时代的存档迁入第一个空档位
+    fn adopt_legacy_save() {
+        for legacy in Self::legacy_paths() {
+            if !Self::candidates(&legacy).iter().any(|p| p.exists()) {
+                continue;
+            }
+            let Some(slot) = Self::first_empty_slot() else { return; };
+            match Self::load_with_fallback(&legacy).and_then(|save| save.save_to(&Self::slot_path(slot))) {
+                Ok(()) => {
+                    Self::remove_files(&Self::candidates(&legacy));
+                    info!("【存档系统】旧存档 {} 已迁入{}", legacy.display(), SLOT_NAMES[slot]);
+                }
+                Err(e) => warn!("【存档系统】旧存档 {} 无法迁入档位: {}", legacy.display(), e),
+            }
+        }
+    }
+
+    fn remove_files(paths: &[PathBuf]) -> bool {
+        let mut deleted = false;
+        for path in paths.iter().filter(|p| p.exists()) {
+            deleted |= fs::remove_file(path).is_ok();
+        }
+        deleted
+    }
+
+    /// 写入指定路径：先写临时文件再替换，原存档轮转为备份
+    pub fn save_to(&self, path: &Path) -> Result<(), String> {
+        let serialized = self.to_json()?;
+        if path.exists() {
+            for n in (1..BACKUP_COUNT).rev() {
+                let older = Self::backup_path(path, n);
+                if older.exists() {
+                    let _ = fs::rename(&older, Self::backup_path(path, n + 1));
+                }
+            }
+            fs::copy(path, Self::backup_path(path, 1)).map_err(|e| format!("备份旧存档失败: {}", e))?;
+        }
+        write_atomically(path, &serialized)
+    }
+
+    /// 依次尝试正式存档与各份备份
+    pub fn load_with_fallback(path: &Path) -> Result<Self, String> {
+        let mut last_error = "没有找到存档".to_string();
+        for candidate in Self::candidates(path) {
+            if !candidate.exists() {
+                continue;
+            }
+            match Self::load_from(&candidate) {
+                Ok(save) => {
+                    if candidate != path {
+                        warn!("【存档系统】正式存档不可用，已从备份 {} 恢复", candidate.display());
+                    }
+                    return Ok(save);
+                }
+                Err(e) => {
+                    warn!("【存档系统】{} 读取失败: {}", candidate.display(), e);
+                    last_error = e;
+                }
+            }
+        }
+        Err(last_error)
+    }
+
+    /// 读取单个存档文件
+    pub fn load_from(path: &Path) -> Result<Self, String> {
+        let data = fs::read_to_string(path)
             .map_err(|e| format!("读取文件失败: {}", e))?;
-        let deserialized: Self = serde_json::from_str(&data)
-            .map_err(|e| format!("反序列化失败: {}", e))?;
-        info!("【存档系统】修仙进度已从磁盘恢复");
-        Ok(deserialized)
+        Self::from_json(&data)
+    }
+
+    /// 序列化为存档 JSON
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("序列化失败: {}", e))
+    }
+
+    /// 解析存档 JSON，旧版本先经迁移链升级
+    pub fn from_json(data: &str) -> Result<Self, String> {
+        let mut value: Value = serde_json::from_str(data)
+            .map_err(|e| format!("存档不是合法的 JSON: {}", e))?;
+        let from = migrate(&mut value)?;
+        if from < SAVE_VERSION {
+            info!("【存档系统】存档已从版本 {} 升级至版本 {}", from, SAVE_VERSION);
+        }
+        serde_json::from_value(value)
+            .map_err(|e| format!("反序列化失败: {}", e))
+    }
+}
+
+/// 把存档升级到当前版本，返回原始版本（无 version 字段视为版本 0）
+pub fn migrate(value: &mut Value) -> Result<u32, String> {
+    let from = value.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+    if from > SAVE_VERSION {
+        return Err(format!("存档版本 {} 高于当前支持的版本 {}，请更新游戏", from, SAVE_VERSION));
     }
+    for version in from..SAVE_VERSION {
+        MIGRATIONS[version as usize](value).map_err(|e| format!("存档从版本 {} 升级失败: {}", version, e))?;
+        value["version"] = Value::from(version + 1);
+    }
+    Ok(from)
+}
+
+/// 版本 0 → 1：补齐命途道种，并把修行者旧的中毒/灼烧/虚弱/易伤字段并入状态表
+fn migrate_v0_to_v1(value: &mut Value) -> Result<(), String> {
+    let root = value.as_object_mut().ok_or("存档根节点不是对象")?;
+    root.entry("seed").or_insert(Value::from(0u64));
 
-    /// 删除存档（道消身殒时调用）
-    pub fn delete_save() {
-        if Self::exists() {
-            let _ = fs::remove_file(Self::get_save_path());
-            info!("【存档系统】存档已删除");
+    let player = root.get_mut("player").and_then(Value::as_object_mut).ok_or("缺少 player")?;
+    let mut statuses = match player.remove("statuses") {
+        Some(existing) => serde_json::from_value(existing).map_err(|e| format!("player.statuses: {}", e))?,
+        None => StatusEffects::default(),
+    };
+    for id in [POISON, BURN, WEAKNESS, VULNERABLE] {
+        if let Some(stacks) = player.remove(id).and_then(|v| v.as_i64()) {
+            statuses.apply(StatusRegistry::builtin(), id, stacks as i32, None);
         }
     }
+    let statuses = serde_json::to_value(statuses).map_err(|e| e.to_string())?;
+    player.insert("statuses".to_string(), statuses);
+    Ok(())
+}
+
+/// 版本 1 → 2：档位预览所需的修行时长与存档时刻
+fn migrate_v1_to_v2(value: &mut Value) -> Result<(), String> {
+    let root = value.as_object_mut().ok_or("存档根节点不是对象")?;
+    root.entry("play_time").or_insert(Value::from(0.0));
+    root.entry("saved_at").or_insert(Value::from(0u64));
+    Ok(())
+}
+
+/// 版本 2 → 3：修行者只保留跨战斗状态，道行上限扣除境界加成
+fn migrate_v2_to_v3(value: &mut Value) -> Result<(), String> {
+    let cultivation: Cultivation = serde_json::from_value(value["cultivation"].clone())
+        .map_err(|e| format!("cultivation: {}", e))?;
+    let player: Player = serde_json::from_value(value["player"].clone())
+        .map_err(|e| format!("player: {}", e))?;
+    value["player"] = serde_json::to_value(PlayerRunState::capture(&player, &cultivation)).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 版本 3 → 4：天劫重数（旧存档均为凡尘）
+fn migrate_v3_to_v4(value: &mut Value) -> Result<(), String> {
+    let root = value.as_object_mut().ok_or("存档根节点不是对象")?;
+    root.entry("ascension").or_insert(Value::from(0u32));
+    Ok(())
+}
+
+/// 版本 4 → 5：随机流位置（旧存档从各流起点接续）
+fn migrate_v4_to_v5(value: &mut Value) -> Result<(), String> {
+    let root = value.as_object_mut().ok_or("存档根节点不是对象")?;
+    let streams = serde_json::to_value(StreamPositions::default()).map_err(|e| e.to_string())?;
+    root.entry("streams").or_insert(streams);
+    Ok(())
+}
+
+/// 版本 5 → 6：宗门（旧存档均为青云剑宗）与战斗中的心法层数
+fn migrate_v5_to_v6(value: &mut Value) -> Result<(), String> {
+    let sect = serde_json::to_value(Sect::default()).map_err(|e| e.to_string())?;
+    let player = value.get_mut("player").and_then(Value::as_object_mut).ok_or("缺少 player")?;
+    player.entry("sect").or_insert(sect);
+    if let Some(combat) = value.get_mut("combat").and_then(Value::as_object_mut) {
+        combat.entry("sect_charge").or_insert(Value::from(0));
+    }
+    Ok(())
+}
+
+/// 版本 6 → 7：战斗中是否已施展神通（旧存档视作尚未施展）
+fn migrate_v6_to_v7(value: &mut Value) -> Result<(), String> {
+    if let Some(combat) = value.get_mut("combat").and_then(Value::as_object_mut) {
+        combat.entry("divine_art_used").or_insert(Value::from(false));
+    }
+    Ok(())
+}
+
+/// 版本 7 → 8：本局已遇到的机缘（旧存档视作尚未遇到）
+fn migrate_v7_to_v8(value: &mut Value) -> Result<(), String> {
+    let root = value.as_object_mut().ok_or("存档根节点不是对象")?;
+    root.entry("seen_events").or_insert(Value::Array(Vec::new()));
+    Ok(())
+}
+
+/// 版本 8 → 9：所在大境（旧存档均在首界）
+fn migrate_v8_to_v9(value: &mut Value) -> Result<(), String> {
+    let root = value.as_object_mut().ok_or("存档根节点不是对象")?;
+    root.entry("act").or_insert(Value::from(0u32));
+    Ok(())
+}
+
+/// 版本 9 → 10：未知节点的揭示概率（旧存档从基础值开始）
+fn migrate_v9_to_v10(value: &mut Value) -> Result<(), String> {
+    let odds = serde_json::to_value(UnknownOdds::default()).map_err(|e| e.to_string())?;
+    let root = value.as_object_mut().ok_or("存档根节点不是对象")?;
+    root.entry("unknown_odds").or_insert(odds);
+    Ok(())
+}
+
+/// 版本 10 → 11：本局战绩（旧存档从零记起）
+fn migrate_v10_to_v11(value: &mut Value) -> Result<(), String> {
+    let stats = serde_json::to_value(RunStats::default()).map_err(|e| e.to_string())?;
+    let root = value.as_object_mut().ok_or("存档根节点不是对象")?;
+    root.entry("run_stats").or_insert(stats);
+    Ok(())
 }
 
 /// 存档插件
 pub struct SavePlugin;
 
 impl Plugin for SavePlugin {
-    fn build(&self, _app: &mut App) {
-        // 后续可添加自动存档系统
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ActiveSaveSlot>()
+            .init_resource::<PlayTime>()
+            .add_systems(Update, tick_play_time.run_if(in_run));
+    }
+}
+
+/// 是否处于一局修行之中（菜单、开场与结算不计时）
+fn in_run(state: Res<State<GameState>>) -> bool {
+    !matches!(state.get(), GameState::Booting | GameState::OpeningVideo | GameState::MainMenu | GameState::GameOver)
+}
+
+/// 累计修行时长
+fn tick_play_time(time: Res<Time>, mut play_time: ResMut<PlayTime>) {
+    play_time.seconds += time.delta_secs_f64();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_save() -> GameStateSave {
+        GameStateSave {
+            version: SAVE_VERSION,
+            player: PlayerRunState::capture(&Player::default(), &Cultivation::new()),
+            cultivation: Cultivation::new(),
+            deck: Vec::new(),
+            relics: Vec::new(),
+            map_nodes: Vec::new(),
+            current_map_node_id: None,
+            current_map_layer: 3,
+            act: 0,
+            unknown_odds: UnknownOdds::default(),
+            seed: 42,
+            streams: StreamPositions::default(),
+            play_time: 3_900.0,
+            saved_at: 1_792_225_800,
+            ascension: 0,
+            combat: None,
+            seen_events: Vec::new(),
+            run_stats: RunStats { elites_slain: 2 },
+        }
+    }
+
+    fn temp_save_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("jiujie_save_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("slot1.json")
+    }
+
+    /// 版本 2 及更早的存档把整个 Player 写入 player 字段
+    fn legacy_save(player: &Player, cultivation: Cultivation) -> Value {
+        let mut legacy = serde_json::to_value(sample_save()).unwrap();
+        legacy["version"] = Value::from(2);
+        legacy.as_object_mut().unwrap().remove("ascension");
+        legacy["player"] = serde_json::to_value(player).unwrap();
+        legacy["cultivation"] = serde_json::to_value(cultivation).unwrap();
+        legacy
+    }
+
+    #[test]
+    fn test_unversioned_save_is_migrated() {
+        let mut legacy = legacy_save(&Player::default(), Cultivation::new());
+        let root = legacy.as_object_mut().unwrap();
+        root.remove("version");
+        root.remove("seed");
+        root.remove("play_time");
+        root.remove("saved_at");
+        root.remove("act");
+        root.remove("run_stats");
+        root.remove("seen_events");
+        root.remove("unknown_odds");
+        let player = root.get_mut("player").unwrap().as_object_mut().unwrap();
+        player.remove("statuses");
+        player.insert("poison".to_string(), Value::from(3));
+        player.insert("weakness".to_string(), Value::from(0));
+
+        let save = GameStateSave::from_json(&legacy.to_string()).unwrap();
+        assert_eq!(save.version, SAVE_VERSION);
+        assert_eq!(save.seed, 0);
+        assert_eq!(save.saved_at, 0);
+        assert_eq!(save.act, 0);
+        assert!(save.seen_events.is_empty());
+        assert_eq!(save.unknown_odds, UnknownOdds::default());
+        assert_eq!(save.run_stats, RunStats::default());
+        assert_eq!(save.ascension, 0);
+        assert_eq!(save.player, PlayerRunState { hp: 80, base_max_hp: 80, gold: 100, sect: Sect::Sword });
+    }
+
+    /// 版本 5 的战斗中途存档：缺少宗门、心法层数与神通记录
+    fn v5_combat_save() -> Value {
+        let snapshot = CombatSnapshot::capture(
+            &Player::default(), Vec::new(), &DrawPile::new(Vec::new()), &DiscardPile::new(), &Hand::new(10), None,
+        );
+        let mut value = serde_json::to_value(GameStateSave { combat: Some(snapshot), ..sample_save() }).unwrap();
+        value["version"] = Value::from(5);
+        value["player"].as_object_mut().unwrap().remove("sect");
+        let combat = value["combat"].as_object_mut().unwrap();
+        combat.remove("sect_charge");
+        combat.remove("divine_art_used");
+        value
+    }
+
+    #[test]
+    fn test_combat_save_fields_are_migrated() {
+        let save = GameStateSave::from_json(&v5_combat_save().to_string()).unwrap();
+        assert_eq!(save.player.sect, Sect::Sword);
+        let combat = save.combat.unwrap();
+        assert_eq!(combat.sect_charge, 0);
+        assert!(!combat.divine_art_used);
+    }
+
+    #[test]
+    fn test_run_state_split_restores_realm_hp_bonus() {
+        let cultivation = Cultivation { realm: Realm::GoldenCore, insight: 0 };
+        let mut player = Player::default();
+        player.max_hp = 80 + cultivation.get_hp_bonus();
+        player.hp = 150;
+        player.gold = 321;
+        player.block = 12;
+        player.turn = 5;
+
+        let save = GameStateSave::from_json(&legacy_save(&player, cultivation).to_string()).unwrap();
+        assert_eq!(save.player.base_max_hp, 80);
+        let restored = save.restore_player();
+        assert_eq!((restored.hp, restored.max_hp, restored.gold), (150, 230, 321));
+        assert_eq!((restored.block, restored.turn), (0, 1));
+        assert_eq!(restored.max_energy, 3 + save.cultivation.get_energy_bonus());
+    }
+
+    #[test]
+    fn test_newer_save_is_rejected() {
+        let mut future = serde_json::to_value(sample_save()).unwrap();
+        future["version"] = Value::from(SAVE_VERSION + 1);
+        let err = GameStateSave::from_json(&future.to_string()).unwrap_err();
+        assert!(err.contains("请更新游戏"), "{}", err);
+    }
+
+    #[test]
+    fn test_corrupted_save_falls_back_to_backup() {
+        let path = temp_save_path("backup");
+        let mut save = sample_save();
+        save.save_to(&path).unwrap();
+        save.current_map_layer = 4;
+        save.save_to(&path).unwrap();
+        assert!(GameStateSave::backup_path(&path, 1).exists());
+
+        // 模拟写到一半崩溃留下的残缺文件
+        fs::write(&path, "{\"version\": 1, \"player\":").unwrap();
+        let restored = GameStateSave::load_with_fallback(&path).unwrap();
+        assert_eq!(restored.current_map_layer, 3);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_writer_keeps_order_and_drops_stale_combat_saves() {
+        let path = temp_save_path("writer");
+        let writer = SaveWriter::default();
+        for layer in 0..10 {
+            writer.write(GameStateSave { current_map_layer: layer, ..sample_save() }, 0, path.clone(), None);
+        }
+        writer.flush();
+        assert_eq!(GameStateSave::load_from(&path).unwrap().current_map_layer, 9);
+        assert_eq!(GameStateSave::load_from(&GameStateSave::backup_path(&path, 1)).unwrap().current_map_layer, 8);
+
+        // 战斗了结前提交、了结后才轮到的战况存档不再写入
+        let stale = writer.combat_epoch.load(Ordering::SeqCst);
+        writer.end_combat();
+        writer.write(GameStateSave { current_map_layer: 20, ..sample_save() }, 0, path.clone(), Some(stale));
+        writer.write(GameStateSave { current_map_layer: 10, ..sample_save() }, 0, path.clone(), None);
+        writer.flush();
+        assert_eq!(GameStateSave::load_from(&path).unwrap().current_map_layer, 10);
+        assert_eq!(GameStateSave::load_from(&GameStateSave::backup_path(&path, 1)).unwrap().current_map_layer, 9);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_slot_summary_labels() {
+        let summary = sample_save().summary(1);
+        assert_eq!(summary.layer, 3);
+        let later = GameStateSave { act: 1, ..sample_save() };
+        assert_eq!(later.summary(1).layer, ActAtlas::builtin().act(0).layers + 3);
+        assert_eq!(summary.play_time_label(), "1时05分");
+        assert_eq!(summary.saved_at_label(), "2026-10-17 08:30");
+    }
+
+    #[test]
+    fn test_combat_snapshot_round_trip() {
+        use rand::SeedableRng;
+
+        let mut boss = Enemy::with_type(1, "大妖", 200, EnemyType::GreatDemon);
+        boss.choose_new_intent(&mut rand::rngs::StdRng::seed_from_u64(1));
+        boss.apply_status(POISON, 2);
+        let mut player = Player::default();
+        player.turn = 4;
+        player.sword_intent = 3;
+        let mut hand = Hand::new(10);
+        hand.seal_slot(2, 1);
+
+        let mut save = sample_save();
+        save.combat = Some(CombatSnapshot::capture(
+            &player, vec![boss.clone()], &DrawPile::new(Vec::new()), &DiscardPile::new(), &hand, Some(&Environment::thick_fog()),
+        ));
+        let restored = GameStateSave::from_json(&serde_json::to_string(&save).unwrap()).unwrap();
+        let combat = restored.combat.unwrap();
+        let enemy = &combat.enemies[0];
+        assert_eq!(enemy.intent, boss.intent);
+        assert_eq!(enemy.behavior.step, 1);
+        assert_eq!(enemy.statuses.stacks(POISON), 2);
+        assert_eq!(combat.hand.sealed_slots, hand.sealed_slots);
+        assert_eq!(combat.environment.name, "浓雾");
+
+        let mut resumed = Player::default();
+        combat.restore_player(&mut resumed);
+        assert_eq!((resumed.turn, resumed.sword_intent), (4, 3));
+    }
+
+    fn assert_round_trip<T: Serialize + serde::de::DeserializeOwned>(value: &T) {
+        let first = serde_json::to_string_pretty(value).unwrap();
+        let restored: T = serde_json::from_str(&first).unwrap();
+        assert_eq!(serde_json::to_string_pretty(&restored).unwrap(), first);
+    }
+
+    /// 存档 → 读档 → 再存档，JSON 须逐字一致
+    #[test]
+    fn test_persisted_resources_round_trip() {
+        use rand::SeedableRng;
+        use crate::resources::card_data::CardLibrary;
+        use crate::resources::relic_data::RelicLibrary;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(9);
+        let cultivation = Cultivation { realm: Realm::FoundationEstablishment, insight: 7 };
+        let mut player = Player::default();
+        player.max_hp += cultivation.get_hp_bonus();
+        player.gold = 250;
+        let deck = CardLibrary::builtin().starting_deck(Sect::Sword);
+        let mut relics: Vec<Relic> = RelicLibrary::builtin().all().iter().take(3).cloned().collect();
+        relics[0].counter = 2;
+        let map_nodes = crate::components::map::generate_map_nodes(&crate::components::map::MapConfig::default(), 0, &mut rng);
+
+        let run_state = PlayerRunState::capture(&player, &cultivation);
+        assert_round_trip(&run_state);
+        assert_round_trip(&cultivation);
+        assert_round_trip(&deck);
+        assert_round_trip(&relics);
+        assert_round_trip(&map_nodes);
+
+        let mut enemy = Enemy::with_type(1, "妖狼", 30, EnemyType::DemonicWolf);
+        enemy.choose_new_intent(&mut rng);
+        let snapshot = CombatSnapshot::capture(
+            &player, vec![enemy], &DrawPile::new(deck.clone()), &DiscardPile::new(), &Hand::new(10), None,
+        );
+        assert_round_trip(&snapshot);
+
+        let save = GameStateSave {
+            player: run_state,
+            cultivation: cultivation.clone(),
+            deck,
+            relics,
+            map_nodes,
+            current_map_node_id: Some(2),
+            combat: Some(snapshot),
+            seen_events: Vec::new(),
+            ..sample_save()
+        };
+        let json = save.to_json().unwrap();
+        let reloaded = GameStateSave::from_json(&json).unwrap();
+        assert_eq!(reloaded.to_json().unwrap(), json);
+        assert_eq!(reloaded.relics[0].counter, 2);
+        let restored = reloaded.restore_player();
+        assert_eq!((restored.hp, restored.max_hp, restored.gold), (player.hp, player.max_hp, 250));
+    }
+
+    #[test]
+    fn test_sect_survives_save_with_its_base_energy() {
+        let cultivation = Cultivation { realm: Realm::GoldenCore, insight: 0 };
+        let mut player = Player::for_sect(Sect::Talisman);
+        player.max_hp += cultivation.get_hp_bonus();
+        let restored = PlayerRunState::capture(&player, &cultivation).to_player(&cultivation);
+        assert_eq!(restored.sect, Sect::Talisman);
+        assert_eq!(restored.max_hp, player.max_hp);
+        assert_eq!(restored.max_energy, Sect::Talisman.base_energy() + cultivation.get_energy_bonus());
+    }
+
+    #[test]
+    fn test_reloaded_streams_continue_uninterrupted_run() {
+        use rand::Rng;
+        use crate::resources::RunSeed;
+
+        // 中途存档：各流消耗的随机数多寡不一
+        let mut run = RunSeed::new(2026);
+        let _: Vec<u32> = (0..37).map(|_| run.map.gen()).collect();
+        let _: Vec<u64> = (0..5).map(|_| run.combat.gen()).collect();
+        let _: f32 = run.rewards.gen();
+        let _ = run.events.gen_range(0..100);
+        let save = GameStateSave { seed: run.seed, streams: run.positions(), ..sample_save() };
+
+        let reloaded = GameStateSave::from_json(&save.to_json().unwrap()).unwrap();
+        let mut resumed = RunSeed::resume(reloaded.seed, reloaded.streams);
+        for _ in 0..10 {
+            assert_eq!(resumed.map.gen::<u64>(), run.map.gen::<u64>());
+            assert_eq!(resumed.combat.gen::<u32>(), run.combat.gen::<u32>());
+            assert_eq!(resumed.rewards.gen_range(10..26), run.rewards.gen_range(10..26));
+            assert_eq!(resumed.shop.gen::<f32>(), run.shop.gen::<f32>());
+            assert_eq!(resumed.events.gen::<u64>(), run.events.gen::<u64>());
+        }
     }
 }
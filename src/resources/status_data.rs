@@ -0,0 +1,271 @@
+//! 状态图谱（数据驱动的状态定义）
+//!
+//! 状态定义在 `assets/data/statuses.ron` 中，编译期内置并在首次使用时解析校验。
+//! 新增状态只需在数据文件中声明叠加规则、钩子与数值修正，无需改动战斗代码。
+
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde::Deserialize;
+
+use crate::components::status::StatusId;
+
+/// 内置状态数据（路径相对 assets 目录）
+const BUILTIN_STATUS_FILE: (&str, &str) = ("data/statuses.ron", include_str!("../../assets/data/statuses.ron"));
+
+// ============================================================================
+// 数据格式
+// ============================================================================
+
+/// 叠加规则
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum StackingRule {
+    /// 层数累加
+    #[default]
+    Stack,
+    /// 取较大者
+    Max,
+    /// 已存在时不再叠加
+    Unique,
+}
+
+/// 行动数值
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum StatusAmount {
+    /// 等于当前层数
+    Stacks,
+    /// 固定数值
+    Fixed(i32),
+}
+
+impl StatusAmount {
+    pub fn resolve(&self, stacks: i32) -> i32 {
+        match self {
+            StatusAmount::Stacks => stacks,
+            StatusAmount::Fixed(value) => *value,
+        }
+    }
+}
+
+/// 钩子触发时执行的行动
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum StatusAction {
+    /// 失去道行（无视护盾）
+    LoseHp(StatusAmount),
+    /// 恢复道行
+    Heal(StatusAmount),
+    /// 获得护盾
+    GainBlock(StatusAmount),
+    /// 对攻击者造成伤害（仅用于受击钩子）
+    DamageAttacker(StatusAmount),
+    /// 层数减少
+    Decay(i32),
+    /// 层数减半
+    Halve,
+    /// 移除状态
+    Remove,
+}
+
+/// 数值修正（按 加减 → 乘算 → 上限 的顺序结算）
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum ValueModifier {
+    /// 每层加减固定数值
+    PerStack(i32),
+    /// 乘以系数（与层数无关）
+    Multiply(f32),
+    /// 单次数值上限
+    Cap(i32),
+}
+
+/// 单个状态定义
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StatusDefinition {
+    /// 状态 id（全图谱唯一）
+    pub id: StatusId,
+    /// 显示名称
+    pub name: String,
+    /// 状态栏徽记（单字）
+    pub icon: String,
+    /// 说明文字，`{stacks}` 替换为当前层数
+    pub tooltip: String,
+    /// 显示颜色 (r, g, b)
+    pub color: (f32, f32, f32),
+    /// 是否为负面状态
+    #[serde(default)]
+    pub debuff: bool,
+    #[serde(default)]
+    pub stacking: StackingRule,
+    #[serde(default)]
+    pub on_turn_start: Vec<StatusAction>,
+    #[serde(default)]
+    pub on_turn_end: Vec<StatusAction>,
+    #[serde(default)]
+    pub on_card_played: Vec<StatusAction>,
+    #[serde(default)]
+    pub on_attacked: Vec<StatusAction>,
+    #[serde(default)]
+    pub outgoing_damage: Vec<ValueModifier>,
+    #[serde(default)]
+    pub incoming_damage: Vec<ValueModifier>,
+    #[serde(default)]
+    pub block_gained: Vec<ValueModifier>,
+}
+
+impl StatusDefinition {
+    /// 填入层数后的说明文字
+    pub fn tooltip_for(&self, stacks: i32) -> String {
+        self.tooltip.replace("{stacks}", &stacks.to_string())
+    }
+
+    fn validate(&self, file: &str) -> Result<(), StatusDataError> {
+        let err = |field: &str, message: &str| StatusDataError::invalid(file, Some(&self.id), field, message);
+
+        if self.name.trim().is_empty() {
+            return Err(err("name", "名称不能为空"));
+        }
+        if self.icon.chars().count() != 1 {
+            return Err(err("icon", "徽记须为单个字符"));
+        }
+        let hooks = [
+            ("on_turn_start", &self.on_turn_start),
+            ("on_turn_end", &self.on_turn_end),
+            ("on_card_played", &self.on_card_played),
+        ];
+        for (field, actions) in hooks {
+            if actions.iter().any(|a| matches!(a, StatusAction::DamageAttacker(_))) {
+                return Err(err(field, "DamageAttacker 仅可用于 on_attacked"));
+            }
+        }
+        let modifiers = [
+            ("outgoing_damage", &self.outgoing_damage),
+            ("incoming_damage", &self.incoming_damage),
+            ("block_gained", &self.block_gained),
+        ];
+        for (field, modifiers) in modifiers {
+            for modifier in modifiers {
+                match modifier {
+                    ValueModifier::Multiply(factor) if *factor < 0.0 => return Err(err(field, "乘算系数不能为负")),
+                    ValueModifier::Cap(cap) if *cap < 0 => return Err(err(field, "上限不能为负")),
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 状态数据文件
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StatusDataFile {
+    pub statuses: Vec<StatusDefinition>,
+}
+
+/// 状态数据错误（指明出错的文件与字段）
+#[derive(Debug)]
+pub enum StatusDataError {
+    Parse { file: String, message: String },
+    Invalid { file: String, status: Option<String>, field: String, message: String },
+}
+
+impl StatusDataError {
+    fn invalid(file: &str, status: Option<&StatusId>, field: &str, message: &str) -> Self {
+        Self::Invalid {
+            file: file.to_string(),
+            status: status.map(|s| s.to_string()),
+            field: field.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for StatusDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse { file, message } => write!(f, "{}: RON 解析失败：{}", file, message),
+            Self::Invalid { file, status: Some(id), field, message } => {
+                write!(f, "{}: 状态 `{}` 字段 `{}`：{}", file, id, field, message)
+            }
+            Self::Invalid { file, status: None, field, message } => {
+                write!(f, "{}: 字段 `{}`：{}", file, field, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StatusDataError {}
+
+// ============================================================================
+// 状态图谱
+// ============================================================================
+
+/// 状态图谱：按数据文件顺序保存的状态定义（钩子亦按此顺序触发）
+#[derive(Debug, Clone)]
+pub struct StatusRegistry {
+    definitions: Vec<StatusDefinition>,
+}
+
+impl StatusRegistry {
+    /// 编译期内置的状态图谱
+    pub fn builtin() -> &'static StatusRegistry {
+        static BUILTIN: OnceLock<StatusRegistry> = OnceLock::new();
+        BUILTIN.get_or_init(|| {
+            let (file, text) = BUILTIN_STATUS_FILE;
+            Self::parse(file, text.as_bytes()).unwrap_or_else(|e| panic!("内置状态数据无效：{}", e))
+        })
+    }
+
+    /// 解析并校验状态数据
+    pub fn parse(file: &str, bytes: &[u8]) -> Result<Self, StatusDataError> {
+        let data: StatusDataFile = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_bytes(bytes)
+            .map_err(|e| StatusDataError::Parse { file: file.to_string(), message: e.to_string() })?;
+
+        let mut ids = HashSet::new();
+        for definition in &data.statuses {
+            if !ids.insert(definition.id.clone()) {
+                return Err(StatusDataError::invalid(file, Some(&definition.id), "id", "状态 id 重复"));
+            }
+            definition.validate(file)?;
+        }
+        Ok(Self { definitions: data.statuses })
+    }
+
+    /// 按 id 查找状态定义
+    pub fn get(&self, id: &StatusId) -> Option<&StatusDefinition> {
+        self.definitions.iter().find(|d| &d.id == id)
+    }
+
+    /// 全部状态定义（数据文件顺序）
+    pub fn definitions(&self) -> &[StatusDefinition] {
+        &self.definitions
+    }
+
+    /// 状态显示名称（未登记的状态回退为 id）
+    pub fn name(&self, id: &StatusId) -> String {
+        self.get(id).map(|d| d.name.clone()).unwrap_or_else(|| id.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_registry_declares_core_statuses() {
+        let registry = StatusRegistry::builtin();
+        for id in ["poison", "burn", "weakness", "vulnerable", "strength", "dexterity", "thorns", "regeneration", "intangible", "frail"] {
+            assert!(registry.get(&StatusId::new(id)).is_some(), "缺少状态 {}", id);
+        }
+        let poison = registry.get(&StatusId::new("poison")).unwrap();
+        assert_eq!(poison.tooltip_for(3), "回合开始时失去3点道行（无视护盾），随后层数减一");
+    }
+
+    #[test]
+    fn test_invalid_status_names_field() {
+        let text = r#"(statuses: [(id: "spikes", name: "尖刺", icon: "刺", tooltip: "", color: (1.0, 1.0, 1.0), on_turn_end: [DamageAttacker(Stacks)])])"#;
+        let err = StatusRegistry::parse("data/test.ron", text.as_bytes()).unwrap_err().to_string();
+        assert!(err.contains("spikes") && err.contains("on_turn_end"), "{}", err);
+    }
+}
@@ -9,6 +9,8 @@ pub enum GameState {
     Booting,
     OpeningVideo,
     MainMenu,
+    /// 择宗：开局前选择宗门
+    SectSelect,
     /// 序章：交代背景
     Prologue,
     Map,
@@ -23,8 +25,12 @@ pub enum GameState {
     Tribulation,
     /// 机缘事件状态
     Event,
+    /// 宝箱状态
+    Treasure,
     /// 移除卡牌状态 (坊市服务)
     CardRemoval,
     /// 游戏结束状态
     GameOver,
+    /// 藏经阁（主菜单中浏览与解锁跨局内容）
+    Archive,
 }
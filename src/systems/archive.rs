@@ -0,0 +1,265 @@
+//! 藏经阁：浏览修行档案，以道韵解锁秘传功法、法宝与开局法宝
+
+use bevy::prelude::*;
+use crate::components::{PlaySfxEvent, SfxType};
+use crate::components::sect::Sect;
+use crate::resources::card_data::CardLibrary;
+use crate::resources::profile::MetaProfile;
+use crate::resources::relic_data::RelicLibrary;
+use crate::states::GameState;
+
+/// 藏经阁界面根节点
+#[derive(Component)]
+pub struct ArchiveUiRoot;
+
+/// 藏经阁中的操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ArchiveAction {
+    /// 参悟秘传功法
+    UnlockCard(u32),
+    /// 解锁法宝
+    UnlockRelic(String),
+    /// 换取开局法宝
+    UnlockStarter(String),
+    /// 择用开局法宝（`None` 为改回宗门本命法宝）
+    ChooseStarter(Option<String>),
+    /// 返回主菜单
+    Back,
+}
+
+/// 藏经阁按钮
+#[derive(Component)]
+struct ArchiveButton(ArchiveAction);
+
+/// 一个条目：名称、说明与可用操作（按钮文字与动作，已得者无操作）
+type ArchiveEntry = (String, String, Option<(String, ArchiveAction)>);
+
+/// 藏经阁插件
+pub struct ArchivePlugin;
+
+impl Plugin for ArchivePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Archive), setup_archive_ui)
+            .add_systems(Update, handle_archive_clicks.run_if(in_state(GameState::Archive)))
+            .add_systems(OnExit(GameState::Archive), cleanup_archive_ui);
+    }
+}
+
+fn setup_archive_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    profile: Res<MetaProfile>,
+    cards: Res<CardLibrary>,
+    relics: Res<RelicLibrary>,
+) {
+    spawn_archive_ui(&mut commands, asset_server.load("fonts/Arial Unicode.ttf"), &profile, &cards, &relics);
+}
+
+/// 档案统计
+fn stats_label(profile: &MetaProfile, cards: &CardLibrary, relics: &RelicLibrary) -> String {
+    let slain = if profile.enemies_slain.is_empty() {
+        "尚无".to_string()
+    } else {
+        profile.enemies_slain.iter().map(|(kind, count)| format!("{} ×{}", kind.name(), count)).collect::<Vec<_>>().join(" · ")
+    };
+    format!(
+        "修行 {} 次 · 飞升 {} 次 · 最高境界 {}\n见识功法 {}/{} · 法宝 {}/{}\n斩妖 {}：{}",
+        profile.total_runs, profile.wins, profile.highest_realm.name(),
+        profile.cards_discovered.len(), cards.len(), profile.relics_discovered.len(), relics.len(),
+        profile.total_slain(), slain,
+    )
+}
+
+/// 生成藏经阁界面（解锁后整体重建）
+fn spawn_archive_ui(commands: &mut Commands, font: Handle<Font>, profile: &MetaProfile, cards: &CardLibrary, relics: &RelicLibrary) {
+    let text_font = |size: f32| TextFont { font: font.clone(), font_size: size, ..default() };
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(18.0),
+                padding: UiRect::all(Val::Px(30.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.08, 0.06, 0.04)), // 古旧书阁的昏黄
+            ArchiveUiRoot,
+        ))
+        .with_children(|parent| {
+            parent.spawn((Text::new("藏 经 阁"), text_font(52.0), TextColor(Color::srgb(1.0, 0.85, 0.4))));
+            parent.spawn((
+                Text::new(stats_label(profile, cards, relics)),
+                text_font(18.0),
+                TextColor(Color::srgb(0.8, 0.8, 0.75)),
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+            parent.spawn((Text::new(format!("道韵：{}", profile.essence)), text_font(24.0), TextColor(Color::srgb(0.6, 0.9, 0.7))));
+
+            parent.spawn(Node {
+                column_gap: Val::Px(24.0),
+                align_items: AlignItems::Start,
+                ..default()
+            }).with_children(|columns| {
+                // 每列：标题 + 若干条目（名称、说明、操作）
+                let spawn_column = |columns: &mut ChildBuilder, title: &str, done: &str, entries: Vec<ArchiveEntry>| {
+                    columns.spawn((
+                        Node {
+                            width: Val::Px(380.0),
+                            flex_direction: FlexDirection::Column,
+                            row_gap: Val::Px(10.0),
+                            padding: UiRect::all(Val::Px(14.0)),
+                            border: UiRect::all(Val::Px(1.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.4)),
+                        BorderColor(Color::srgba(1.0, 0.85, 0.4, 0.3)),
+                    )).with_children(|column| {
+                        column.spawn((Text::new(title), text_font(26.0), TextColor(Color::srgb(1.0, 0.85, 0.4))));
+                        for (name, detail, action) in entries {
+                            column.spawn(Node {
+                                justify_content: JustifyContent::SpaceBetween,
+                                align_items: AlignItems::Center,
+                                column_gap: Val::Px(10.0),
+                                ..default()
+                            }).with_children(|row| {
+                                row.spawn(Node { flex_direction: FlexDirection::Column, flex_shrink: 1.0, ..default() }).with_children(|info| {
+                                    info.spawn((Text::new(name), text_font(18.0), TextColor(Color::WHITE)));
+                                    info.spawn((Text::new(detail), text_font(13.0), TextColor(Color::srgb(0.65, 0.65, 0.6))));
+                                });
+                                match action {
+                                    Some((label, action)) => {
+                                        row.spawn((
+                                            Node {
+                                                min_width: Val::Px(96.0), height: Val::Px(34.0),
+                                                justify_content: JustifyContent::Center, align_items: AlignItems::Center,
+                                                border: UiRect::all(Val::Px(1.0)), ..default()
+                                            },
+                                            BackgroundColor(Color::srgba(0.3, 0.22, 0.1, 0.9)),
+                                            BorderColor(Color::srgba(1.0, 0.85, 0.4, 0.5)),
+                                            Button,
+                                            ArchiveButton(action),
+                                        )).with_children(|btn| {
+                                            btn.spawn((Text::new(label), text_font(16.0), TextColor(Color::WHITE)));
+                                        });
+                                    }
+                                    None => {
+                                        row.spawn((Text::new(done), text_font(16.0), TextColor(Color::srgb(0.5, 0.8, 0.5))));
+                                    }
+                                }
+                            });
+                        }
+                    });
+                };
+
+                // 秘传功法
+                let card_entries = cards.unlockable().map(|d| {
+                    let card = d.instantiate(d.id);
+                    let action = (!profile.unlocked_cards.contains(&d.id))
+                        .then(|| (format!("参悟 {}", d.unlock_cost.unwrap_or_default()), ArchiveAction::UnlockCard(d.id)));
+                    (format!("{}（{} 灵力）", card.name, card.cost), card.description, action)
+                }).collect();
+                spawn_column(columns, "秘传功法", "已参悟", card_entries);
+
+                // 法宝
+                let relic_entries = relics.unlockable().map(|r| {
+                    let action = (!profile.unlocked_relics.contains(r.id.as_str()))
+                        .then(|| (format!("解锁 {}", r.unlock_cost.unwrap_or_default()), ArchiveAction::UnlockRelic(r.id.to_string())));
+                    (r.name.clone(), r.description.clone(), action)
+                }).collect();
+                spawn_column(columns, "法宝", "已解锁", relic_entries);
+
+                // 开局法宝：首项为各宗本命法宝，已择用者不再给出操作
+                let chosen = profile.starting_relic.clone().filter(|id| profile.starter_available(id));
+                let sect_relics = Sect::ALL
+                    .iter()
+                    .filter_map(|sect| relics.get(sect.starting_relic()).map(|r| format!("{}·{}", sect.name(), r.name)))
+                    .collect::<Vec<_>>()
+                    .join(" / ");
+                let innate = (
+                    "宗门本命法宝".to_string(),
+                    sect_relics,
+                    chosen.is_some().then(|| ("择用".to_string(), ArchiveAction::ChooseStarter(None))),
+                );
+                let starter_entries = std::iter::once(innate).chain(relics.starter_choices().map(|r| {
+                    let id = r.id.to_string();
+                    let action = if chosen.as_deref() == Some(id.as_str()) {
+                        None
+                    } else if profile.starter_available(&id) {
+                        Some(("择用".to_string(), ArchiveAction::ChooseStarter(Some(id))))
+                    } else {
+                        Some((format!("换取 {}", r.starter_cost.unwrap_or_default()), ArchiveAction::UnlockStarter(id)))
+                    };
+                    (r.name.clone(), r.description.clone(), action)
+                })).collect();
+                spawn_column(columns, "开局法宝", "择用中", starter_entries);
+            });
+
+            // 返回
+            parent.spawn((
+                Node {
+                    width: Val::Px(200.0), height: Val::Px(56.0),
+                    justify_content: JustifyContent::Center, align_items: AlignItems::Center,
+                    border: UiRect::all(Val::Px(2.0)), ..default()
+                },
+                BackgroundColor(Color::srgba(0.1, 0.1, 0.15, 0.9)),
+                BorderColor(Color::srgb(0.3, 0.3, 0.5)),
+                Button,
+                ArchiveButton(ArchiveAction::Back),
+            )).with_children(|btn| {
+                btn.spawn((Text::new("返 回"), text_font(26.0), TextColor(Color::srgb(0.8, 0.8, 0.8))));
+            });
+        });
+}
+
+/// 处理藏经阁按钮：解锁成功后保存档案并重建界面
+fn handle_archive_clicks(
+    mut commands: Commands,
+    button_query: Query<(&Interaction, &ArchiveButton), Changed<Interaction>>,
+    root_query: Query<Entity, With<ArchiveUiRoot>>,
+    mut profile: ResMut<MetaProfile>,
+    cards: Res<CardLibrary>,
+    relics: Res<RelicLibrary>,
+    asset_server: Res<AssetServer>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut sfx_events: EventWriter<PlaySfxEvent>,
+) {
+    for (interaction, button) in button_query.iter() {
+        if !matches!(interaction, Interaction::Pressed) { continue; }
+        sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
+
+        let changed = match &button.0 {
+            ArchiveAction::Back => {
+                next_state.set(GameState::MainMenu);
+                return;
+            }
+            ArchiveAction::UnlockCard(id) => cards.definition(*id).is_some_and(|d| profile.unlock_card(d)),
+            ArchiveAction::UnlockRelic(id) => relics.get(id).is_some_and(|r| profile.unlock_relic(&r)),
+            ArchiveAction::UnlockStarter(id) => relics.get(id).is_some_and(|r| profile.unlock_starter(&r)),
+            ArchiveAction::ChooseStarter(id) => {
+                profile.starting_relic = id.clone();
+                true
+            }
+        };
+        if !changed {
+            info!("【藏经阁】道韵不足（结余 {}）", profile.essence);
+            return;
+        }
+
+        info!("【藏经阁】{:?}，结余道韵 {}", button.0, profile.essence);
+        profile.save();
+        for entity in root_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+        spawn_archive_ui(&mut commands, asset_server.load("fonts/Arial Unicode.ttf"), &profile, &cards, &relics);
+        return;
+    }
+}
+
+fn cleanup_archive_ui(mut commands: Commands, query: Query<Entity, With<ArchiveUiRoot>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
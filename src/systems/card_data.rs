@@ -0,0 +1,78 @@
+//! 功法图谱加载系统
+//!
+//! 启动时加载 `assets/data/cards` 下的全部功法数据文件，文件新增/修改后重新汇总图谱（调试构建下支持热重载）。
+
+use bevy::asset::LoadedFolder;
+use bevy::prelude::*;
+
+use crate::resources::card_data::{CardDataFile, CardDataLoader, CardLibrary};
+
+/// 功法数据目录（相对 assets）
+const CARD_DATA_DIR: &str = "data/cards";
+
+/// 功法图谱插件
+pub struct CardDataPlugin;
+
+impl Plugin for CardDataPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<CardDataFile>()
+            .init_asset_loader::<CardDataLoader>()
+            // 先以内置图谱兜底，资源加载完成后替换
+            .init_resource::<CardLibrary>()
+            .add_systems(Startup, load_card_data)
+            .add_systems(Update, rebuild_card_library);
+    }
+}
+
+/// 功法数据目录句柄（持有以保证资源常驻并可热重载）
+#[derive(Resource)]
+struct CardDataFolder(Handle<LoadedFolder>);
+
+fn load_card_data(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(CardDataFolder(asset_server.load_folder(CARD_DATA_DIR)));
+}
+
+/// 数据文件加载或变更后重新汇总功法图谱
+///
+/// 校验失败的文件不会进入资源库（错误由加载器报告文件与字段），图谱保持上一次的有效版本。
+fn rebuild_card_library(
+    mut file_events: EventReader<AssetEvent<CardDataFile>>,
+    mut folder_events: EventReader<AssetEvent<LoadedFolder>>,
+    folder: Option<Res<CardDataFolder>>,
+    folders: Res<Assets<LoadedFolder>>,
+    files: Res<Assets<CardDataFile>>,
+    asset_server: Res<AssetServer>,
+    mut library: ResMut<CardLibrary>,
+) {
+    let files_changed = file_events.read().count() > 0;
+    let folder_changed = folder_events.read().count() > 0;
+    if !files_changed && !folder_changed {
+        return;
+    }
+    let Some(folder) = folder else { return; };
+    let Some(loaded) = folders.get(&folder.0) else { return; };
+
+    let mut sources: Vec<(String, &CardDataFile)> = loaded
+        .handles
+        .iter()
+        .filter_map(|handle| {
+            let handle = handle.clone().try_typed::<CardDataFile>().ok()?;
+            let data = files.get(&handle)?;
+            let path = asset_server.get_path(handle.id())?.to_string();
+            Some((path, data))
+        })
+        .collect();
+    if sources.is_empty() {
+        return;
+    }
+    // 按路径排序，保证初始牌组的拼接顺序稳定
+    sources.sort_by(|a, b| a.0.cmp(&b.0));
+
+    match CardLibrary::from_files(sources.iter().map(|(path, data)| (path.as_str(), *data))) {
+        Ok(rebuilt) => {
+            info!("【功法图谱】已载入 {} 个数据文件，共 {} 种功法", sources.len(), rebuilt.len());
+            *library = rebuilt;
+        }
+        Err(e) => error!("【功法图谱】数据无效，沿用上一版图谱：{}", e),
+    }
+}
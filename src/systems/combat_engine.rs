@@ -0,0 +1,1328 @@
+//! 无头战斗规则引擎
+//!
+//! 不依赖窗口、渲染与 ECS 查询，纯数据推演一场战斗（出牌、结束回合、敌人行动）。
+//! 每个操作返回一组 [`CombatEvent`]，由 Bevy 表现层映射为动画、粒子与音效；
+//! 单元测试与数值平衡工具可直接批量模拟对局。
+
+use rand_chacha::ChaCha12Rng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use crate::components::cards::{
+    Card, CardEffect, CardRarity, CardType, DiscardPile, DrawPile, EffectCondition, Hand, ScaleSource,
+    HEAVENLY_STRIKE_DAMAGE,
+};
+use crate::components::combat::{Enemy, EnemyIntent, Environment, Player, TurnPhase};
+use crate::components::cultivation::{Realm, RealmPassive};
+use crate::components::relic::{CounterEvent, Relic, RelicCondition, RelicEffect, RelicId};
+use crate::components::sect::Sect;
+use crate::components::status::{StatusId, StatusTrigger, TriggeredEffect, POISON, WEAKNESS};
+
+/// 每回合抽牌数
+pub const CARDS_PER_TURN: usize = 5;
+/// 手牌上限
+pub const HAND_SIZE: usize = 10;
+
+// ============================================================================
+// 领域事件
+// ============================================================================
+
+/// 战斗领域事件（规则层产出，表现层消费）
+#[derive(Debug, Clone)]
+pub enum CombatEvent {
+    /// 打出卡牌
+    CardPlayed { card_id: u32, card_type: CardType },
+    /// 剑意积累
+    SwordIntentGained { stacks: i32 },
+    /// 剑意满层后再出攻击牌，人剑合一爆发
+    SwordIntentBurst,
+    /// 动用非攻伐功法，剑意溃散
+    SwordIntentLost,
+    /// 宗门心法（丹火、符印）积累
+    SectChargeGained { stacks: i32 },
+    /// 宗门心法释放（丹火化盾、符印引爆），`stacks` 为释放的层数
+    SectChargeReleased { stacks: i32 },
+    /// 敌人受到攻击（damage 为结算易伤前的攻击力度）
+    EnemyDamaged { enemy_id: u32, damage: i32, killed: bool },
+    /// 对敌人施加状态
+    StatusApplied { enemy_id: u32, status: StatusId, count: i32 },
+    /// 玩家获得状态
+    StatusGained { status: StatusId, count: i32 },
+    /// 玩家获得护甲
+    BlockGained { amount: i32 },
+    /// 玩家回复生命
+    Healed { amount: i32 },
+    /// 玩家获得灵力
+    EnergyGained { amount: i32 },
+    /// 抽牌（含归墟洗回剑冢）
+    CardsDrawn { count: usize },
+    /// 天象改变
+    EnvironmentChanged { environment: Environment },
+    /// 引动天雷演出（伤害由演出结束时结算）
+    HeavenlyStrikeStarted { damage: i32, environment_name: String },
+    /// 玩家结束回合
+    TurnEnded { discarded: usize },
+    /// 敌人道行跌破阈值，进入新阶段（`phase` 为妖物定义中的阶段序号）
+    EnemyPhaseChanged { enemy_id: u32, phase: usize },
+    /// 敌人执行意图（charged 表示该次行动处于蓄势状态）
+    EnemyActed { enemy_id: u32, intent: EnemyIntent, charged: bool },
+    /// 玩家受到敌人攻击
+    PlayerDamaged { enemy_id: u32, damage: i32 },
+    /// 心魔诅咒注入归墟
+    CurseAdded { card_id: u32 },
+    /// 气穴被封印
+    SlotSealed { duration: u32 },
+    /// 状态钩子触发（中毒发作、回春、荆棘反伤……）
+    ///
+    /// `enemy_id` 为状态持有者，None 表示玩家；`killed` 表示持有者因此陨落。
+    StatusTriggered { enemy_id: Option<u32>, status: StatusId, effect: TriggeredEffect, killed: bool },
+    /// 遗物触发（每次生效一条，供界面闪烁对应遗物）
+    RelicTriggered { relic_id: RelicId, effect: RelicEffect },
+    /// 境界被动生效
+    RealmPassiveTriggered { passive: RealmPassive },
+    /// 施展境界神通
+    DivineArtCast { realm: Realm },
+    /// 玩家回合开始
+    PlayerTurnStarted { turn: u32 },
+    /// 战斗胜利
+    Victory,
+    /// 战斗失败
+    Defeat,
+}
+
+// ============================================================================
+// 规则引擎
+// ============================================================================
+
+/// 一场战斗的完整规则状态
+#[derive(Debug, Clone)]
+pub struct CombatEngine {
+    pub player: Player,
+    /// 参战敌人（顺序即行动与默认目标顺序）
+    pub enemies: Vec<Enemy>,
+    pub draw_pile: DrawPile,
+    pub discard_pile: DiscardPile,
+    pub hand: Hand,
+    pub environment: Environment,
+    pub phase: TurnPhase,
+    /// 携带的遗物（按获得顺序触发）
+    pub relics: Vec<Relic>,
+    /// 战斗随机流（洗牌与敌人意图），由 `RunSeed::combat` 提供即可复现
+    pub rng: ChaCha12Rng,
+}
+
+impl CombatEngine {
+    /// 以牌组开一场新战斗（尚未抽牌，需调用 `start_combat`）
+    pub fn new(player: Player, enemies: Vec<Enemy>, deck: Vec<Card>) -> Self {
+        Self {
+            player,
+            enemies,
+            draw_pile: DrawPile::new(deck),
+            discard_pile: DiscardPile::new(),
+            hand: Hand::new(HAND_SIZE),
+            environment: Environment::default(),
+            phase: TurnPhase::PlayerStart,
+            relics: Vec::new(),
+            rng: ChaCha12Rng::from_entropy(),
+        }
+    }
+
+    /// 指定战斗随机流（用于复现对局）
+    pub fn with_rng(mut self, rng: ChaCha12Rng) -> Self {
+        self.rng = rng;
+        self
+    }
+
+    /// 携带遗物参战
+    pub fn with_relics(mut self, relics: Vec<Relic>) -> Self {
+        self.relics = relics;
+        self
+    }
+
+    /// 由现有战斗数据组装引擎（供 ECS 快照使用）
+    pub fn from_parts(
+        player: Player,
+        enemies: Vec<Enemy>,
+        draw_pile: DrawPile,
+        discard_pile: DiscardPile,
+        hand: Hand,
+        environment: Environment,
+        phase: TurnPhase,
+        rng: ChaCha12Rng,
+    ) -> Self {
+        Self { player, enemies, draw_pile, discard_pile, hand, environment, phase, relics: Vec::new(), rng }
+    }
+
+    /// 战斗开始：洗牌并抽取首回合手牌，随后触发战斗开始类遗物
+    pub fn start_combat(&mut self) -> Vec<CombatEvent> {
+        self.draw_pile.cards.shuffle(&mut self.rng);
+        let drawn = self.draw_cards(CARDS_PER_TURN);
+        self.phase = TurnPhase::PlayerAction;
+        let mut events = vec![CombatEvent::CardsDrawn { count: drawn }];
+        events.extend(self.trigger_combat_start_relics());
+        events
+    }
+
+    /// 首回合抽牌后触发的遗物与境界被动：额外抽牌、战斗开始效果（伤害、护甲、抽牌、状态）与开战计数
+    pub fn trigger_combat_start_relics(&mut self) -> Vec<CombatEvent> {
+        let mut events = Vec::new();
+        self.trigger_draw_passive(&mut events);
+        if self.has_passive(RealmPassive::SolidFoundation) {
+            events.push(CombatEvent::RealmPassiveTriggered { passive: RealmPassive::SolidFoundation });
+            self.gain_block(RealmPassive::FOUNDATION_BLOCK, &mut events);
+        }
+        self.trigger_draw_relics(&mut events);
+        for (relic_id, effect) in self.relic_effects(None) {
+            if matches!(effect, RelicEffect::OnCombatStart { .. } | RelicEffect::StatusAtCombatStart { .. }) {
+                self.fire_relic_effect(relic_id, effect, &mut events);
+            }
+        }
+        self.advance_relic_counters(CounterEvent::CombatStarted, &mut events);
+        if self.is_victory() {
+            events.push(CombatEvent::Victory);
+        }
+        events
+    }
+
+    /// 是否所有敌人都已倒下
+    pub fn is_victory(&self) -> bool {
+        self.enemies.iter().all(|e| e.hp <= 0)
+    }
+
+    /// 玩家是否已陨落
+    pub fn is_defeat(&self) -> bool {
+        self.player.hp <= 0
+    }
+
+    /// 打出一张手牌
+    ///
+    /// `target` 为玩家指定的敌人 id，仅对单体类功法生效；目标缺失或已陨落时回退到首个存活敌人。
+    pub fn play_card(&mut self, card_id: u32, target: Option<u32>) -> Result<Vec<CombatEvent>, String> {
+        if self.phase == TurnPhase::EnemyTurn {
+            return Err("敌人回合中，无法出牌".to_string());
+        }
+        let index = self.hand.cards.iter().position(|c| c.id == card_id)
+            .ok_or_else(|| format!("手牌中不存在卡牌 {}", card_id))?;
+        // X 费功法耗尽当前全部灵力，否则按标注费用扣除
+        let cost = if self.hand.cards[index].x_cost { self.player.energy } else { self.hand.cards[index].cost };
+        if self.player.energy < cost {
+            return Err(format!("能量不足！需要: {}, 当前: {}", cost, self.player.energy));
+        }
+
+        // 先移出手牌，避免抽牌类效果被手牌上限卡住
+        let card = self.hand.remove_card(index).expect("索引已校验");
+        let card_type = card.card_type;
+        self.player.energy -= cost;
+
+        let mut events = vec![CombatEvent::CardPlayed { card_id: card.id, card_type: card.card_type }];
+        self.update_sect_mechanic(&card, &mut events);
+        self.apply_effects(&card.effects, target, cost, &mut events);
+        // 元婴分身重演本回合首张功法（天象功法只引动一次）
+        let echoes = self.player.turn_cards_played == 0
+            && card_type != CardType::Curse
+            && !card.effects.iter().any(|e| matches!(e, CardEffect::ChangeEnvironment { .. }));
+        if echoes && self.has_passive(RealmPassive::SpiritClone) && !self.is_victory() {
+            events.push(CombatEvent::RealmPassiveTriggered { passive: RealmPassive::SpiritClone });
+            self.apply_effects(&card.effects, target, cost, &mut events);
+        }
+        self.player.turn_cards_played += 1;
+        // 符印随这张攻伐功法一并引爆
+        if self.player.sect == Sect::Talisman && card_type == CardType::Attack && self.player.sect_charge > 0 {
+            events.push(CombatEvent::SectChargeReleased { stacks: self.player.sect_charge });
+            self.player.sect_charge = 0;
+        }
+        self.discard_pile.add_card(card);
+
+        let triggers = self.player.card_played_statuses();
+        self.push_status_events(None, triggers, &mut events);
+
+        // 每打出第 N 张功法触发的遗物
+        self.player.cards_played += 1;
+        self.advance_relic_counters(CounterEvent::CardPlayed, &mut events);
+        if card_type == CardType::Attack {
+            self.advance_relic_counters(CounterEvent::AttackPlayed, &mut events);
+        }
+        for (relic_id, effect) in self.relic_effects(None) {
+            let RelicEffect::OnCardPlayed { every_nth, draw_cards } = effect else { continue; };
+            if every_nth > 0 && self.player.cards_played % every_nth == 0 {
+                events.push(CombatEvent::RelicTriggered { relic_id, effect });
+                let count = self.draw_cards(draw_cards.max(0) as usize);
+                events.push(CombatEvent::CardsDrawn { count });
+            }
+        }
+
+        if self.is_victory() {
+            events.push(CombatEvent::Victory);
+        } else if self.is_defeat() {
+            events.push(CombatEvent::Defeat);
+        }
+        Ok(events)
+    }
+
+    /// 施展当前境界的神通（每场战斗一次，不耗灵力）
+    ///
+    /// `target` 与单体功法相同：缺失或已陨落时回退到首个存活敌人。
+    pub fn use_divine_art(&mut self, target: Option<u32>) -> Result<Vec<CombatEvent>, String> {
+        if self.phase == TurnPhase::EnemyTurn {
+            return Err("敌人回合中，无法施展神通".to_string());
+        }
+        if self.player.divine_art_used {
+            return Err("本场战斗已施展过神通".to_string());
+        }
+        let realm = self.player.realm;
+        let art = realm.divine_art().ok_or_else(|| format!("{}尚无神通", realm.name()))?;
+        self.player.divine_art_used = true;
+
+        let mut events = vec![CombatEvent::DivineArtCast { realm }];
+        self.apply_effects(&art.effects, target, 0, &mut events);
+        if self.is_victory() {
+            events.push(CombatEvent::Victory);
+        } else if self.is_defeat() {
+            events.push(CombatEvent::Defeat);
+        }
+        Ok(events)
+    }
+
+    /// 结束玩家回合：剑意归零，丹火化盾，回合结束类状态结算，手牌进入归墟（留牌类遗物与金丹留转可保留最左侧若干张）
+    pub fn end_turn(&mut self) -> Vec<CombatEvent> {
+        let mut events = Vec::new();
+        self.player.reset_sword_intent();
+        if self.player.sect == Sect::Alchemy && self.player.sect_charge > 0 {
+            let stacks = self.player.sect_charge;
+            self.player.sect_charge = stacks / 2;
+            events.push(CombatEvent::SectChargeReleased { stacks });
+            self.gain_block(2 * stacks, &mut events);
+        }
+        let triggers = self.player.decay_statuses();
+        self.push_status_events(None, triggers, &mut events);
+
+        let keeper = self
+            .relic_effects(None)
+            .into_iter()
+            .filter(|(_, effect)| matches!(effect, RelicEffect::OnTurnEnd { keep_cards } if *keep_cards > 0))
+            .max_by_key(|(_, effect)| match effect {
+                RelicEffect::OnTurnEnd { keep_cards } => *keep_cards,
+                _ => 0,
+            });
+        let mut keep = match &keeper {
+            Some((_, RelicEffect::OnTurnEnd { keep_cards })) => *keep_cards as usize,
+            _ => 0,
+        };
+        if let Some((relic_id, effect)) = keeper {
+            if !self.hand.is_empty() {
+                events.push(CombatEvent::RelicTriggered { relic_id, effect });
+            }
+        }
+        if self.has_passive(RealmPassive::GoldenRetention) && self.hand.cards.len() > keep {
+            keep += 1;
+            events.push(CombatEvent::RealmPassiveTriggered { passive: RealmPassive::GoldenRetention });
+        }
+
+        let mut discarded = 0;
+        while let Some(card) = self.hand.remove_card(keep) {
+            self.discard_pile.add_card(card);
+            discarded += 1;
+        }
+        self.phase = TurnPhase::EnemyTurn;
+        events.push(CombatEvent::TurnEnded { discarded });
+        events
+    }
+
+    /// 结算单个敌人的行动（表现层逐个播放时使用）
+    pub fn resolve_enemy_action(&mut self, enemy_id: u32) -> Vec<CombatEvent> {
+        let mut events = Vec::new();
+        let attacker = self.enemies.iter().position(|e| e.id == enemy_id);
+        let guards: Vec<(RelicId, RelicEffect)> = self
+            .relic_effects(attacker)
+            .into_iter()
+            .filter(|(_, effect)| matches!(effect, RelicEffect::OnTakeDamage { reduction } if *reduction > 0))
+            .collect();
+        let tempered = self.has_passive(RealmPassive::TemperedBody);
+        let Some(enemy) = self.enemies.iter_mut().find(|e| e.id == enemy_id && e.hp > 0) else {
+            return events;
+        };
+
+        // 行动前毒火发作，伏诛则不再行动
+        let triggers = enemy.tick_statuses();
+        let killed = enemy.hp <= 0;
+        events.extend(triggers.into_iter().map(|t| CombatEvent::StatusTriggered {
+            enemy_id: Some(enemy_id), status: t.status, effect: t.effect, killed,
+        }));
+        if killed {
+            if self.is_victory() {
+                events.push(CombatEvent::Victory);
+            }
+            return events;
+        }
+
+        if let Some(phase) = enemy.start_turn(&mut self.rng) {
+            events.push(CombatEvent::EnemyPhaseChanged { enemy_id, phase });
+        }
+        let charged = enemy.is_charged;
+        let intent = enemy.execute_intent();
+        events.push(CombatEvent::EnemyActed { enemy_id, intent, charged });
+
+        match intent {
+            EnemyIntent::Attack { damage } => {
+                let mut final_damage = enemy.calculate_outgoing_damage_with_env(damage, Some(&self.environment));
+                enemy.consume_charge();
+                // 减伤类遗物
+                for (relic_id, effect) in guards {
+                    let RelicEffect::OnTakeDamage { reduction } = effect else { continue; };
+                    if final_damage > 0 {
+                        final_damage = (final_damage - reduction).max(0);
+                        events.push(CombatEvent::RelicTriggered { relic_id, effect });
+                    }
+                }
+                if tempered && final_damage > 0 {
+                    final_damage = (final_damage - RealmPassive::TEMPERED_REDUCTION).max(0);
+                    events.push(CombatEvent::RealmPassiveTriggered { passive: RealmPassive::TemperedBody });
+                }
+                self.player.take_damage_with_env(final_damage, Some(&self.environment));
+                // 攻击附带的词缀效果 (灼烧/中毒/虚弱)
+                enemy.apply_attack_affixes(&mut self.player);
+                events.push(CombatEvent::PlayerDamaged { enemy_id, damage: final_damage });
+
+                // 修行者的受击状态（如荆棘）反伤攻击者
+                for trigger in self.player.attacked_statuses() {
+                    if let TriggeredEffect::DamageAttacker(amount) = trigger.effect {
+                        enemy.take_damage(amount);
+                        events.push(CombatEvent::EnemyDamaged { enemy_id, damage: amount, killed: enemy.hp <= 0 });
+                    }
+                    events.push(CombatEvent::StatusTriggered {
+                        enemy_id: None, status: trigger.status, effect: trigger.effect, killed: self.player.hp <= 0,
+                    });
+                }
+                if self.player.hp <= 0 {
+                    events.push(CombatEvent::Defeat);
+                }
+            }
+            EnemyIntent::Curse { card_id } => {
+                let curse_card = Card::new(
+                    card_id, "心魔干扰", CardType::Curse, 0,
+                    vec![CardEffect::CurseDamage { amount: 2 }],
+                    CardRarity::Special, "textures/cards/special.png"
+                );
+                self.discard_pile.add_card(curse_card);
+                events.push(CombatEvent::CurseAdded { card_id });
+            }
+            EnemyIntent::Seal { slot_index, duration } => {
+                self.hand.seal_slot(slot_index, duration);
+                events.push(CombatEvent::SlotSealed { duration });
+            }
+            EnemyIntent::Debuff { poison, weakness } => {
+                self.player.apply_status(POISON, poison);
+                self.player.apply_status(WEAKNESS, weakness);
+            }
+            _ => {}
+        }
+
+        if enemy.hp > 0 {
+            let triggers = enemy.decay_statuses();
+            let killed = enemy.hp <= 0;
+            events.extend(triggers.into_iter().map(|t| CombatEvent::StatusTriggered {
+                enemy_id: Some(enemy_id), status: t.status, effect: t.effect, killed,
+            }));
+        }
+        if self.is_victory() {
+            events.push(CombatEvent::Victory);
+        }
+        events
+    }
+
+    /// 开启玩家新回合：回复灵力、更新封印并抽牌
+    pub fn start_player_turn(&mut self) -> Vec<CombatEvent> {
+        let mut events = Vec::new();
+        self.player.start_turn();
+
+        // 毒火先于抽牌发作，道行耗尽即告败北
+        let triggers = self.player.tick_statuses();
+        self.push_status_events(None, triggers, &mut events);
+        if self.is_defeat() {
+            events.push(CombatEvent::Defeat);
+            return events;
+        }
+
+        self.hand.update_seals();
+        let drawn = self.draw_cards(CARDS_PER_TURN);
+        self.phase = TurnPhase::PlayerAction;
+        events.push(CombatEvent::PlayerTurnStarted { turn: self.player.turn });
+        events.push(CombatEvent::CardsDrawn { count: drawn });
+
+        self.trigger_draw_passive(&mut events);
+        self.trigger_draw_relics(&mut events);
+        for (relic_id, effect) in self.relic_effects(None) {
+            if matches!(effect, RelicEffect::OnTurnStart { .. }) {
+                self.fire_relic_effect(relic_id, effect, &mut events);
+            }
+        }
+        self.advance_relic_counters(CounterEvent::TurnStarted, &mut events);
+        events
+    }
+
+    /// 结算完整敌人回合：存活敌人依次行动，随后开启玩家新回合
+    pub fn resolve_enemy_turn(&mut self) -> Vec<CombatEvent> {
+        let mut events = Vec::new();
+        let enemy_ids: Vec<u32> = self.enemies.iter().filter(|e| e.hp > 0).map(|e| e.id).collect();
+        for enemy_id in enemy_ids {
+            events.extend(self.resolve_enemy_action(enemy_id));
+            if self.is_defeat() || self.is_victory() {
+                return events;
+            }
+        }
+        events.extend(self.start_player_turn());
+        events
+    }
+
+    /// 抽牌，剑冢耗尽时将归墟洗回
+    pub fn draw_cards(&mut self, amount: usize) -> usize {
+        let mut drawn = 0;
+        for _ in 0..amount {
+            if self.draw_pile.cards.is_empty() {
+                let cards = self.discard_pile.clear();
+                if cards.is_empty() { break; }
+                self.draw_pile.shuffle_from_discard(cards, &mut self.rng);
+            }
+            let Some(card) = self.draw_pile.draw_card() else { break; };
+            if !self.hand.add_card(card.clone()) {
+                // 手牌已满，放回剑冢顶部
+                self.draw_pile.cards.insert(0, card);
+                self.draw_pile.count += 1;
+                break;
+            }
+            drawn += 1;
+        }
+        drawn
+    }
+
+    /// 当前生效的遗物效果（按遗物获得顺序展开，条件效果满足时展开为内层效果）
+    ///
+    /// `target` 为本次结算涉及的敌人下标（攻击目标或攻击者），供按妖物种类判定的条件使用。
+    fn relic_effects(&self, target: Option<usize>) -> Vec<(RelicId, RelicEffect)> {
+        let mut active = Vec::new();
+        for relic in &self.relics {
+            for effect in &relic.effects {
+                let mut effect = effect;
+                while let RelicEffect::When { condition, effect: inner } = effect {
+                    if !self.relic_condition_holds(condition, target) {
+                        break;
+                    }
+                    effect = inner.as_ref();
+                }
+                if !matches!(effect, RelicEffect::When { .. }) {
+                    active.push((relic.id.clone(), effect.clone()));
+                }
+            }
+        }
+        active
+    }
+
+    fn relic_condition_holds(&self, condition: &RelicCondition, target: Option<usize>) -> bool {
+        match condition {
+            RelicCondition::EnemyType(enemy_type) => match target {
+                Some(i) => self.enemies[i].enemy_type == *enemy_type,
+                None => self.enemies.iter().any(|e| e.hp > 0 && e.enemy_type == *enemy_type),
+            },
+            RelicCondition::HpBelowPercent(percent) => self.player.hp * 100 < self.player.max_hp * percent,
+            RelicCondition::FirstTurn => self.player.turn <= 1,
+        }
+    }
+
+    /// 结算一次主动触发的遗物效果（战斗开始、回合开始及计数满额时）
+    fn fire_relic_effect(&mut self, relic_id: RelicId, effect: RelicEffect, events: &mut Vec<CombatEvent>) {
+        events.push(CombatEvent::RelicTriggered { relic_id, effect: effect.clone() });
+        match effect {
+            RelicEffect::OnCombatStart { damage, block, draw_cards } => {
+                if damage > 0 {
+                    for enemy in self.enemies.iter_mut().filter(|e| e.hp > 0) {
+                        enemy.take_damage_with_env(damage, Some(&self.environment));
+                        events.push(CombatEvent::EnemyDamaged { enemy_id: enemy.id, damage, killed: enemy.hp <= 0 });
+                    }
+                }
+                if block > 0 {
+                    self.gain_block(block, events);
+                }
+                if draw_cards > 0 {
+                    let count = self.draw_cards(draw_cards as usize);
+                    events.push(CombatEvent::CardsDrawn { count });
+                }
+            }
+            RelicEffect::OnTurnStart { energy, draw_cards } => {
+                if energy > 0 {
+                    self.player.gain_energy(energy);
+                    events.push(CombatEvent::EnergyGained { amount: energy });
+                }
+                if draw_cards > 0 {
+                    let count = self.draw_cards(draw_cards as usize);
+                    events.push(CombatEvent::CardsDrawn { count });
+                }
+            }
+            RelicEffect::StatusAtCombatStart { status, stacks, enemies: true } => {
+                for enemy in self.enemies.iter_mut().filter(|e| e.hp > 0) {
+                    enemy.apply_status(status.as_str(), stacks);
+                    events.push(CombatEvent::StatusApplied { enemy_id: enemy.id, status: status.clone(), count: stacks });
+                }
+            }
+            RelicEffect::StatusAtCombatStart { status, stacks, enemies: false } => {
+                self.player.apply_status(status.as_str(), stacks);
+                events.push(CombatEvent::StatusGained { status, count: stacks });
+            }
+            // 灵石、道行上限与回复直接记在修行者身上
+            other => {
+                let before = self.player.hp;
+                if other.grant_to(&mut self.player) && self.player.hp > before {
+                    events.push(CombatEvent::Healed { amount: self.player.hp - before });
+                }
+            }
+        }
+    }
+
+    /// 推进计数类遗物，计满时结算其内层效果
+    fn advance_relic_counters(&mut self, event: CounterEvent, events: &mut Vec<CombatEvent>) {
+        let fired: Vec<(RelicId, RelicEffect)> = self
+            .relics
+            .iter_mut()
+            .filter_map(|relic| relic.advance_counter(event).map(|effect| (relic.id.clone(), effect)))
+            .collect();
+        for (relic_id, effect) in fired {
+            self.fire_relic_effect(relic_id, effect, events);
+        }
+    }
+
+    /// 修行者是否已领悟某项境界被动
+    fn has_passive(&self, passive: RealmPassive) -> bool {
+        self.player.realm.has_passive(passive)
+    }
+
+    /// 神识通明：回合抽牌后多抽 1 张
+    fn trigger_draw_passive(&mut self, events: &mut Vec<CombatEvent>) {
+        if self.has_passive(RealmPassive::DivineSense) {
+            events.push(CombatEvent::RealmPassiveTriggered { passive: RealmPassive::DivineSense });
+            let count = self.draw_cards(1);
+            events.push(CombatEvent::CardsDrawn { count });
+        }
+    }
+
+    /// 回合抽牌后的额外抽牌类遗物
+    fn trigger_draw_relics(&mut self, events: &mut Vec<CombatEvent>) {
+        for (relic_id, effect) in self.relic_effects(None) {
+            let RelicEffect::OnDraw { extra_cards } = effect else { continue; };
+            if extra_cards > 0 {
+                events.push(CombatEvent::RelicTriggered { relic_id, effect });
+                let count = self.draw_cards(extra_cards as usize);
+                events.push(CombatEvent::CardsDrawn { count });
+            }
+        }
+    }
+
+    /// 攻击伤害：叠加增伤类遗物（`target` 为目标敌人下标）后再经状态与天象修正
+    fn attack_damage(&self, base: i32, target: Option<usize>, events: &mut Vec<CombatEvent>) -> i32 {
+        let mut bonus = 0;
+        for (relic_id, effect) in self.relic_effects(target) {
+            if let RelicEffect::OnDealDamage { extra_damage } = effect {
+                bonus += extra_damage;
+                events.push(CombatEvent::RelicTriggered { relic_id, effect });
+            }
+        }
+        self.player.calculate_outgoing_damage_with_env(base + bonus, Some(&self.environment))
+    }
+
+    /// 宗门心法：剑宗积剑意，丹鼎门逢牌积丹火，天符宗以非攻伐功法积符印
+    fn update_sect_mechanic(&mut self, card: &Card, events: &mut Vec<CombatEvent>) {
+        let gains = match self.player.sect {
+            Sect::Sword => return self.update_sword_intent(card, events),
+            Sect::Alchemy => true,
+            Sect::Talisman => card.card_type != CardType::Attack,
+        };
+        if gains {
+            let stacks = self.player.add_sect_charge(1);
+            events.push(CombatEvent::SectChargeGained { stacks });
+        }
+    }
+
+    fn update_sword_intent(&mut self, card: &Card, events: &mut Vec<CombatEvent>) {
+        if card.card_type == CardType::Attack {
+            if self.player.sword_intent >= 5 {
+                self.player.reset_sword_intent();
+                events.push(CombatEvent::SwordIntentBurst);
+            } else {
+                self.player.add_sword_intent(1);
+                events.push(CombatEvent::SwordIntentGained { stacks: self.player.sword_intent });
+            }
+        } else {
+            self.player.reset_sword_intent();
+            events.push(CombatEvent::SwordIntentLost);
+        }
+    }
+
+    /// 解析单体功法的目标：优先玩家所选，否则取首个存活敌人
+    fn target_index(&self, target: Option<u32>) -> Option<usize> {
+        target
+            .and_then(|id| self.enemies.iter().position(|e| e.id == id && e.hp > 0))
+            .or_else(|| self.enemies.iter().position(|e| e.hp > 0))
+    }
+
+    /// 命中敌人；敌人的受击状态（如荆棘）反伤修行者
+    fn strike(enemy: &mut Enemy, player: &mut Player, damage: i32, environment: &Environment, events: &mut Vec<CombatEvent>) {
+        if enemy.hp <= 0 {
+            return;
+        }
+        enemy.take_damage_with_env(damage, Some(environment));
+        events.push(CombatEvent::EnemyDamaged { enemy_id: enemy.id, damage, killed: enemy.hp <= 0 });
+        for trigger in enemy.attacked_statuses() {
+            if let TriggeredEffect::DamageAttacker(amount) = trigger.effect {
+                player.take_damage(amount);
+            }
+            events.push(CombatEvent::StatusTriggered {
+                enemy_id: Some(enemy.id), status: trigger.status, effect: trigger.effect, killed: enemy.hp <= 0,
+            });
+        }
+    }
+
+    /// 将玩家或敌人的状态触发转为领域事件
+    fn push_status_events(&self, enemy_id: Option<u32>, triggers: Vec<StatusTrigger>, events: &mut Vec<CombatEvent>) {
+        let killed = match enemy_id {
+            Some(id) => self.enemies.iter().any(|e| e.id == id && e.hp <= 0),
+            None => self.is_defeat(),
+        };
+        events.extend(triggers.into_iter().map(|t| CombatEvent::StatusTriggered { enemy_id, status: t.status, effect: t.effect, killed }));
+    }
+
+    fn gain_block(&mut self, amount: i32, events: &mut Vec<CombatEvent>) {
+        let before = self.player.block;
+        self.player.gain_block_with_env(amount, Some(&self.environment));
+        events.push(CombatEvent::BlockGained { amount: self.player.block - before });
+    }
+
+    /// 按顺序结算一组功法效果；`energy_spent` 为本次出牌实际消耗的灵力（供 X 费缩放）
+    fn apply_effects(&mut self, effects: &[CardEffect], target: Option<u32>, energy_spent: i32, events: &mut Vec<CombatEvent>) {
+        for effect in effects {
+            self.apply_effect(effect, target, energy_spent, events);
+        }
+    }
+
+    fn check_condition(&self, condition: &EffectCondition, target: Option<u32>) -> bool {
+        match condition {
+            EffectCondition::TargetHas(status) => {
+                self.target_index(target).is_some_and(|i| self.enemies[i].statuses.has(status.as_str()))
+            }
+        }
+    }
+
+    fn apply_effect(&mut self, effect: &CardEffect, target: Option<u32>, energy_spent: i32, events: &mut Vec<CombatEvent>) {
+        let env = self.environment.clone();
+        match effect {
+            CardEffect::DealDamage { amount } => {
+                if let Some(i) = self.target_index(target) {
+                    let damage = self.attack_damage(*amount, Some(i), events);
+                    Self::strike(&mut self.enemies[i], &mut self.player, damage, &env, events);
+                }
+            }
+            CardEffect::DealAoEDamage { amount } => {
+                let alive: Vec<usize> = (0..self.enemies.len()).filter(|&i| self.enemies[i].hp > 0).collect();
+                for i in alive {
+                    let damage = self.attack_damage(*amount, Some(i), events);
+                    Self::strike(&mut self.enemies[i], &mut self.player, damage, &env, events);
+                }
+            }
+            CardEffect::MultiAttack { damage, times } => {
+                if let Some(i) = self.target_index(target) {
+                    let damage = self.attack_damage(*damage, Some(i), events);
+                    for _ in 0..*times {
+                        Self::strike(&mut self.enemies[i], &mut self.player, damage, &env, events);
+                    }
+                }
+            }
+            CardEffect::AttackAndDraw { damage, cards } => {
+                if let Some(i) = self.target_index(target) {
+                    let damage = self.attack_damage(*damage, Some(i), events);
+                    Self::strike(&mut self.enemies[i], &mut self.player, damage, &env, events);
+                }
+                let count = self.draw_cards(*cards as usize);
+                events.push(CombatEvent::CardsDrawn { count });
+            }
+            CardEffect::ApplyStatus { status, count } => {
+                if let Some(i) = self.target_index(target) {
+                    let enemy = &mut self.enemies[i];
+                    enemy.apply_status(status.as_str(), *count);
+                    events.push(CombatEvent::StatusApplied { enemy_id: enemy.id, status: status.clone(), count: *count });
+                }
+            }
+            CardEffect::GainStatus { status, count } => {
+                self.player.apply_status(status.as_str(), *count);
+                events.push(CombatEvent::StatusGained { status: status.clone(), count: *count });
+            }
+            CardEffect::GainBlock { amount } => self.gain_block(*amount, events),
+            CardEffect::Heal { amount } => {
+                let before = self.player.hp;
+                self.player.heal(*amount);
+                events.push(CombatEvent::Healed { amount: self.player.hp - before });
+            }
+            CardEffect::GainEnergy { amount } => {
+                self.player.gain_energy(*amount);
+                events.push(CombatEvent::EnergyGained { amount: *amount });
+            }
+            CardEffect::DrawCards { amount } => {
+                let count = self.draw_cards(*amount as usize);
+                events.push(CombatEvent::CardsDrawn { count });
+            }
+            CardEffect::ChangeEnvironment { name } => {
+                if name == "雷暴" {
+                    // 仅启动演出，伤害与天象切换在演出结束时结算
+                    events.push(CombatEvent::HeavenlyStrikeStarted { damage: HEAVENLY_STRIKE_DAMAGE, environment_name: name.clone() });
+                } else {
+                    // 护甲按施法前的天象结算
+                    self.gain_block(5, events);
+                    self.environment = if name == "浓雾" { Environment::thick_fog() } else { Environment::default() };
+                    events.push(CombatEvent::EnvironmentChanged { environment: self.environment.clone() });
+                }
+            }
+            CardEffect::If { condition, effects } => {
+                if self.check_condition(condition, target) {
+                    self.apply_effects(effects, target, energy_spent, events);
+                }
+            }
+            CardEffect::Scaled { per, effect } => {
+                let factor = match per {
+                    ScaleSource::CardsInHand => self.hand.cards.len() as i32,
+                    ScaleSource::EnergySpent => energy_spent,
+                };
+                if factor > 0 {
+                    self.apply_effect(&effect.scaled(factor), target, energy_spent, events);
+                }
+            }
+            // 诅咒牌在手牌中持续生效，由诅咒系统处理
+            CardEffect::CurseWeakness | CardEffect::CurseDamage { .. } => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::combat::EnemyType;
+    use crate::components::relic::{ANCHOR, STRANGE_SPOON};
+    use crate::components::status::{BURN, VULNERABLE};
+    use crate::resources::relic_data::RelicLibrary;
+
+    fn card(id: u32, card_type: CardType, cost: i32, effect: CardEffect) -> Card {
+        Card::new(id, "测试", card_type, cost, vec![effect], CardRarity::Common, "")
+    }
+
+    fn engine_with_hand(enemies: Vec<Enemy>, hand: Vec<Card>) -> CombatEngine {
+        let mut engine = CombatEngine::new(Player::default(), enemies, Vec::new());
+        for c in hand {
+            engine.hand.add_card(c);
+        }
+        engine.phase = TurnPhase::PlayerAction;
+        engine
+    }
+
+    #[test]
+    fn test_play_card_deals_damage_and_spends_energy() {
+        let strike = card(1, CardType::Attack, 1, CardEffect::DealDamage { amount: 6 });
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 30)], vec![strike]);
+
+        let events = engine.play_card(1, None).unwrap();
+
+        assert_eq!(engine.enemies[0].hp, 24);
+        assert_eq!(engine.player.energy, 2);
+        assert!(engine.hand.is_empty());
+        assert_eq!(engine.discard_pile.count, 1);
+        assert!(events.iter().any(|e| matches!(e, CombatEvent::EnemyDamaged { enemy_id: 1, damage: 6, killed: false })));
+    }
+
+    #[test]
+    fn test_targeted_card_hits_chosen_enemy() {
+        let hand = vec![
+            card(1, CardType::Attack, 1, CardEffect::DealDamage { amount: 6 }),
+            card(2, CardType::Attack, 1, CardEffect::DealDamage { amount: 6 }),
+        ];
+        let enemies = vec![Enemy::new(1, "妖狼", 30), Enemy::new(2, "妖狼", 30)];
+        let mut engine = engine_with_hand(enemies, hand);
+
+        engine.play_card(1, Some(2)).unwrap();
+        assert_eq!(engine.enemies[0].hp, 30);
+        assert_eq!(engine.enemies[1].hp, 24);
+
+        // 目标已陨落时回退到首个存活敌人
+        engine.enemies[1].hp = 0;
+        engine.play_card(2, Some(2)).unwrap();
+        assert_eq!(engine.enemies[0].hp, 24);
+    }
+
+    #[test]
+    fn test_effects_resolve_in_order_with_condition() {
+        let effects = vec![
+            CardEffect::DealDamage { amount: 4 },
+            CardEffect::If {
+                condition: EffectCondition::TargetHas(StatusId::new(POISON)),
+                effects: vec![CardEffect::DealDamage { amount: 6 }],
+            },
+        ];
+        let needle = |id| Card::new(id, "蚀骨毒针", CardType::Attack, 1, effects.clone(), CardRarity::Common, "");
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 30)], vec![needle(1), needle(2)]);
+
+        // 目标未中毒，条件分支不触发
+        engine.play_card(1, None).unwrap();
+        assert_eq!(engine.enemies[0].hp, 26);
+
+        engine.enemies[0].apply_status(POISON, 2);
+        engine.play_card(2, None).unwrap();
+        assert_eq!(engine.enemies[0].hp, 16);
+    }
+
+    #[test]
+    fn test_scaled_effects_and_x_cost() {
+        let tide = card(1, CardType::Attack, 1, CardEffect::Scaled {
+            per: ScaleSource::CardsInHand,
+            effect: Box::new(CardEffect::DealDamage { amount: 2 }),
+        });
+        let burn_out = card(2, CardType::Attack, 0, CardEffect::Scaled {
+            per: ScaleSource::EnergySpent,
+            effect: Box::new(CardEffect::MultiAttack { damage: 3, times: 1 }),
+        }).with_x_cost();
+        let filler = card(3, CardType::Skill, 1, CardEffect::GainBlock { amount: 1 });
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 100)], vec![tide, burn_out, filler]);
+
+        // 打出后手中余 2 张牌
+        engine.play_card(1, None).unwrap();
+        assert_eq!(engine.enemies[0].hp, 96);
+
+        // 剩余 2 点灵力全部耗尽，3 点伤害打 2 次
+        let events = engine.play_card(2, None).unwrap();
+        assert_eq!(engine.player.energy, 0);
+        assert_eq!(engine.enemies[0].hp, 90);
+        assert_eq!(events.iter().filter(|e| matches!(e, CombatEvent::EnemyDamaged { .. })).count(), 2);
+    }
+
+    #[test]
+    fn test_play_card_rejects_insufficient_energy() {
+        let big = card(1, CardType::Attack, 5, CardEffect::DealDamage { amount: 30 });
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 30)], vec![big]);
+
+        assert!(engine.play_card(1, None).is_err());
+        assert_eq!(engine.hand.len(), 1);
+        assert_eq!(engine.player.energy, 3);
+    }
+
+    #[test]
+    fn test_killing_last_enemy_emits_victory() {
+        let aoe = card(1, CardType::Attack, 1, CardEffect::DealAoEDamage { amount: 10 });
+        let enemies = vec![Enemy::new(1, "妖狼", 5), Enemy::new(2, "妖狼", 8)];
+        let mut engine = engine_with_hand(enemies, vec![aoe]);
+
+        let events = engine.play_card(1, None).unwrap();
+
+        assert!(engine.is_victory());
+        assert!(matches!(events.last(), Some(CombatEvent::Victory)));
+    }
+
+    #[test]
+    fn test_sword_intent_builds_and_breaks() {
+        let hand = vec![
+            card(1, CardType::Attack, 0, CardEffect::DealDamage { amount: 1 }),
+            card(2, CardType::Skill, 0, CardEffect::GainBlock { amount: 1 }),
+        ];
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 100)], hand);
+
+        engine.play_card(1, None).unwrap();
+        assert_eq!(engine.player.sword_intent, 1);
+        let events = engine.play_card(2, None).unwrap();
+        assert_eq!(engine.player.sword_intent, 0);
+        assert!(events.iter().any(|e| matches!(e, CombatEvent::SwordIntentLost)));
+    }
+
+    #[test]
+    fn test_sect_mechanics_charge_and_release() {
+        // 天符宗：两张非攻伐功法积两层符印，下一张攻伐功法 +6 伤害并耗尽符印
+        let hand = vec![
+            card(1, CardType::Skill, 0, CardEffect::GainBlock { amount: 1 }),
+            card(2, CardType::Defense, 0, CardEffect::GainBlock { amount: 1 }),
+            card(3, CardType::Attack, 0, CardEffect::DealDamage { amount: 5 }),
+        ];
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 100)], hand);
+        engine.player.sect = Sect::Talisman;
+        engine.play_card(1, None).unwrap();
+        engine.play_card(2, None).unwrap();
+        assert_eq!((engine.player.sect_charge, engine.player.sword_intent), (2, 0));
+        engine.play_card(3, None).unwrap();
+        assert_eq!(engine.enemies[0].hp, 100 - 11);
+        assert_eq!(engine.player.sect_charge, 0);
+
+        // 丹鼎门：三张功法积三层丹火，回合结束化作 6 点护盾，余火一层
+        let hand = (1..=3).map(|id| card(id, CardType::Attack, 0, CardEffect::DealDamage { amount: 1 })).collect();
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 100)], hand);
+        engine.player.sect = Sect::Alchemy;
+        for id in 1..=3 {
+            engine.play_card(id, None).unwrap();
+        }
+        let events = engine.end_turn();
+        assert_eq!((engine.player.block, engine.player.sect_charge), (6, 1));
+        assert!(events.iter().any(|e| matches!(e, CombatEvent::SectChargeReleased { stacks: 3 })));
+
+        // 丹火化盾同样受浓雾加成：五层丹火 10 点护盾 → 12 点
+        let hand = (1..=5).map(|id| card(id, CardType::Attack, 0, CardEffect::DealDamage { amount: 1 })).collect();
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 100)], hand);
+        engine.player.sect = Sect::Alchemy;
+        engine.environment = Environment::thick_fog();
+        for id in 1..=5 {
+            engine.play_card(id, None).unwrap();
+        }
+        let events = engine.end_turn();
+        assert_eq!(engine.player.block, 12);
+        assert!(events.iter().any(|e| matches!(e, CombatEvent::BlockGained { amount: 12 })));
+    }
+
+    #[test]
+    fn test_realm_passives_and_divine_art() {
+        // 元婴期：首张功法由分身重演，第二张照常；金丹留转保留 1 张手牌
+        let hand = vec![
+            card(1, CardType::Attack, 0, CardEffect::DealDamage { amount: 5 }),
+            card(2, CardType::Attack, 0, CardEffect::DealDamage { amount: 5 }),
+            card(3, CardType::Defense, 0, CardEffect::GainBlock { amount: 5 }),
+            card(4, CardType::Defense, 0, CardEffect::GainBlock { amount: 5 }),
+        ];
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 100)], hand);
+        engine.player.sect = Sect::Alchemy;
+        engine.player.realm = Realm::NascentSoul;
+        let events = engine.play_card(1, None).unwrap();
+        assert_eq!(engine.enemies[0].hp, 90);
+        assert!(events.iter().any(|e| matches!(e, CombatEvent::RealmPassiveTriggered { passive: RealmPassive::SpiritClone })));
+        engine.play_card(2, None).unwrap();
+        assert_eq!(engine.enemies[0].hp, 85);
+        engine.end_turn();
+        assert_eq!(engine.hand.cards.len(), 1);
+
+        // 神通每场一次
+        let events = engine.use_divine_art(None);
+        assert!(events.is_err(), "敌人回合不可施展");
+        engine.phase = TurnPhase::PlayerAction;
+        engine.use_divine_art(None).unwrap();
+        assert_eq!(engine.enemies[0].hp, 85 - 18);
+        assert!(engine.use_divine_art(None).is_err());
+
+        // 炼气期既无被动也无神通
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 100)], Vec::new());
+        assert!(engine.use_divine_art(None).is_err());
+        assert!(!engine.player.divine_art_used);
+    }
+
+    #[test]
+    fn test_end_turn_discards_hand() {
+        let hand = vec![
+            card(1, CardType::Attack, 1, CardEffect::DealDamage { amount: 6 }),
+            card(2, CardType::Defense, 1, CardEffect::GainBlock { amount: 5 }),
+        ];
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 30)], hand);
+
+        let events = engine.end_turn();
+
+        assert!(engine.hand.is_empty());
+        assert_eq!(engine.discard_pile.count, 2);
+        assert_eq!(engine.phase, TurnPhase::EnemyTurn);
+        assert!(matches!(events[0], CombatEvent::TurnEnded { discarded: 2 }));
+        assert!(engine.play_card(1, None).is_err());
+    }
+
+    #[test]
+    fn test_enemy_turn_then_player_redraws_from_discard() {
+        let deck: Vec<Card> = (0..5)
+            .map(|i| card(i, CardType::Defense, 1, CardEffect::GainBlock { amount: 5 }))
+            .collect();
+        let mut engine = CombatEngine::new(Player::default(), vec![Enemy::new(1, "妖狼", 30)], deck);
+        engine.start_combat();
+        assert_eq!(engine.hand.len(), 5);
+
+        engine.end_turn();
+        let events = engine.resolve_enemy_turn();
+
+        assert!(events.iter().any(|e| matches!(e, CombatEvent::EnemyActed { enemy_id: 1, .. })));
+        assert_eq!(engine.phase, TurnPhase::PlayerAction);
+        assert_eq!(engine.player.turn, 2);
+        assert_eq!(engine.player.energy, engine.player.max_energy);
+        assert_eq!(engine.hand.len(), 5);
+    }
+
+    #[test]
+    fn test_statuses_tick_and_decay() {
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 30)], Vec::new());
+        engine.enemies[0].apply_status(POISON, 3);
+        engine.enemies[0].apply_status(BURN, 4);
+        engine.enemies[0].apply_status(VULNERABLE, 1);
+        engine.player.apply_status(POISON, 2);
+        engine.player.apply_status(WEAKNESS, 1);
+
+        engine.end_turn();
+        assert!(!engine.player.statuses.has(WEAKNESS));
+
+        let events = engine.resolve_enemy_turn();
+        // 中毒 3 + 灼烧 4，随后毒减一层、火减半，易伤消退
+        assert_eq!(engine.enemies[0].hp, 23);
+        let statuses = &engine.enemies[0].statuses;
+        assert_eq!((statuses.stacks(POISON), statuses.stacks(BURN), statuses.stacks(VULNERABLE)), (2, 2, 0));
+        assert!(events.iter().any(|e| matches!(e,
+            CombatEvent::StatusTriggered { enemy_id: Some(1), status, effect: TriggeredEffect::LoseHp(4), killed: false } if status.as_str() == BURN
+        )));
+        // 玩家回合开始时毒发
+        assert!(events.iter().any(|e| matches!(e,
+            CombatEvent::StatusTriggered { enemy_id: None, status, effect: TriggeredEffect::LoseHp(2), .. } if status.as_str() == POISON
+        )));
+        assert_eq!(engine.player.statuses.stacks(POISON), 1);
+    }
+
+    #[test]
+    fn test_status_kills_end_combat() {
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 3)], Vec::new());
+        engine.enemies[0].apply_status(POISON, 5);
+        engine.end_turn();
+        let events = engine.resolve_enemy_turn();
+        assert!(events.iter().any(|e| matches!(e, CombatEvent::StatusTriggered { killed: true, .. })));
+        assert!(!events.iter().any(|e| matches!(e, CombatEvent::EnemyActed { .. })));
+        assert!(matches!(events.last(), Some(CombatEvent::Victory)));
+
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 30)], Vec::new());
+        engine.player.hp = 2;
+        engine.player.apply_status(BURN, 3);
+        let events = engine.start_player_turn();
+        assert_eq!(engine.player.hp, 0);
+        assert!(matches!(events.last(), Some(CombatEvent::Defeat)));
+        assert!(!events.iter().any(|e| matches!(e, CombatEvent::CardsDrawn { .. })));
+    }
+
+    #[test]
+    fn test_status_hooks_modify_combat() {
+        let strike = card(1, CardType::Attack, 1, CardEffect::DealDamage { amount: 6 });
+        let qi = card(2, CardType::Power, 1, CardEffect::GainStatus { status: StatusId::new("guarding_qi"), count: 2 });
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 30)], vec![strike, qi]);
+        engine.player.apply_status("strength", 2);
+        engine.enemies[0].apply_status("thorns", 3);
+
+        // 力量加伤，敌人荆棘反伤
+        engine.play_card(1, None).unwrap();
+        assert_eq!(engine.enemies[0].hp, 22);
+        assert_eq!(engine.player.hp, 77);
+
+        // 护体罡气：获得后本张即触发
+        let events = engine.play_card(2, None).unwrap();
+        assert_eq!(engine.player.block, 2);
+        assert!(events.iter().any(|e| matches!(e, CombatEvent::StatusTriggered { effect: TriggeredEffect::GainBlock(2), .. })));
+    }
+
+    fn relic(effects: Vec<RelicEffect>) -> Relic {
+        Relic {
+            id: RelicId::new("test_relic"),
+            name: "测试法宝".to_string(),
+            description: String::new(),
+            rarity: crate::components::relic::RelicRarity::Common,
+            effects,
+            min_realm: None,
+            counter: 0,
+            unlock_cost: None,
+            starter_cost: None,
+        }
+    }
+
+    fn relic_triggers(events: &[CombatEvent]) -> usize {
+        events.iter().filter(|e| matches!(e, CombatEvent::RelicTriggered { .. })).count()
+    }
+
+    #[test]
+    fn test_relics_trigger_on_combat_and_turn_start() {
+        let deck: Vec<Card> = (0..20)
+            .map(|i| card(i, CardType::Defense, 1, CardEffect::GainBlock { amount: 5 }))
+            .collect();
+        let relics = vec![relic(vec![
+            RelicEffect::OnCombatStart { damage: 3, block: 4, draw_cards: 1 },
+            RelicEffect::OnDraw { extra_cards: 1 },
+            RelicEffect::OnTurnStart { energy: 1, draw_cards: 1 },
+        ])];
+        let mut engine = CombatEngine::new(Player::default(), vec![Enemy::new(1, "妖狼", 30)], deck)
+            .with_rng(ChaCha12Rng::seed_from_u64(7))
+            .with_relics(relics);
+
+        // 首回合：5 张 + 额外抽 1 张 + 战斗开始抽 1 张
+        let events = engine.start_combat();
+        assert_eq!(engine.hand.len(), 7);
+        assert_eq!(engine.enemies[0].hp, 27);
+        assert_eq!(engine.player.block, 4);
+        assert_eq!(relic_triggers(&events), 2);
+
+        engine.end_turn();
+        let events = engine.resolve_enemy_turn();
+        assert_eq!(engine.hand.len(), 7);
+        assert_eq!(engine.player.energy, engine.player.max_energy + 1);
+        assert_eq!(relic_triggers(&events), 2);
+    }
+
+    #[test]
+    fn test_relics_trigger_on_play_damage_and_turn_end() {
+        let hand: Vec<Card> = (1..=3)
+            .map(|i| card(i, CardType::Attack, 0, CardEffect::DealDamage { amount: 5 }))
+            .collect();
+        let library = RelicLibrary::builtin();
+        let mut engine = engine_with_hand(vec![Enemy::new(1, "妖狼", 100)], hand);
+        engine.draw_pile = DrawPile::new(vec![card(9, CardType::Skill, 1, CardEffect::GainBlock { amount: 1 })]);
+        engine.relics = vec![
+            relic(vec![RelicEffect::OnDealDamage { extra_damage: 2 }]),
+            library.get(STRANGE_SPOON).unwrap(),
+            library.get(ANCHOR).unwrap(),
+        ];
+
+        let events = engine.play_card(1, None).unwrap();
+        assert_eq!(engine.enemies[0].hp, 93);
+        assert_eq!(relic_triggers(&events), 1);
+        engine.play_card(2, None).unwrap();
+        // 聚灵阵：第 3 张牌时抽 1 张（此时剑意 3 层，另加 2 点）
+        let events = engine.play_card(3, None).unwrap();
+        assert_eq!(engine.enemies[0].hp, 77);
+        assert_eq!(relic_triggers(&events), 2);
+        assert_eq!(engine.hand.cards[0].id, 9);
+
+        // 定风珠：回合结束保留手牌
+        let events = engine.end_turn();
+        assert_eq!(engine.hand.len(), 1);
+        assert!(events.iter().any(|e| matches!(e, CombatEvent::RelicTriggered { relic_id, .. } if relic_id.as_str() == ANCHOR)));
+        assert!(matches!(events.last(), Some(CombatEvent::TurnEnded { discarded: 0 })));
+    }
+
+    #[test]
+    fn test_relic_reduces_enemy_attack_damage() {
+        for seed in 0..20 {
+            let mut plain = engine_with_hand(vec![Enemy::new(1, "妖狼", 30)], Vec::new()).with_rng(ChaCha12Rng::seed_from_u64(seed));
+            let mut guarded = plain.clone().with_relics(vec![relic(vec![RelicEffect::OnTakeDamage { reduction: 2 }])]);
+
+            let plain_events = plain.resolve_enemy_action(1);
+            let guarded_events = guarded.resolve_enemy_action(1);
+            let Some(damage) = plain_events.iter().find_map(|e| match e {
+                CombatEvent::PlayerDamaged { damage, .. } => Some(*damage),
+                _ => None,
+            }) else { continue; };
+
+            assert!(guarded_events.iter().any(|e| matches!(e,
+                CombatEvent::PlayerDamaged { damage: reduced, .. } if *reduced == (damage - 2).max(0)
+            )));
+            assert_eq!(relic_triggers(&guarded_events), 1);
+            return;
+        }
+        panic!("20 个种子内敌人均未攻击");
+    }
+
+    #[test]
+    fn test_conditional_relics_follow_target_and_hp() {
+        let hand: Vec<Card> = (1..=2)
+            .map(|i| card(i, CardType::Attack, 0, CardEffect::DealDamage { amount: 5 }))
+            .collect();
+        let enemies = vec![Enemy::new(1, "妖狼", 100), Enemy::with_type(2, "毒蛛", 100, EnemyType::PoisonSpider)];
+        let mut engine = engine_with_hand(enemies, hand);
+        engine.relics = vec![relic(vec![
+            RelicEffect::When { condition: RelicCondition::EnemyType(EnemyType::PoisonSpider), effect: Box::new(RelicEffect::OnDealDamage { extra_damage: 3 }) },
+            RelicEffect::When { condition: RelicCondition::HpBelowPercent(50), effect: Box::new(RelicEffect::OnDealDamage { extra_damage: 10 }) },
+        ])];
+
+        engine.play_card(1, Some(1)).unwrap();
+        engine.play_card(2, Some(2)).unwrap();
+        assert_eq!((engine.enemies[0].hp, engine.enemies[1].hp), (95, 92));
+
+        engine.player.hp = 30;
+        engine.hand.add_card(card(3, CardType::Attack, 0, CardEffect::DealDamage { amount: 5 }));
+        engine.play_card(3, Some(1)).unwrap();
+        // 残血增伤 10，另有 3 层剑意加成 2
+        assert_eq!(engine.enemies[0].hp, 95 - 17);
+    }
+
+    #[test]
+    fn test_counter_relic_carries_progress_between_fights() {
+        let counter = relic(vec![RelicEffect::Counter {
+            counts: CounterEvent::CombatStarted,
+            every: 2,
+            effect: Box::new(RelicEffect::OnCombatStart { damage: 0, block: 7, draw_cards: 0 }),
+        }]);
+        let deck: Vec<Card> = (0..10).map(|i| card(i, CardType::Defense, 1, CardEffect::GainBlock { amount: 5 })).collect();
+
+        let mut first = CombatEngine::new(Player::default(), vec![Enemy::new(1, "妖狼", 30)], deck.clone()).with_relics(vec![counter]);
+        first.start_combat();
+        assert_eq!((first.player.block, first.relics[0].counter), (0, 1));
+
+        let mut second = CombatEngine::new(Player::default(), vec![Enemy::new(1, "妖狼", 30)], deck).with_relics(first.relics.clone());
+        let events = second.start_combat();
+        assert_eq!((second.player.block, second.relics[0].counter), (7, 0));
+        assert_eq!(relic_triggers(&events), 1);
+    }
+
+    #[test]
+    fn test_boss_enters_rage_phase_from_data() {
+        let mut boss = Enemy::with_type(1, "大妖", 200, EnemyType::GreatDemon);
+        boss.hp = 99;
+        let mut engine = engine_with_hand(vec![boss], Vec::new());
+
+        let events = engine.resolve_enemy_action(1);
+        assert!(events.iter().any(|e| matches!(e, CombatEvent::EnemyPhaseChanged { enemy_id: 1, phase: 1 })));
+        assert!(events.iter().any(|e| matches!(e, CombatEvent::EnemyActed { intent: EnemyIntent::Attack { damage: 35 }, .. })));
+        assert_eq!(engine.enemies[0].phase().unwrap().name, "狂暴二阶段");
+
+        let events = engine.resolve_enemy_action(1);
+        assert!(!events.iter().any(|e| matches!(e, CombatEvent::EnemyPhaseChanged { .. })));
+        assert_eq!(engine.enemies[0].current_move().unwrap().id, "blood_rage");
+    }
+
+    #[test]
+    fn test_weighted_moves_respect_max_repeats() {
+        let mut rng = ChaCha12Rng::seed_from_u64(7);
+        let mut wolf = Enemy::new(1, "妖狼", 30);
+        let mut previous: Option<String> = None;
+        let mut streak = 0;
+        for _ in 0..300 {
+            wolf.choose_new_intent(&mut rng);
+            let chosen = wolf.current_move().unwrap();
+            streak = if previous.as_deref() == Some(chosen.id.as_str()) { streak + 1 } else { 1 };
+            if let Some(limit) = chosen.max_repeats {
+                assert!(streak <= limit, "{} 连续施展了 {} 次", chosen.id, streak);
+            }
+            previous = Some(chosen.id.clone());
+        }
+    }
+
+    #[test]
+    fn test_same_rng_reproduces_fight() {
+        let run = |seed: u64| {
+            let mut engine = CombatEngine::new(
+                Player::default(),
+                vec![Enemy::new(1, "妖狼", 30)],
+                crate::components::cards::create_starting_deck(),
+            ).with_rng(ChaCha12Rng::seed_from_u64(seed));
+            engine.start_combat();
+            let opening: Vec<u32> = engine.hand.cards.iter().map(|c| c.id).collect();
+            engine.end_turn();
+            engine.resolve_enemy_turn();
+            (opening, engine.enemies[0].intent, engine.player.hp)
+        };
+
+        assert_eq!(run(2024), run(2024));
+    }
+
+    #[test]
+    fn test_simulate_many_fights_headless() {
+        for _ in 0..200 {
+            let mut engine = CombatEngine::new(
+                Player::default(),
+                vec![Enemy::new(1, "妖狼", 30)],
+                crate::components::cards::create_starting_deck(),
+            );
+            engine.start_combat();
+            for _ in 0..50 {
+                while let Some(id) = engine.hand.cards.iter()
+                    .find(|c| c.cost <= engine.player.energy && c.card_type != CardType::Curse)
+                    .map(|c| c.id)
+                {
+                    engine.play_card(id, None).unwrap();
+                    if engine.is_victory() { break; }
+                }
+                if engine.is_victory() { break; }
+                engine.end_turn();
+                engine.resolve_enemy_turn();
+                if engine.is_defeat() { break; }
+            }
+            assert!(engine.is_victory() || engine.is_defeat() || engine.player.turn > 1);
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 use rand::Rng;
-use crate::components::combat::{Enemy, EnemyType, EnemyAffix};
+use crate::components::combat::{Enemy, EnemyAffix};
+use crate::resources::act_data::{ActDefinition, ScalingCurve};
+use crate::resources::enemy_data::{EnemyDefinition, EnemyRoster};
 
 /// 生成的敌人结果，包含组件数据和视觉配置
 pub struct GeneratedEnemy {
@@ -17,94 +19,88 @@ pub struct GeneratedEnemy {
 pub struct EnemyGenerator;
 
 impl EnemyGenerator {
-    /// 根据深度生成一个敌人
-    pub fn generate_enemy(depth: u32, id: u32) -> GeneratedEnemy {
-        let mut rng = rand::thread_rng();
+    /// 在当前大境的指定层生成一个敌人
+    pub fn generate_enemy(act: &ActDefinition, layer: u32, id: u32, rng: &mut impl Rng) -> GeneratedEnemy {
+        // 1. 按出场权重从本界妖物中选择
+        let archetype = EnemyRoster::builtin().pick(&act.enemies, act.scaling.depth(layer), rng);
+        Self::generate_from(archetype, &act.scaling, layer, id, rng)
+    }
 
-        // 1. 选择原型
-        let archetype = Self::pick_archetype(depth, &mut rng);
+    /// 按指定妖物定义生成一个敌人（数值按大境的强度曲线缩放并掷词缀）
+    pub fn generate_from(archetype: &EnemyDefinition, scaling: &ScalingCurve, layer: u32, id: u32, rng: &mut impl Rng) -> GeneratedEnemy {
+        let depth = scaling.depth(layer);
 
         // 2. 基础数值计算
         let scaling_factor = 1.0 + (depth as f32 * 0.2); 
-        let base_hp = (archetype.base_hp_range.0 as f32 * scaling_factor) as i32;
+        let base_hp = scaling.scale_hp((archetype.hp.0 as f32 * scaling_factor) as i32);
         let hp_variance = rng.gen_range(0.9..=1.1);
         let mut final_hp = (base_hp as f32 * hp_variance) as i32;
 
         // 3. 构建 Enemy 组件
-        let mut enemy = Enemy::with_type(
+        let mut enemy = Enemy::from_definition(
             id,
             "".to_string(), // 稍后生成名字
             final_hp,
-            archetype.enemy_type
+            archetype
         );
-        
+
         // 随深度增加基础攻击力
         if depth > 5 {
             enemy.strength = ((depth - 5) as f32 * 0.5) as i32;
         }
+        enemy.strength += scaling.strength_bonus;
 
-        // 4. 随机生成词缀
-        let affixes = Self::roll_affixes(depth, &mut rng);
+        // 4. 随机生成词缀（精英妖物必带「精英」词缀）
+        let affixes = if archetype.elite {
+            vec![EnemyAffix::Elite]
+        } else {
+            Self::roll_affixes(depth, rng)
+        };
         enemy.affixes = affixes.clone();
 
         // 5. 应用词缀数值修正
-        let mut visual_scale = Vec2::ONE;
-        let mut visual_color = Color::WHITE;
-
         for affix in &affixes {
             match affix {
                 EnemyAffix::Elite => {
                     final_hp = (final_hp as f32 * 1.5) as i32;
                     enemy.strength += 2;
                     enemy.block += 5;
-                    visual_scale *= 1.3;
-                    visual_color = Color::srgba(1.0, 0.9, 0.6, 1.0); // 金色
                 }
                 EnemyAffix::Weak => {
                     final_hp = (final_hp as f32 * 0.7) as i32;
                     enemy.strength = (enemy.strength - 1).max(0);
-                    visual_scale *= 0.8;
-                    visual_color = Color::srgba(0.8, 0.8, 0.8, 1.0); // 灰色
                 }
                 EnemyAffix::Berserk => {
                     enemy.strength += (depth as i32 / 3).max(3); // 攻击大幅提升
                     final_hp = (final_hp as f32 * 0.8) as i32;   // 血量稍减
-                    visual_color = Color::srgba(1.0, 0.4, 0.4, 1.0); // 红色
-                    // 修改 AI: 极高攻击概率
-                    enemy.ai_pattern.attack_chance = 0.9;
-                    enemy.ai_pattern.defend_chance = 0.0;
+                    // 修改 AI: 攻击招式权重大增，不再防御
+                    enemy.behavior.attack_bias = 3.0;
+                    enemy.behavior.defend_bias = 0.0;
                 }
                 EnemyAffix::Tank => {
                     final_hp = (final_hp as f32 * 1.3) as i32;
                     enemy.block += 10;
-                    visual_scale *= 1.1;
-                    visual_color = Color::srgba(0.4, 0.6, 1.0, 1.0); // 蓝色
-                    // 修改 AI: 高防御概率
-                    enemy.ai_pattern.defend_chance += 0.3;
-                    enemy.ai_pattern.attack_chance -= 0.2;
-                }
-                EnemyAffix::Swift => {
-                    visual_color = Color::srgba(0.4, 1.0, 0.8, 1.0); // 青色
+                    // 修改 AI: 防御招式权重提升
+                    enemy.behavior.defend_bias = 2.5;
+                    enemy.behavior.attack_bias = 0.7;
                 }
+                EnemyAffix::Swift => {}
                 EnemyAffix::Fire => {
-                    visual_color = Color::srgba(1.0, 0.3, 0.0, 1.0); // 橘红
                     enemy.strength += 1;
                 }
-                EnemyAffix::Poison => {
-                    visual_color = Color::srgba(0.2, 0.8, 0.2, 1.0); // 毒绿
-                }
+                EnemyAffix::Poison => {}
                 EnemyAffix::Ice => {
-                    visual_color = Color::srgba(0.4, 0.8, 1.0, 1.0); // 冰蓝
                     enemy.block += 3;
                 }
             }
         }
-        
+        let (visual_scale, visual_color) = Self::affix_visuals(&affixes);
+
         enemy.hp = final_hp;
         enemy.max_hp = final_hp;
 
         // 6. 生成最终名称
-        enemy.name = Self::generate_name(&archetype, depth, &affixes);
+        enemy.name = Self::generate_name(archetype, depth, &affixes);
 
         GeneratedEnemy {
             enemy,
@@ -113,32 +109,40 @@ impl EnemyGenerator {
         }
     }
 
-    /// 生成指定深度的 Boss
-    pub fn generate_boss(depth: u32, id: u32) -> GeneratedEnemy {
-        let mut rng = rand::thread_rng();
-        let archetype = EnemyArchetypeData::great_demon();
-        
+    /// 按当前大境的精英阵容生成一场精英战（阵容随机抽取，精英妖物必带「精英」词缀，随从照常掷词缀）
+    pub fn generate_elite(act: &ActDefinition, layer: u32, rng: &mut impl Rng) -> Vec<GeneratedEnemy> {
+        let roster = EnemyRoster::builtin();
+        let party = &act.elites[rng.gen_range(0..act.elites.len())];
+        party.iter()
+            .filter_map(|id| roster.get(id))
+            .enumerate()
+            .map(|(i, archetype)| Self::generate_from(archetype, &act.scaling, layer, i as u32, rng))
+            .collect()
+    }
+
+    /// 生成当前大境的镇守首领
+    pub fn generate_boss(act: &ActDefinition, layer: u32, id: u32, rng: &mut impl Rng) -> GeneratedEnemy {
+        let depth = act.scaling.depth(layer);
+        let archetype = EnemyRoster::builtin().pick_boss(&act.bosses, depth, rng);
+
         let scaling_factor = 1.2 + (depth as f32 * 0.2); 
-        let base_hp = (archetype.base_hp_range.0 as f32 * scaling_factor) as i32;
+        let base_hp = act.scaling.scale_hp((archetype.hp.0 as f32 * scaling_factor) as i32);
         let hp_variance = rng.gen_range(0.95..=1.05); 
         let final_hp = (base_hp as f32 * hp_variance) as i32;
 
-        let mut enemy = Enemy::with_type(
+        let mut enemy = Enemy::from_definition(
             id,
             format!("【镇守】{}", archetype.name),
             final_hp,
-            archetype.enemy_type
+            archetype
         );
 
-        enemy.strength = (depth / 2) as i32 + 2;
+        enemy.strength = (depth / 2) as i32 + 2 + act.scaling.strength_bonus;
         enemy.block = (depth / 2) as i32 + 5;
-        
-        enemy.ai_pattern.damage_range.0 += enemy.strength;
-        enemy.ai_pattern.damage_range.1 += enemy.strength;
 
         // Boss 默认自带精英属性的视觉效果，但不加 Elite 词缀以免数值膨胀过度
         // 或者我们可以给 Boss 加一些特殊词缀
-        
+
         GeneratedEnemy {
             enemy,
             visual_scale: Vec2::splat(1.5), // Boss 默认巨大
@@ -146,6 +150,44 @@ impl EnemyGenerator {
         }
     }
 
+    /// 读档续战时重建敌人（外观由词缀推得）
+    pub fn restore(enemy: Enemy, boss: bool) -> GeneratedEnemy {
+        let (visual_scale, visual_color) = if boss {
+            (Vec2::splat(1.5), Color::WHITE)
+        } else {
+            Self::affix_visuals(&enemy.affixes)
+        };
+        GeneratedEnemy { enemy, visual_scale, visual_color }
+    }
+
+    /// 词缀带来的体型与染色
+    fn affix_visuals(affixes: &[EnemyAffix]) -> (Vec2, Color) {
+        let mut visual_scale = Vec2::ONE;
+        let mut visual_color = Color::WHITE;
+        for affix in affixes {
+            match affix {
+                EnemyAffix::Elite => {
+                    visual_scale *= 1.3;
+                    visual_color = Color::srgba(1.0, 0.9, 0.6, 1.0); // 金色
+                }
+                EnemyAffix::Weak => {
+                    visual_scale *= 0.8;
+                    visual_color = Color::srgba(0.8, 0.8, 0.8, 1.0); // 灰色
+                }
+                EnemyAffix::Berserk => visual_color = Color::srgba(1.0, 0.4, 0.4, 1.0), // 红色
+                EnemyAffix::Tank => {
+                    visual_scale *= 1.1;
+                    visual_color = Color::srgba(0.4, 0.6, 1.0, 1.0); // 蓝色
+                }
+                EnemyAffix::Swift => visual_color = Color::srgba(0.4, 1.0, 0.8, 1.0), // 青色
+                EnemyAffix::Fire => visual_color = Color::srgba(1.0, 0.3, 0.0, 1.0), // 橘红
+                EnemyAffix::Poison => visual_color = Color::srgba(0.2, 0.8, 0.2, 1.0), // 毒绿
+                EnemyAffix::Ice => visual_color = Color::srgba(0.4, 0.8, 1.0, 1.0), // 冰蓝
+            }
+        }
+        (visual_scale, visual_color)
+    }
+
     fn roll_affixes(depth: u32, rng: &mut impl Rng) -> Vec<EnemyAffix> {
         let mut affixes = Vec::new();
         let roll: f32 = rng.gen();
@@ -173,20 +215,9 @@ impl EnemyGenerator {
         affixes
     }
 
-    fn pick_archetype(depth: u32, rng: &mut impl Rng) -> EnemyArchetypeData {
-        let roll: f32 = rng.gen();
-        if depth < 3 {
-            if roll < 0.6 { EnemyArchetypeData::demonic_wolf() } else { EnemyArchetypeData::poison_spider() }
-        } else if depth < 7 {
-            if roll < 0.4 { EnemyArchetypeData::demonic_wolf() } else if roll < 0.7 { EnemyArchetypeData::poison_spider() } else { EnemyArchetypeData::cursed_spirit() }
-        } else {
-            if roll < 0.3 { EnemyArchetypeData::demonic_wolf() } else if roll < 0.5 { EnemyArchetypeData::poison_spider() } else if roll < 0.8 { EnemyArchetypeData::cursed_spirit() } else { EnemyArchetypeData::great_demon() }
-        }
-    }
-
-    fn generate_name(archetype: &EnemyArchetypeData, depth: u32, affixes: &[EnemyAffix]) -> String {
+    fn generate_name(archetype: &EnemyDefinition, depth: u32, affixes: &[EnemyAffix]) -> String {
         let age_prefix = if depth <= 2 { "幼年" } else if depth <= 5 { "成年" } else if depth <= 8 { "狂暴" } else { "千年" };
-        
+
         let affix_prefix = if let Some(affix) = affixes.first() {
             match affix {
                 EnemyAffix::Elite => "精英",
@@ -210,15 +241,26 @@ impl EnemyGenerator {
     }
 }
 
-struct EnemyArchetypeData {
-    enemy_type: EnemyType,
-    name: &'static str,
-    base_hp_range: (i32, i32),
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::act_data::ActAtlas;
+    use rand::SeedableRng;
 
-impl EnemyArchetypeData {
-    fn demonic_wolf() -> Self { Self { enemy_type: EnemyType::DemonicWolf, name: "妖狼", base_hp_range: (25, 35) } }
-    fn poison_spider() -> Self { Self { enemy_type: EnemyType::PoisonSpider, name: "毒蛛", base_hp_range: (40, 50) } }
-    fn cursed_spirit() -> Self { Self { enemy_type: EnemyType::CursedSpirit, name: "怨灵", base_hp_range: (60, 80) } }
-    fn great_demon() -> Self { Self { enemy_type: EnemyType::GreatDemon, name: "大妖", base_hp_range: (150, 200) } }
-}
\ No newline at end of file
+    #[test]
+    fn test_elite_parties_carry_elite_affix() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        for act in ActAtlas::builtin().acts() {
+            for _ in 0..20 {
+                let party = EnemyGenerator::generate_elite(act, 4, &mut rng);
+                assert!(!party.is_empty());
+                let leaders: Vec<&Enemy> = party.iter()
+                    .map(|g| &g.enemy)
+                    .filter(|e| e.definition().is_some_and(|d| d.elite))
+                    .collect();
+                assert!(!leaders.is_empty(), "{} 的精英阵容缺少精英妖物", act.id);
+                assert!(leaders.iter().all(|e| e.affixes == [EnemyAffix::Elite] && e.name.starts_with("精英")));
+            }
+        }
+    }
+}
@@ -1,34 +1,111 @@
 use bevy::prelude::*;
 use crate::states::GameState;
-use crate::components::{Player, MapProgress, RelicCollection, PlayerDeck};
+use crate::components::{Player, Cultivation, MapProgress, RelicCollection, PlayerDeck, PlaySfxEvent, SfxType};
+use crate::components::dialogue::Dialogue;
+use crate::resources::{CardLibrary, RunSeed};
+use crate::resources::act_data::ActAtlas;
+use crate::resources::event_data::{EventBook, EventDefinition, EventHistory, EventTarget, EventView};
+use crate::resources::relic_data::RelicLibrary;
 
 pub struct EventPlugin;
 
 impl Plugin for EventPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Event), setup_event_ui)
-           .add_systems(Update, handle_event_choices.run_if(in_state(GameState::Event)))
-           .add_systems(OnExit(GameState::Event), cleanup_event_ui);
+        app.init_resource::<EventHistory>()
+           .add_systems(OnEnter(GameState::Event), setup_event_ui)
+           .add_systems(Update, (advance_event_dialogue, handle_event_choices).chain().run_if(in_state(GameState::Event)))
+           .add_systems(OnExit(GameState::Event), cleanup_event_ui)
+           .add_systems(OnExit(GameState::Combat), end_event_encounter);
     }
 }
 
 #[derive(Component)]
 pub struct EventUiRoot;
 
+/// 页内第 N 句台词
+#[derive(Component)]
+pub struct EventLine(pub usize);
+
+/// "点击继续"提示
+#[derive(Component)]
+pub struct EventContinueHint;
+
+/// 选项区（台词播完后显示）
+#[derive(Component)]
+pub struct EventChoiceArea;
+
+/// 选项按钮（门槛未满足时不可点）
 #[derive(Component, Debug)]
-pub enum EventChoiceButton {
-    GainGold(i32),
-    Heal(i32),
-    Leave,
+pub struct EventChoiceButton {
+    pub index: usize,
+    pub enabled: bool,
+}
+
+/// 正在进行的机缘
+#[derive(Resource, Debug, Clone)]
+pub struct ActiveEvent {
+    pub event_id: String,
+    pub page: String,
+    /// 上一选项的结算结果（翻页后展示）
+    pub results: Vec<String>,
+}
+
+/// 机缘引发的战斗：进入战斗时按此生成妖物，离开战斗时撤去
+#[derive(Resource, Debug, Clone)]
+pub struct EventEncounter {
+    pub enemies: Vec<String>,
 }
 
 /// 设置机缘事件界面
 fn setup_event_ui(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut map_progress: ResMut<MapProgress>,
+    mut history: ResMut<EventHistory>,
+    mut run_seed: ResMut<RunSeed>,
+    player_query: Query<(&Player, &Cultivation)>,
+    deck: Res<PlayerDeck>,
+    relics: Res<RelicCollection>,
+    card_library: Res<CardLibrary>,
+    relic_library: Res<RelicLibrary>,
 ) {
-    info!("【机缘】进入奇遇事件");
+    let layer = map_progress.get_current_node().map(|n| n.layer()).unwrap_or(map_progress.current_layer);
+    // 后续大境按折算深度取权重，深处的机缘不会随换界而消失
+    let depth = ActAtlas::builtin().act(map_progress.act).scaling.depth(layer);
+    let Some(event) = EventBook::builtin().pick(depth, &history, &mut run_seed.events) else {
+        warn!("【机缘】第 {} 层已无机缘可遇，悄然离去", layer);
+        map_progress.complete_current_node();
+        next_state.set(GameState::Map);
+        return;
+    };
+    info!("【机缘】进入奇遇事件：{}", event.title);
+    history.record(&event.id);
+
+    let active = ActiveEvent { event_id: event.id.clone(), page: event.opening().id.clone(), results: Vec::new() };
+    if let Ok((player, cultivation)) = player_query.get_single() {
+        let view = EventView { player, cultivation, deck: &deck, relics: &relics, cards: &card_library };
+        spawn_event_page(&mut commands, &asset_server, event, &active, &view, &relic_library);
+    }
+    commands.insert_resource(active);
+}
+
+/// 生成当前页：标题、结算结果、逐句显现的台词与选项
+fn spawn_event_page(
+    commands: &mut Commands,
+    asset_server: &AssetServer,
+    event: &EventDefinition,
+    active: &ActiveEvent,
+    view: &EventView,
+    relic_library: &RelicLibrary,
+) {
+    let Some(page) = event.page(&active.page) else {
+        error!("【机缘】{} 缺少页 `{}`", event.id, active.page);
+        return;
+    };
     let font = asset_server.load("fonts/Arial Unicode.ttf");
+    let dialogue = Dialogue::new(page.lines.clone());
+    let single_line = dialogue.is_last_line();
 
     commands.spawn((
         Node {
@@ -37,109 +114,215 @@ fn setup_event_ui(
             flex_direction: FlexDirection::Column,
             align_items: AlignItems::Center,
             justify_content: JustifyContent::Center,
-            row_gap: Val::Px(30.0),
+            row_gap: Val::Px(24.0),
             ..default()
         },
         BackgroundColor(Color::srgba(0.05, 0.05, 0.1, 0.9)),
         EventUiRoot,
+        dialogue,
     )).with_children(|parent| {
         // 标题
         parent.spawn((
-            Text::new("古 修 遗 迹"),
+            Text::new(event.title.clone()),
             TextFont { font: font.clone(), font_size: 48.0, ..default() },
             TextColor(Color::srgb(0.4, 0.8, 1.0)),
         ));
 
-        // 描述
-        parent.spawn((
-            Text::new("你在一处断崖下发现了一尊古老的石像，石像手中握着一颗微弱发光的灵石，而基座上似乎刻着某种愈合咒文。"),
-            Node { max_width: Val::Px(600.0), ..default() },
-            TextFont { font: font.clone(), font_size: 20.0, ..default() },
-            TextColor(Color::WHITE),
-            TextLayout::new_with_justify(JustifyText::Center),
-        ));
+        // 上一选项的结算结果
+        if !active.results.is_empty() {
+            parent.spawn((
+                Text::new(active.results.join("　")),
+                TextFont { font: font.clone(), font_size: 18.0, ..default() },
+                TextColor(Color::srgb(1.0, 0.85, 0.4)),
+            ));
+        }
 
-        // 选项区
+        // 台词（旁白不署名）
         parent.spawn(Node {
             flex_direction: FlexDirection::Column,
-            row_gap: Val::Px(15.0),
+            row_gap: Val::Px(10.0),
+            max_width: Val::Px(640.0),
             ..default()
-        }).with_children(|choices| {
-            // 选项 1: 拿走灵石
-            create_event_button(choices, "取走灵石 (+50 灵石)", EventChoiceButton::GainGold(50), font.clone());
-            // 选项 2: 虔诚祈祷
-            create_event_button(choices, "虔诚祈祷 (回复 20 HP)", EventChoiceButton::Heal(20), font.clone());
-            // 选项 3: 离去
-            create_event_button(choices, "因果莫测，径直离去", EventChoiceButton::Leave, font.clone());
+        }).with_children(|lines| {
+            for (index, line) in page.lines.iter().enumerate() {
+                let (text, color) = if line.speaker.is_empty() || line.speaker == "旁白" {
+                    (line.text.clone(), Color::WHITE)
+                } else {
+                    (format!("{}：{}", line.speaker, line.text), Color::srgb(0.85, 0.9, 0.7))
+                };
+                lines.spawn((
+                    Text::new(text),
+                    Node { display: if index == 0 { Display::Flex } else { Display::None }, ..default() },
+                    TextFont { font: font.clone(), font_size: 20.0, ..default() },
+                    TextColor(color),
+                    TextLayout::new_with_justify(JustifyText::Center),
+                    EventLine(index),
+                ));
+            }
+        });
+
+        parent.spawn((
+            Text::new("—— 点击继续 ——"),
+            Node { display: if single_line { Display::None } else { Display::Flex }, ..default() },
+            TextFont { font: font.clone(), font_size: 16.0, ..default() },
+            TextColor(Color::srgba(1.0, 1.0, 1.0, 0.4)),
+            EventContinueHint,
+        ));
+
+        // 选项区
+        parent.spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(15.0),
+                display: if single_line { Display::Flex } else { Display::None },
+                ..default()
+            },
+            EventChoiceArea,
+        )).with_children(|choices| {
+            for (index, choice) in page.choices.iter().enumerate() {
+                let enabled = choice.available(view);
+                let mut hints: Vec<String> = choice.requires.iter().map(|r| r.describe(view.cards, relic_library)).collect();
+                hints.extend(choice.outcomes.iter().map(|o| o.describe(view.cards, relic_library)));
+                let label = if hints.is_empty() {
+                    choice.label.clone()
+                } else {
+                    format!("{}（{}）", choice.label, hints.join("，"))
+                };
+                create_event_button(choices, &label, EventChoiceButton { index, enabled }, font.clone());
+            }
         });
     });
 }
 
 fn create_event_button(parent: &mut ChildBuilder, label: &str, choice: EventChoiceButton, font: Handle<Font>) {
+    let (background, text_color) = if choice.enabled {
+        (Color::srgba(0.2, 0.2, 0.3, 0.8), Color::WHITE)
+    } else {
+        (Color::srgba(0.15, 0.15, 0.15, 0.6), Color::srgb(0.5, 0.5, 0.5))
+    };
     parent.spawn((
         Button,
         Node {
-            width: Val::Px(400.0),
-            height: Val::Px(50.0),
+            min_width: Val::Px(400.0),
+            max_width: Val::Px(640.0),
+            min_height: Val::Px(50.0),
+            padding: UiRect::axes(Val::Px(16.0), Val::Px(8.0)),
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
             ..default()
         },
-        BackgroundColor(Color::srgba(0.2, 0.2, 0.3, 0.8)),
+        BackgroundColor(background),
         BorderRadius::all(Val::Px(8.0)),
         choice,
     )).with_children(|p| {
         p.spawn((
             Text::new(label),
             TextFont { font, font_size: 18.0, ..default() },
-            TextColor(Color::WHITE),
+            TextColor(text_color),
+            TextLayout::new_with_justify(JustifyText::Center),
         ));
     });
 }
 
-/// 处理机缘事件选择
+/// 点击推进台词，播到最后一句时亮出选项
+fn advance_event_dialogue(
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    touches: Res<Touches>,
+    mut root_query: Query<&mut Dialogue, With<EventUiRoot>>,
+    mut line_query: Query<(&EventLine, &mut Node), (Without<EventContinueHint>, Without<EventChoiceArea>)>,
+    mut hint_query: Query<&mut Node, (With<EventContinueHint>, Without<EventChoiceArea>)>,
+    mut choice_area_query: Query<&mut Node, (With<EventChoiceArea>, Without<EventContinueHint>)>,
+) {
+    if !mouse_button.just_pressed(MouseButton::Left) && !touches.any_just_pressed() {
+        return;
+    }
+    let Ok(mut dialogue) = root_query.get_single_mut() else { return; };
+    if dialogue.is_last_line() {
+        return;
+    }
+    dialogue.next();
+    for (line, mut node) in line_query.iter_mut() {
+        if line.0 <= dialogue.index {
+            node.display = Display::Flex;
+        }
+    }
+    if dialogue.is_last_line() {
+        for mut node in hint_query.iter_mut() {
+            node.display = Display::None;
+        }
+        for mut node in choice_area_query.iter_mut() {
+            node.display = Display::Flex;
+        }
+    }
+}
+
+/// 处理机缘事件选择：结算结果后翻页、转入战斗或离去
 fn handle_event_choices(
-    commands: Commands,
-    current_state: Res<State<GameState>>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
     mut next_state: ResMut<NextState<GameState>>,
-    mut player_query: Query<(&mut Player, &crate::components::Cultivation)>,
+    active: Option<ResMut<ActiveEvent>>,
+    mut player_query: Query<(&mut Player, &mut Cultivation)>,
     mut map_progress: ResMut<MapProgress>,
-    _deck: Res<PlayerDeck>,
-    _relics: Res<RelicCollection>,
+    mut deck: ResMut<PlayerDeck>,
+    mut relics: ResMut<RelicCollection>,
+    card_library: Res<CardLibrary>,
+    relic_library: Res<RelicLibrary>,
+    mut run_seed: ResMut<RunSeed>,
     button_query: Query<(&Interaction, &EventChoiceButton), Changed<Interaction>>,
+    root_query: Query<Entity, With<EventUiRoot>>,
+    mut sfx_events: EventWriter<PlaySfxEvent>,
 ) {
-    if *current_state.get() != GameState::Event {
+    let Some(mut active) = active else { return; };
+    let Some(pressed) = button_query.iter()
+        .find(|(interaction, button)| **interaction == Interaction::Pressed && button.enabled)
+        .map(|(_, button)| button.index) else { return; };
+    // 防止单帧内多次设置状态
+    if next_state.is_changed() { return; }
+
+    let Some(event) = EventBook::builtin().get(&active.event_id) else { return; };
+    let Some(choice) = event.page(&active.page).and_then(|page| page.choices.get(pressed)) else { return; };
+    let Ok((mut player, mut cultivation)) = player_query.get_single_mut() else { return; };
+    sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
+    info!("【机缘】{}：{}", event.title, choice.label);
+
+    let mut target = EventTarget {
+        player: &mut player,
+        cultivation: &mut cultivation,
+        deck: &mut deck,
+        relics: &mut relics,
+        cards: &card_library,
+        relic_library: &relic_library,
+    };
+    // 门槛在显示后可能已变化（如同页前一选项花去了灵石），此处再核一次
+    if !choice.available(&target.view()) {
         return;
     }
+    let results: Vec<String> = choice.outcomes.iter()
+        .filter_map(|outcome| outcome.apply(&mut target, &mut run_seed.events))
+        .collect();
+    for result in &results {
+        info!("【机缘】{}", result);
+    }
 
-    for (interaction, choice) in button_query.iter() {
-        if *interaction == Interaction::Pressed {
-            // 防止单帧内多次设置状态
-            if next_state.is_changed() { continue; }
-
-            if let Ok((mut player, _)) = player_query.get_single_mut() {
-                match choice {
-                    EventChoiceButton::GainGold(amt) => {
-                        player.gold += *amt;
-                        info!("【机缘】获得灵石 {}", amt);
-                    }
-                    EventChoiceButton::Heal(amt) => {
-                        player.hp = (player.hp + *amt).min(player.max_hp);
-                        info!("【机缘】回复生命 {}", amt);
-                    }
-                    EventChoiceButton::Leave => {
-                        info!("【机缘】悄然离去");
-                    }
-                }
-            }
-
-            // 完成当前节点
-            map_progress.complete_current_node();
-            info!("【机缘】事件节点已完成，下一层已解锁");
+    for entity in root_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
 
-            // 清理逻辑现在由 OnExit 处理
-            next_state.set(GameState::Map);
-        }
+    if let Some(enemies) = choice.combat() {
+        // 节点在战后领取奖励时完成
+        info!("【机缘】妖物现身，转入战斗");
+        commands.insert_resource(EventEncounter { enemies: enemies.to_vec() });
+        next_state.set(GameState::Combat);
+    } else if let Some(next) = &choice.next {
+        active.page = next.clone();
+        active.results = results;
+        let view = target.view();
+        spawn_event_page(&mut commands, &asset_server, event, &active, &view, &relic_library);
+    } else {
+        map_progress.complete_current_node();
+        info!("【机缘】事件节点已完成，下一层已解锁");
+        next_state.set(GameState::Map);
     }
 }
 
@@ -150,5 +333,10 @@ fn cleanup_event_ui(
     for entity in query.iter() {
         commands.entity(entity).despawn_recursive();
     }
+    commands.remove_resource::<ActiveEvent>();
     info!("【机缘】UI 已清理");
 }
+
+fn end_event_encounter(mut commands: Commands) {
+    commands.remove_resource::<EventEncounter>();
+}
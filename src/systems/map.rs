@@ -6,11 +6,17 @@ use bevy::prelude::*;
 use crate::states::GameState;
 use crate::components::{
     Player, Cultivation, PlayerDeck,
-    PlaySfxEvent, SfxType, CombatUiRoot,
+    PlaySfxEvent, SfxType, CombatUiRoot, PlayBgmEvent,
     relic::RelicCollection,
-    map::{MapProgress, MapNode, NodeType, MapNodeButton, RippleEffect, MapNodeContainer, MapUiRoot, BreakthroughButtonMarker, BreathingAnimation, OriginalSize, HoverEffect, EntranceAnimation, PulseAnimation, ConnectorDot}
+    map::{MapProgress, MapNode, NodeType, MapNodeButton, RippleEffect, MapNodeContainer, MapUiRoot, BreakthroughButtonMarker, BreathingAnimation, OriginalSize, HoverEffect, EntranceAnimation, PulseAnimation, ConnectorDot},
+    map::{route_range, ConnectionLine, MapNodeTooltip, MapNodeTooltipText, PlanRouteButton, RouteOverlayText, RoutePlan},
 };
-use crate::resources::save::GameStateSave;
+use crate::resources::save::{unix_now, ActiveSaveSlot, GameStateSave, PlayTime, PlayerRunState, SaveWriter, SAVE_VERSION};
+use crate::resources::ascension::Ascension;
+use crate::resources::act_data::ActAtlas;
+use crate::resources::event_data::EventHistory;
+use crate::resources::profile::{MetaProfile, RunVictory};
+use crate::resources::{EnvironmentConfig, RunSeed, RunStats};
 use crate::plugins::init_player;
 
 /// 地图插件
@@ -20,10 +26,12 @@ impl Plugin for MapPlugin {
     fn build(&self, app: &mut App) {
         // 初始化地图进度资源（确保重新开始时不闪退）
         app.init_resource::<MapProgress>();
+        app.init_resource::<RoutePlan>();
         
         // 在进入Map状态时设置地图UI
         app.add_systems(OnEnter(GameState::Map), (
             init_player,
+            check_run_victory,
             setup_map_ui, 
             setup_breakthrough_button, 
             setup_cultivation_status_ui
@@ -35,10 +43,54 @@ impl Plugin for MapPlugin {
             handle_map_button_clicks,
             handle_map_scrolling,
             animate_connector_dots, // 激活灵力流动
+            handle_route_planning,
+            refresh_route_overlay.run_if(resource_changed::<RoutePlan>),
+            update_node_tooltip,
         ).run_if(in_state(GameState::Map)));
+        // 换界时切换氛围与曲目
+        app.add_systems(Update, sync_act_atmosphere);
     }
 }
 
+/// 所在大境变化时套用该界的雾色、环境光与风力；在地图上换界时淡入该界曲目
+fn sync_act_atmosphere(
+    map_progress: Option<Res<MapProgress>>,
+    state: Res<State<GameState>>,
+    mut applied: Local<Option<u32>>,
+    mut environment: ResMut<EnvironmentConfig>,
+    ambient: Option<ResMut<AmbientLight>>,
+    mut cameras: Query<(Entity, &mut Camera), With<Camera3d>>,
+    mut commands: Commands,
+    mut bgm_events: EventWriter<PlayBgmEvent>,
+) {
+    let act_index = map_progress.map_or(0, |p| p.act);
+    if *applied == Some(act_index) {
+        return;
+    }
+    let act = ActAtlas::builtin().act(act_index);
+    *environment = act.environment_config();
+
+    if let Some(mut ambient) = ambient {
+        ambient.color = environment.ambient_color;
+        ambient.brightness = environment.ambient_brightness;
+    }
+    for (entity, mut camera) in cameras.iter_mut() {
+        camera.clear_color = ClearColorConfig::Custom(environment.fog_color);
+        commands.entity(entity).insert(DistanceFog {
+            color: environment.fog_color,
+            falloff: FogFalloff::Linear { start: 25.0, end: 80.0 },
+            ..default()
+        });
+    }
+
+    if applied.is_some() && *state.get() == GameState::Map {
+        bgm_events.send(PlayBgmEvent::new(act.music.map).with_fade_in(2.0));
+        info!("【背景音乐】换界切换: {}", act.music.map.chinese_name());
+    }
+    info!("【氛围】已套用{}的天象", act.name);
+    *applied = Some(act_index);
+}
+
 /// 清理地图UI
 fn cleanup_map_ui(mut commands: Commands, query: Query<Entity, With<MapUiRoot>>) {
     for entity in query.iter() {
@@ -46,6 +98,43 @@ fn cleanup_map_ui(mut commands: Commands, query: Query<Entity, With<MapUiRoot>>)
     }
 }
 
+/// 镇守首领伏诛：尚有下一界则带着牌组、法宝与修为踏入下一界；
+/// 末界首领伏诛即飞升，记入修行档案、解锁下一重天劫并清空档位
+fn check_run_victory(
+    map_progress: Option<ResMut<MapProgress>>,
+    victory: Option<Res<RunVictory>>,
+    mut profile: ResMut<MetaProfile>,
+    ascension: Res<Ascension>,
+    save_slot: Res<ActiveSaveSlot>,
+    save_writer: Res<SaveWriter>,
+    mut run_seed: ResMut<RunSeed>,
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(mut map_progress) = map_progress else { return };
+    if victory.is_some() || !map_progress.is_boss_defeated() {
+        return;
+    }
+    let atlas = ActAtlas::builtin();
+    if !atlas.is_final(map_progress.act) {
+        let next = atlas.act(map_progress.act + 1);
+        map_progress.advance_act(&next.map_config(&ascension), &mut run_seed.map);
+        // 新地图的节点 id 从零重排，旧的路线规划不再作数
+        commands.insert_resource(RoutePlan::default());
+        info!("🌌【换界】镇守伏诛，界壁洞开，踏入{}！", next.name);
+        return;
+    }
+    let unlocked = profile.record_win(*ascension);
+    profile.save();
+    save_writer.delete(save_slot.0);
+    match unlocked {
+        Some(level) => info!("【飞升】渡尽劫波！已解锁天劫第 {} 重", level),
+        None => info!("【飞升】渡尽劫波！累计飞升 {} 次", profile.wins),
+    }
+    commands.insert_resource(RunVictory { unlocked });
+    next_state.set(GameState::GameOver);
+}
+
 /// 设置地图UI
 pub fn setup_map_ui(
     mut commands: Commands,
@@ -55,35 +144,42 @@ pub fn setup_map_ui(
     player_deck: Res<PlayerDeck>,
     relic_collection: Res<RelicCollection>,
     existing_ui: Query<Entity, With<CombatUiRoot>>, 
+    mut run_seed: ResMut<RunSeed>,
+    save_slot: Res<ActiveSaveSlot>,
+    play_time: Res<PlayTime>,
+    ascension: Res<Ascension>,
+    event_history: Res<EventHistory>,
+    mut route_plan: ResMut<RoutePlan>,
+    run_stats: Res<RunStats>,
+    save_writer: Res<SaveWriter>,
 ) {
     let chinese_font: Handle<Font> = asset_server.load("fonts/Arial Unicode.ttf");
 
-    // --- [统一自动存档点] 进入地图即保存，安全稳健 ---
-    if let Some(progress) = &map_progress {
+    // --- [统一自动存档点] 进入地图即保存，安全稳健（飞升后档位已清空，不再存档） ---
+    if let Some(progress) = map_progress.as_ref().filter(|p| !p.is_boss_defeated()) {
         if let Ok((player, cultivation)) = player_query.get_single() {
             let save = GameStateSave {
-                player: player.clone(),
+                version: SAVE_VERSION,
+                player: PlayerRunState::capture(player, cultivation),
                 cultivation: cultivation.clone(),
                 deck: player_deck.cards.clone(),
                 relics: relic_collection.relic.clone(),
                 map_nodes: progress.nodes.clone(),
                 current_map_node_id: progress.current_node_id,
                 current_map_layer: progress.current_layer,
+                act: progress.act,
+                unknown_odds: progress.unknown_odds,
+                seed: run_seed.seed,
+                streams: run_seed.positions(),
+                play_time: play_time.seconds,
+                saved_at: unix_now(),
+                ascension: ascension.level,
+                combat: None,
+                seen_events: event_history.seen.clone(),
+                run_stats: *run_stats,
             };
-            
-            // [核心修复] 使用线程池进行真正的异步磁盘 IO
-            // 彻底解决阻塞主线程导致的卡死问题
-            use bevy::tasks::AsyncComputeTaskPool;
-            let thread_pool = AsyncComputeTaskPool::get();
-            thread_pool.spawn(async move {
-                if let Err(e) = save.save_to_disk() {
-                    error!("【存档失败】无法持久化识海进度: {}", e);
-                } else {
-                    // 注意：在异步线程中直接使用 info! 是安全的，日志宏通常是线程安全的
-                    // 但不要在里面操作 World
-                }
-            }).detach();
-            
+            // 交给存档线程排队写入，不阻塞主线程，也不与战斗存档交错
+            save_writer.save(save, save_slot.0);
             info!("【自动存档】正在同步进度至识海...");
         }
     }
@@ -92,6 +188,7 @@ pub fn setup_map_ui(
     if map_progress.is_none() {
         warn!("【地图】识海中未发现地图进度资源，正在推演全新命途...");
         commands.insert_resource(MapProgress::default());
+        *route_plan = RoutePlan::default();
         // 标记本次 setup 提前结束，依赖下一帧的资源就绪
         return; 
     }
@@ -106,14 +203,19 @@ pub fn setup_map_ui(
     // 关键修复：如果进度中节点为空（可能由于坏档或旧版本存档），强制重新生成
     if progress.nodes.is_empty() {
         warn!("【地图系统】检测到空地图节点，正在强制重新生成...");
-        use crate::components::map::{MapConfig, generate_map_nodes};
-        progress.nodes = generate_map_nodes(&MapConfig::default(), 0);
+        use crate::components::map::generate_map_nodes;
+        let act = ActAtlas::builtin().act(progress.act);
+        progress.nodes = generate_map_nodes(&act.map_config(&ascension), 0, &mut run_seed.map);
         progress.refresh_unlocks();
+        *route_plan = RoutePlan::default();
         // 立即更新资源，防止其它系统也读到空数据
         commands.insert_resource(progress.clone());
     }
 
     let nodes = progress.nodes.clone();
+    route_plan.sync(&nodes);
+    let atlas = ActAtlas::builtin();
+    let act_label = format!("{} · 第 {}/{} 界", atlas.act(progress.act).name, progress.act + 1, atlas.count());
     
     // 自动推断当前活跃层级：优先取当前所在节点，若无则取已完成节点的最高层
     let current_layer = if let Some(id) = progress.current_node_id {
@@ -174,6 +276,11 @@ pub fn setup_map_ui(
                 margin: UiRect::bottom(Val::Px(20.0)),
                 ..default()
             }).with_children(|header| {
+                header.spawn((
+                    Text::new(act_label.clone()),
+                    TextFont { font: chinese_font.clone(), font_size: 18.0, ..default() },
+                    TextColor(Color::srgb(0.7, 0.7, 0.5)),
+                ));
                 header.spawn((
                     Text::new("寻 仙 觅 缘"),
                     TextFont { font: chinese_font.clone(), font_size: 42.0, ..default() },
@@ -186,6 +293,8 @@ pub fn setup_map_ui(
                         Realm::FoundationEstablishment => "筑基期 - 神识范围: 2层",
                         Realm::GoldenCore => "金丹期 - 神识范围: 3层",
                         Realm::NascentSoul => "元婴期 - 神识纵览全程",
+                        Realm::SpiritTransformation => "化神期 - 神识纵览全程",
+                        Realm::TribulationTranscendence => "渡劫期 - 神识纵览全程",
                     }
                 } else {
                     "神识扫描中..."
@@ -270,7 +379,7 @@ pub fn setup_map_ui(
                                         // 显示该层的所有节点
                                         for node in &nodes {
                                             if node.position.0 == layer {
-                                                spawn_map_node(layer_parent, node, &chinese_font, &progress);
+                                                spawn_map_node(layer_parent, node, &chinese_font, &progress, &route_plan);
                                             }
                                         }
                                     });
@@ -292,7 +401,7 @@ pub fn setup_map_ui(
                                             if node.position.0 == layer {
                                                 for &next_id in &node.next_nodes {
                                                     if let Some(next_node) = nodes.iter().find(|n| n.id == next_id) {
-                                                        spawn_path_indicator(connector, node, next_node, from_count, to_count, &progress);
+                                                        spawn_path_indicator(connector, node, next_node, from_count, to_count, &route_plan);
                                                     }
                                                 }
                                             }
@@ -334,22 +443,265 @@ pub fn setup_map_ui(
                     ));
                 });
 
-                // 2. 灵石信息
+                // 2. 规划路线开关
+                footer.spawn((
+                    Button,
+                    Node {
+                        width: Val::Px(120.0),
+                        height: Val::Px(45.0),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    BackgroundColor(plan_button_color(route_plan.active)),
+                    BorderRadius::all(Val::Px(8.0)),
+                    PlanRouteButton,
+                )).with_children(|btn| {
+                    btn.spawn((
+                        Text::new("规划路线"),
+                        TextFont { font: chinese_font.clone(), font_size: 22.0, ..default() },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+
+                // 3. 灵石信息
                 footer.spawn((
                     Text::new(format!("灵石: {}", player_gold)),
                     TextFont { font: chinese_font.clone(), font_size: 20.0, ..default() },
                     TextColor(Color::srgb(1.0, 0.8, 0.2)),
                 ));
             });
+
+            // 规划路线统计（右下角）
+            let overlay = route_overlay_text(&route_plan, &nodes);
+            parent.spawn((
+                Text::new(overlay.clone()),
+                TextFont { font: chinese_font.clone(), font_size: 18.0, ..default() },
+                TextColor(PLAN_COLOR),
+                Node {
+                    display: if overlay.is_empty() { Display::None } else { Display::Flex },
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(20.0),
+                    bottom: Val::Px(20.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                RouteOverlayText,
+            ));
+
+            // 节点悬停提示（跟随鼠标）
+            parent.spawn((
+                Node {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    width: Val::Px(TOOLTIP_WIDTH),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    border: UiRect::all(Val::Px(1.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.95)),
+                BorderColor(Color::srgb(0.5, 0.5, 0.4)),
+                BorderRadius::all(Val::Px(8.0)),
+                ZIndex(10),
+                MapNodeTooltip,
+            )).with_children(|tooltip| {
+                tooltip.spawn((
+                    Text::new(""),
+                    TextFont { font: chinese_font.clone(), font_size: 16.0, ..default() },
+                    TextColor(Color::srgb(0.9, 0.9, 0.85)),
+                    MapNodeTooltipText,
+                ));
+            });
         });
 }
 
+/// 规划路线的标记色
+const PLAN_COLOR: Color = Color::srgb(1.0, 0.8, 0.2);
+/// 悬停提示宽度
+const TOOLTIP_WIDTH: f32 = 280.0;
+
+fn plan_button_color(active: bool) -> Color {
+    if active { Color::srgb(0.6, 0.45, 0.1) } else { Color::srgb(0.25, 0.25, 0.2) }
+}
+
+/// 节点描边：规划路线上的节点以金色标出
+fn node_border(node: &MapNode, progress: &MapProgress, plan: &RoutePlan) -> (UiRect, Color) {
+    if plan.route.contains(&node.id) {
+        return (UiRect::all(Val::Px(3.0)), PLAN_COLOR);
+    }
+    let is_current_active = progress.current_node_id == Some(node.id) && !node.completed;
+    let color = if node.completed {
+        Color::BLACK
+    } else if is_current_active || node.unlocked {
+        Color::WHITE
+    } else {
+        Color::BLACK
+    };
+    let width = if node.unlocked && !node.completed { 3.0 } else { 1.0 };
+    (UiRect::all(Val::Px(width)), color)
+}
+
+/// 连线光晕：规划路线为金色，已走通的路为幽蓝
+fn path_glow(from_node: &MapNode, to_node: &MapNode, plan: &RoutePlan) -> Color {
+    if plan.includes_edge(from_node.id, to_node.id) {
+        Color::srgba(1.0, 0.8, 0.2, 0.8)
+    } else if from_node.completed {
+        Color::srgba(0.4, 0.7, 1.0, 0.5)
+    } else {
+        Color::srgba(0.0, 0.0, 0.0, 0.0)
+    }
+}
+
+/// 区间显示：上下限相同时只写一个数
+fn format_span((lo, hi): (u32, u32)) -> String {
+    if lo == hi { lo.to_string() } else { format!("{}~{}", lo, hi) }
+}
+
+/// 规划路线统计面板的内容（未规划且不在规划模式时为空）
+fn route_overlay_text(plan: &RoutePlan, nodes: &[MapNode]) -> String {
+    let Some(&last) = plan.route.last() else {
+        return if plan.active {
+            "规划中：点击可前往的节点起笔，逐个标记通往首领的路线".to_string()
+        } else {
+            String::new()
+        };
+    };
+    let Some(range) = plan.summary(nodes) else {
+        return format!("已规划 {} 步，此路不通首领", plan.route.len());
+    };
+    let reaches_boss = nodes.iter().any(|n| n.id == last && n.node_type == NodeType::Boss);
+    let head = if reaches_boss {
+        format!("已规划 {} 步，直抵首领", plan.route.len())
+    } else {
+        format!("已规划 {} 步，其后余路 {} 条", plan.route.len(), range.routes)
+    };
+    format!(
+        "{}\n精英 {}　洞府 {}　坊市 {}",
+        head, format_span(range.elites), format_span(range.rests), format_span(range.shops)
+    )
+}
+
+/// 规划模式：开关按钮切换模式，点击节点标记或撤回
+fn handle_route_planning(
+    mut plan: ResMut<RoutePlan>,
+    progress: Res<MapProgress>,
+    plan_buttons: Query<Ref<Interaction>, With<PlanRouteButton>>,
+    node_buttons: Query<(Ref<Interaction>, &MapNodeButton)>,
+    mut sfx_events: EventWriter<PlaySfxEvent>,
+) {
+    let pressed = |interaction: &Ref<Interaction>| interaction.is_changed() && **interaction == Interaction::Pressed;
+
+    if plan_buttons.iter().any(|i| pressed(&i)) {
+        sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
+        plan.active = !plan.active;
+        info!("【地图】{}路线规划", if plan.active { "开始" } else { "结束" });
+        return;
+    }
+    if !plan.active {
+        return;
+    }
+    for (interaction, button) in node_buttons.iter() {
+        if !pressed(&interaction) {
+            continue;
+        }
+        if plan.mark(&progress.nodes, button.node_id) {
+            sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
+        } else {
+            sfx_events.send(PlaySfxEvent::new(SfxType::UiError));
+        }
+    }
+}
+
+/// 规划路线变化时刷新节点描边、连线光晕与统计面板
+fn refresh_route_overlay(
+    plan: Res<RoutePlan>,
+    progress: Res<MapProgress>,
+    mut buttons: Query<(&MapNodeButton, &mut Node, &mut BorderColor), Without<RouteOverlayText>>,
+    mut dots: Query<(&ConnectionLine, &mut BoxShadow)>,
+    mut overlay: Query<(&mut Text, &mut Node), With<RouteOverlayText>>,
+    mut plan_buttons: Query<&mut BackgroundColor, With<PlanRouteButton>>,
+) {
+    let find = |id: u32| progress.nodes.iter().find(|n| n.id == id);
+
+    for (button, mut node, mut border) in buttons.iter_mut() {
+        if let Some(map_node) = find(button.node_id) {
+            let (width, color) = node_border(map_node, &progress, &plan);
+            node.border = width;
+            border.0 = color;
+        }
+    }
+    for (line, mut shadow) in dots.iter_mut() {
+        if let (Some(from), Some(to)) = (find(line.from_node_id), find(line.to_node_id)) {
+            shadow.color = path_glow(from, to, &plan);
+        }
+    }
+    if let Ok((mut text, mut node)) = overlay.get_single_mut() {
+        text.0 = route_overlay_text(&plan, &progress.nodes);
+        node.display = if text.0.is_empty() { Display::None } else { Display::Flex };
+    }
+    for mut color in plan_buttons.iter_mut() {
+        color.0 = plan_button_color(plan.active);
+    }
+}
+
+/// 悬停节点时显示类型说明与经此通往首领的路线统计
+fn update_node_tooltip(
+    mut shown: Local<Option<u32>>,
+    progress: Res<MapProgress>,
+    buttons: Query<(&Interaction, &MapNodeButton)>,
+    windows: Query<&Window>,
+    mut tooltip: Query<&mut Node, With<MapNodeTooltip>>,
+    mut tooltip_text: Query<&mut Text, With<MapNodeTooltipText>>,
+) {
+    let Ok(mut panel) = tooltip.get_single_mut() else { return };
+    let hovered = buttons.iter()
+        .find(|(interaction, _)| **interaction != Interaction::None)
+        .and_then(|(_, button)| progress.nodes.iter().find(|n| n.id == button.node_id));
+    let Some(node) = hovered else {
+        panel.display = Display::None;
+        *shown = None;
+        return;
+    };
+
+    if let Some(cursor) = windows.get_single().ok().and_then(|w| w.cursor_position().map(|c| (c, w.width()))) {
+        let (position, width) = cursor;
+        let left = if position.x + 20.0 + TOOLTIP_WIDTH > width { position.x - 20.0 - TOOLTIP_WIDTH } else { position.x + 20.0 };
+        panel.left = Val::Px(left.max(0.0));
+        panel.top = Val::Px(position.y + 20.0);
+    }
+    panel.display = Display::Flex;
+
+    if *shown == Some(node.id) {
+        return;
+    }
+    *shown = Some(node.id);
+
+    let mut lines = vec![
+        format!("{}　第 {} 层", node.node_type.name(), node.layer() + 1),
+        node.node_type.description().to_string(),
+    ];
+    if node.node_type != NodeType::Boss && !node.completed {
+        if let Some(range) = route_range(&progress.nodes, node.id) {
+            lines.push(format!("经此通往首领的路线 {} 条", range.routes));
+            lines.push(format!(
+                "精英 {}　洞府 {}　坊市 {}",
+                format_span(range.elites), format_span(range.rests), format_span(range.shops)
+            ));
+        }
+    }
+    if let Ok(mut text) = tooltip_text.get_single_mut() {
+        text.0 = lines.join("\n");
+    }
+}
+
 /// 地图节点生成器
 fn spawn_map_node(
     parent: &mut ChildBuilder,
     node: &MapNode,
     font: &Handle<Font>,
     map_progress: &MapProgress,
+    route_plan: &RoutePlan,
 ) {
     // 检查是否是当前节点 (且尚未完成)
     let is_current_active = map_progress.current_node_id == Some(node.id) && !node.completed;
@@ -367,21 +719,23 @@ fn spawn_map_node(
     };
 
     // 根据节点状态计算显示颜色
-    let (display_color, border_color) = if node.completed {
+    let display_color = if node.completed {
         // 已完成：深蓝色足迹
-        (Color::srgb(0.1, 0.3, 0.6), Color::BLACK)
+        Color::srgb(0.1, 0.3, 0.6)
     } else if is_current_active {
         // 当前正处于：黄色发光
-        (Color::srgb(1.0, 0.9, 0.3), Color::WHITE)
+        Color::srgb(1.0, 0.9, 0.3)
     } else if node.unlocked {
-        // 已解锁：高对比度彩色 + 白色描边
-        (node_color, Color::WHITE)
+        // 已解锁：高对比度彩色
+        node_color
     } else {
         // 未解锁：暗色半透明
         let mut c = node_color;
         c.set_alpha(0.2); 
-        (c, Color::BLACK)
+        c
     };
+    // 描边：已解锁为白色，规划路线上为金色
+    let (border, border_color) = node_border(node, map_progress, route_plan);
 
     let mut entity_cmds = parent.spawn((
         Button,
@@ -390,7 +744,7 @@ fn spawn_map_node(
             height: Val::Px(60.0),
             justify_content: JustifyContent::Center,
             align_items: AlignItems::Center,
-            border: if node.unlocked && !node.completed { UiRect::all(Val::Px(3.0)) } else { UiRect::all(Val::Px(1.0)) },
+            border,
             ..default()
         },
         BackgroundColor(display_color),
@@ -425,8 +779,9 @@ fn spawn_map_node(
             NodeType::Boss => "王",
             NodeType::Shop => "坊",
             NodeType::Rest => "府",
-            NodeType::Treasure => "缘",
-            _ => "？",
+            NodeType::Treasure => "宝",
+            NodeType::Event => "缘",
+            NodeType::Unknown => "？",
         };
         btn.spawn((
             Text::new(icon),
@@ -446,7 +801,11 @@ fn handle_map_button_clicks(
     relics: Res<RelicCollection>,
     button_queries: Query<(&Interaction, &MapNodeButton, &Node)>, // 移除 Changed<Interaction>
     mut sfx_events: EventWriter<PlaySfxEvent>,
+    mut run_seed: ResMut<RunSeed>,
+    route_plan: Res<RoutePlan>,
 ) {
+    // 规划模式下点击节点只作标记
+    if route_plan.active { return; }
     let Some(mut map_progress) = map_progress_opt else { return; };
     for (interaction, node_btn, node) in button_queries.iter() {
         if matches!(interaction, Interaction::Pressed) {
@@ -502,6 +861,15 @@ fn handle_map_button_clicks(
             
             // --- [优化] 移除此处同步存档，防止跳转时的 IO 阻塞 ---
 
+            // 未知节点在踏入时才揭示真身
+            let node_type = if node_type == NodeType::Unknown {
+                let revealed = map_progress.unknown_odds.roll(&mut run_seed.events);
+                info!("【地图】未知节点 {} 揭示为 {:?}", node_id, revealed);
+                revealed
+            } else {
+                node_type
+            };
+
             // 根据节点类型切换状态
             match node_type {
                 NodeType::Normal | NodeType::Elite | NodeType::Boss => {
@@ -522,11 +890,9 @@ fn handle_map_button_clicks(
                 }
                 NodeType::Treasure => {
                     info!("【地图】偶遇上古宝箱: {}", node_id);
-                    next_state.set(GameState::Reward);
-                }
-                _ => {
-                    warn!("【地图】节点 {} 类型 {:?} 尚未实现逻辑", node_id, node_type);
+                    next_state.set(GameState::Treasure);
                 }
+                NodeType::Unknown => unreachable!("未知节点已在上方揭示"),
             }
         }
     }
@@ -559,12 +925,7 @@ fn setup_cultivation_status_ui(
     let chinese_font: Handle<Font> = asset_server.load("fonts/Arial Unicode.ttf");
 
     if let Ok((player, cultivation)) = player_query.get_single() {
-        let realm_name = match cultivation.realm {
-            crate::components::cultivation::Realm::QiRefining => "炼气期",
-            crate::components::cultivation::Realm::FoundationEstablishment => "筑基期",
-            crate::components::cultivation::Realm::GoldenCore => "金丹期",
-            crate::components::cultivation::Realm::NascentSoul => "元婴期",
-        };
+        let realm_name = cultivation.realm.name();
 
         commands
             .spawn((
@@ -689,9 +1050,10 @@ fn spawn_path_indicator(
     to_node: &MapNode,
     from_count: f32,
     to_count: f32,
-    _progress: &MapProgress,
+    route_plan: &RoutePlan,
 ) {
     let is_path_unlocked = from_node.completed;
+    let glow = path_glow(from_node, to_node, route_plan);
     
     // --- 极致对位算法 ---
     let get_x_percent = |idx: i32, total: f32| -> f32 {
@@ -736,11 +1098,7 @@ fn spawn_path_indicator(
             }),
             // --- 大作级视觉细节：外发光 ---
             BoxShadow {
-                color: if is_path_unlocked {
-                    Color::srgba(0.4, 0.7, 1.0, 0.5) // 幽蓝光晕
-                } else {
-                    Color::srgba(0.0, 0.0, 0.0, 0.0)
-                },
+                color: glow,
                 blur_radius: Val::Px(8.0),
                 spread_radius: Val::Px(2.0),
                 ..default()
@@ -749,6 +1107,7 @@ fn spawn_path_indicator(
             ConnectorDot {
                 offset: (from_node.id as f32 * 1.3) + (i as f32 * 0.25), // 灵动的相位偏移
             },
+            ConnectionLine { from_node_id: from_node.id, to_node_id: to_node.id },
         ));
     }
 }
@@ -10,12 +10,18 @@ pub mod hit_stop;
 pub mod relic;
 pub mod shop;
 pub mod rest;
+pub mod treasure;
 pub mod audio;
 pub mod background_music;
 pub mod ui;
 pub mod map;
 pub mod event;
 pub mod enemy_gen;
+pub mod combat_engine;
+pub mod card_data;
+pub mod relic_data;
+pub mod archive;
+pub mod tribulation;
 
 pub use animation::AnimationPlugin;
 pub use sprite::SpritePlugin;
@@ -26,12 +32,18 @@ pub use hit_stop::HitStopPlugin;
 pub use screen_effect::ScreenEffectPlugin;
 pub use relic::{
     RelicPlugin, RelicUiPlugin, CombatStartProcessed,
-    trigger_relics_on_combat_start, trigger_relics_on_phase_change, trigger_relics_on_card_played
+    trigger_relics_on_combat_start, send_relic_triggered_events,
 };
 pub use shop::{ShopPlugin, update_gold_display};
 pub use rest::RestPlugin;
+pub use treasure::TreasurePlugin;
 pub use event::EventPlugin;
 pub use audio::SfxPlugin;
 pub use background_music::BackgroundMusicPlugin;
 pub use ui::UiPlugin;
-pub use map::MapPlugin;
\ No newline at end of file
+pub use map::MapPlugin;
+pub use combat_engine::{CombatEngine, CombatEvent};
+pub use card_data::CardDataPlugin;
+pub use relic_data::RelicDataPlugin;
+pub use archive::ArchivePlugin;
+pub use tribulation::{TribulationBattle, TribulationPlugin};
\ No newline at end of file
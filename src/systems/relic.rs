@@ -5,8 +5,12 @@
 use bevy::prelude::*;
 use crate::components::*;
 use crate::components::combat::Environment;
-use crate::components::relic::{RelicUiMarker, RelicItemMarker};
+use crate::components::relic::{RelicUiMarker, RelicItemMarker, RelicFlash};
 use crate::states::GameState;
+use crate::resources::RunSeed;
+use crate::resources::relic_data::RelicLibrary;
+use crate::plugins::{snapshot_combat_engine, write_back_combat_engine};
+use crate::systems::combat_engine::CombatEvent;
 
 /// 遗物插件
 pub struct RelicPlugin;
@@ -21,15 +25,12 @@ impl Plugin for RelicPlugin {
         app.init_resource::<RelicCollection>();
         app.add_systems(Startup, initialize_starting_relics);
         app.init_resource::<CombatStartProcessed>();
-        app.init_resource::<PreviousTurnPhase>();
 
-        // 遗物效果系统
+        // 遗物效果系统（其余时机由规则引擎在出牌、回合切换与敌人行动中触发）
         app.add_systems(Update, (
-            trigger_relics_on_combat_start.run_if(in_state(GameState::Combat)),
-            trigger_relics_on_phase_change.run_if(in_state(GameState::Combat)),
-            trigger_relics_on_draw.run_if(in_state(GameState::Combat)),
-            trigger_relics_on_card_played.run_if(in_state(GameState::Combat)),
-        ));
+            trigger_relics_on_combat_start,
+            log_relic_triggers,
+        ).run_if(in_state(GameState::Combat)));
 
         // 在退出战斗状态时重置遗物触发标志
         app.add_systems(OnExit(GameState::Combat), reset_relic_triggers);
@@ -40,12 +41,6 @@ impl Plugin for RelicPlugin {
 // 遗物效果触发系统
 // ============================================================================
 
-/// 跟踪前一个回合阶段（用于检测阶段变化）
-#[derive(Resource, Default)]
-struct PreviousTurnPhase {
-    phase: Option<TurnPhase>,
-}
-
 /// 标记战斗开始时是否已处理过遗物
 #[derive(Resource, Default)]
 pub struct CombatStartProcessed {
@@ -53,14 +48,14 @@ pub struct CombatStartProcessed {
 }
 
 /// 初始化玩家起始遗物
-fn initialize_starting_relics(mut relic_collection: ResMut<RelicCollection>) {
+fn initialize_starting_relics(mut relic_collection: ResMut<RelicCollection>, library: Res<RelicLibrary>) {
     info!("【遗物系统】初始化起始遗物");
 
-    // 添加燃烧之血作为初始遗物（方便测试和游戏体验）
-    let burning_blood = Relic::burning_blood();
-    relic_collection.add_relic_forced(burning_blood);
+    // 开局法宝由图谱指定（默认宗门的飞剑符；新开一局时按所选宗门重新发放）
+    let starting = library.starting_relic(Sect::default());
+    info!("【遗物系统】已添加初始遗物: {}（{}）", starting.name, starting.description);
+    relic_collection.add_relic_forced(starting);
 
-    info!("【遗物系统】已添加初始遗物: 燃烧之血（战斗开始时对所有敌人造成3点伤害）");
     info!("【遗物系统】当前遗物数量: {}", relic_collection.count());
 }
 
@@ -73,15 +68,21 @@ fn reset_relic_triggers(mut combat_start_processed: ResMut<CombatStartProcessed>
 }
 
 /// 战斗开始时触发遗物效果
+///
+/// 等待首回合手牌抽完、敌人就位后，由规则引擎结算战斗开始类与额外抽牌类遗物。
+/// 其余时机（回合开始/结束、出牌、造成与受到伤害）由引擎在对应操作中自行触发。
 pub fn trigger_relics_on_combat_start(
     mut combat_start_processed: ResMut<CombatStartProcessed>,
-    relic_collection: Res<RelicCollection>,
-    mut enemy_query: Query<&mut Enemy>,
-    mut player_query: Query<&mut Player>,
+    mut relic_collection: ResMut<RelicCollection>,
+    combat_state: Option<Res<CombatState>>,
+    mut player_query: Query<(&mut Player, &Cultivation)>,
+    mut enemy_query: Query<(Entity, &mut Enemy)>,
     mut hand_query: Query<&mut Hand>,
     mut draw_pile_query: Query<&mut DrawPile>,
     mut discard_pile_query: Query<&mut DiscardPile>,
     env: Option<Res<Environment>>,
+    mut run_seed: ResMut<RunSeed>,
+    mut relic_events: EventWriter<RelicTriggeredEvent>,
 ) {
     // 防止重复触发
     if combat_start_processed.processed {
@@ -89,201 +90,52 @@ pub fn trigger_relics_on_combat_start(
     }
 
     // 检查是否有必要的目标存在（避免在实体尚未生成时提前锁定标志位）
-    // 我们至少需要看到玩家或者是敌人
-    if player_query.is_empty() && enemy_query.is_empty() {
+    let Some(combat_state) = combat_state else { return; };
+    if !combat_state.cards_drawn_this_turn || enemy_query.is_empty() {
         return;
     }
 
-    let mut effect_applied = false;
-    let env_ref = env.as_ref().map(|r| r.as_ref());
-
-    for relic in &relic_collection.relic {
-        for effect in &relic.effects {
-            match effect {
-                RelicEffect::OnCombatStart { damage, block, draw_cards } => {
-                    // 造成伤害 (对所有敌人)
-                    if *damage > 0 {
-                        let mut damage_dealt = false;
-                        for mut enemy in enemy_query.iter_mut() {
-                            enemy.take_damage_with_env(*damage, env_ref);
-                            info!("  遗物 [{}] 触发：对敌人 [{}] 造成 {} 点伤害", relic.name, enemy.name, damage);
-                            damage_dealt = true;
-                        }
-                        if damage_dealt { effect_applied = true; }
-                    }
-
-                    // 获得护甲
-                    if *block > 0 {
-                        if let Ok(mut player) = player_query.get_single_mut() {
-                            player.gain_block(*block);
-                            info!("  遗物 [{}] 触发：获得 {} 点护甲", relic.name, block);
-                            effect_applied = true;
-                        }
-                    }
-
-                    // 抽牌
-                    if *draw_cards > 0 {
-                        let mut drawn = 0;
-                        for _ in 0..*draw_cards {
-                            if let Ok(mut draw_pile) = draw_pile_query.get_single_mut() {
-                                if draw_pile.count == 0 {
-                                    if let Ok(mut discard_pile) = discard_pile_query.get_single_mut() {
-                                        let cards = discard_pile.clear();
-                                        if !cards.is_empty() {
-                                            draw_pile.shuffle_from_discard(cards);
-                                        }
-                                    }
-                                }
-
-                                if let Some(card) = draw_pile.draw_card() {
-                                    if let Ok(mut hand) = hand_query.get_single_mut() {
-                                        if hand.add_card(card) {
-                                            drawn += 1;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        if drawn > 0 {
-                            info!("  遗物 [{}] 触发：抽了 {} 张牌", relic.name, drawn);
-                            effect_applied = true;
-                        }
-                    }
-                }
-                _ => {}
-            }
+    let Some(mut engine) = snapshot_combat_engine(
+        &player_query, &enemy_query, &draw_pile_query, &discard_pile_query, &hand_query,
+        env.as_deref(), combat_state.phase, &run_seed, &relic_collection,
+    ) else { return; };
+    let combat_events = engine.trigger_combat_start_relics();
+    write_back_combat_engine(
+        &engine, &mut player_query, &mut enemy_query, &mut draw_pile_query, &mut discard_pile_query, &mut hand_query, &mut run_seed, &mut relic_collection,
+    );
+    send_relic_triggered_events(&combat_events, &mut relic_events);
+    for event in &combat_events {
+        if let CombatEvent::RealmPassiveTriggered { passive } = event {
+            info!("【境界】{}：{}", passive.name(), passive.description());
         }
     }
 
-    if effect_applied {
-        info!("【遗物系统】战斗开始，遗物效果已应用");
-        combat_start_processed.processed = true;
-    }
+    info!("【遗物系统】战斗开始，遗物效果已应用");
+    combat_start_processed.processed = true;
 }
 
-/// 监听回合阶段变化，在适当时机触发遗物效果
-pub fn trigger_relics_on_phase_change(
-    relic_collection: Res<RelicCollection>,
-    mut player_query: Query<&mut Player>,
-    hand_query: Query<&Hand>,
-    combat_state: Res<CombatState>,
-    mut prev_phase: ResMut<PreviousTurnPhase>,
-) {
-    let current_phase = combat_state.phase;
-
-    // 检查阶段是否发生变化
-    if prev_phase.phase == Some(current_phase) {
-        return; // 阶段没变，不处理
-    }
-
-    let old_phase = prev_phase.phase;
-    prev_phase.phase = Some(current_phase);
-
-    // 检测特定的阶段转换
-    match (old_phase, current_phase) {
-        // 敌人回合 → 玩家行动：新回合开始
-        (Some(TurnPhase::EnemyTurn), TurnPhase::PlayerAction) => {
-            info!("【遗物系统】新回合开始，触发回合开始遗物");
-            for relic in &relic_collection.relic {
-                for effect in &relic.effects {
-                    match effect {
-                        RelicEffect::OnTurnStart { energy, draw_cards } => {
-                            if *energy > 0 {
-                                if let Ok(mut player) = player_query.get_single_mut() {
-                                    player.gain_energy(*energy);
-                                    info!("  遗物 [{}] 触发：获得 {} 点能量", relic.name, energy);
-                                }
-                            }
-                            // 还可以处理 draw_cards 等其他效果
-                        }
-                        _ => {}
-                    }
-                }
-            }
-        }
-        // 玩家行动 → 敌人回合：回合结束
-        (Some(TurnPhase::PlayerAction), TurnPhase::EnemyTurn) => {
-            info!("【遗物系统】回合结束，触发回合结束遗物");
-            for relic in &relic_collection.relic {
-                for effect in &relic.effects {
-                    match effect {
-                        RelicEffect::OnTurnEnd { keep_cards } => {
-                            if let Ok(hand) = hand_query.get_single() {
-                                if hand.cards.len() > *keep_cards as usize {
-                                    info!("  遗物 [{}] 触发：保留最多 {} 张牌（当前手牌: {} 张）",
-                                        relic.name, keep_cards, hand.cards.len());
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
+/// 将规则引擎中的遗物触发转发为 [`RelicTriggeredEvent`]（日志与遗物栏闪烁）
+pub fn send_relic_triggered_events(combat_events: &[CombatEvent], relic_events: &mut EventWriter<RelicTriggeredEvent>) {
+    for event in combat_events {
+        if let CombatEvent::RelicTriggered { relic_id, effect } = event {
+            relic_events.send(RelicTriggeredEvent { relic_id: relic_id.clone(), effects: vec![effect.clone()] });
         }
-        _ => {}
     }
 }
 
-/// 抽牌时触发遗物效果
-fn trigger_relics_on_draw(
-    _relic_collection: Res<RelicCollection>,
-) {
-    // 额外抽牌逻辑在 draw_cards 系统中处理
-}
-
-/// 打出牌时触发遗物效果（如奇怪勺子）
-pub fn trigger_relics_on_card_played(
+/// 遗物触发日志
+fn log_relic_triggers(
+    mut relic_events: EventReader<RelicTriggeredEvent>,
     relic_collection: Res<RelicCollection>,
-    mut card_played_count: Local<CardPlayedCount>,
-    mut hand_query: Query<&mut Hand>,
-    mut draw_pile_query: Query<&mut DrawPile>,
-    mut discard_pile_query: Query<&mut DiscardPile>,
 ) {
-    card_played_count.0 += 1;
-
-    for relic in &relic_collection.relic {
-        for effect in &relic.effects {
-            match effect {
-                RelicEffect::OnCardPlayed { every_nth, draw_cards } => {
-                    if card_played_count.0 % every_nth == 0 {
-                        // 触发抽牌
-                        let mut drawn = 0;
-                        for _ in 0..*draw_cards {
-                            if let Ok(mut draw_pile) = draw_pile_query.get_single_mut() {
-                                if draw_pile.count == 0 {
-                                    if let Ok(mut discard_pile) = discard_pile_query.get_single_mut() {
-                                        let cards = discard_pile.clear();
-                                        if !cards.is_empty() {
-                                            draw_pile.shuffle_from_discard(cards);
-                                        }
-                                    }
-                                }
-
-                                if let Some(card) = draw_pile.draw_card() {
-                                    if let Ok(mut hand) = hand_query.get_single_mut() {
-                                        if hand.add_card(card) {
-                                            drawn += 1;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-
-                        if drawn > 0 {
-                            info!("  遗物 [{}] 触发：第 {} 张牌，抽 {} 张", relic.name, card_played_count.0, drawn);
-                        }
-                    }
-                }
-                _ => {}
-            }
+    for event in relic_events.read() {
+        let name = relic_collection.get(event.relic_id.as_str()).map(|r| r.name.as_str()).unwrap_or("未知遗物");
+        for effect in &event.effects {
+            info!("  遗物 [{}] 触发：{:?}", name, effect);
         }
     }
 }
 
-/// 打出的牌计数（本地资源）
-#[derive(Resource, Default)]
-struct CardPlayedCount(pub i32);
-
 // ============================================================================
 // 遗物UI系统
 // ============================================================================
@@ -294,7 +146,11 @@ pub struct RelicUiPlugin;
 impl Plugin for RelicUiPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(GameState::Combat), setup_relic_ui)
-            .add_systems(Update, update_relic_ui.run_if(in_state(GameState::Combat)))
+            .add_systems(Update, (
+                update_relic_ui,
+                flash_triggered_relics,
+                update_relic_flash,
+            ).chain().run_if(in_state(GameState::Combat)))
             .add_systems(OnExit(GameState::Combat), cleanup_relic_ui);
     }
 }
@@ -364,7 +220,7 @@ fn create_relic_item(parent: &mut ChildBuilder, relic: &Relic) {
             },
             BackgroundColor(rarity_color),
             BorderRadius::all(Val::Px(4.0)),
-            RelicItemMarker,
+            RelicItemMarker { relic_id: relic.id.clone() },
         ))
         .with_children(|parent| {
             // 遗物名称
@@ -476,6 +332,42 @@ fn update_relic_ui(
     }
 }
 
+/// 遗物触发时点亮对应的遗物栏
+fn flash_triggered_relics(
+    mut commands: Commands,
+    mut relic_events: EventReader<RelicTriggeredEvent>,
+    mut item_query: Query<(Entity, &RelicItemMarker, &mut BackgroundColor, Option<&RelicFlash>)>,
+) {
+    for event in relic_events.read() {
+        for (entity, marker, mut color, flash) in item_query.iter_mut() {
+            if marker.relic_id != event.relic_id {
+                continue;
+            }
+            // 连续触发时沿用最初的底色
+            let base_color = flash.map(|f| f.base_color).unwrap_or(color.0);
+            color.0 = Color::srgb(1.0, 0.95, 0.6);
+            commands.entity(entity).insert(RelicFlash { timer: Timer::from_seconds(0.5, TimerMode::Once), base_color });
+        }
+    }
+}
+
+/// 闪烁渐隐回稀有度底色
+fn update_relic_flash(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut item_query: Query<(Entity, &mut RelicFlash, &mut BackgroundColor)>,
+) {
+    for (entity, mut flash, mut color) in item_query.iter_mut() {
+        flash.timer.tick(time.delta());
+        let t = flash.timer.fraction();
+        color.0 = Color::srgb(1.0, 0.95, 0.6).mix(&flash.base_color, t);
+        if flash.timer.finished() {
+            color.0 = flash.base_color;
+            commands.entity(entity).remove::<RelicFlash>();
+        }
+    }
+}
+
 /// 清理遗物UI
 fn cleanup_relic_ui(
     mut commands: Commands,
@@ -0,0 +1,78 @@
+//! 法宝图谱加载系统
+//!
+//! 启动时加载 `assets/data/relics` 下的全部法宝数据文件，文件新增/修改后重新汇总图谱（调试构建下支持热重载）。
+
+use bevy::asset::LoadedFolder;
+use bevy::prelude::*;
+
+use crate::resources::relic_data::{RelicDataFile, RelicDataLoader, RelicLibrary};
+
+/// 法宝数据目录（相对 assets）
+const RELIC_DATA_DIR: &str = "data/relics";
+
+/// 法宝图谱插件
+pub struct RelicDataPlugin;
+
+impl Plugin for RelicDataPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<RelicDataFile>()
+            .init_asset_loader::<RelicDataLoader>()
+            // 先以内置图谱兜底，资源加载完成后替换
+            .init_resource::<RelicLibrary>()
+            .add_systems(Startup, load_relic_data)
+            .add_systems(Update, rebuild_relic_library);
+    }
+}
+
+/// 法宝数据目录句柄（持有以保证资源常驻并可热重载）
+#[derive(Resource)]
+struct RelicDataFolder(Handle<LoadedFolder>);
+
+fn load_relic_data(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(RelicDataFolder(asset_server.load_folder(RELIC_DATA_DIR)));
+}
+
+/// 数据文件加载或变更后重新汇总法宝图谱
+///
+/// 校验失败的文件不会进入资源库（错误由加载器报告文件与字段），图谱保持上一次的有效版本。
+fn rebuild_relic_library(
+    mut file_events: EventReader<AssetEvent<RelicDataFile>>,
+    mut folder_events: EventReader<AssetEvent<LoadedFolder>>,
+    folder: Option<Res<RelicDataFolder>>,
+    folders: Res<Assets<LoadedFolder>>,
+    files: Res<Assets<RelicDataFile>>,
+    asset_server: Res<AssetServer>,
+    mut library: ResMut<RelicLibrary>,
+) {
+    let files_changed = file_events.read().count() > 0;
+    let folder_changed = folder_events.read().count() > 0;
+    if !files_changed && !folder_changed {
+        return;
+    }
+    let Some(folder) = folder else { return; };
+    let Some(loaded) = folders.get(&folder.0) else { return; };
+
+    let mut sources: Vec<(String, &RelicDataFile)> = loaded
+        .handles
+        .iter()
+        .filter_map(|handle| {
+            let handle = handle.clone().try_typed::<RelicDataFile>().ok()?;
+            let data = files.get(&handle)?;
+            let path = asset_server.get_path(handle.id())?.to_string();
+            Some((path, data))
+        })
+        .collect();
+    if sources.is_empty() {
+        return;
+    }
+    // 按路径排序，保证图谱顺序稳定
+    sources.sort_by(|a, b| a.0.cmp(&b.0));
+
+    match RelicLibrary::from_files(sources.iter().map(|(path, data)| (path.as_str(), *data))) {
+        Ok(rebuilt) => {
+            info!("【法宝图谱】已载入 {} 个数据文件，共 {} 件法宝", sources.len(), rebuilt.len());
+            *library = rebuilt;
+        }
+        Err(e) => error!("【法宝图谱】数据无效，沿用上一版图谱：{}", e),
+    }
+}
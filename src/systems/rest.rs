@@ -4,6 +4,8 @@ use bevy::prelude::*;
 use bevy::text::TextFont;
 use crate::components::{Player, PlaySfxEvent, SfxType};
 use crate::states::GameState;
+use crate::resources::RunSeed;
+use crate::resources::ascension::Ascension;
 
 /// 休息UI标记
 #[derive(Component)]
@@ -52,6 +54,7 @@ pub fn setup_rest_ui(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     player_query: Query<(&Player, &crate::components::Cultivation)>,
+    ascension: Res<Ascension>,
 ) {
     info!("【洞府闭关】设置闭关UI");
 
@@ -133,7 +136,7 @@ pub fn setup_rest_ui(
                         TextColor(Color::WHITE),
                     ));
                     btn.spawn((
-                        Text::new("运转周天，恢复 30% 道行"),
+                        Text::new(format!("运转周天，恢复 {}% 道行", ascension.rest_heal_percent())),
                         TextFont { font_size: 14.0, font: chinese_font.clone(), ..default() },
                         TextColor(Color::srgb(0.7, 0.7, 0.7)),
                     ));
@@ -223,6 +226,8 @@ pub fn handle_rest_interactions(
     mut text_query: Query<&mut Text>,
     children_query: Query<&Children>,
     mut sfx_events: EventWriter<PlaySfxEvent>,
+    mut run_seed: ResMut<RunSeed>,
+    ascension: Res<Ascension>,
 ) {
     let player = player_query.get_single().expect("必须有玩家实体");
 
@@ -249,7 +254,7 @@ pub fn handle_rest_interactions(
     // 处理调息
     for interaction in breath_buttons.iter() {
         if matches!(interaction, Interaction::Pressed) {
-            let heal_amount = (player.max_hp as f32 * 0.3) as i32;
+            let heal_amount = player.max_hp * ascension.rest_heal_percent() as i32 / 100;
             // [关键修复] 立即执行治疗
             if let Ok(mut player_mut) = player_query.get_single_mut() {
                 player_mut.heal(heal_amount);
@@ -272,8 +277,7 @@ pub fn handle_rest_interactions(
 
             if !upgradable_indices.is_empty() {
                 use rand::seq::SliceRandom;
-                let mut rng = rand::thread_rng();
-                if let Some(&index) = upgradable_indices.choose(&mut rng) {
+                if let Some(&index) = upgradable_indices.choose(&mut run_seed.events) {
                     let old_name = player_deck.cards[index].name.clone();
                     player_deck.cards[index].upgrade();
                     let new_name = player_deck.cards[index].name.clone();
@@ -29,7 +29,7 @@ fn update_screen_warning(
 ) {
     if let Ok(player) = player_query.get_single() {
         let is_low_hp = (player.hp as f32 / player.max_hp as f32) < 0.35; 
-        let is_weakened = player.weakness > 0;
+        let is_weakened = player.statuses.has(crate::components::status::WEAKNESS);
         
         if let Ok((mut vis, mut color)) = warning_query.get_single_mut() {
             if is_low_hp || is_weakened {
@@ -6,6 +6,8 @@ use crate::components::*;
 use crate::components::relic::RelicCollection;
 use crate::components::map::MapProgress;
 use crate::states::GameState;
+use crate::resources::{CardLibrary, RunSeed};
+use crate::resources::relic_data::RelicLibrary;
 
 const COLOR_GOLD: Color = Color::srgb(1.0, 0.84, 0.0);
 
@@ -44,6 +46,9 @@ pub fn setup_shop_ui(
     player_deck: Res<PlayerDeck>,
     relic_collection: Res<RelicCollection>,
     player_query: Query<(&Player, &Cultivation)>,
+    mut run_seed: ResMut<RunSeed>,
+    card_library: Res<CardLibrary>,
+    relic_library: Res<RelicLibrary>,
 ) {
     info!("【仙家坊市】设置坊市UI");
 
@@ -60,7 +65,9 @@ pub fn setup_shop_ui(
     };
 
     if current_items.items.is_empty() {
-        current_items.items = generate_shop_items(&player_deck, &relic_collection);
+        let realm = player_query.get_single().map(|(_, c)| c.realm).unwrap_or_default();
+        let sect = player_query.get_single().map(|(p, _)| p.sect).unwrap_or_default();
+        current_items.items = generate_shop_items(&player_deck, &relic_collection, realm, sect, &card_library, &relic_library, &mut run_seed.shop);
     }
     
     let chinese_font: Handle<Font> = asset_server.load("fonts/Arial Unicode.ttf");
@@ -131,7 +138,7 @@ pub fn setup_shop_ui(
                         item_parent.spawn((Text::new(item.get_description()), TextFont { font_size: 13.0, font: chinese_font.clone(), ..default() }, TextColor(desc_color), Node { max_width: Val::Px(160.0), ..default() }));
                         
                         if !is_sold_out && !is_relic_full {
-                            item_parent.spawn((Text::new(format!("{} 灵石", item.get_price())), TextFont { font_size: 18.0, font: chinese_font.clone(), ..default() }, TextColor(COLOR_GOLD)));
+                            item_parent.spawn((Text::new(format!("{} 灵石", relic_collection.discounted_price(item.get_price()))), TextFont { font_size: 18.0, font: chinese_font.clone(), ..default() }, TextColor(COLOR_GOLD)));
                         }
                         
                         let action_text = match item {
@@ -183,12 +190,18 @@ pub fn setup_shop_ui(
 }
 
 /// 生成商店商品
-fn generate_shop_items(_player_deck: &PlayerDeck, relic_collection: &RelicCollection) -> Vec<ShopItem> {
-    let all_cards = crate::components::cards::CardPool::all_cards();
+fn generate_shop_items(
+    _player_deck: &PlayerDeck,
+    relic_collection: &RelicCollection,
+    realm: Realm,
+    sect: Sect,
+    card_library: &CardLibrary,
+    relic_library: &RelicLibrary,
+    rng: &mut impl rand::Rng,
+) -> Vec<ShopItem> {
+    let all_cards = card_library.all_cards(sect);
     let mut items = vec![];
     use rand::seq::SliceRandom;
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
 
     // 1. 功法生成 (3张)
     // --- 测试专用：强制加入万剑归宗 ---
@@ -200,40 +213,19 @@ fn generate_shop_items(_player_deck: &PlayerDeck, relic_collection: &RelicCollec
         .filter(|c| c.name != "万剑归宗")
         .cloned()
         .collect();
-    for card in remaining_cards.choose_multiple(&mut rng, 2) { 
+    for card in remaining_cards.choose_multiple(rng, 3 - items.len()) { 
         items.push(ShopItem::Card(card.clone())); 
     }
 
     // 2. 法宝生成 (1-2个)
     let num_relics = rng.gen_range(1..=2);
-    let mut generated_relic_ids = std::collections::HashSet::new();
-    
+    let mut generated_relic_ids = Vec::new();
+
     for _ in 0..num_relics {
-        // 概率：70% 常见, 25% 罕见, 5% 稀有
-        let roll = rng.gen::<f32>();
-        let rarity = if roll < 0.7 {
-            crate::components::relic::RelicRarity::Common
-        } else if roll < 0.95 {
-            crate::components::relic::RelicRarity::Uncommon
-        } else {
-            crate::components::relic::RelicRarity::Rare
-        };
-
-        let mut available_relics = crate::components::relic::Relic::by_rarity(rarity);
-        // 过滤掉玩家已有的和本次已经生成的
-        available_relics.retain(|r| !relic_collection.has(r.id) && !generated_relic_ids.contains(&r.id));
-
-        if let Some(relic) = available_relics.choose(&mut rng) {
-            generated_relic_ids.insert(relic.id);
-            items.push(ShopItem::Relic(relic.clone()));
-        } else if rarity != crate::components::relic::RelicRarity::Common {
-            // 如果高稀有度没货了，尝试降级生成
-            let mut common_relics = crate::components::relic::Relic::by_rarity(crate::components::relic::RelicRarity::Common);
-            common_relics.retain(|r| !relic_collection.has(r.id) && !generated_relic_ids.contains(&r.id));
-            if let Some(common) = common_relics.choose(&mut rng) {
-                generated_relic_ids.insert(common.id);
-                items.push(ShopItem::Relic(common.clone()));
-            }
+        // 概率：70% 常见, 25% 罕见, 5% 稀有（高稀有度没货时由图谱降级生成），过滤掉已有的和本次已经生成的
+        if let Some(relic) = relic_library.roll([0.7, 0.25, 0.05], relic_collection, realm, &generated_relic_ids, rng) {
+            generated_relic_ids.push(relic.id.clone());
+            items.push(ShopItem::Relic(relic));
         }
     }
 
@@ -242,7 +234,7 @@ fn generate_shop_items(_player_deck: &PlayerDeck, relic_collection: &RelicCollec
         ShopItem::Elixir { name: "洗髓丹".to_string(), hp_restore: 20, price: 40, description: "洗筋伐髓，恢复 20 点道行".to_string() },
         ShopItem::Elixir { name: "九转还魂丹".to_string(), hp_restore: 50, price: 90, description: "生死肉骨，恢复 50 点道行".to_string() },
     ];
-    if let Some(elixir) = elixirs.choose(&mut rng) { items.push(elixir.clone()); }
+    if let Some(elixir) = elixirs.choose(rng) { items.push(elixir.clone()); }
 
     // 4. 服务项目
     items.push(ShopItem::ForgetTechnique);
@@ -282,7 +274,7 @@ pub fn handle_shop_interactions(
             if index >= current_items.items.len() { continue; }
 
             let item = &current_items.items[index];
-            let price = item.get_price();
+            let price = relic_collection.discounted_price(item.get_price());
             if let Ok((mut player, _)) = player_query.get_single_mut() {
                 if player.gold >= price {
                     match item {
@@ -324,18 +316,19 @@ pub fn handle_shop_interactions(
                 
                 let item = &current_items.items[index];
                 if let Ok((mut player, cultivation)) = player_query.get_single_mut() {
-                    let price = item.get_price();
+                    let price = relic_collection.discounted_price(item.get_price());
                     if player.gold >= price {
                         if let ShopItem::Relic(relic) = item {
                             if relic_collection.add_relic(relic.clone(), cultivation) {
                                 player.gold -= price;
+                                relic.on_pickup(&mut player);
                                 sfx_events.send(PlaySfxEvent::new(SfxType::GoldGain));
                                 info!("【仙家坊市】购得法宝: {}", relic.name);
                                 purchased_index = Some(index);
                             } else {
                                 // 失败反馈：可能是法宝位满了
                                 sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
-                                let msg = if relic_collection.has(relic.id) {
+                                let msg = if relic_collection.has(relic.id.as_str()) {
                                     "已有此法宝".to_string()
                                 } else {
                                     "法宝位已满，需破境".to_string()
@@ -1689,9 +1689,9 @@ pub fn spawn_character_sprite(
 
             // [新增] 如果是玩家，挂载武器模块
             if is_player {
-                if let Some(pa) = player_assets {
+                if let Some(weapon) = player_assets.and_then(|pa| pa.weapon.as_ref()) {
                     parent.spawn((
-                        SceneRoot(pa.weapon.clone()),
+                        SceneRoot(weapon.clone()),
                         // 修正：将剑从竖直状态旋转至斜指前方
                         Transform::from_xyz(0.2, 0.8, 0.2) 
                             .with_rotation(Quat::from_rotation_z(-std::f32::consts::FRAC_PI_2) * Quat::from_rotation_y(0.3)),
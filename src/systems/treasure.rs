@@ -0,0 +1,355 @@
+//! 宝箱系统
+//!
+//! 宝箱节点（以及揭示为宝箱的未知节点）随机出现小、中、大三种宝箱。
+//! 宝箱越大，灵石越多，开出的法宝也越珍稀；开箱所得在进入节点时即由命途道种定下。
+
+use std::ops::RangeInclusive;
+
+use bevy::prelude::*;
+use bevy::text::TextFont;
+use rand::Rng;
+
+use crate::components::map::MapProgress;
+use crate::components::relic::{Relic, RelicCollection};
+use crate::components::{Cultivation, Player, PlaySfxEvent, Realm, SfxType};
+use crate::resources::relic_data::RelicLibrary;
+use crate::resources::RunSeed;
+use crate::states::GameState;
+
+const COLOR_GOLD: Color = Color::srgb(1.0, 0.84, 0.0);
+
+// ============================================================================
+// 开箱规则
+// ============================================================================
+
+/// 宝箱大小
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChestSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl ChestSize {
+    /// 按 50% / 33% / 17% 抽取宝箱大小
+    pub fn roll(rng: &mut impl Rng) -> Self {
+        match rng.gen_range(0..100) {
+            0..=49 => Self::Small,
+            50..=82 => Self::Medium,
+            _ => Self::Large,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Small => "青 木 匣",
+            Self::Medium => "玄 铁 箱",
+            Self::Large => "紫 金 宝 椟",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Small => "一只寻常木匣，封条尚新",
+            Self::Medium => "玄铁铸就的箱子，箱面刻着镇守符纹",
+            Self::Large => "宝光透椟而出，似是某位前辈的珍藏",
+        }
+    }
+
+    /// 箱中法宝的稀有度权重（常见 / 罕见 / 稀有）
+    pub fn relic_weights(&self) -> [f32; 3] {
+        match self {
+            Self::Small => [0.75, 0.25, 0.0],
+            Self::Medium => [0.35, 0.5, 0.15],
+            Self::Large => [0.0, 0.75, 0.25],
+        }
+    }
+
+    /// 箱中灵石数量区间
+    pub fn gold_range(&self) -> RangeInclusive<i32> {
+        match self {
+            Self::Small => 20..=35,
+            Self::Medium => 40..=60,
+            Self::Large => 70..=100,
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Self::Small => Color::srgb(0.6, 0.45, 0.25),
+            Self::Medium => Color::srgb(0.55, 0.6, 0.7),
+            Self::Large => Color::srgb(0.8, 0.5, 1.0),
+        }
+    }
+}
+
+/// 箱中所得
+#[derive(Debug, Clone)]
+pub struct ChestLoot {
+    pub gold: i32,
+    /// 法宝（图鉴中已无可得之物时为 None）
+    pub relic: Option<Relic>,
+}
+
+impl ChestLoot {
+    /// 按宝箱大小抽取所得，已持有的法宝不会重复开出
+    pub fn roll(size: ChestSize, library: &RelicLibrary, owned: &RelicCollection, realm: Realm, rng: &mut impl Rng) -> Self {
+        let gold = rng.gen_range(size.gold_range());
+        let relic = library.roll(size.relic_weights(), owned, realm, &[], rng);
+        Self { gold, relic }
+    }
+}
+
+// ============================================================================
+// 宝箱插件
+// ============================================================================
+
+/// 当前宝箱（进入宝箱节点时生成，离开时移除）
+#[derive(Resource, Debug, Clone)]
+pub struct CurrentChest {
+    pub size: ChestSize,
+    pub loot: ChestLoot,
+}
+
+/// 宝箱UI标记
+#[derive(Component)]
+pub struct TreasureUiRoot;
+
+/// 开箱按钮
+#[derive(Component)]
+pub struct OpenChestButton;
+
+/// 开箱结果区域
+#[derive(Component)]
+pub struct ChestResultArea;
+
+/// 继续前行按钮
+#[derive(Component)]
+pub struct TreasureContinueButton;
+
+/// 宝箱插件
+pub struct TreasurePlugin;
+
+impl Plugin for TreasurePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Treasure), setup_treasure_ui)
+            .add_systems(Update, (
+                handle_open_chest,
+                handle_treasure_continue,
+            ).run_if(in_state(GameState::Treasure)))
+            .add_systems(OnExit(GameState::Treasure), cleanup_treasure_ui);
+    }
+}
+
+/// 设置宝箱UI：抽定宝箱大小与所得
+pub fn setup_treasure_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    player_query: Query<&Cultivation, With<Player>>,
+    relic_collection: Res<RelicCollection>,
+    relic_library: Res<RelicLibrary>,
+    mut run_seed: ResMut<RunSeed>,
+) {
+    let realm = player_query.get_single().map(|c| c.realm).unwrap_or_default();
+    let size = ChestSize::roll(&mut run_seed.rewards);
+    let loot = ChestLoot::roll(size, &relic_library, &relic_collection, realm, &mut run_seed.rewards);
+    info!("【宝箱】发现{}", size.name());
+
+    let chinese_font: Handle<Font> = asset_server.load("fonts/Arial Unicode.ttf");
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(30.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.05, 0.04, 0.02)),
+            TreasureUiRoot,
+            ZIndex(300),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(size.name()),
+                TextFont { font_size: 52.0, font: chinese_font.clone(), ..default() },
+                TextColor(size.color()),
+            ));
+            parent.spawn((
+                Text::new(size.description()),
+                TextFont { font_size: 18.0, font: chinese_font.clone(), ..default() },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+
+            parent.spawn((
+                Button,
+                OpenChestButton,
+                Node {
+                    width: Val::Px(200.0),
+                    height: Val::Px(200.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    border: UiRect::all(Val::Px(3.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.15, 0.1, 0.05, 0.9)),
+                BorderColor(size.color()),
+                BorderRadius::all(Val::Px(15.0)),
+            )).with_children(|btn| {
+                btn.spawn((
+                    Text::new("开 启"),
+                    TextFont { font_size: 32.0, font: chinese_font.clone(), ..default() },
+                    TextColor(Color::WHITE),
+                ));
+            });
+
+            // 开箱结果（初始隐藏）
+            parent.spawn((
+                Node {
+                    display: Display::None,
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    row_gap: Val::Px(20.0),
+                    ..default()
+                },
+                ChestResultArea,
+            )).with_children(|res| {
+                res.spawn((
+                    Text::new(format!("灵石 +{}", loot.gold)),
+                    TextFont { font_size: 28.0, font: chinese_font.clone(), ..default() },
+                    TextColor(COLOR_GOLD),
+                ));
+                if let Some(relic) = &loot.relic {
+                    res.spawn((
+                        Text::new(format!("法宝「{}」", relic.name)),
+                        TextFont { font_size: 28.0, font: chinese_font.clone(), ..default() },
+                        TextColor(relic.rarity.color()),
+                    ));
+                    res.spawn((
+                        Text::new(relic.description.clone()),
+                        TextFont { font_size: 16.0, font: chinese_font.clone(), ..default() },
+                        TextColor(Color::srgb(0.7, 0.7, 0.7)),
+                        Node { max_width: Val::Px(420.0), ..default() },
+                    ));
+                }
+
+                res.spawn((
+                    Button,
+                    TreasureContinueButton,
+                    Node {
+                        padding: UiRect::axes(Val::Px(40.0), Val::Px(15.0)),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.8)),
+                    BorderRadius::all(Val::Px(10.0)),
+                )).with_children(|btn| {
+                    btn.spawn((
+                        Text::new("继续前行"),
+                        TextFont { font_size: 24.0, font: chinese_font.clone(), ..default() },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+            });
+        });
+
+    commands.insert_resource(CurrentChest { size, loot });
+}
+
+/// 开箱：发放灵石与法宝，显示结果
+pub fn handle_open_chest(
+    mut commands: Commands,
+    chest: Option<Res<CurrentChest>>,
+    mut player_query: Query<(&mut Player, &Cultivation)>,
+    mut relic_collection: ResMut<RelicCollection>,
+    open_buttons: Query<(Entity, &Interaction), With<OpenChestButton>>,
+    mut result_area: Query<&mut Node, With<ChestResultArea>>,
+    mut sfx_events: EventWriter<PlaySfxEvent>,
+) {
+    let Some(chest) = chest else { return };
+    for (entity, interaction) in open_buttons.iter() {
+        if !matches!(interaction, Interaction::Pressed) {
+            continue;
+        }
+        let Ok((mut player, cultivation)) = player_query.get_single_mut() else { return };
+
+        player.gold += chest.loot.gold;
+        sfx_events.send(PlaySfxEvent::new(SfxType::GoldGain));
+        info!("【宝箱】开启{}，获得灵石 {}", chest.size.name(), chest.loot.gold);
+
+        if let Some(relic) = &chest.loot.relic {
+            if relic_collection.add_relic(relic.clone(), cultivation) {
+                relic.on_pickup(&mut player);
+                sfx_events.send(PlaySfxEvent::new(SfxType::RelicObtain));
+                info!("【宝箱】获得法宝「{}」", relic.name);
+            } else {
+                info!("【宝箱】法宝「{}」与你无缘（法宝位已满或已持有）", relic.name);
+            }
+        }
+
+        commands.entity(entity).despawn_recursive();
+        if let Ok(mut node) = result_area.get_single_mut() {
+            node.display = Display::Flex;
+        }
+        return;
+    }
+}
+
+/// 继续前行：完成节点并返回地图
+pub fn handle_treasure_continue(
+    mut next_state: ResMut<NextState<GameState>>,
+    mut map_progress: ResMut<MapProgress>,
+    continue_buttons: Query<&Interaction, (Changed<Interaction>, With<TreasureContinueButton>)>,
+    mut sfx_events: EventWriter<PlaySfxEvent>,
+) {
+    for interaction in continue_buttons.iter() {
+        if matches!(interaction, Interaction::Pressed) {
+            sfx_events.send(PlaySfxEvent::new(SfxType::UiClick));
+            map_progress.complete_current_node();
+            next_state.set(GameState::Map);
+            return;
+        }
+    }
+}
+
+/// 清理宝箱UI
+pub fn cleanup_treasure_ui(
+    mut commands: Commands,
+    ui_query: Query<Entity, With<TreasureUiRoot>>,
+) {
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<CurrentChest>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::relic::RelicRarity;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_chest_sizes_scale_rewards() {
+        let library = RelicLibrary::builtin();
+        let owned = RelicCollection::default();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let mut seen = std::collections::HashSet::new();
+        for _ in 0..200 {
+            let size = ChestSize::roll(&mut rng);
+            seen.insert(size as u8);
+            let loot = ChestLoot::roll(size, library, &owned, Realm::QiRefining, &mut rng);
+            assert!(size.gold_range().contains(&loot.gold));
+            let relic = loot.relic.expect("图鉴中尚有法宝");
+            match size {
+                ChestSize::Small => assert_ne!(relic.rarity, RelicRarity::Rare),
+                ChestSize::Large => assert_ne!(relic.rarity, RelicRarity::Common),
+                ChestSize::Medium => {}
+            }
+        }
+        assert_eq!(seen.len(), 3);
+        assert!(ChestSize::Small.gold_range().end() < ChestSize::Large.gold_range().start());
+    }
+}
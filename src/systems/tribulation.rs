@@ -0,0 +1,543 @@
+//! 渡劫之战
+//!
+//! 冲击下一境界时，修行者须在一场特殊战斗中抵御「天劫」：劫云每回合降下愈演愈烈的天雷
+//! （招式序列见 `assets/data/enemies.ron` 中的劫云定义，强度由目标境界的 [`TribulationRules`] 决定）。
+//! 修行者以自身牌组外加渡劫功法应对，法宝与境界被动照常减伤。
+//! 撑过全部劫波或击散劫云即破境；道行耗尽则渡劫失败，散去部分感悟、折损道行上限，但不致身死。
+//!
+//! 劫云生成、成败判定与得失结算为纯数据逻辑，可脱离 ECS 单元测试；插件只负责开场演出、衔接战斗与结算演出。
+
+use bevy::prelude::*;
+
+use crate::components::cards::{Card, CardEffect, CardRarity, CardType};
+use crate::components::combat::{CombatUiRoot, Enemy, Player};
+use crate::components::cultivation::{Cultivation, Realm, TribulationRules};
+use crate::components::map::{generate_map_nodes, EntranceAnimation, MapProgress, MapUiRoot};
+use crate::components::{EffectType, PlayerDeck, PlaySfxEvent, ScreenEffectEvent, SfxType, SpawnEffectEvent};
+use crate::resources::act_data::ActAtlas;
+use crate::resources::ascension::Ascension;
+use crate::resources::card_data::INNATE_SPELL_ID;
+use crate::resources::enemy_data::EnemyRoster;
+use crate::resources::{CardLibrary, RunSeed};
+use crate::states::GameState;
+use crate::systems::enemy_gen::GeneratedEnemy;
+
+/// 劫云的妖物定义 id
+pub const TRIBULATION_CLOUD_ID: &str = "tribulation_cloud";
+/// 劫云在战斗中的敌人 id
+const CLOUD_ENEMY_ID: u32 = 0;
+/// 开场演出时长（秒），点击可跳过
+const INTRO_SECONDS: f32 = 4.0;
+
+// ============================================================================
+// 渡劫规则
+// ============================================================================
+
+/// 渡劫结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TribulationOutcome {
+    /// 撑过全部劫波或击散劫云
+    Passed,
+    /// 道行耗尽
+    Failed,
+}
+
+/// 渡劫失败的代价
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TribulationPenalty {
+    /// 散去的感悟
+    pub insight_lost: u32,
+    /// 折损的道行上限
+    pub max_hp_lost: i32,
+}
+
+/// 破境所得
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakthroughReward {
+    /// 道行上限提升
+    pub hp_bonus: i32,
+    /// 天道赏赐的灵石
+    pub stones: i32,
+}
+
+/// 进行中的渡劫之战（存在期间，战斗按渡劫规则结算，不进入机缘奖励）
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct TribulationBattle {
+    /// 冲击的境界
+    pub target: Realm,
+    pub rules: TribulationRules,
+}
+
+impl TribulationBattle {
+    /// 由当前境界开启渡劫（巅峰境界无劫可渡）
+    pub fn new(current: Realm) -> Option<Self> {
+        let target = current.next()?;
+        Some(Self { target, rules: target.tribulation() })
+    }
+
+    /// 降下劫云：道行与天雷威力取自本次雷劫规则
+    pub fn cloud(&self) -> Enemy {
+        let definition = EnemyRoster::builtin().get(TRIBULATION_CLOUD_ID).expect("妖物图谱缺少劫云定义");
+        let mut cloud = Enemy::from_definition(
+            CLOUD_ENEMY_ID,
+            format!("{}天劫", self.target.name()),
+            self.rules.cloud_hp,
+            definition,
+        );
+        cloud.strength = self.rules.strike_bonus;
+        cloud
+    }
+
+    /// 劫云的战场外观（体型压迫、紫电染色）
+    pub fn spawn_cloud(&self) -> GeneratedEnemy {
+        GeneratedEnemy {
+            enemy: self.cloud(),
+            visual_scale: Vec2::splat(1.6),
+            visual_color: Color::srgb(0.6, 0.55, 1.0),
+        }
+    }
+
+    /// 已撑过的劫波数（每撑过一次劫云行动，修行者进入新回合）
+    pub fn waves_survived(&self, player: &Player) -> u32 {
+        player.turn.saturating_sub(1).min(self.rules.waves)
+    }
+
+    /// 本场渡劫是否已见分晓：道行耗尽为失败，劫云散去或撑过全部劫波为渡过
+    pub fn judge<'a>(&self, player: &Player, mut enemies: impl Iterator<Item = &'a Enemy>) -> Option<TribulationOutcome> {
+        if player.hp <= 0 {
+            Some(TribulationOutcome::Failed)
+        } else if !enemies.any(|e| e.hp > 0) || player.turn > self.rules.waves {
+            Some(TribulationOutcome::Passed)
+        } else {
+            None
+        }
+    }
+
+    /// 渡劫失败：散去部分感悟、折损道行上限，以四分之一道行退回地图
+    pub fn apply_failure(&self, player: &mut Player, cultivation: &mut Cultivation) -> TribulationPenalty {
+        let insight_lost = cultivation.insight * self.rules.insight_loss_percent / 100;
+        cultivation.insight -= insight_lost;
+        let max_hp_lost = self.rules.max_hp_loss.min(player.max_hp - 1).max(0);
+        player.max_hp -= max_hp_lost;
+        player.hp = (player.max_hp / 4).max(1);
+        player.block = 0;
+        player.clear_statuses();
+        TribulationPenalty { insight_lost, max_hp_lost }
+    }
+}
+
+/// 破境：晋升境界，道行上限按境界加成之差提升（读档时据此还原）并回满，获赏灵石
+pub fn apply_breakthrough(player: &mut Player, cultivation: &mut Cultivation) -> Option<BreakthroughReward> {
+    let previous_hp_bonus = cultivation.get_hp_bonus();
+    if !cultivation.breakthrough() {
+        return None;
+    }
+    let hp_bonus = cultivation.get_hp_bonus() - previous_hp_bonus;
+    let stones = match cultivation.realm {
+        Realm::FoundationEstablishment => 100,
+        Realm::GoldenCore => 200,
+        _ => 50,
+    };
+    player.max_hp += hp_bonus;
+    player.hp = player.max_hp;
+    player.gold += stones;
+    player.realm = cultivation.realm;
+    Some(BreakthroughReward { hp_bonus, stones })
+}
+
+// ============================================================================
+// 渡劫插件
+// ============================================================================
+
+/// 渡劫开场演出 UI 标记
+#[derive(Component)]
+pub struct TribulationUiMarker;
+
+/// 战斗中的劫波进度文字
+#[derive(Component)]
+pub struct TribulationWaveText;
+
+/// 渡劫开场演出计时
+#[derive(Resource)]
+struct TribulationIntro {
+    timer: Timer,
+}
+
+impl Default for TribulationIntro {
+    fn default() -> Self {
+        Self { timer: Timer::from_seconds(INTRO_SECONDS, TimerMode::Once) }
+    }
+}
+
+/// 渡劫插件
+pub struct TribulationPlugin;
+
+impl Plugin for TribulationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TribulationIntro>()
+            .add_systems(OnEnter(GameState::Tribulation), setup_tribulation)
+            .add_systems(Update, update_tribulation.run_if(in_state(GameState::Tribulation)))
+            .add_systems(OnExit(GameState::Tribulation), cleanup_tribulation_ui)
+            .add_systems(OnEnter(GameState::Combat), setup_tribulation_hud.run_if(resource_exists::<TribulationBattle>))
+            .add_systems(Update, (
+                update_tribulation_hud,
+                settle_tribulation,
+            ).chain().run_if(in_state(GameState::Combat)).run_if(resource_exists::<TribulationBattle>))
+            .add_systems(OnExit(GameState::Combat), end_tribulation_battle);
+    }
+}
+
+/// 天劫将至：定下本次雷劫规则并展示劫波与失败代价
+fn setup_tribulation(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    cultivation_query: Query<&Cultivation>,
+    mut intro: ResMut<TribulationIntro>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut screen_events: EventWriter<ScreenEffectEvent>,
+    mut sfx_events: EventWriter<PlaySfxEvent>,
+) {
+    let Some(battle) = cultivation_query.get_single().ok().and_then(|c| TribulationBattle::new(c.realm)) else {
+        warn!("【渡劫】已至巅峰境界，无劫可渡");
+        next_state.set(GameState::Map);
+        return;
+    };
+    info!("🌩️ 天地震动，雷劫将至！冲击{}：{} 重劫波", battle.target.name(), battle.rules.waves);
+    intro.timer.reset();
+
+    let chinese_font = asset_server.load("fonts/Arial Unicode.ttf");
+
+    // 渡劫背景（半透明深紫色遮罩，允许 3D 特效透出）
+    commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.01, 0.0, 0.02, 0.65)),
+        ZIndex(-10),
+        TribulationUiMarker,
+    ));
+
+    // 渡劫标题、劫波说明与失败代价
+    let rules = battle.rules;
+    commands.spawn((
+        Node {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(16.0),
+            position_type: PositionType::Absolute,
+            ..default()
+        },
+        ZIndex(50),
+        TribulationUiMarker,
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new(format!("{}天劫将至", battle.target.name())),
+            TextFont { font: chinese_font.clone(), font_size: 72.0, ..default() },
+            TextColor(Color::srgb(0.8, 0.8, 1.0)),
+        ));
+        parent.spawn((
+            Text::new(format!("劫云将降下 {} 重劫波，撑过或击散劫云即可破境", rules.waves)),
+            TextFont { font: chinese_font.clone(), font_size: 26.0, ..default() },
+            TextColor(Color::srgb(0.6, 0.6, 0.8)),
+        ));
+        parent.spawn((
+            Text::new(format!(
+                "渡劫失败将散去 {}% 感悟、折损 {} 点道行上限",
+                rules.insight_loss_percent, rules.max_hp_loss
+            )),
+            TextFont { font: chinese_font.clone(), font_size: 22.0, ..default() },
+            TextColor(Color::srgb(0.9, 0.5, 0.5)),
+        ));
+        parent.spawn((
+            Text::new("点击迎劫"),
+            TextFont { font: chinese_font, font_size: 20.0, ..default() },
+            TextColor(Color::srgba(1.0, 1.0, 1.0, 0.6)),
+        ));
+    });
+
+    commands.insert_resource(battle);
+    sfx_events.send(PlaySfxEvent::new(SfxType::LightningStrike));
+    screen_events.send(ScreenEffectEvent::Flash {
+        color: Color::srgba(0.5, 0.2, 0.8, 0.5),
+        duration: 0.5,
+    });
+}
+
+/// 开场演出：雷云翻涌，时间到或点击后进入渡劫之战
+fn update_tribulation(
+    time: Res<Time>,
+    mut intro: ResMut<TribulationIntro>,
+    mouse_button: Res<ButtonInput<MouseButton>>,
+    battle: Option<Res<TribulationBattle>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut screen_events: EventWriter<ScreenEffectEvent>,
+    mut effect_events: EventWriter<SpawnEffectEvent>,
+) {
+    if battle.is_none() { return; }
+
+    screen_events.send(ScreenEffectEvent::Shake { trauma: 0.05, decay: 10.0 });
+    if time.elapsed_secs() as u32 % 2 == 0 {
+        effect_events.send(SpawnEffectEvent::new(EffectType::AmbientSpirit, Vec3::new(0.0, 0.0, 5.0)).burst(2));
+    }
+
+    intro.timer.tick(time.delta());
+    if intro.timer.finished() || mouse_button.just_pressed(MouseButton::Left) {
+        info!("【渡劫】劫云压顶，迎劫而上！");
+        next_state.set(GameState::Combat);
+    }
+}
+
+fn cleanup_tribulation_ui(mut commands: Commands, ui_query: Query<Entity, With<TribulationUiMarker>>) {
+    for entity in ui_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// 战斗中显示劫波进度（随战斗 UI 一同清理）
+fn setup_tribulation_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(70.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        PickingBehavior::IGNORE,
+        ZIndex(250),
+        CombatUiRoot,
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new(""),
+            TextFont { font: asset_server.load("fonts/Arial Unicode.ttf"), font_size: 28.0, ..default() },
+            TextColor(Color::srgb(0.75, 0.7, 1.0)),
+            TribulationWaveText,
+        ));
+    });
+}
+
+fn update_tribulation_hud(
+    battle: Res<TribulationBattle>,
+    player_query: Query<&Player, Changed<Player>>,
+    mut text_query: Query<&mut Text, With<TribulationWaveText>>,
+) {
+    let Ok(player) = player_query.get_single() else { return; };
+    for mut text in text_query.iter_mut() {
+        text.0 = format!(
+            "{}天劫 · 已撑过劫波 {}/{}",
+            battle.target.name(), battle.waves_survived(player), battle.rules.waves
+        );
+    }
+}
+
+/// 渡劫见分晓：破境或承受失败代价，随后返回地图
+fn settle_tribulation(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    battle: Res<TribulationBattle>,
+    mut player_query: Query<(&mut Player, &mut Cultivation)>,
+    enemy_query: Query<&Enemy>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut deck: ResMut<PlayerDeck>,
+    mut map_progress: ResMut<MapProgress>,
+    mut run_seed: ResMut<RunSeed>,
+    card_library: Res<CardLibrary>,
+    ascension: Res<Ascension>,
+    mut sfx_events: EventWriter<PlaySfxEvent>,
+    mut effect_events: EventWriter<SpawnEffectEvent>,
+    mut screen_events: EventWriter<ScreenEffectEvent>,
+) {
+    let Ok((mut player, mut cultivation)) = player_query.get_single_mut() else { return; };
+    let Some(outcome) = battle.judge(&player, enemy_query.iter()) else { return; };
+    commands.remove_resource::<TribulationBattle>();
+    next_state.set(GameState::Map);
+
+    let banner_text = match outcome {
+        TribulationOutcome::Failed => {
+            let penalty = battle.apply_failure(&mut player, &mut cultivation);
+            info!(
+                "💀【渡劫失败】雷劫未渡，散去 {} 点感悟，道行上限折损 {} 点，残存道行 {}",
+                penalty.insight_lost, penalty.max_hp_lost, player.hp
+            );
+            sfx_events.send(PlaySfxEvent::new(SfxType::PlayerHit));
+            screen_events.send(ScreenEffectEvent::Flash { color: Color::srgba(0.3, 0.0, 0.0, 0.8), duration: 0.6 });
+            "渡 劫 失 败"
+        }
+        TribulationOutcome::Passed => {
+            let Some(reward) = apply_breakthrough(&mut player, &mut cultivation) else {
+                warn!("【渡劫】雷劫已渡，但感悟不足以破境");
+                return;
+            };
+            info!(
+                "✨【破境成功】成功晋升至 {}！道行大进，上限增加 {} 点，获灵石 {} 块",
+                cultivation.realm.name(), reward.hp_bonus, reward.stones
+            );
+
+            // 开启新征程：重新生成地图
+            let map_config = ActAtlas::builtin().act(map_progress.act).map_config(&ascension);
+            map_progress.nodes = generate_map_nodes(&map_config, 0, &mut run_seed.map);
+            map_progress.current_node_id = None;
+            map_progress.current_layer = 0;
+            map_progress.refresh_unlocks();
+            info!("🗺️【天道演化】新的地图已生成，开启下一境界的修行！");
+
+            // 功法质变：发放本命功法
+            if cultivation.realm == Realm::FoundationEstablishment {
+                if let Some(innate_spell) = card_library.innate_spell() {
+                    info!("📖【本命功法】获得筑基期本命功法：{}", innate_spell.name);
+                    deck.add_card(innate_spell);
+                } else {
+                    warn!("【本命功法】功法图谱中缺少本命功法 #{}", INNATE_SPELL_ID);
+                }
+            } else if cultivation.realm == Realm::GoldenCore {
+                // 金丹期自动领悟万剑归宗
+                let aoe_spell = Card::new(
+                    151, "万剑归宗", CardType::Attack, 2,
+                    vec![CardEffect::DealAoEDamage { amount: 10 }],
+                    CardRarity::Rare,
+                    "textures/cards/attack.png"
+                );
+                deck.add_card(aoe_spell.clone());
+                info!("📖【大能神通】晋升金丹，领悟群体攻伐：{}", aoe_spell.name);
+            }
+
+            sfx_events.send(PlaySfxEvent::new(SfxType::BreakthroughSuccess));
+            effect_events.send(SpawnEffectEvent::new(EffectType::Victory, Vec3::new(0.0, 0.0, 999.0)).burst(100));
+            // 瞬间白闪与剧烈震动，体现破境时的力量爆发
+            screen_events.send(ScreenEffectEvent::Flash { color: Color::srgba(1.5, 1.5, 2.0, 0.95), duration: 0.5 });
+            screen_events.send(ScreenEffectEvent::Shake { trauma: 1.0, decay: 2.0 });
+
+            match cultivation.realm {
+                Realm::FoundationEstablishment => "筑 基 大 成",
+                Realm::GoldenCore => "金 丹 已 成",
+                Realm::NascentSoul => "元 婴 出 世",
+                Realm::SpiritTransformation => "化 神 通 玄",
+                Realm::TribulationTranscendence => "渡 劫 之 境",
+                _ => "破 境 成 功",
+            }
+        }
+    };
+
+    // 结果横幅随地图 UI 一起清理
+    let chinese_font = asset_server.load("fonts/Arial Unicode.ttf");
+    let color = match outcome {
+        TribulationOutcome::Passed => Color::srgb(1.0, 0.9, 0.3),
+        TribulationOutcome::Failed => Color::srgb(0.8, 0.3, 0.3),
+    };
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Px(200.0),
+            top: Val::Percent(35.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        ZIndex(1000),
+        MapUiRoot,
+        EntranceAnimation::new(0.6),
+    )).with_children(|banner| {
+        banner.spawn((
+            Text::new(banner_text),
+            TextFont { font: chinese_font, font_size: 120.0, ..default() },
+            TextColor(color),
+            EntranceAnimation::new(0.8),
+        ));
+    });
+}
+
+/// 离开战斗即结束渡劫（含中途退出）
+fn end_tribulation_battle(mut commands: Commands) {
+    commands.remove_resource::<TribulationBattle>();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::systems::combat_engine::{CombatEngine, CombatEvent};
+
+    fn strikes_until_settled(battle: &TribulationBattle, player: Player) -> (Option<TribulationOutcome>, Vec<i32>, CombatEngine) {
+        let mut engine = CombatEngine::new(player, vec![battle.cloud()], Vec::new());
+        engine.start_combat();
+        let mut strikes = Vec::new();
+        while battle.judge(&engine.player, engine.enemies.iter()).is_none() {
+            engine.end_turn();
+            for event in engine.resolve_enemy_turn() {
+                if let CombatEvent::PlayerDamaged { damage, .. } = event {
+                    strikes.push(damage);
+                }
+            }
+        }
+        (battle.judge(&engine.player, engine.enemies.iter()), strikes, engine)
+    }
+
+    #[test]
+    fn test_tribulation_waves_escalate_until_survived() {
+        let battle = TribulationBattle::new(Realm::QiRefining).unwrap();
+        assert_eq!(battle.target, Realm::FoundationEstablishment);
+        assert!(TribulationBattle::new(Realm::TribulationTranscendence).is_none());
+
+        let (outcome, strikes, engine) = strikes_until_settled(&battle, Player::default());
+        assert_eq!(outcome, Some(TribulationOutcome::Passed));
+        // 惊雷、紫霄神雷，第三重为心魔劫
+        assert_eq!(strikes, vec![10, 14]);
+        assert_eq!(battle.waves_survived(&engine.player), 3);
+        assert_eq!(engine.player.hp, 80 - 24);
+
+        // 更高境界的天雷更猛、劫波更多
+        let nascent = TribulationBattle::new(Realm::GoldenCore).unwrap();
+        let mut player = Player::default();
+        player.hp = 300;
+        player.max_hp = 300;
+        let (_, strikes, _) = strikes_until_settled(&nascent, player);
+        assert_eq!(strikes, vec![14, 18]);
+    }
+
+    #[test]
+    fn test_tribulation_outcomes_and_failure_costs() {
+        let battle = TribulationBattle::new(Realm::GoldenCore).unwrap();
+        let mut player = Player::default();
+        let mut cloud = battle.cloud();
+        assert_eq!((cloud.hp, cloud.strength), (battle.rules.cloud_hp, battle.rules.strike_bonus));
+        assert_eq!(battle.judge(&player, [&cloud].into_iter()), None);
+
+        // 击散劫云提前渡过
+        cloud.hp = 0;
+        assert_eq!(battle.judge(&player, [&cloud].into_iter()), Some(TribulationOutcome::Passed));
+
+        // 道行耗尽：散去感悟、折损道行上限，但不致身死
+        player.hp = 0;
+        cloud.hp = 10;
+        assert_eq!(battle.judge(&player, [&cloud].into_iter()), Some(TribulationOutcome::Failed));
+        let mut cultivation = Cultivation { realm: Realm::GoldenCore, insight: 700 };
+        let penalty = battle.apply_failure(&mut player, &mut cultivation);
+        assert_eq!(penalty, TribulationPenalty { insight_lost: 350, max_hp_lost: 10 });
+        assert_eq!((cultivation.realm, cultivation.insight), (Realm::GoldenCore, 350));
+        assert_eq!((player.max_hp, player.hp), (70, 17));
+        assert!(!cultivation.can_breakthrough());
+
+        // 再次积满感悟后渡过即破境
+        cultivation.insight = 600;
+        let reward = apply_breakthrough(&mut player, &mut cultivation).unwrap();
+        assert_eq!(cultivation.realm, Realm::NascentSoul);
+        assert_eq!(reward, BreakthroughReward { hp_bonus: 150, stones: 50 });
+        assert_eq!((player.max_hp, player.hp, player.realm), (220, 220, Realm::NascentSoul));
+    }
+
+    #[test]
+    fn test_tribulation_cards_are_battle_only() {
+        let library = CardLibrary::builtin();
+        let cards = library.tribulation_cards();
+        assert_eq!(cards.len(), 3);
+        for sect in [crate::components::Sect::Sword, crate::components::Sect::Alchemy, crate::components::Sect::Talisman] {
+            assert!(library.all_cards(sect).iter().all(|c| !cards.iter().any(|t| t.id == c.id)));
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
-use crate::components::combat::{DamageNumber, DamageEffectEvent, BlockIconMarker, BlockText, Player, Enemy, StatusIndicator, StatusEffectEvent};
+use crate::components::combat::{DamageNumber, DamageEffectEvent, BlockIconMarker, BlockText, Player, Enemy, StatusIndicator, StatusTooltip, StatusEffectEvent, CombatUiRoot};
+use crate::resources::status_data::StatusRegistry;
 use crate::states::GameState;
 
 pub struct UiPlugin;
@@ -14,6 +15,7 @@ impl Plugin for UiPlugin {
             update_damage_numbers,
             update_block_visuals,
             update_status_indicators,
+            update_status_tooltips,
         ).run_if(in_state(GameState::Combat)));
 
         // 支持商店中的漂字系统
@@ -76,26 +78,88 @@ fn update_status_indicators(
     let player_data = player_query.get_single().ok();
 
     for (mut indicator, mut text, is_player_ui) in query.iter_mut() {
-        let mut status_parts = Vec::new();
+        let mut statuses = None;
 
         // 核心修复：如果是玩家UI但没有绑定实体，或者实体已失效，尝试重新绑定
         if is_player_ui {
             if let Some((p_ent, player)) = player_data {
                 indicator.owner = p_ent; // 自动修复绑定
-                if player.weakness > 0 { status_parts.push(format!("虚弱:{}", player.weakness)); }
-                if player.vulnerable > 0 { status_parts.push(format!("易伤:{}", player.vulnerable)); }
-                if player.poison > 0 { status_parts.push(format!("中毒:{}", player.poison)); }
+                statuses = Some(&player.statuses);
             }
+        } else if let Ok(enemy) = enemy_query.get(indicator.owner) {
+            statuses = Some(&enemy.statuses);
+        } else if let Ok((_, player)) = player_query.get(indicator.owner) {
+            statuses = Some(&player.statuses);
+        }
+
+        text.0 = statuses
+            .map(|s| {
+                s.summaries(StatusRegistry::builtin())
+                    .iter()
+                    .map(|summary| format!("{}{}", summary.icon, summary.stacks))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .unwrap_or_default();
+    }
+}
+
+/// 悬停状态栏时显示各状态的说明
+fn update_status_tooltips(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    indicator_query: Query<(&StatusIndicator, &Interaction, &GlobalTransform, Has<crate::components::PlayerUiMarker>)>,
+    mut tooltip_query: Query<(Entity, &mut Text, &mut Node), With<StatusTooltip>>,
+    player_query: Query<&Player>,
+    enemy_query: Query<&Enemy>,
+) {
+    let hovered = indicator_query.iter().find(|(_, interaction, _, _)| **interaction == Interaction::Hovered);
+    let lines = hovered.and_then(|(indicator, _, transform, is_player_ui)| {
+        let statuses = if is_player_ui {
+            &player_query.get_single().ok()?.statuses
+        } else if let Ok(enemy) = enemy_query.get(indicator.owner) {
+            &enemy.statuses
         } else {
-            // 敌人逻辑保持不变
-            if let Ok(enemy) = enemy_query.get(indicator.owner) {
-                if enemy.weakness > 0 { status_parts.push(format!("虚弱:{}", enemy.weakness)); }
-                if enemy.vulnerable > 0 { status_parts.push(format!("易伤:{}", enemy.vulnerable)); }
-                if enemy.poison > 0 { status_parts.push(format!("中毒:{}", enemy.poison)); }
+            &player_query.get(indicator.owner).ok()?.statuses
+        };
+        let lines: Vec<String> = statuses
+            .summaries(StatusRegistry::builtin())
+            .iter()
+            .map(|s| format!("【{}】{}", s.name, s.tooltip))
+            .collect();
+        (!lines.is_empty()).then(|| (lines.join("\n"), transform.translation().truncate()))
+    });
+
+    match (lines, tooltip_query.get_single_mut()) {
+        (Some((content, pos)), Ok((_, mut text, mut node))) => {
+            text.0 = content;
+            node.left = Val::Px(pos.x);
+            node.top = Val::Px(pos.y + 16.0);
+        }
+        (Some((content, pos)), Err(_)) => {
+            commands.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(pos.x),
+                    top: Val::Px(pos.y + 16.0),
+                    padding: UiRect::all(Val::Px(6.0)),
+                    max_width: Val::Px(280.0),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.05, 0.05, 0.1, 0.9)),
+                Text::new(content),
+                TextFont { font: asset_server.load("fonts/Arial Unicode.ttf"), font_size: 14.0, ..default() },
+                TextColor(Color::WHITE),
+                ZIndex(400),
+                StatusTooltip,
+                CombatUiRoot,
+            ));
+        }
+        (None, _) => {
+            for (entity, _, _) in tooltip_query.iter() {
+                commands.entity(entity).despawn_recursive();
             }
         }
-
-        text.0 = status_parts.join(" ");
     }
 }
 
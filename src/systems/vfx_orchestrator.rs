@@ -116,7 +116,7 @@ pub fn handle_vfx_events(
                     p.target_index = Some(i as usize);
                     p.target_group = if event.target_group.is_empty() { None } else { Some(event.target_group.clone()) };
                     p.start_pos = event.position.truncate();
-                    let model = event.model_override.as_ref().or(player_assets_opt.as_ref().map(|pa| &pa.weapon));
+                    let model = event.model_override.as_ref().or(player_assets_opt.as_ref().and_then(|pa| pa.weapon.as_ref()));
                     if let Some(m) = model { spawn_3d_sword_particle(&mut commands, &assets, p, m); } else { spawn_vfx_entity(&mut commands, &assets, p); }
                 }
             }